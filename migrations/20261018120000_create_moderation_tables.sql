@@ -0,0 +1,34 @@
+-- Users with access to the moderation queue
+CREATE TABLE moderator (
+    user_id    INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TYPE moderation_action_kind AS ENUM ('dismiss', 'archive', 'delete');
+
+-- Audit trail of moderator decisions
+CREATE TABLE moderation_action (
+    id           INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    post_id      INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    moderator_id INT REFERENCES users(id) ON DELETE SET NULL,
+    action       moderation_action_kind NOT NULL,
+    note         non_empty_text,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TYPE report_reason AS ENUM ('spam', 'harassment', 'hate', 'violence', 'sexual', 'other');
+
+CREATE TABLE post_report (
+    id          INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    post_id     INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    reporter_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason      report_reason NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- The moderator action that closed this report, NULL while the report is open
+    action_id   INT REFERENCES moderation_action(id) ON DELETE SET NULL
+);
+
+-- Enforce one open report per user per post
+CREATE UNIQUE INDEX post_report_open_unique
+    ON post_report (post_id, reporter_id)
+    WHERE action_id IS NULL;
@@ -0,0 +1,15 @@
+CREATE TYPE notification_kind AS ENUM ('reply', 'friend_request', 'friend_accepted');
+
+CREATE TABLE notification (
+    id           INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    recipient_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The user whose action caused the notification
+    actor_id     INT REFERENCES users(id) ON DELETE SET NULL,
+    kind         notification_kind NOT NULL,
+    -- The post the notification is about, if any
+    post_id      INT REFERENCES post(id) ON DELETE CASCADE,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    read_at      TIMESTAMPTZ
+);
+
+CREATE INDEX notification_recipient_idx ON notification (recipient_id, created_at DESC, id DESC);
@@ -0,0 +1,28 @@
+-- Announce new, edited, and deleted posts to live thread viewers. Notifications are only
+-- delivered once the transaction that made the change commits.
+CREATE FUNCTION notify_post_change() RETURNS TRIGGER AS $$
+DECLARE
+    change TEXT;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        change := 'created';
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        change := 'deleted';
+    ELSIF NEW.body IS DISTINCT FROM OLD.body THEN
+        change := 'edited';
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    PERFORM pg_notify(
+        'post_change',
+        json_build_object('kind', change, 'post_id', NEW.id, 'parent_id', NEW.parent_id)::text
+    );
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_change_notify
+    AFTER INSERT OR UPDATE ON post
+    FOR EACH ROW EXECUTE FUNCTION notify_post_change();
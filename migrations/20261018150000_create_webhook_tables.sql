@@ -0,0 +1,47 @@
+CREATE TYPE webhook_event_kind AS ENUM ('post.created', 'friendship.requested', 'friendship.accepted');
+
+-- Endpoints that are sent signed event payloads
+CREATE TABLE webhook (
+    id         INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id   INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url        non_empty_text NOT NULL,
+    -- The key used to sign payloads
+    secret     non_empty_text NOT NULL,
+    events     webhook_event_kind[] NOT NULL
+                   CONSTRAINT webhook_events_non_empty CHECK (cardinality(events) > 0),
+    -- Whether events involving any user are sent rather than only those involving the owner
+    all_users  BOOL NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX webhook_owner_idx ON webhook (owner_id);
+
+-- Outbox of events, written in the same transaction as the change that caused each event
+CREATE TABLE webhook_event (
+    id         INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    kind       webhook_event_kind NOT NULL,
+    payload    JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'succeeded', 'failed');
+
+CREATE TABLE webhook_delivery (
+    id              INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    webhook_id      INT NOT NULL REFERENCES webhook(id) ON DELETE CASCADE,
+    event_id        INT NOT NULL REFERENCES webhook_event(id) ON DELETE CASCADE,
+    status          webhook_delivery_status NOT NULL DEFAULT 'pending',
+    attempts        INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TIMESTAMPTZ,
+    -- The HTTP status of the last response, NULL if no response was received
+    response_status INT,
+    last_error      TEXT,
+    CONSTRAINT webhook_delivery_unique UNIQUE (webhook_id, event_id)
+);
+
+CREATE INDEX webhook_delivery_due_idx
+    ON webhook_delivery (next_attempt_at)
+    WHERE status = 'pending';
+
+CREATE INDEX webhook_delivery_log_idx ON webhook_delivery (webhook_id, id DESC);
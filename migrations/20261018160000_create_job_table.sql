@@ -0,0 +1,26 @@
+CREATE TYPE job_status AS ENUM ('pending', 'succeeded', 'dead');
+
+-- Background jobs, enqueued in the same transaction as the change that requires them
+CREATE TABLE job (
+    id          INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    -- Identifies the handler that runs the job
+    kind        non_empty_text NOT NULL,
+    payload     JSONB NOT NULL,
+    -- Prevents the same job from being enqueued again while it is pending, if set
+    dedupe_key  TEXT,
+    status      job_status NOT NULL DEFAULT 'pending',
+    -- Incremented when the job is claimed, so jobs that crash the worker still run out of attempts
+    attempts    INT NOT NULL DEFAULT 0,
+    -- When the job is next due, pushed back while the job is claimed and after each failure
+    run_at      TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error  TEXT,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX job_due_idx ON job (run_at) WHERE status = 'pending';
+
+CREATE UNIQUE INDEX job_dedupe_key_unique ON job (dedupe_key) WHERE status = 'pending';
+
+-- Dead-lettered jobs, for inspection and manual retries
+CREATE INDEX job_dead_idx ON job (finished_at DESC) WHERE status = 'dead';
@@ -0,0 +1,43 @@
+-- The top-level post that the post's thread starts from, which is the post itself for top-level
+-- posts. The root post belongs to no thread.
+ALTER TABLE post ADD COLUMN thread_id INT REFERENCES post(id) ON DELETE RESTRICT;
+
+WITH RECURSIVE thread AS (
+    SELECT p.id, p.id AS thread_id
+    FROM post root
+    JOIN post p ON p.parent_id = root.id
+    WHERE root.parent_id IS NULL
+
+    UNION ALL
+
+    SELECT p.id, t.thread_id
+    FROM thread t
+    JOIN post p ON p.parent_id = t.id
+)
+UPDATE post
+SET thread_id = thread.thread_id
+FROM thread
+WHERE post.id = thread.id;
+
+-- Place each new post in its parent's thread, or start a new one below the root post
+CREATE FUNCTION set_post_thread() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.parent_id IS NOT NULL THEN
+        NEW.thread_id := COALESCE((SELECT thread_id FROM post WHERE id = NEW.parent_id), NEW.id);
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_thread
+BEFORE INSERT ON post
+FOR EACH ROW EXECUTE FUNCTION set_post_thread();
+
+-- Supports gathering the home timeline by author and by thread, newest first, instead of scanning
+-- every post
+CREATE INDEX post_author_timeline_idx ON post (author_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
+
+CREATE INDEX post_thread_timeline_idx ON post (thread_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
@@ -0,0 +1,74 @@
+-- The number of posts anywhere below each post, for ranking replies. Not counted for the root post,
+-- which is never ranked.
+ALTER TABLE post ADD COLUMN descendant_count INT NOT NULL DEFAULT 0;
+
+WITH RECURSIVE ancestry AS (
+    SELECT id AS post_id, parent_id AS ancestor_id
+    FROM post
+    WHERE parent_id IS NOT NULL
+
+    UNION ALL
+
+    SELECT a.post_id, p.parent_id
+    FROM ancestry a
+    JOIN post p ON p.id = a.ancestor_id
+    WHERE p.parent_id IS NOT NULL
+)
+UPDATE post
+SET descendant_count = counts.descendant_count
+FROM (
+    SELECT ancestor_id, COUNT(*) AS descendant_count
+    FROM ancestry
+    GROUP BY ancestor_id
+) AS counts
+WHERE post.id = counts.ancestor_id AND post.parent_id IS NOT NULL;
+
+-- Count each new post towards all of its ancestors' descendants, other than the root post's. The
+-- root post is never ranked against other posts, and counting every post towards it would make
+-- every reply wait on its row.
+CREATE FUNCTION count_post_descendant() RETURNS TRIGGER AS $$
+DECLARE
+    ancestor_ids INT[];
+BEGIN
+    WITH RECURSIVE ancestor AS (
+        SELECT id, parent_id FROM post WHERE id = NEW.parent_id
+
+        UNION ALL
+
+        SELECT p.id, p.parent_id
+        FROM ancestor a
+        JOIN post p ON p.id = a.parent_id
+    )
+    SELECT array_agg(id) INTO ancestor_ids
+    FROM ancestor
+    WHERE parent_id IS NOT NULL;
+
+    -- Lock the ancestors from the parent upwards, the order in which replies already lock their
+    -- parent, so that replies in overlapping branches wait on each other instead of deadlocking.
+    -- Ancestors always have lower IDs than their descendants.
+    PERFORM 1 FROM post WHERE id = ANY(ancestor_ids) ORDER BY id DESC FOR UPDATE;
+
+    UPDATE post
+    SET descendant_count = descendant_count + 1
+    WHERE id = ANY(ancestor_ids);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_descendant_count
+AFTER INSERT ON post
+FOR EACH ROW EXECUTE FUNCTION count_post_descendant();
+
+-- Ranks posts by activity, with newer posts needing less activity to rank as highly. Each tenfold
+-- increase in descendants is worth the same as being posted 12.5 hours later. Stored so that its
+-- index is kept up to date along with the row.
+ALTER TABLE post ADD COLUMN hot_score DOUBLE PRECISION NOT NULL GENERATED ALWAYS AS (
+    log(greatest(descendant_count, 1))
+        + extract(EPOCH FROM created_at AT TIME ZONE 'UTC') / 45000
+) STORED;
+
+-- Support each way of sorting a post's children
+CREATE INDEX post_children_new_idx ON post (parent_id, created_at, id);
+CREATE INDEX post_children_top_idx ON post (parent_id, descendant_count, id);
+CREATE INDEX post_children_hot_idx ON post (parent_id, hot_score, id);
@@ -0,0 +1,39 @@
+CREATE TYPE reaction_kind AS ENUM ('like', 'love', 'laugh', 'wow', 'sad', 'angry');
+
+-- Lightweight responses to posts, at most one per user per post
+CREATE TABLE post_reaction (
+    post_id    INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind       reaction_kind NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (post_id, user_id)
+);
+
+CREATE INDEX post_reaction_user_idx ON post_reaction (user_id);
+
+-- Summarizes the reactions to a post as a JSON array with an element for each kind of reaction
+-- received, in the order the kinds are declared, and whether the viewer reacted with that kind.
+-- Deleted posts show no reactions.
+CREATE FUNCTION post_reactions(target_post_id INT, viewer_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        jsonb_agg(
+            jsonb_build_object(
+                'kind', counts.kind,
+                'count', counts.count,
+                'reacted_by_me', counts.reacted_by_me
+            )
+            ORDER BY counts.kind
+        ),
+        '[]'::JSONB
+    )
+    FROM (
+        SELECT r.kind, COUNT(*) AS count, COALESCE(bool_or(r.user_id = viewer_id), false) AS reacted_by_me
+        FROM post_reaction r
+        JOIN post p ON p.id = r.post_id
+        WHERE r.post_id = target_post_id AND p.deleted_at IS NULL
+        GROUP BY r.kind
+    ) AS counts
+$$;
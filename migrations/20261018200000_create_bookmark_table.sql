@@ -0,0 +1,11 @@
+-- Posts that users have saved to come back to later. Soft-deleted posts stay bookmarked and are
+-- shown as tombstones.
+CREATE TABLE bookmark (
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    post_id    INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, post_id)
+);
+
+-- Supports paging through a user's bookmarks in save order
+CREATE INDEX bookmark_saved_idx ON bookmark (user_id, created_at DESC, post_id DESC);
@@ -0,0 +1,12 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Full-text search over post bodies. Deleted posts have no body, so they never match.
+ALTER TABLE post
+ADD COLUMN search_vector TSVECTOR NOT NULL
+    GENERATED ALWAYS AS (to_tsvector('english', COALESCE(body, ''))) STORED;
+
+CREATE INDEX post_search_idx ON post USING GIN (search_vector);
+
+-- Fuzzy search over usernames and display names
+CREATE INDEX users_username_trgm_idx ON users USING GIN (username gin_trgm_ops);
+CREATE INDEX users_name_trgm_idx ON users USING GIN (name gin_trgm_ops);
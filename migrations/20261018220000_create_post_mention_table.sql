@@ -0,0 +1,37 @@
+ALTER TYPE notification_kind ADD VALUE 'mention';
+
+-- Users mentioned in posts, one row per @username in the body that matched an existing user
+CREATE TABLE post_mention (
+    post_id      INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    user_id      INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The UTF-8 byte offsets of the mention (including the @) within the post body
+    start_offset INT NOT NULL,
+    end_offset   INT NOT NULL,
+    PRIMARY KEY (post_id, start_offset),
+    CONSTRAINT post_mention_span_ordering CHECK (0 <= start_offset AND start_offset < end_offset)
+);
+
+CREATE INDEX post_mention_user_idx ON post_mention (user_id, post_id);
+
+-- Lists the mentions in a post as a JSON array of spans in order of appearance, each with the
+-- mentioned user's username. Deleted posts have no body, so they show no mentions.
+CREATE FUNCTION post_mentions(target_post_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        jsonb_agg(
+            jsonb_build_object(
+                'start', m.start_offset,
+                'end', m.end_offset,
+                'username', u.username
+            )
+            ORDER BY m.start_offset
+        ),
+        '[]'::JSONB
+    )
+    FROM post_mention m
+    JOIN users u ON u.id = m.user_id
+    JOIN post p ON p.id = m.post_id
+    WHERE m.post_id = target_post_id AND p.deleted_at IS NULL
+$$;
@@ -0,0 +1,16 @@
+-- Topics that posts can be tagged with, stored in normalized (lowercase) form
+CREATE TABLE tag (
+    id   INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    name non_empty_text NOT NULL
+             CONSTRAINT tag_name_unique UNIQUE
+             CONSTRAINT tag_name_lowercase CHECK (name = lower(name))
+);
+
+-- The tags found in each post's body
+CREATE TABLE post_tag (
+    post_id INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    tag_id  INT NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, tag_id)
+);
+
+CREATE INDEX post_tag_tag_idx ON post_tag (tag_id, post_id);
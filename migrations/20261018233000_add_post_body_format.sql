@@ -0,0 +1,4 @@
+CREATE TYPE body_format AS ENUM ('plain', 'markdown');
+
+-- How the body should be rendered. Posts from before formats existed are plain text.
+ALTER TABLE post ADD COLUMN body_format body_format NOT NULL DEFAULT 'plain';
@@ -0,0 +1,2 @@
+-- Reports without a reporter are opened automatically, such as by the content filter
+ALTER TABLE post_report ALTER COLUMN reporter_id DROP NOT NULL;
@@ -0,0 +1,41 @@
+-- Images attached to posts. The image files themselves are kept in blob storage under the keys.
+CREATE TABLE attachment (
+    id            INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    post_id       INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    content_type  non_empty_text NOT NULL,
+    width         INT NOT NULL,
+    height        INT NOT NULL,
+    byte_size     INT NOT NULL,
+    storage_key   non_empty_text NOT NULL CONSTRAINT attachment_storage_key_unique UNIQUE,
+    thumbnail_key non_empty_text NOT NULL CONSTRAINT attachment_thumbnail_key_unique UNIQUE,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- When the attachment's images finished being written. Attachments are recorded before their
+    -- images are written, to reserve their place on the post, and stay hidden until then.
+    stored_at     TIMESTAMPTZ,
+    CONSTRAINT attachment_positive_size CHECK (width > 0 AND height > 0 AND byte_size > 0)
+);
+
+CREATE INDEX attachment_post_idx ON attachment (post_id, id);
+
+-- Lists the attachments on a post as a JSON array in upload order. Deleted posts show no
+-- attachments.
+CREATE FUNCTION post_attachments(target_post_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        jsonb_agg(
+            jsonb_build_object(
+                'id', a.id,
+                'content_type', a.content_type,
+                'width', a.width,
+                'height', a.height
+            )
+            ORDER BY a.id
+        ),
+        '[]'::JSONB
+    )
+    FROM attachment a
+    JOIN post p ON p.id = a.post_id
+    WHERE a.post_id = target_post_id AND a.stored_at IS NOT NULL AND p.deleted_at IS NULL
+$$;
@@ -0,0 +1,14 @@
+-- Unfinished replies saved by their authors. Like replies themselves, there can be at most one per
+-- author and parent. The body is only fully checked once the draft is published.
+CREATE TABLE draft (
+    author_id   INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    parent_id   INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    body        TEXT NOT NULL,
+    body_format body_format NOT NULL DEFAULT 'plain',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (author_id, parent_id)
+);
+
+-- Supports listing a user's drafts, most recently saved first
+CREATE INDEX draft_updated_idx ON draft (author_id, updated_at DESC, parent_id DESC);
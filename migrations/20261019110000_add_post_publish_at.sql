@@ -0,0 +1,56 @@
+-- When a scheduled post will be published. Posts are hidden until then, and NULL once published.
+ALTER TABLE post ADD COLUMN publish_at TIMESTAMPTZ;
+
+-- Supports finding the scheduled posts that are due
+CREATE INDEX post_scheduled_idx ON post (publish_at, id) WHERE publish_at IS NOT NULL;
+
+-- Scheduled posts are left out of the home timeline until published
+DROP INDEX post_author_timeline_idx;
+DROP INDEX post_thread_timeline_idx;
+
+CREATE INDEX post_author_timeline_idx ON post (author_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL AND publish_at IS NULL;
+
+CREATE INDEX post_thread_timeline_idx ON post (thread_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL AND publish_at IS NULL;
+
+-- Why a scheduled post could not be published and was returned to its author's drafts instead
+ALTER TABLE draft ADD COLUMN publish_error TEXT;
+
+-- Scheduled posts are announced when they are published rather than when they are inserted
+CREATE OR REPLACE FUNCTION notify_post_change() RETURNS TRIGGER AS $$
+DECLARE
+    change TEXT;
+BEGIN
+    IF NEW.publish_at IS NOT NULL THEN
+        RETURN NULL;
+    ELSIF TG_OP = 'INSERT' OR OLD.publish_at IS NOT NULL THEN
+        change := 'created';
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        change := 'deleted';
+    ELSIF NEW.body IS DISTINCT FROM OLD.body THEN
+        change := 'edited';
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    PERFORM pg_notify(
+        'post_change',
+        json_build_object('kind', change, 'post_id', NEW.id, 'parent_id', NEW.parent_id)::text
+    );
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Likewise, scheduled posts only count towards their ancestors' descendants once published
+DROP TRIGGER post_descendant_count ON post;
+
+CREATE TRIGGER post_descendant_count
+AFTER INSERT ON post
+FOR EACH ROW WHEN (NEW.publish_at IS NULL) EXECUTE FUNCTION count_post_descendant();
+
+CREATE TRIGGER post_descendant_count_on_publish
+AFTER UPDATE OF publish_at ON post
+FOR EACH ROW WHEN (OLD.publish_at IS NOT NULL AND NEW.publish_at IS NULL)
+EXECUTE FUNCTION count_post_descendant();
@@ -0,0 +1,24 @@
+-- The post that a post quotes, if any. Quoted posts can be anywhere, not just in the same thread.
+ALTER TABLE post ADD COLUMN quoted_post_id INT REFERENCES post(id) ON DELETE SET NULL;
+
+-- Supports listing the posts that quote a post, most recent first
+CREATE INDEX post_quoted_idx
+    ON post (quoted_post_id, created_at DESC, id DESC)
+    WHERE quoted_post_id IS NOT NULL;
+
+-- Summarizes a quoted post as a JSON object with its ID, its author's username, and the beginning
+-- of its body, or NULL if there is no quoted post. Deleted posts have no body, so their excerpt is
+-- NULL. The excerpt is as long as the parent summaries in timelines.
+CREATE FUNCTION post_quote(target_post_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT jsonb_build_object(
+        'id', q.id,
+        'author_username', u.username,
+        'excerpt', LEFT(q.body, 140)
+    )
+    FROM post q
+    LEFT JOIN users u ON u.id = q.author_id
+    WHERE q.id = target_post_id
+$$;
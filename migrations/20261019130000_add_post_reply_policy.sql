@@ -0,0 +1,7 @@
+CREATE TYPE reply_policy AS ENUM ('anyone', 'friends', 'nobody');
+
+-- Who may reply to the post. Friends are the friends of the post's author.
+ALTER TABLE post ADD COLUMN reply_policy reply_policy NOT NULL DEFAULT 'anyone';
+
+-- Whether the reply policy also applies to replies anywhere below the post
+ALTER TABLE post ADD COLUMN reply_policy_cascades BOOL NOT NULL DEFAULT false;
@@ -0,0 +1,73 @@
+-- How far below the root post the post is. The root post has a depth of 0.
+ALTER TABLE post ADD COLUMN depth INT NOT NULL DEFAULT 0;
+
+-- The number of published posts directly below this post, for limiting thread size
+ALTER TABLE post ADD COLUMN child_count INT NOT NULL DEFAULT 0;
+
+WITH RECURSIVE tree AS (
+    SELECT id, 0 AS depth FROM post WHERE parent_id IS NULL
+
+    UNION ALL
+
+    SELECT p.id, t.depth + 1
+    FROM tree t
+    JOIN post p ON p.parent_id = t.id
+)
+UPDATE post
+SET depth = tree.depth
+FROM tree
+WHERE post.id = tree.id;
+
+UPDATE post
+SET child_count = children.count
+FROM (
+    SELECT parent_id, COUNT(*) AS count
+    FROM post
+    WHERE parent_id IS NOT NULL AND publish_at IS NULL
+    GROUP BY parent_id
+) children
+WHERE post.id = children.parent_id;
+
+-- Place each new post one level below its parent
+CREATE FUNCTION set_post_depth() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.depth := COALESCE((SELECT depth + 1 FROM post WHERE id = NEW.parent_id), 0);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_depth
+BEFORE INSERT ON post
+FOR EACH ROW EXECUTE FUNCTION set_post_depth();
+
+-- Count each new post towards its parent's children and all of its ancestors' descendants once it
+-- is published, other than the root post's descendants
+CREATE OR REPLACE FUNCTION count_post_descendant() RETURNS TRIGGER AS $$
+DECLARE
+    ancestor_ids INT[];
+BEGIN
+    WITH RECURSIVE ancestor AS (
+        SELECT id, parent_id FROM post WHERE id = NEW.parent_id
+
+        UNION ALL
+
+        SELECT p.id, p.parent_id
+        FROM ancestor a
+        JOIN post p ON p.id = a.parent_id
+    )
+    SELECT array_agg(id) INTO ancestor_ids
+    FROM ancestor
+    WHERE parent_id IS NOT NULL OR id = NEW.parent_id;
+
+    -- Locked in the same order as before, from the parent upwards
+    PERFORM 1 FROM post WHERE id = ANY(ancestor_ids) ORDER BY id DESC FOR UPDATE;
+
+    UPDATE post
+    SET descendant_count = descendant_count + (parent_id IS NOT NULL)::INT,
+        child_count = child_count + (id = NEW.parent_id)::INT
+    WHERE id = ANY(ancestor_ids);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
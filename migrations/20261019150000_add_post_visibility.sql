@@ -0,0 +1,60 @@
+CREATE TYPE post_visibility AS ENUM ('public', 'friends', 'participants');
+
+-- Who may see the post. Friends are the friends of the post's author, and participants are the
+-- author of the post being replied to and the users mentioned in the post. The author can always
+-- see their own post.
+ALTER TABLE post ADD COLUMN visibility post_visibility NOT NULL DEFAULT 'public';
+
+-- Whether the viewer may see the post. Posts whose visibility was restricted by an author who has
+-- since been deleted are never visible.
+CREATE FUNCTION post_visible_to(target_post_id INT, viewer_id INT)
+RETURNS BOOL
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        p.visibility = 'public'
+        OR p.author_id = viewer_id
+        OR (
+            p.visibility = 'friends' AND EXISTS (
+                SELECT 1 FROM friendship f
+                WHERE f.confirmed_at IS NOT NULL
+                    AND f.lesser_id = LEAST(viewer_id, p.author_id)
+                    AND f.greater_id = GREATEST(viewer_id, p.author_id)
+            )
+        )
+        OR (
+            p.visibility = 'participants' AND (
+                EXISTS (
+                    SELECT 1 FROM post parent
+                    WHERE parent.id = p.parent_id AND parent.author_id = viewer_id
+                )
+                OR EXISTS (
+                    SELECT 1 FROM post_mention m
+                    WHERE m.post_id = p.id AND m.user_id = viewer_id
+                )
+            )
+        ),
+        false
+    )
+    FROM post p
+    WHERE p.id = target_post_id
+$$;
+
+-- Quotes of posts that the viewer may not see leave out the author and excerpt
+DROP FUNCTION post_quote(INT);
+
+CREATE FUNCTION post_quote(target_post_id INT, viewer_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT jsonb_build_object(
+        'id', q.id,
+        'author_username', CASE WHEN visible THEN u.username END,
+        'excerpt', CASE WHEN visible THEN LEFT(q.body, 140) END,
+        'hidden', NOT visible
+    )
+    FROM post q
+    LEFT JOIN users u ON u.id = q.author_id
+    CROSS JOIN LATERAL post_visible_to(q.id, viewer_id) AS visible
+    WHERE q.id = target_post_id
+$$;
@@ -0,0 +1,19 @@
+-- Key pairs that users' ActivityPub actors sign outgoing requests with, created on first use
+CREATE TABLE actor_key (
+    user_id         INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Both keys are PEM-encoded, the public key as SPKI and the private key as PKCS#8
+    public_key_pem  non_empty_text NOT NULL,
+    private_key_pem non_empty_text NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Actors on other ActivityPub servers that follow local users
+CREATE TABLE remote_follower (
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The remote actor's ID, which is also the URL of its actor document
+    actor_id   non_empty_text NOT NULL,
+    -- Where activities for the remote actor are delivered
+    inbox_url  non_empty_text NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, actor_id)
+);
@@ -1,3 +1,5 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
 -- Reusable type for non-empty text columns
 CREATE DOMAIN non_empty_text AS TEXT
     CONSTRAINT text_non_empty CHECK (VALUE ~ '\S'); -- At least one non-whitespace character
@@ -25,6 +27,12 @@ CREATE TABLE friendship (
     CONSTRAINT friendship_id_ordering CHECK (lesser_id < greater_id)
 );
 
+CREATE TYPE body_format AS ENUM ('plain', 'markdown');
+
+CREATE TYPE reply_policy AS ENUM ('anyone', 'friends', 'nobody');
+
+CREATE TYPE post_visibility AS ENUM ('public', 'friends', 'participants');
+
 CREATE TABLE post (
     id          INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
     author_id   INT REFERENCES users(id) ON DELETE SET NULL,
@@ -34,6 +42,40 @@ CREATE TABLE post (
     edited_at   TIMESTAMPTZ,
     archived_at TIMESTAMPTZ,
     deleted_at  TIMESTAMPTZ,
+    -- The top-level post that the post's thread starts from, which is the post itself for
+    -- top-level posts. The root post belongs to no thread.
+    thread_id   INT REFERENCES post(id) ON DELETE RESTRICT,
+    -- The number of posts anywhere below this post, for ranking replies. Not counted for the root
+    -- post, which is never ranked.
+    descendant_count INT NOT NULL DEFAULT 0,
+    -- Ranks posts by activity, with newer posts needing less activity to rank as highly. Each
+    -- tenfold increase in descendants is worth the same as being posted 12.5 hours later.
+    hot_score   DOUBLE PRECISION NOT NULL GENERATED ALWAYS AS (
+        log(greatest(descendant_count, 1))
+            + extract(EPOCH FROM created_at AT TIME ZONE 'UTC') / 45000
+    ) STORED,
+    -- For full-text search. Deleted posts have no body, so they never match.
+    search_vector TSVECTOR NOT NULL
+        GENERATED ALWAYS AS (to_tsvector('english', COALESCE(body, ''))) STORED,
+    -- How the body should be rendered. Posts from before formats existed are plain text.
+    body_format body_format NOT NULL DEFAULT 'plain',
+    -- When a scheduled post will be published. Hidden until then, and NULL once published.
+    publish_at  TIMESTAMPTZ,
+    -- The post that this post quotes, if any. Quoted posts can be anywhere, not just in the same
+    -- thread.
+    quoted_post_id INT REFERENCES post(id) ON DELETE SET NULL,
+    -- Who may reply to the post. Friends are the friends of the post's author.
+    reply_policy reply_policy NOT NULL DEFAULT 'anyone',
+    -- Whether the reply policy also applies to replies anywhere below the post
+    reply_policy_cascades BOOL NOT NULL DEFAULT false,
+    -- How far below the root post the post is. The root post has a depth of 0.
+    depth       INT NOT NULL DEFAULT 0,
+    -- The number of published posts directly below this post, for limiting thread size
+    child_count INT NOT NULL DEFAULT 0,
+    -- Who may see the post. Friends are the friends of the post's author, and participants are the
+    -- author of the post being replied to and the users mentioned in the post. The author can
+    -- always see their own post.
+    visibility  post_visibility NOT NULL DEFAULT 'public',
     -- Enforce one child post per user per parent post
     CONSTRAINT post_author_parent_unique UNIQUE (author_id, parent_id)
 );
@@ -43,3 +85,497 @@ CREATE UNIQUE INDEX one_root_post
     ON post ((true))
     WHERE parent_id IS NULL;
 
+-- Supports finding the scheduled posts that are due
+CREATE INDEX post_scheduled_idx ON post (publish_at, id) WHERE publish_at IS NOT NULL;
+
+-- Supports listing the posts that quote a post, most recent first
+CREATE INDEX post_quoted_idx
+    ON post (quoted_post_id, created_at DESC, id DESC)
+    WHERE quoted_post_id IS NOT NULL;
+
+-- Users with access to the moderation queue
+CREATE TABLE moderator (
+    user_id    INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TYPE moderation_action_kind AS ENUM ('dismiss', 'archive', 'delete');
+
+-- Audit trail of moderator decisions
+CREATE TABLE moderation_action (
+    id           INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    post_id      INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    moderator_id INT REFERENCES users(id) ON DELETE SET NULL,
+    action       moderation_action_kind NOT NULL,
+    note         non_empty_text,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TYPE report_reason AS ENUM ('spam', 'harassment', 'hate', 'violence', 'sexual', 'other');
+
+CREATE TABLE post_report (
+    id          INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    post_id     INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    -- NULL for reports opened automatically, such as by the content filter
+    reporter_id INT REFERENCES users(id) ON DELETE CASCADE,
+    reason      report_reason NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- The moderator action that closed this report, NULL while the report is open
+    action_id   INT REFERENCES moderation_action(id) ON DELETE SET NULL
+);
+
+-- Enforce one open report per user per post
+CREATE UNIQUE INDEX post_report_open_unique
+    ON post_report (post_id, reporter_id)
+    WHERE action_id IS NULL;
+
+CREATE TYPE notification_kind AS ENUM ('reply', 'friend_request', 'friend_accepted', 'mention');
+
+CREATE TABLE notification (
+    id           INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    recipient_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The user whose action caused the notification
+    actor_id     INT REFERENCES users(id) ON DELETE SET NULL,
+    kind         notification_kind NOT NULL,
+    -- The post the notification is about, if any
+    post_id      INT REFERENCES post(id) ON DELETE CASCADE,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    read_at      TIMESTAMPTZ
+);
+
+CREATE INDEX notification_recipient_idx ON notification (recipient_id, created_at DESC, id DESC);
+
+-- Announce new, edited, and deleted posts to live thread viewers. Notifications are only
+-- delivered once the transaction that made the change commits. Scheduled posts are announced when
+-- they are published rather than when they are inserted.
+CREATE FUNCTION notify_post_change() RETURNS TRIGGER AS $$
+DECLARE
+    change TEXT;
+BEGIN
+    IF NEW.publish_at IS NOT NULL THEN
+        RETURN NULL;
+    ELSIF TG_OP = 'INSERT' OR OLD.publish_at IS NOT NULL THEN
+        change := 'created';
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        change := 'deleted';
+    ELSIF NEW.body IS DISTINCT FROM OLD.body THEN
+        change := 'edited';
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    PERFORM pg_notify(
+        'post_change',
+        json_build_object('kind', change, 'post_id', NEW.id, 'parent_id', NEW.parent_id)::text
+    );
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_change_notify
+    AFTER INSERT OR UPDATE ON post
+    FOR EACH ROW EXECUTE FUNCTION notify_post_change();
+
+CREATE TYPE webhook_event_kind AS ENUM ('post.created', 'friendship.requested', 'friendship.accepted');
+
+-- Endpoints that are sent signed event payloads
+CREATE TABLE webhook (
+    id         INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id   INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url        non_empty_text NOT NULL,
+    -- The key used to sign payloads
+    secret     non_empty_text NOT NULL,
+    events     webhook_event_kind[] NOT NULL
+                   CONSTRAINT webhook_events_non_empty CHECK (cardinality(events) > 0),
+    -- Whether events involving any user are sent rather than only those involving the owner
+    all_users  BOOL NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX webhook_owner_idx ON webhook (owner_id);
+
+-- Outbox of events, written in the same transaction as the change that caused each event
+CREATE TABLE webhook_event (
+    id         INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    kind       webhook_event_kind NOT NULL,
+    payload    JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'succeeded', 'failed');
+
+CREATE TABLE webhook_delivery (
+    id              INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    webhook_id      INT NOT NULL REFERENCES webhook(id) ON DELETE CASCADE,
+    event_id        INT NOT NULL REFERENCES webhook_event(id) ON DELETE CASCADE,
+    status          webhook_delivery_status NOT NULL DEFAULT 'pending',
+    attempts        INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TIMESTAMPTZ,
+    -- The HTTP status of the last response, NULL if no response was received
+    response_status INT,
+    last_error      TEXT,
+    CONSTRAINT webhook_delivery_unique UNIQUE (webhook_id, event_id)
+);
+
+CREATE INDEX webhook_delivery_due_idx
+    ON webhook_delivery (next_attempt_at)
+    WHERE status = 'pending';
+
+CREATE INDEX webhook_delivery_log_idx ON webhook_delivery (webhook_id, id DESC);
+
+CREATE TYPE job_status AS ENUM ('pending', 'succeeded', 'dead');
+
+-- Background jobs, enqueued in the same transaction as the change that requires them
+CREATE TABLE job (
+    id          INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    -- Identifies the handler that runs the job
+    kind        non_empty_text NOT NULL,
+    payload     JSONB NOT NULL,
+    -- Prevents the same job from being enqueued again while it is pending, if set
+    dedupe_key  TEXT,
+    status      job_status NOT NULL DEFAULT 'pending',
+    -- Incremented when the job is claimed, so jobs that crash the worker still run out of attempts
+    attempts    INT NOT NULL DEFAULT 0,
+    -- When the job is next due, pushed back while the job is claimed and after each failure
+    run_at      TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error  TEXT,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX job_due_idx ON job (run_at) WHERE status = 'pending';
+
+CREATE UNIQUE INDEX job_dedupe_key_unique ON job (dedupe_key) WHERE status = 'pending';
+
+-- Dead-lettered jobs, for inspection and manual retries
+CREATE INDEX job_dead_idx ON job (finished_at DESC) WHERE status = 'dead';
+
+-- Supports gathering the home timeline by author and by thread, newest first
+CREATE INDEX post_author_timeline_idx ON post (author_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL AND publish_at IS NULL;
+
+CREATE INDEX post_thread_timeline_idx ON post (thread_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL AND publish_at IS NULL;
+
+-- Place each new post one level below its parent
+CREATE FUNCTION set_post_depth() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.depth := COALESCE((SELECT depth + 1 FROM post WHERE id = NEW.parent_id), 0);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_depth
+BEFORE INSERT ON post
+FOR EACH ROW EXECUTE FUNCTION set_post_depth();
+
+-- Place each new post in its parent's thread, or start a new one below the root post
+CREATE FUNCTION set_post_thread() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.parent_id IS NOT NULL THEN
+        NEW.thread_id := COALESCE((SELECT thread_id FROM post WHERE id = NEW.parent_id), NEW.id);
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_thread
+BEFORE INSERT ON post
+FOR EACH ROW EXECUTE FUNCTION set_post_thread();
+
+-- Count each new post towards its parent's children and all of its ancestors' descendants once it
+-- is published, other than the root post's descendants. The root post is never ranked against
+-- other posts, and counting every post towards it made every reply wait on its row.
+CREATE FUNCTION count_post_descendant() RETURNS TRIGGER AS $$
+DECLARE
+    ancestor_ids INT[];
+BEGIN
+    WITH RECURSIVE ancestor AS (
+        SELECT id, parent_id FROM post WHERE id = NEW.parent_id
+
+        UNION ALL
+
+        SELECT p.id, p.parent_id
+        FROM ancestor a
+        JOIN post p ON p.id = a.parent_id
+    )
+    SELECT array_agg(id) INTO ancestor_ids
+    FROM ancestor
+    WHERE parent_id IS NOT NULL OR id = NEW.parent_id;
+
+    -- Lock the ancestors from the parent upwards, the order in which replies already lock their
+    -- parent, so that replies in overlapping branches wait on each other instead of deadlocking.
+    -- Ancestors always have lower IDs than their descendants.
+    PERFORM 1 FROM post WHERE id = ANY(ancestor_ids) ORDER BY id DESC FOR UPDATE;
+
+    UPDATE post
+    SET descendant_count = descendant_count + (parent_id IS NOT NULL)::INT,
+        child_count = child_count + (id = NEW.parent_id)::INT
+    WHERE id = ANY(ancestor_ids);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER post_descendant_count
+AFTER INSERT ON post
+FOR EACH ROW WHEN (NEW.publish_at IS NULL) EXECUTE FUNCTION count_post_descendant();
+
+CREATE TRIGGER post_descendant_count_on_publish
+AFTER UPDATE OF publish_at ON post
+FOR EACH ROW WHEN (OLD.publish_at IS NOT NULL AND NEW.publish_at IS NULL)
+EXECUTE FUNCTION count_post_descendant();
+
+-- Support each way of sorting a post's children
+CREATE INDEX post_children_new_idx ON post (parent_id, created_at, id);
+CREATE INDEX post_children_top_idx ON post (parent_id, descendant_count, id);
+CREATE INDEX post_children_hot_idx ON post (parent_id, hot_score, id);
+
+CREATE TYPE reaction_kind AS ENUM ('like', 'love', 'laugh', 'wow', 'sad', 'angry');
+
+-- Lightweight responses to posts, at most one per user per post
+CREATE TABLE post_reaction (
+    post_id    INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind       reaction_kind NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (post_id, user_id)
+);
+
+CREATE INDEX post_reaction_user_idx ON post_reaction (user_id);
+
+-- Summarizes the reactions to a post as a JSON array with an element for each kind of reaction
+-- received, in the order the kinds are declared, and whether the viewer reacted with that kind.
+-- Deleted posts show no reactions.
+CREATE FUNCTION post_reactions(target_post_id INT, viewer_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        jsonb_agg(
+            jsonb_build_object(
+                'kind', counts.kind,
+                'count', counts.count,
+                'reacted_by_me', counts.reacted_by_me
+            )
+            ORDER BY counts.kind
+        ),
+        '[]'::JSONB
+    )
+    FROM (
+        SELECT r.kind, COUNT(*) AS count, COALESCE(bool_or(r.user_id = viewer_id), false) AS reacted_by_me
+        FROM post_reaction r
+        JOIN post p ON p.id = r.post_id
+        WHERE r.post_id = target_post_id AND p.deleted_at IS NULL
+        GROUP BY r.kind
+    ) AS counts
+$$;
+
+-- Posts that users have saved to come back to later. Soft-deleted posts stay bookmarked and are
+-- shown as tombstones.
+CREATE TABLE bookmark (
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    post_id    INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, post_id)
+);
+
+-- Supports paging through a user's bookmarks in save order
+CREATE INDEX bookmark_saved_idx ON bookmark (user_id, created_at DESC, post_id DESC);
+
+CREATE INDEX post_search_idx ON post USING GIN (search_vector);
+
+-- Fuzzy search over usernames and display names
+CREATE INDEX users_username_trgm_idx ON users USING GIN (username gin_trgm_ops);
+CREATE INDEX users_name_trgm_idx ON users USING GIN (name gin_trgm_ops);
+
+-- Users mentioned in posts, one row per @username in the body that matched an existing user
+CREATE TABLE post_mention (
+    post_id      INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    user_id      INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The UTF-8 byte offsets of the mention (including the @) within the post body
+    start_offset INT NOT NULL,
+    end_offset   INT NOT NULL,
+    PRIMARY KEY (post_id, start_offset),
+    CONSTRAINT post_mention_span_ordering CHECK (0 <= start_offset AND start_offset < end_offset)
+);
+
+CREATE INDEX post_mention_user_idx ON post_mention (user_id, post_id);
+
+-- Lists the mentions in a post as a JSON array of spans in order of appearance, each with the
+-- mentioned user's username. Deleted posts have no body, so they show no mentions.
+CREATE FUNCTION post_mentions(target_post_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        jsonb_agg(
+            jsonb_build_object(
+                'start', m.start_offset,
+                'end', m.end_offset,
+                'username', u.username
+            )
+            ORDER BY m.start_offset
+        ),
+        '[]'::JSONB
+    )
+    FROM post_mention m
+    JOIN users u ON u.id = m.user_id
+    JOIN post p ON p.id = m.post_id
+    WHERE m.post_id = target_post_id AND p.deleted_at IS NULL
+$$;
+
+-- Topics that posts can be tagged with, stored in normalized (lowercase) form
+CREATE TABLE tag (
+    id   INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    name non_empty_text NOT NULL
+             CONSTRAINT tag_name_unique UNIQUE
+             CONSTRAINT tag_name_lowercase CHECK (name = lower(name))
+);
+
+-- The tags found in each post's body
+CREATE TABLE post_tag (
+    post_id INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    tag_id  INT NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, tag_id)
+);
+
+CREATE INDEX post_tag_tag_idx ON post_tag (tag_id, post_id);
+
+-- Images attached to posts. The image files themselves are kept in blob storage under the keys.
+CREATE TABLE attachment (
+    id            INT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    post_id       INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    content_type  non_empty_text NOT NULL,
+    width         INT NOT NULL,
+    height        INT NOT NULL,
+    byte_size     INT NOT NULL,
+    storage_key   non_empty_text NOT NULL CONSTRAINT attachment_storage_key_unique UNIQUE,
+    thumbnail_key non_empty_text NOT NULL CONSTRAINT attachment_thumbnail_key_unique UNIQUE,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- When the attachment's images finished being written. Attachments are recorded before their
+    -- images are written, to reserve their place on the post, and stay hidden until then.
+    stored_at     TIMESTAMPTZ,
+    CONSTRAINT attachment_positive_size CHECK (width > 0 AND height > 0 AND byte_size > 0)
+);
+
+CREATE INDEX attachment_post_idx ON attachment (post_id, id);
+
+-- Lists the attachments on a post as a JSON array in upload order. Deleted posts show no
+-- attachments.
+CREATE FUNCTION post_attachments(target_post_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        jsonb_agg(
+            jsonb_build_object(
+                'id', a.id,
+                'content_type', a.content_type,
+                'width', a.width,
+                'height', a.height
+            )
+            ORDER BY a.id
+        ),
+        '[]'::JSONB
+    )
+    FROM attachment a
+    JOIN post p ON p.id = a.post_id
+    WHERE a.post_id = target_post_id AND a.stored_at IS NOT NULL AND p.deleted_at IS NULL
+$$;
+
+-- Whether the viewer may see the post. Posts whose visibility was restricted by an author who has
+-- since been deleted are never visible.
+CREATE FUNCTION post_visible_to(target_post_id INT, viewer_id INT)
+RETURNS BOOL
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT COALESCE(
+        p.visibility = 'public'
+        OR p.author_id = viewer_id
+        OR (
+            p.visibility = 'friends' AND EXISTS (
+                SELECT 1 FROM friendship f
+                WHERE f.confirmed_at IS NOT NULL
+                    AND f.lesser_id = LEAST(viewer_id, p.author_id)
+                    AND f.greater_id = GREATEST(viewer_id, p.author_id)
+            )
+        )
+        OR (
+            p.visibility = 'participants' AND (
+                EXISTS (
+                    SELECT 1 FROM post parent
+                    WHERE parent.id = p.parent_id AND parent.author_id = viewer_id
+                )
+                OR EXISTS (
+                    SELECT 1 FROM post_mention m
+                    WHERE m.post_id = p.id AND m.user_id = viewer_id
+                )
+            )
+        ),
+        false
+    )
+    FROM post p
+    WHERE p.id = target_post_id
+$$;
+
+-- Summarizes a quoted post as a JSON object with its ID, its author's username, and the beginning
+-- of its body, or NULL if there is no quoted post. Deleted posts have no body, so their excerpt is
+-- NULL, and posts that the viewer may not see have neither an author nor an excerpt. The excerpt is
+-- as long as the parent summaries in timelines.
+CREATE FUNCTION post_quote(target_post_id INT, viewer_id INT)
+RETURNS JSONB
+LANGUAGE SQL STABLE PARALLEL SAFE
+AS $$
+    SELECT jsonb_build_object(
+        'id', q.id,
+        'author_username', CASE WHEN visible THEN u.username END,
+        'excerpt', CASE WHEN visible THEN LEFT(q.body, 140) END,
+        'hidden', NOT visible
+    )
+    FROM post q
+    LEFT JOIN users u ON u.id = q.author_id
+    CROSS JOIN LATERAL post_visible_to(q.id, viewer_id) AS visible
+    WHERE q.id = target_post_id
+$$;
+
+-- Unfinished replies saved by their authors. Like replies themselves, there can be at most one per
+-- author and parent. The body is only fully checked once the draft is published.
+CREATE TABLE draft (
+    author_id   INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    parent_id   INT NOT NULL REFERENCES post(id) ON DELETE CASCADE,
+    body        TEXT NOT NULL,
+    body_format body_format NOT NULL DEFAULT 'plain',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Why a scheduled post could not be published, in which case it was returned to the drafts
+    publish_error TEXT,
+    PRIMARY KEY (author_id, parent_id)
+);
+
+-- Supports listing a user's drafts, most recently saved first
+CREATE INDEX draft_updated_idx ON draft (author_id, updated_at DESC, parent_id DESC);
+
+-- Key pairs that users' ActivityPub actors sign outgoing requests with, created on first use
+CREATE TABLE actor_key (
+    user_id         INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- Both keys are PEM-encoded, the public key as SPKI and the private key as PKCS#8
+    public_key_pem  non_empty_text NOT NULL,
+    private_key_pem non_empty_text NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Actors on other ActivityPub servers that follow local users
+CREATE TABLE remote_follower (
+    user_id    INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The remote actor's ID, which is also the URL of its actor document
+    actor_id   non_empty_text NOT NULL,
+    -- Where activities for the remote actor are delivered
+    inbox_url  non_empty_text NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, actor_id)
+);
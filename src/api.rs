@@ -4,5 +4,7 @@ mod dto;
 mod error;
 mod handler;
 mod middleware;
+mod optional_requester;
 mod router;
 mod validated_json;
+mod validated_query;
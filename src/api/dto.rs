@@ -1,3 +1,6 @@
+pub mod activitypub;
+pub mod feed;
+pub mod markdown;
 pub mod requests;
 pub mod responses;
 pub mod signup_request;
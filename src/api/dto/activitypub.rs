@@ -0,0 +1,306 @@
+use {
+    crate::{
+        api::dto::{feed::rfc3339, markdown::render_html},
+        config::PublicUrl,
+        domain::federation::{KEY_FRAGMENT, actor_path},
+        models::{federation::LocalActor, post::PostWithAuthor},
+    },
+    serde::Serialize,
+    utoipa::ToSchema,
+};
+
+/// The media type of ActivityPub documents.
+pub const ACTIVITY_JSON: &str = "application/activity+json; charset=utf-8";
+
+/// The media type of WebFinger responses.
+pub const JRD_JSON: &str = "application/jrd+json; charset=utf-8";
+
+/// The maximum number of posts in an outbox. Paging is not supported, so older posts are left out.
+pub const MAX_OUTBOX_ITEMS: usize = 50;
+
+const ACTIVITY_STREAMS_CONTEXT: &str = "https://www.w3.org/ns/activitystreams";
+const SECURITY_CONTEXT: &str = "https://w3id.org/security/v1";
+
+/// Addresses an object to everyone.
+const PUBLIC_COLLECTION: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// A WebFinger (RFC 7033) description of an account, linking it to its ActivityPub actor.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+pub struct WebFingerResponse {
+    pub subject: String,
+    pub aliases: Vec<String>,
+    pub links: Vec<WebFingerLink>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+pub struct WebFingerLink {
+    pub rel: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub href: String,
+}
+
+impl WebFingerResponse {
+    pub fn new(public_url: &PublicUrl, username: &str) -> Self {
+        let actor_id = public_url.join(&actor_path(username));
+
+        Self {
+            subject: format!("acct:{username}@{}", public_url.host()),
+            aliases: vec![actor_id.clone()],
+            links: vec![WebFingerLink {
+                rel: String::from("self"),
+                kind: String::from("application/activity+json"),
+                href: actor_id,
+            }],
+        }
+    }
+}
+
+/// The ActivityPub actor of a user.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub preferred_username: String,
+    pub name: String,
+    pub inbox: String,
+    pub outbox: String,
+    pub public_key: PublicKey,
+}
+
+/// The key that an actor's requests are signed with.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKey {
+    pub id: String,
+    pub owner: String,
+    #[serde(rename = "publicKeyPem")]
+    pub pem: String,
+}
+
+impl Person {
+    pub fn new(public_url: &PublicUrl, actor: LocalActor) -> Self {
+        let id = public_url.join(&actor_path(&actor.username));
+
+        Self {
+            context: vec![String::from(ACTIVITY_STREAMS_CONTEXT), String::from(SECURITY_CONTEXT)],
+            kind: String::from("Person"),
+            inbox: format!("{id}/inbox"),
+            outbox: format!("{id}/outbox"),
+            public_key: PublicKey {
+                id: [id.as_str(), KEY_FRAGMENT].concat(),
+                owner: id.clone(),
+                pem: actor.public_key_pem,
+            },
+            preferred_username: actor.username,
+            name: actor.name,
+            id,
+        }
+    }
+}
+
+/// A user's outbox, listing their most recent public posts as `Create` activities.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Outbox {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The number of items listed, since older posts are left out
+    pub total_items: usize,
+    pub ordered_items: Vec<Create>,
+}
+
+/// The activity of a post being written.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+pub struct Create {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub actor: String,
+    pub published: String,
+    pub to: Vec<String>,
+    pub object: Note,
+}
+
+/// A post as an ActivityPub object.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub attributed_to: String,
+    /// The post's body as sanitized HTML
+    pub content: String,
+    pub published: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    /// The ID of the parent post, if the post is a reply
+    pub in_reply_to: Option<String>,
+    pub to: Vec<String>,
+}
+
+impl Outbox {
+    /// Creates the outbox of a user from their posts, most recent first, skipping any that are
+    /// deleted or hidden and keeping up to `MAX_OUTBOX_ITEMS`.
+    pub fn new(
+        public_url: &PublicUrl,
+        username: &str,
+        posts: impl IntoIterator<Item = PostWithAuthor>,
+    ) -> Self {
+        let actor_id = public_url.join(&actor_path(username));
+
+        let ordered_items = posts
+            .into_iter()
+            .filter_map(|post| Create::from_post(public_url, &actor_id, post))
+            .take(MAX_OUTBOX_ITEMS)
+            .collect::<Vec<_>>();
+
+        Self {
+            context: String::from(ACTIVITY_STREAMS_CONTEXT),
+            id: format!("{actor_id}/outbox"),
+            kind: String::from("OrderedCollection"),
+            total_items: ordered_items.len(),
+            ordered_items,
+        }
+    }
+}
+
+impl Create {
+    fn from_post(public_url: &PublicUrl, actor_id: &str, post: PostWithAuthor) -> Option<Self> {
+        let (Some(body), false) = (post.body, post.hidden) else {
+            return None;
+        };
+
+        let id = public_url.join(&format!("/posts/{}", post.id));
+
+        Some(Self {
+            id: format!("{id}/activity"),
+            kind: String::from("Create"),
+            actor: actor_id.to_owned(),
+            published: rfc3339(post.created_at),
+            to: vec![String::from(PUBLIC_COLLECTION)],
+            object: Note {
+                id,
+                kind: String::from("Note"),
+                attributed_to: actor_id.to_owned(),
+                content: render_html(&body, post.body_format),
+                published: rfc3339(post.created_at),
+                updated: post.edited_at.map(rfc3339),
+                in_reply_to: post
+                    .parent_id
+                    .map(|parent_id| public_url.join(&format!("/posts/{parent_id}"))),
+                to: vec![String::from(PUBLIC_COLLECTION)],
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::dummy_data::{actor_key, post_with_author},
+        anyhow::Result,
+    };
+
+    fn public_url() -> PublicUrl { PublicUrl::default() }
+
+    #[test]
+    fn links_accounts_to_their_actors() {
+        let webfinger = WebFingerResponse::new(&public_url(), "drake_conan");
+
+        assert_eq!("acct:drake_conan@spur.example", webfinger.subject);
+        assert_eq!(vec!["https://spur.example/users/drake_conan"], webfinger.aliases);
+        assert_eq!(
+            vec![("self", "application/activity+json", "https://spur.example/users/drake_conan")],
+            webfinger
+                .links
+                .iter()
+                .map(|link| (link.rel.as_str(), link.kind.as_str(), link.href.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn describes_actors_with_their_keys_and_collections() {
+        let person = Person::new(
+            &public_url(),
+            LocalActor {
+                user_id: 1,
+                username: String::from("drake_conan"),
+                name: String::from("Drake"),
+                public_key_pem: actor_key::number1().public_key_pem,
+            },
+        );
+
+        assert_eq!("https://spur.example/users/drake_conan", person.id);
+        assert_eq!("Person", person.kind);
+        assert_eq!("drake_conan", person.preferred_username);
+        assert_eq!("Drake", person.name);
+        assert_eq!("https://spur.example/users/drake_conan/inbox", person.inbox);
+        assert_eq!("https://spur.example/users/drake_conan/outbox", person.outbox);
+        assert_eq!("https://spur.example/users/drake_conan#main-key", person.public_key.id);
+        assert_eq!(person.id, person.public_key.owner);
+        assert_eq!(actor_key::number1().public_key_pem, person.public_key.pem);
+    }
+
+    #[test]
+    fn maps_posts_to_notes_replying_to_their_parents() -> Result<()> {
+        let [mut root, mut reply, mut hidden] = post_with_author::all3()?;
+        root.parent_id = None;
+        reply.parent_id = Some(root.id);
+        hidden.hidden = true;
+        let root_id = root.id;
+        let reply_id = reply.id;
+
+        let outbox = Outbox::new(&public_url(), "jack54444mack", [reply.clone(), root, hidden]);
+
+        assert_eq!("https://spur.example/users/jack54444mack/outbox", outbox.id);
+        assert_eq!(2, outbox.total_items);
+
+        let [first, second] = outbox.ordered_items.as_slice() else {
+            anyhow::bail!("expected two items, got {:?}", outbox.ordered_items);
+        };
+        assert_eq!(format!("https://spur.example/posts/{reply_id}/activity"), first.id);
+        assert_eq!("https://spur.example/users/jack54444mack", first.actor);
+        assert_eq!(format!("https://spur.example/posts/{reply_id}"), first.object.id);
+        assert_eq!(first.actor, first.object.attributed_to);
+        assert_eq!(reply.edited_at.map(rfc3339), first.object.updated);
+        assert_eq!(Some(format!("https://spur.example/posts/{root_id}")), first.object.in_reply_to);
+        assert_eq!(vec![PUBLIC_COLLECTION], first.object.to);
+        assert_eq!(None, second.object.in_reply_to);
+
+        Ok(())
+    }
+
+    #[test]
+    fn caps_outboxes() -> Result<()> {
+        let post = post_with_author::number1()?;
+
+        let outbox = Outbox::new(
+            &public_url(),
+            "jack54444mack",
+            std::iter::repeat_n(post, MAX_OUTBOX_ITEMS.saturating_add(1)),
+        );
+
+        assert_eq!(MAX_OUTBOX_ITEMS, outbox.total_items);
+        assert_eq!(MAX_OUTBOX_ITEMS, outbox.ordered_items.len());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+use {
+    crate::{api::dto::markdown::render_html, config::PublicUrl, models::post::PostWithAuthor},
+    chrono::{DateTime, SecondsFormat, Utc},
+    serde::{Deserialize, Serialize},
+    unicode_segmentation::UnicodeSegmentation as _,
+    utoipa::ToSchema,
+};
+
+/// The maximum number of entries in a feed. Feed readers poll for new posts, so older ones are
+/// left out.
+pub const MAX_FEED_ENTRIES: i64 = 50;
+
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n";
+
+/// The maximum number of graphemes from the first line of a post used as its title.
+const TITLE_GRAPHEMES: usize = 80;
+
+/// A syndication format that a feed can be rendered in.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    /// RSS 2.0
+    Rss,
+    /// Atom (RFC 4287)
+    Atom,
+    /// JSON Feed 1.1
+    Json,
+}
+
+impl FeedFormat {
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml; charset=utf-8",
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Rss => "rss",
+            Self::Atom => "atom",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// A feed of posts that can be rendered in any `FeedFormat`.
+#[cfg_attr(test, derive(Debug))]
+pub struct Feed {
+    title: String,
+    /// The resource that the feed follows
+    home_url: String,
+    /// The feed's own URL, without the format extension. Also used as the feed's ID so that it
+    /// stays the same across formats.
+    url: String,
+    updated: DateTime<Utc>,
+    entries: Vec<FeedEntry>,
+}
+
+#[cfg_attr(test, derive(Debug))]
+struct FeedEntry {
+    /// The post's URL, which is also its ID since post IDs never change
+    url: String,
+    author: String,
+    title: String,
+    html: String,
+    published: DateTime<Utc>,
+    updated: DateTime<Utc>,
+}
+
+impl Feed {
+    /// Creates a feed of the provided posts, skipping any that are deleted or hidden. `feed_path`
+    /// should not include the format extension. `updated` is used as the feed's update time if
+    /// it is later than that of every entry.
+    pub fn new(
+        public_url: &PublicUrl,
+        title: String,
+        home_path: &str,
+        feed_path: &str,
+        posts: impl IntoIterator<Item = PostWithAuthor>,
+        updated: DateTime<Utc>,
+    ) -> Self {
+        let entries = posts
+            .into_iter()
+            .filter_map(|post| FeedEntry::from_post(public_url, post))
+            .collect::<Vec<_>>();
+
+        Self {
+            title,
+            home_url: public_url.join(home_path),
+            url: public_url.join(feed_path),
+            updated: entries
+                .iter()
+                .map(|entry| entry.updated)
+                .fold(updated, DateTime::max),
+            entries,
+        }
+    }
+
+    /// Renders the feed as a document in the provided format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a JSON Feed cannot be serialized.
+    pub fn render(&self, format: FeedFormat) -> serde_json::Result<String> {
+        match format {
+            FeedFormat::Rss => Ok(self.rss()),
+            FeedFormat::Atom => Ok(self.atom()),
+            FeedFormat::Json => self.json_feed(),
+        }
+    }
+
+    fn self_url(&self, format: FeedFormat) -> String {
+        format!("{}.{}", self.url, format.extension())
+    }
+
+    fn rss(&self) -> String {
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| {
+                [
+                    String::from("<item>"),
+                    element("title", &entry.title),
+                    element("link", &entry.url),
+                    format!("<guid isPermaLink=\"true\">{}</guid>", escape(&entry.url)),
+                    element("dc:creator", &entry.author),
+                    element("pubDate", &entry.published.to_rfc2822()),
+                    element("description", &entry.html),
+                    String::from("</item>"),
+                ]
+                .concat()
+            })
+            .collect::<String>();
+
+        [
+            String::from(XML_DECLARATION),
+            String::from(
+                "<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+                 xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><channel>",
+            ),
+            element("title", &self.title),
+            element("link", &self.home_url),
+            element("description", &self.title),
+            format!(
+                "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>",
+                escape(&self.self_url(FeedFormat::Rss)),
+            ),
+            element("lastBuildDate", &self.updated.to_rfc2822()),
+            items,
+            String::from("</channel></rss>\n"),
+        ]
+        .concat()
+    }
+
+    fn atom(&self) -> String {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                [
+                    String::from("<entry>"),
+                    element("id", &entry.url),
+                    element("title", &entry.title),
+                    format!("<link rel=\"alternate\" href=\"{}\"/>", escape(&entry.url)),
+                    format!("<author>{}</author>", element("name", &entry.author)),
+                    element("published", &rfc3339(entry.published)),
+                    element("updated", &rfc3339(entry.updated)),
+                    format!("<content type=\"html\">{}</content>", escape(&entry.html)),
+                    String::from("</entry>"),
+                ]
+                .concat()
+            })
+            .collect::<String>();
+
+        [
+            String::from(XML_DECLARATION),
+            String::from("<feed xmlns=\"http://www.w3.org/2005/Atom\">"),
+            element("id", &self.url),
+            element("title", &self.title),
+            element("updated", &rfc3339(self.updated)),
+            format!("<link rel=\"self\" href=\"{}\"/>", escape(&self.self_url(FeedFormat::Atom))),
+            format!("<link rel=\"alternate\" href=\"{}\"/>", escape(&self.home_url)),
+            entries,
+            String::from("</feed>\n"),
+        ]
+        .concat()
+    }
+
+    fn json_feed(&self) -> serde_json::Result<String> {
+        let feed = JsonFeed {
+            version: "https://jsonfeed.org/version/1.1",
+            title: &self.title,
+            home_page_url: &self.home_url,
+            feed_url: self.self_url(FeedFormat::Json),
+            items: self
+                .entries
+                .iter()
+                .map(|entry| JsonFeedItem {
+                    id: &entry.url,
+                    url: &entry.url,
+                    title: &entry.title,
+                    content_html: &entry.html,
+                    date_published: rfc3339(entry.published),
+                    date_modified: rfc3339(entry.updated),
+                    authors: [JsonFeedAuthor { name: &entry.author }],
+                })
+                .collect(),
+        };
+
+        serde_json::to_string(&feed)
+    }
+}
+
+impl FeedEntry {
+    /// Converts a post into a feed entry, or returns `None` if it is deleted or hidden.
+    fn from_post(public_url: &PublicUrl, post: PostWithAuthor) -> Option<Self> {
+        let (Some(body), Some(author), false) = (post.body, post.author_username, post.hidden)
+        else {
+            return None;
+        };
+
+        Some(Self {
+            url: public_url.join(&format!("/posts/{}", post.id)),
+            author,
+            title: title_of(&body),
+            html: render_html(&body, post.body_format),
+            published: post.created_at,
+            updated: post.edited_at.unwrap_or(post.created_at),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct JsonFeed<'a> {
+    version: &'static str,
+    title: &'a str,
+    home_page_url: &'a str,
+    feed_url: String,
+    items: Vec<JsonFeedItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem<'a> {
+    id: &'a str,
+    url: &'a str,
+    title: &'a str,
+    content_html: &'a str,
+    date_published: String,
+    date_modified: String,
+    authors: [JsonFeedAuthor<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor<'a> {
+    name: &'a str,
+}
+
+/// Uses the beginning of the first line of a post body as its title.
+fn title_of(body: &str) -> String {
+    let mut lines = body.trim().lines();
+    let mut graphemes = lines.next().unwrap_or_default().graphemes(true);
+    let title = graphemes.by_ref().take(TITLE_GRAPHEMES).collect::<String>();
+
+    if graphemes.next().is_some() || lines.next().is_some() { format!("{title}…") } else { title }
+}
+
+/// Creates an XML element containing escaped text.
+fn element(name: &str, text: &str) -> String { format!("<{name}>{}</{name}>", escape(text)) }
+
+/// Formats a time in RFC 3339 format to the second, in UTC.
+pub fn rfc3339(time: DateTime<Utc>) -> String { time.to_rfc3339_opts(SecondsFormat::Secs, true) }
+
+/// Escapes text for use in XML content or attribute values, leaving out characters that XML does
+/// not allow even when escaped.
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                '\u{0}'..='\u{8}'
+                | '\u{b}'
+                | '\u{c}'
+                | '\u{e}'..='\u{1f}'
+                | '\u{fffe}'
+                | '\u{ffff}' => {}
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::test_utils::dummy_data::post_with_author, anyhow::Result,
+        serde_json::Value,
+    };
+
+    fn feed() -> Result<Feed> {
+        let [mut first, second, _] = post_with_author::all3()?;
+        first.body = Some(String::from("Fish & <chips>\nand more"));
+        let mut deleted = post_with_author::number3()?;
+        deleted.body = None;
+        deleted.author_username = None;
+
+        Ok(Feed::new(
+            &"https://spur.example/".parse()?,
+            String::from("Posts by \"someone\""),
+            "/posts/user/someone",
+            "/users/someone/feed",
+            vec![first, deleted, second],
+            DateTime::UNIX_EPOCH,
+        ))
+    }
+
+    #[test]
+    fn skips_deleted_posts_and_uses_the_latest_edit_as_the_update_time() -> Result<()> {
+        let feed = feed()?;
+        let [first, second, _] = post_with_author::all3()?;
+
+        assert_eq!(
+            vec![
+                format!("https://spur.example/posts/{}", first.id),
+                format!("https://spur.example/posts/{}", second.id)
+            ],
+            feed.entries
+                .iter()
+                .map(|entry| entry.url.clone())
+                .collect::<Vec<_>>(),
+        );
+        let latest = [&first, &second]
+            .into_iter()
+            .map(|post| post.edited_at.unwrap_or(post.created_at))
+            .max();
+        assert_eq!(latest, Some(feed.updated));
+        assert_eq!(second.edited_at, feed.entries.get(1).map(|entry| entry.updated));
+
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_text_in_xml_formats() -> Result<()> {
+        let feed = feed()?;
+
+        let rss = feed.render(FeedFormat::Rss)?;
+        assert!(rss.contains("<title>Posts by &quot;someone&quot;</title>"));
+        assert!(rss.contains("<title>Fish &amp; &lt;chips&gt;…</title>"));
+        assert!(rss.contains("<description>&lt;p&gt;Fish &amp;amp; &amp;lt;chips&amp;gt;"));
+        assert!(rss.contains("href=\"https://spur.example/users/someone/feed.rss\""));
+
+        let atom = feed.render(FeedFormat::Atom)?;
+        assert!(atom.contains("<id>https://spur.example/users/someone/feed</id>"));
+        assert!(
+            atom.contains("<content type=\"html\">&lt;p&gt;Fish &amp;amp; &amp;lt;chips&amp;gt;")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn leaves_out_control_characters_in_xml_formats() -> Result<()> {
+        assert_eq!("tab\tline\nreturn\r", escape("tab\tline\nreturn\r"));
+        assert_eq!("bell, escape, and &lt;", escape("bell\u{7}, escape\u{1b}, and \u{0}<\u{fffe}"));
+
+        let mut post = post_with_author::number1()?;
+        post.body = Some(String::from("Form\u{c}feed"));
+        let feed = Feed::new(
+            &"https://spur.example".parse()?,
+            String::from("Title\u{1}"),
+            "/",
+            "/feed",
+            [post],
+            DateTime::UNIX_EPOCH,
+        );
+        for format in [FeedFormat::Rss, FeedFormat::Atom] {
+            let xml = feed.render(format)?;
+            assert!(
+                !xml.chars()
+                    .any(|c| c < ' ' && !matches!(c, '\t' | '\n' | '\r')),
+                "{xml}"
+            );
+            assert!(xml.contains("Formfeed"));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn renders_json_feed() -> Result<()> {
+        let json = serde_json::from_str::<Value>(&feed()?.render(FeedFormat::Json)?)?;
+
+        assert_eq!(
+            Some("https://jsonfeed.org/version/1.1"),
+            json.pointer("/version").and_then(Value::as_str)
+        );
+        assert_eq!(
+            Some("https://spur.example/users/someone/feed.json"),
+            json.pointer("/feed_url").and_then(Value::as_str)
+        );
+        assert_eq!(Some("Fish & <chips>…"), json.pointer("/items/0/title").and_then(Value::as_str));
+        assert_eq!(
+            Some("jack54444mack"),
+            json.pointer("/items/0/authors/0/name")
+                .and_then(Value::as_str)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn shortens_long_or_multiline_titles() {
+        assert_eq!("short", title_of("short"));
+        assert_eq!("first…", title_of("first\nsecond"));
+        assert_eq!(format!("{}…", "ab".repeat(40)), title_of(&"ab".repeat(41)));
+    }
+}
@@ -0,0 +1,157 @@
+use {
+    crate::models::post::BodyFormat,
+    ammonia::Builder,
+    pulldown_cmark::{Event, Options, Parser, Tag, TagEnd, html},
+    std::{collections::HashSet, iter, sync::LazyLock},
+    validator::ValidationError,
+};
+
+const MARKDOWN_CODE: &str = "markdown";
+
+/// The only link schemes allowed in Markdown bodies.
+const LINK_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// The HTML tags produced by the supported Markdown subset. Anything else is stripped from the
+/// rendered output as a second line of defense.
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::empty();
+    builder
+        .tags(HashSet::from(["p", "br", "em", "strong", "del", "code", "pre", "a", "blockquote"]))
+        .tag_attributes([("a", HashSet::from(["href"]))].into())
+        .url_schemes(HashSet::from(LINK_SCHEMES))
+        .link_rel(Some("noopener noreferrer nofollow"));
+    builder
+});
+
+fn parser(body: &str) -> Parser<'_> { Parser::new_ext(body, Options::ENABLE_STRIKETHROUGH) }
+
+fn disallowed(construct: &str) -> ValidationError {
+    ValidationError::new(MARKDOWN_CODE)
+        .with_message(format!("markdown bodies cannot contain {construct}").into())
+}
+
+/// Checks that a Markdown body only uses the supported subset: emphasis, strikethrough, inline
+/// code, code blocks, links to web or email addresses, and block quotes.
+pub fn check_markdown(body: &str) -> Result<(), ValidationError> {
+    for event in parser(body) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => return Err(disallowed("headings")),
+            Event::Start(Tag::List(_) | Tag::Item) => return Err(disallowed("lists")),
+            Event::Start(Tag::Image { .. }) => return Err(disallowed("images")),
+            Event::Start(Tag::HtmlBlock) | Event::Html(_) | Event::InlineHtml(_) => {
+                return Err(disallowed("HTML"));
+            }
+            Event::Rule => return Err(disallowed("horizontal rules")),
+            Event::Start(Tag::Link { dest_url, .. })
+                if !dest_url.split_once(':').is_some_and(|(scheme, _)| {
+                    LINK_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str())
+                }) =>
+            {
+                return Err(disallowed("links other than http, https, or mailto links"));
+            }
+            Event::Start(
+                Tag::Paragraph
+                | Tag::BlockQuote(_)
+                | Tag::CodeBlock(_)
+                | Tag::Emphasis
+                | Tag::Strong
+                | Tag::Strikethrough
+                | Tag::Link { .. },
+            )
+            | Event::End(_)
+            | Event::Text(_)
+            | Event::Code(_)
+            | Event::SoftBreak
+            | Event::HardBreak => {}
+            _ => return Err(disallowed("unsupported formatting")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders a post body as sanitized HTML. Plain text is escaped and kept line for line.
+pub fn render_html(body: &str, format: BodyFormat) -> String {
+    let mut unsanitized = String::new();
+
+    match format {
+        BodyFormat::Plain => {
+            let lines = body.lines().enumerate().flat_map(|(i, line)| {
+                let line_break = (i > 0).then_some(Event::HardBreak);
+                line_break.into_iter().chain([Event::Text(line.into())])
+            });
+            let paragraph = iter::once(Event::Start(Tag::Paragraph))
+                .chain(lines)
+                .chain([Event::End(TagEnd::Paragraph)]);
+            html::push_html(&mut unsanitized, paragraph);
+        }
+        BodyFormat::Markdown => html::push_html(&mut unsanitized, parser(body)),
+    }
+
+    SANITIZER.clean(&unsanitized).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_the_supported_subset() {
+        for body in [
+            "plain words",
+            "*em* **strong** ~~struck~~ `code`",
+            "[a link](https://example.com) and <https://example.com> and [mail](mailto:a@b.c)",
+            "> a quote\n>\n> > nested",
+            "```rust\nfn main() {}\n```",
+            "line one  \nline two",
+        ] {
+            assert!(check_markdown(body).is_ok(), "{body}");
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_constructs() {
+        for (body, construct) in [
+            ("# Big", "headings"),
+            ("- one\n- two", "lists"),
+            ("1. one", "lists"),
+            ("![alt](https://example.com/a.png)", "images"),
+            ("<div>hi</div>", "HTML"),
+            ("some <b>bold</b>", "HTML"),
+            ("above\n\n---", "horizontal rules"),
+            ("[x](javascript:alert(1))", "links other than http, https, or mailto links"),
+            ("[x](/relative)", "links other than http, https, or mailto links"),
+        ] {
+            let message = check_markdown(body)
+                .err()
+                .and_then(|e| e.message)
+                .map(|m| m.to_string());
+            assert_eq!(
+                Some(format!("markdown bodies cannot contain {construct}")),
+                message,
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn renders_markdown_as_sanitized_html() {
+        let expected = concat!(
+            "<p><em>hi</em> ",
+            "<a href=\"https://example.com\" rel=\"noopener noreferrer nofollow\">there</a></p>\n",
+            "<blockquote>\n<p><code>&lt;x&gt;</code></p>\n</blockquote>\n",
+        );
+        assert_eq!(
+            expected,
+            render_html("*hi* [there](https://example.com)\n\n> `<x>`", BodyFormat::Markdown),
+        );
+    }
+
+    #[test]
+    fn renders_plain_text_unchanged_but_escaped() {
+        assert_eq!(
+            "<p>*not emphasis* &lt;b&gt;<br>\nsecond line</p>\n",
+            render_html("*not emphasis* <b>\nsecond line", BodyFormat::Plain),
+        );
+    }
+}
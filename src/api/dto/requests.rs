@@ -1,7 +1,16 @@
 use {
+    crate::{
+        api::dto::markdown::check_markdown,
+        models::{
+            moderation::{ModerationActionKind, ReportReason},
+            post::{BodyFormat, PostCursor, PostSort, PostVisibility, ReplyPolicy},
+            search::SearchKind,
+            webhook::WebhookEventKind,
+        },
+    },
     serde::{Deserialize, Serialize},
-    utoipa::ToSchema,
-    validator::Validate,
+    utoipa::{IntoParams, ToSchema},
+    validator::{Validate, ValidationError},
 };
 
 /// A request for logging in to an existing account.
@@ -25,6 +34,7 @@ pub struct AddFriendRequest {
 /// A request for creating a new post.
 #[derive(Serialize, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_body_format"))]
 pub struct CreatePostRequest {
     /// The ID of the post that this post is in reply to.
     #[validate(range(min = 1, message = "parent ID must be positive"))]
@@ -33,4 +43,200 @@ pub struct CreatePostRequest {
     /// The content of the post.
     #[validate(length(min = 1, message = "post body cannot be empty"))]
     pub body: String,
+
+    /// How the body is written. Defaults to `plain`.
+    #[serde(default)]
+    pub format: BodyFormat,
+
+    /// The ID of a post to quote, which can be anywhere rather than only in this thread.
+    #[serde(default)]
+    #[validate(range(min = 1, message = "quoted post ID must be positive"))]
+    pub quoted_post_id: Option<i32>,
+}
+
+fn validate_body_format(req: &CreatePostRequest) -> Result<(), ValidationError> {
+    match req.format {
+        BodyFormat::Plain => Ok(()),
+        BodyFormat::Markdown => check_markdown(&req.body),
+    }
+}
+
+/// A request for saving a draft reply. The body is not checked until the draft is published, so it
+/// may be empty or unfinished.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+pub struct SaveDraftRequest {
+    /// The content of the draft.
+    pub body: String,
+
+    /// How the body is written. Defaults to `plain`.
+    #[serde(default)]
+    pub format: BodyFormat,
+}
+
+/// A request for scheduling a draft reply to be published later.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleDraftRequest {
+    /// When to publish the draft in milliseconds since the Unix epoch. Must be in the future.
+    pub publish_at_ms: i64,
+}
+
+/// A request for changing who may reply to a post.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyPolicyRequest {
+    /// Who may reply to the post.
+    pub policy: ReplyPolicy,
+
+    /// Whether the policy also applies to replies anywhere below the post. Defaults to `false`.
+    #[serde(default)]
+    pub cascades: bool,
+}
+
+/// A request for changing who may see a post.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VisibilityRequest {
+    /// Who may see the post.
+    pub visibility: PostVisibility,
+}
+
+/// A request for reporting a post to the moderators.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+pub struct ReportPostRequest {
+    /// The reason the post is being reported.
+    pub reason: ReportReason,
+}
+
+/// A multipart form for attaching an image to a post.
+#[derive(ToSchema)]
+#[expect(dead_code, reason = "Only documents the request, since the upload is read field by field")]
+pub struct AttachmentUploadForm {
+    /// A JPEG, PNG, GIF, or WebP image.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// A request for resolving the open reports on a post.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+pub struct ResolveReportsRequest {
+    /// The action to take on the reported post.
+    pub action: ModerationActionKind,
+
+    /// An optional explanation of the decision for the audit trail.
+    #[validate(length(min = 1, message = "note cannot be empty if provided"))]
+    pub note: Option<String>,
+}
+
+/// A request for marking notifications as read.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+pub struct MarkNotificationsReadRequest {
+    /// The IDs of the notifications to mark as read. Marks all notifications if omitted.
+    #[validate(length(min = 1, message = "omit the IDs to mark all notifications as read"))]
+    pub ids: Option<Vec<i32>>,
+}
+
+/// A request for registering a webhook.
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterWebhookRequest {
+    /// The URL that events will be sent to, which must use `https` and have a publicly routable
+    /// host.
+    #[validate(url(message = "not a valid URL"))]
+    pub url: String,
+
+    /// The kinds of events to send.
+    #[validate(length(min = 1, message = "at least one event kind is required"))]
+    pub events: Vec<WebhookEventKind>,
+
+    /// Whether to send events involving any user rather than only the registering user. Requires
+    /// moderator access.
+    #[serde(default)]
+    pub all_users: bool,
+}
+
+/// A message sent by the client over the live thread WebSocket.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveThreadRequest {
+    /// Starts receiving changes to the specified posts and their direct replies.
+    #[serde(rename_all = "camelCase")]
+    Subscribe { post_ids: Vec<i32> },
+    /// Stops receiving changes to the specified posts and their direct replies.
+    #[serde(rename_all = "camelCase")]
+    Unsubscribe { post_ids: Vec<i32> },
+}
+
+/// Query parameters for retrieving a page of a list of posts.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    /// Where to begin, as returned in `nextCursor` by the previous page. Omit for the first page.
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<PostCursor>,
+
+    /// The maximum number of posts to return. Defaults to 20.
+    #[validate(range(min = 1, max = 100, message = "limit must be between 1 and 100"))]
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for searching posts or users.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// What to search for. Post searches support quoted phrases, `or`, and `-` for exclusion.
+    #[validate(length(
+        min = 1,
+        max = 200,
+        message = "query must be between 1 and 200 characters"
+    ))]
+    pub q: String,
+
+    /// Whether to search posts or users. Defaults to `posts`.
+    #[serde(default, rename = "type")]
+    #[param(rename = "type")]
+    pub kind: SearchKind,
+
+    /// The number of results to skip, as returned in `nextOffset` by the previous page. Defaults
+    /// to 0.
+    #[validate(range(min = 0, message = "offset cannot be negative"))]
+    pub offset: Option<i64>,
+
+    /// The maximum number of results to return. Defaults to 20.
+    #[validate(range(min = 1, max = 100, message = "limit must be between 1 and 100"))]
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for listing trending tags.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TrendingQuery {
+    /// How far back to count posts, in hours. Defaults to 24.
+    #[validate(range(min = 1, max = 168, message = "hours must be between 1 and 168"))]
+    pub hours: Option<i64>,
+
+    /// The maximum number of tags to return. Defaults to 10.
+    #[validate(range(min = 1, max = 50, message = "limit must be between 1 and 50"))]
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for listing the children of a post.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChildPostsQuery {
+    /// The order to list the children in. Defaults to `new`.
+    #[serde(default)]
+    pub sort: PostSort,
+}
+
+/// Query parameters for looking up an account with WebFinger.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebFingerQuery {
+    /// The account to look up, as `acct:{username}@{host}`.
+    pub resource: String,
 }
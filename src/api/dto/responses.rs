@@ -1,5 +1,28 @@
 use {
-    crate::models::post::PostWithAuthor,
+    crate::{
+        api::dto::markdown::render_html,
+        map_into::MapInto as _,
+        models::{
+            attachment::{Attachment, AttachmentSummary},
+            bookmark::BookmarkPage,
+            draft::DraftWithParent,
+            mention::{MentionPage, MentionSpan},
+            moderation::{
+                ModerationActionKind, ModerationActionRecord, ReportReason, ReportedPost,
+            },
+            notification::{NotificationKind, NotificationWithActor, UnreadCounts},
+            post::{
+                BodyFormat, ParentSummary, PostVisibility, PostWithAuthor, QuotePage, QuotedPost,
+                ReplyPolicy, TimelinePage, TimelinePost,
+            },
+            reaction::{ReactionCount, ReactionKind},
+            search::{SearchPage, UserSearchResult},
+            tag::{TagPage, TrendingTag},
+            webhook::{
+                NewWebhook, Webhook, WebhookDeliveryRecord, WebhookDeliveryStatus, WebhookEventKind,
+            },
+        },
+    },
     serde::{Deserialize, Serialize},
     utoipa::ToSchema,
 };
@@ -36,8 +59,12 @@ pub struct PostResponse {
     pub author_username: String,
     /// The ID of the post that this post is in reply to.
     pub parent_id: Option<i32>,
-    /// The content of the post.
+    /// The content of the post as written.
     pub body: String,
+    /// How the body is written.
+    pub format: BodyFormat,
+    /// The body rendered as sanitized HTML.
+    pub html: String,
     /// The time the post was created in milliseconds since the Unix epoch.
     pub created_at_ms: i64,
     /// If edited, the time the post was edited in milliseconds since the Unix epoch.
@@ -46,21 +73,659 @@ pub struct PostResponse {
     pub archived_at_ms: Option<i64>,
     /// If deleted, the time the post was deleted in milliseconds since the Unix epoch.
     pub deleted_at_ms: Option<i64>,
+    /// The number of replies anywhere below the post. Always 0 for the root post.
+    pub descendant_count: i32,
+    /// Who may reply to the post.
+    pub reply_policy: ReplyPolicy,
+    /// Whether the reply policy also applies to replies anywhere below the post.
+    pub reply_policy_cascades: bool,
+    /// Who may see the post.
+    pub visibility: PostVisibility,
+    /// Whether the requester may not see the post, in which case it is only a placeholder that
+    /// keeps its place in the thread.
+    pub hidden: bool,
+    /// The reactions to the post, one element per kind of reaction received.
+    pub reactions: Vec<ReactionCountResponse>,
+    /// The parts of the body that mention users, in order of appearance.
+    pub mentions: Vec<MentionSpanResponse>,
+    /// The images attached to the post, in the order they were uploaded.
+    pub attachments: Vec<AttachmentResponse>,
+    /// A preview of the post that this post quotes, if any.
+    pub quoted: Option<QuotedPostResponse>,
 }
 
 impl From<PostWithAuthor> for PostResponse {
     fn from(pwa: PostWithAuthor) -> Self {
+        let missing = if pwa.hidden { "[hidden]" } else { "[deleted]" };
+
         Self {
             id: pwa.id,
-            author_username: pwa
-                .author_username
-                .unwrap_or_else(|| String::from("[deleted]")),
+            author_username: pwa.author_username.unwrap_or_else(|| String::from(missing)),
             parent_id: pwa.parent_id,
-            body: pwa.body.unwrap_or_else(|| String::from("[deleted]")),
+            html: pwa.body.as_deref().map_or_else(
+                || render_html(missing, BodyFormat::Plain),
+                |body| render_html(body, pwa.body_format),
+            ),
+            body: pwa.body.unwrap_or_else(|| String::from(missing)),
+            format: pwa.body_format,
             created_at_ms: pwa.created_at.timestamp_millis(),
             edited_at_ms: pwa.edited_at.map(|ms| ms.timestamp_millis()),
             archived_at_ms: pwa.archived_at.map(|ms| ms.timestamp_millis()),
             deleted_at_ms: pwa.deleted_at.map(|ms| ms.timestamp_millis()),
+            descendant_count: pwa.descendant_count,
+            reply_policy: pwa.reply_policy,
+            reply_policy_cascades: pwa.reply_policy_cascades,
+            visibility: pwa.visibility,
+            hidden: pwa.hidden,
+            reactions: pwa.reactions.0.map_into(),
+            mentions: pwa.mentions.0.map_into(),
+            attachments: pwa.attachments.0.map_into(),
+            quoted: pwa.quoted.map(|quoted| quoted.0.into()),
+        }
+    }
+}
+
+/// A response for sending a preview of a quoted post.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotedPostResponse {
+    /// The numeric ID of the quoted post.
+    pub id: i32,
+    /// The username of the author of the quoted post.
+    pub author_username: String,
+    /// The beginning of the content of the quoted post.
+    pub excerpt: String,
+}
+
+impl From<QuotedPost> for QuotedPostResponse {
+    fn from(quoted: QuotedPost) -> Self {
+        let missing = if quoted.hidden { "[hidden]" } else { "[deleted]" };
+
+        Self {
+            id: quoted.id,
+            author_username: quoted
+                .author_username
+                .unwrap_or_else(|| String::from(missing)),
+            excerpt: quoted.excerpt.unwrap_or_else(|| String::from(missing)),
+        }
+    }
+}
+
+/// A response for sending the number of reactions of one kind to a post.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionCountResponse {
+    /// The kind of reaction.
+    pub kind: ReactionKind,
+    /// The number of users who reacted with this kind.
+    pub count: i64,
+    /// Whether the requester is one of those users.
+    pub reacted_by_me: bool,
+}
+
+impl From<ReactionCount> for ReactionCountResponse {
+    fn from(count: ReactionCount) -> Self {
+        Self { kind: count.kind, count: count.count, reacted_by_me: count.reacted_by_me }
+    }
+}
+
+/// A response for sending the part of a post body that mentions a user.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MentionSpanResponse {
+    /// The UTF-8 byte offset of the @ in the body.
+    pub start: i32,
+    /// The UTF-8 byte offset just after the end of the username in the body.
+    pub end: i32,
+    /// The username of the mentioned user.
+    pub username: String,
+}
+
+impl From<MentionSpan> for MentionSpanResponse {
+    fn from(span: MentionSpan) -> Self {
+        Self { start: span.start, end: span.end, username: span.username }
+    }
+}
+
+/// A response for sending an image attached to a post.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentResponse {
+    /// The numeric ID of the attachment.
+    pub id: i32,
+    /// The media type of the image.
+    pub content_type: String,
+    /// The width of the full-size image in pixels.
+    pub width: i32,
+    /// The height of the full-size image in pixels.
+    pub height: i32,
+    /// The path to retrieve the full-size image from.
+    pub url: String,
+    /// The path to retrieve a reduced copy of the image from.
+    pub thumbnail_url: String,
+}
+
+impl AttachmentResponse {
+    fn new(id: i32, content_type: String, width: i32, height: i32) -> Self {
+        Self {
+            id,
+            content_type,
+            width,
+            height,
+            url: format!("/attachments/{id}"),
+            thumbnail_url: format!("/attachments/{id}/thumbnail"),
+        }
+    }
+}
+
+impl From<AttachmentSummary> for AttachmentResponse {
+    fn from(summary: AttachmentSummary) -> Self {
+        Self::new(summary.id, summary.content_type, summary.width, summary.height)
+    }
+}
+
+impl From<Attachment> for AttachmentResponse {
+    fn from(attachment: Attachment) -> Self {
+        Self::new(attachment.id, attachment.content_type, attachment.width, attachment.height)
+    }
+}
+
+/// A response for sending a page of the requester's bookmarks.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkPageResponse {
+    /// The bookmarked posts on this page, most recently saved first. Deleted posts are included as
+    /// tombstones.
+    pub posts: Vec<PostResponse>,
+    /// Pass as `cursor` to retrieve the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl From<BookmarkPage> for BookmarkPageResponse {
+    fn from(page: BookmarkPage) -> Self {
+        Self {
+            posts: page.posts.map_into(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// A response for sending a page of the posts that mention the requester.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MentionPageResponse {
+    /// The posts on this page, most recent first.
+    pub posts: Vec<PostResponse>,
+    /// Pass as `cursor` to retrieve the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl From<MentionPage> for MentionPageResponse {
+    fn from(page: MentionPage) -> Self {
+        Self {
+            posts: page.posts.map_into(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// A response for sending a page of the posts that quote a post.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotePageResponse {
+    /// The posts on this page, most recent first.
+    pub posts: Vec<PostResponse>,
+    /// Pass as `cursor` to retrieve the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl From<QuotePage> for QuotePageResponse {
+    fn from(page: QuotePage) -> Self {
+        Self {
+            posts: page.posts.map_into(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// A response for sending a page of the posts with a particular tag.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPageResponse {
+    /// The posts on this page, most recent first.
+    pub posts: Vec<PostResponse>,
+    /// Pass as `cursor` to retrieve the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl From<TagPage> for TagPageResponse {
+    fn from(page: TagPage) -> Self {
+        Self {
+            posts: page.posts.map_into(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// A response for sending a tag that has been used by many recent posts.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendingTagResponse {
+    /// The normalized (lowercase) tag, without the #.
+    pub name: String,
+    /// The number of recent posts with the tag.
+    pub post_count: i64,
+}
+
+impl From<TrendingTag> for TrendingTagResponse {
+    fn from(tag: TrendingTag) -> Self { Self { name: tag.name, post_count: tag.post_count } }
+}
+
+/// A response for sending a user found by searching.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UserSearchResultResponse {
+    /// The user's unique username.
+    pub username: String,
+    /// The user's display name.
+    pub name: String,
+}
+
+impl From<UserSearchResult> for UserSearchResultResponse {
+    fn from(user: UserSearchResult) -> Self { Self { username: user.username, name: user.name } }
+}
+
+/// A response for sending a page of search results, best match first.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SearchResponse {
+    /// Posts whose content matches the query.
+    #[serde(rename_all = "camelCase")]
+    Posts { results: Vec<PostResponse>, next_offset: Option<i64> },
+    /// Users whose username or name resembles the query.
+    #[serde(rename_all = "camelCase")]
+    Users { results: Vec<UserSearchResultResponse>, next_offset: Option<i64> },
+}
+
+impl From<SearchPage<PostWithAuthor>> for SearchResponse {
+    fn from(page: SearchPage<PostWithAuthor>) -> Self {
+        Self::Posts { results: page.results.map_into(), next_offset: page.next_offset }
+    }
+}
+
+impl From<SearchPage<UserSearchResult>> for SearchResponse {
+    fn from(page: SearchPage<UserSearchResult>) -> Self {
+        Self::Users { results: page.results.map_into(), next_offset: page.next_offset }
+    }
+}
+
+/// A response for sending a summary of the post that another post replied to.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentSummaryResponse {
+    /// The numeric ID of the parent post.
+    pub id: i32,
+    /// The username of the author of the parent post.
+    pub author_username: String,
+    /// The beginning of the content of the parent post.
+    pub excerpt: String,
+}
+
+impl From<ParentSummary> for ParentSummaryResponse {
+    fn from(summary: ParentSummary) -> Self {
+        Self {
+            id: summary.id,
+            author_username: summary
+                .author_username
+                .unwrap_or_else(|| String::from("[deleted]")),
+            excerpt: summary.excerpt.unwrap_or_else(|| String::from("[deleted]")),
+        }
+    }
+}
+
+/// A response for sending a draft reply.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftResponse {
+    /// A summary of the post that the draft replies to.
+    pub parent: ParentSummaryResponse,
+    /// The content of the draft as last saved.
+    pub body: String,
+    /// How the body is written.
+    pub format: BodyFormat,
+    /// The time the draft was first saved in milliseconds since the Unix epoch.
+    pub created_at_ms: i64,
+    /// The time the draft was last saved in milliseconds since the Unix epoch.
+    pub updated_at_ms: i64,
+    /// If the draft was scheduled but could not be published, the reason why.
+    pub publish_error: Option<String>,
+}
+
+impl From<DraftWithParent> for DraftResponse {
+    fn from(dwp: DraftWithParent) -> Self {
+        Self {
+            parent: dwp.parent.into(),
+            body: dwp.draft.body,
+            format: dwp.draft.body_format,
+            created_at_ms: dwp.draft.created_at.timestamp_millis(),
+            updated_at_ms: dwp.draft.updated_at.timestamp_millis(),
+            publish_error: dwp.draft.publish_error,
+        }
+    }
+}
+
+/// A response for sending a post in the home timeline.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelinePostResponse {
+    /// The post itself.
+    pub post: PostResponse,
+    /// A summary of the post that this post is in reply to, if any.
+    pub parent: Option<ParentSummaryResponse>,
+}
+
+impl From<TimelinePost> for TimelinePostResponse {
+    fn from(timeline_post: TimelinePost) -> Self {
+        Self { post: timeline_post.post.into(), parent: timeline_post.parent.map(Into::into) }
+    }
+}
+
+/// A response for sending a page of the home timeline.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelinePageResponse {
+    /// The posts on this page, most recent first.
+    pub posts: Vec<TimelinePostResponse>,
+    /// Pass as `cursor` to retrieve the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl From<TimelinePage> for TimelinePageResponse {
+    fn from(page: TimelinePage) -> Self {
+        Self {
+            posts: page.posts.map_into(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+        }
+    }
+}
+
+/// A response for sending information about a post in the moderation queue.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedPostResponse {
+    /// The numeric ID of the reported post.
+    pub post_id: i32,
+    /// The username of the author of the reported post.
+    pub author_username: String,
+    /// The content of the reported post.
+    pub body: String,
+    /// The number of open reports on the post.
+    pub report_count: i64,
+    /// The distinct reasons given in the open reports.
+    pub reasons: Vec<ReportReason>,
+    /// The time of the earliest open report in milliseconds since the Unix epoch.
+    pub first_reported_at_ms: i64,
+    /// The time of the latest open report in milliseconds since the Unix epoch.
+    pub last_reported_at_ms: i64,
+}
+
+impl From<ReportedPost> for ReportedPostResponse {
+    fn from(rp: ReportedPost) -> Self {
+        Self {
+            post_id: rp.post_id,
+            author_username: rp
+                .author_username
+                .unwrap_or_else(|| String::from("[deleted]")),
+            body: rp.body.unwrap_or_else(|| String::from("[deleted]")),
+            report_count: rp.report_count,
+            reasons: rp.reasons,
+            first_reported_at_ms: rp.first_reported_at.timestamp_millis(),
+            last_reported_at_ms: rp.last_reported_at.timestamp_millis(),
         }
     }
 }
+
+/// A response for sending information about an entry in the moderation audit trail.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationActionResponse {
+    /// The numeric ID of the action.
+    pub id: i32,
+    /// The ID of the post that the action was taken on.
+    pub post_id: i32,
+    /// The username of the moderator who took the action.
+    pub moderator_username: String,
+    /// The action taken.
+    pub action: ModerationActionKind,
+    /// The moderator's explanation of the decision, if provided.
+    pub note: Option<String>,
+    /// The number of reports closed by the action.
+    pub reports_resolved: i64,
+    /// The time the action was taken in milliseconds since the Unix epoch.
+    pub created_at_ms: i64,
+}
+
+impl From<ModerationActionRecord> for ModerationActionResponse {
+    fn from(record: ModerationActionRecord) -> Self {
+        Self {
+            id: record.id,
+            post_id: record.post_id,
+            moderator_username: record
+                .moderator_username
+                .unwrap_or_else(|| String::from("[deleted]")),
+            action: record.action,
+            note: record.note,
+            reports_resolved: record.reports_resolved,
+            created_at_ms: record.created_at.timestamp_millis(),
+        }
+    }
+}
+
+/// A response for sending a notification.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationResponse {
+    /// The numeric ID of the notification.
+    pub id: i32,
+    /// The event that the notification is about.
+    pub kind: NotificationKind,
+    /// The username of the user who caused the notification.
+    pub actor_username: String,
+    /// The ID of the post that the notification is about, if any.
+    pub post_id: Option<i32>,
+    /// The time the notification was created in milliseconds since the Unix epoch.
+    pub created_at_ms: i64,
+    /// If read, the time the notification was read in milliseconds since the Unix epoch.
+    pub read_at_ms: Option<i64>,
+}
+
+impl From<NotificationWithActor> for NotificationResponse {
+    fn from(nwa: NotificationWithActor) -> Self {
+        Self {
+            id: nwa.id,
+            kind: nwa.kind,
+            actor_username: nwa
+                .actor_username
+                .unwrap_or_else(|| String::from("[deleted]")),
+            post_id: nwa.post_id,
+            created_at_ms: nwa.created_at.timestamp_millis(),
+            read_at_ms: nwa.read_at.map(|ms| ms.timestamp_millis()),
+        }
+    }
+}
+
+/// A response for sending the number of unread notifications.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadCountsResponse {
+    /// The total number of unread notifications.
+    pub total: i64,
+    /// The number of unread reply notifications.
+    pub reply: i64,
+    /// The number of unread friend request notifications.
+    pub friend_request: i64,
+    /// The number of unread accepted friend request notifications.
+    pub friend_accepted: i64,
+    /// The number of unread mention notifications.
+    pub mention: i64,
+}
+
+impl From<UnreadCounts> for UnreadCountsResponse {
+    fn from(counts: UnreadCounts) -> Self {
+        Self {
+            total: counts
+                .reply
+                .saturating_add(counts.friend_request)
+                .saturating_add(counts.friend_accepted)
+                .saturating_add(counts.mention),
+            reply: counts.reply,
+            friend_request: counts.friend_request,
+            friend_accepted: counts.friend_accepted,
+            mention: counts.mention,
+        }
+    }
+}
+
+/// A response for sending a user's notification inbox.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationInboxResponse {
+    /// The number of unread notifications.
+    pub unread: UnreadCountsResponse,
+    /// The notifications on this page, most recent first.
+    pub notifications: Vec<NotificationResponse>,
+    /// Pass as `cursor` to retrieve the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// A response for sending information about a webhook.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResponse {
+    /// The numeric ID of the webhook.
+    pub id: i32,
+    /// The URL that events are sent to.
+    pub url: String,
+    /// The kinds of events sent.
+    pub events: Vec<WebhookEventKind>,
+    /// Whether events involving any user are sent rather than only the owner's.
+    pub all_users: bool,
+    /// The time the webhook was registered in milliseconds since the Unix epoch.
+    pub created_at_ms: i64,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events,
+            all_users: webhook.all_users,
+            created_at_ms: webhook.created_at.timestamp_millis(),
+        }
+    }
+}
+
+/// A response for sending a newly registered webhook's signing secret, which is only ever shown
+/// once.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct NewWebhookResponse {
+    /// The numeric ID of the webhook.
+    pub id: i32,
+    /// The secret used to sign the webhook's payloads.
+    pub secret: String,
+}
+
+impl From<NewWebhook> for NewWebhookResponse {
+    fn from(new_webhook: NewWebhook) -> Self {
+        Self { id: new_webhook.id, secret: new_webhook.secret }
+    }
+}
+
+/// A response for sending an entry in a webhook's delivery log.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResponse {
+    /// The numeric ID of the delivery.
+    pub id: i32,
+    /// The numeric ID of the event being delivered, which is the same for every webhook.
+    pub event_id: i32,
+    /// The kind of event being delivered.
+    pub kind: WebhookEventKind,
+    /// Whether the delivery succeeded, failed, or is still being attempted.
+    pub status: WebhookDeliveryStatus,
+    /// The number of attempts made so far.
+    pub attempts: i32,
+    /// The time of the most recent attempt in milliseconds since the Unix epoch, if any.
+    pub last_attempt_at_ms: Option<i64>,
+    /// The time of the next attempt in milliseconds since the Unix epoch, if still pending.
+    pub next_attempt_at_ms: Option<i64>,
+    /// The HTTP status code of the most recent response, if any.
+    pub response_status: Option<i32>,
+    /// The reason no response was received on the most recent attempt, if applicable.
+    pub last_error: Option<String>,
+    /// The time the event occurred in milliseconds since the Unix epoch.
+    pub event_created_at_ms: i64,
+}
+
+impl From<WebhookDeliveryRecord> for WebhookDeliveryResponse {
+    fn from(record: WebhookDeliveryRecord) -> Self {
+        Self {
+            id: record.id,
+            event_id: record.event_id,
+            kind: record.kind,
+            status: record.status,
+            attempts: record.attempts,
+            last_attempt_at_ms: record.last_attempt_at.map(|ms| ms.timestamp_millis()),
+            next_attempt_at_ms: matches!(record.status, WebhookDeliveryStatus::Pending)
+                .then(|| record.next_attempt_at.timestamp_millis()),
+            response_status: record.response_status,
+            last_error: record.last_error,
+            event_created_at_ms: record.event_created_at.timestamp_millis(),
+        }
+    }
+}
+
+/// A message sent by the server over the live thread WebSocket.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveThreadEvent {
+    /// The full set of posts the connection is subscribed to, sent in response to every subscribe
+    /// or unsubscribe message.
+    #[serde(rename_all = "camelCase")]
+    Subscribed { post_ids: Vec<i32> },
+    /// A reply was posted to a subscribed post.
+    Created { post: PostResponse },
+    /// A subscribed post or one of its replies was edited.
+    Edited { post: PostResponse },
+    /// A subscribed post or one of its replies was deleted.
+    #[serde(rename_all = "camelCase")]
+    Deleted { post_id: i32, parent_id: Option<i32> },
+    /// The connection fell behind and missed some changes, so the subscribed posts should be
+    /// fetched again.
+    Lagged { missed: u64 },
+    /// The last message from the client could not be handled.
+    Error { message: String },
+}
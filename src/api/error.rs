@@ -1,11 +1,18 @@
 use {
     crate::{
         api::dto::responses::ErrorResponse,
-        domain::{auth::AuthError, friendship::error::FriendshipError, post::error::PostError},
+        domain::{
+            attachment::error::AttachmentError, auth::AuthError, bookmark::error::BookmarkError,
+            draft::error::DraftError, federation::error::FederationError,
+            friendship::error::FriendshipError, moderation::error::ModerationError,
+            notification::error::NotificationError, post::error::PostError,
+            reaction::error::ReactionError, webhook::error::WebhookError,
+        },
         read_models::ReadError,
     },
     axum::{
         Json,
+        extract::multipart::MultipartError,
         http::StatusCode,
         response::{IntoResponse, Response},
     },
@@ -26,22 +33,76 @@ pub enum ApiError {
     #[error(transparent)]
     Post(#[from] PostError),
 
+    #[error(transparent)]
+    Reaction(#[from] ReactionError),
+
+    #[error(transparent)]
+    Bookmark(#[from] BookmarkError),
+
+    #[error(transparent)]
+    Draft(#[from] DraftError),
+
+    #[error(transparent)]
+    Moderation(#[from] ModerationError),
+
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
+
+    #[error(transparent)]
+    Federation(#[from] FederationError),
+
+    #[error(transparent)]
+    Attachment(#[from] AttachmentError),
+
+    #[error(transparent)]
+    Multipart(#[from] MultipartError),
+
     #[error(transparent)]
     Read(#[from] ReadError),
+
+    #[error("Too many requests. Log in or try again later.")]
+    RateLimited,
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
+            // Multipart errors know their own status
+            Self::Multipart(e) => (e.status(), e.body_text()),
+
+            // 400 Bad Request
+            Self::Federation(FederationError::InvalidActivity(_)) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+
             // 401 Unauthorized
-            Self::Auth(AuthError::TokenValidation | AuthError::InvalidPassword) => {
+            Self::Auth(AuthError::TokenValidation | AuthError::InvalidPassword)
+            | Self::Federation(FederationError::InvalidSignature) => {
                 (StatusCode::UNAUTHORIZED, self.to_string())
             }
 
+            // 403 Forbidden
+            Self::Moderation(ModerationError::NotModerator)
+            | Self::Webhook(WebhookError::NotModerator)
+            | Self::Attachment(AttachmentError::NotAuthor)
+            | Self::Post(
+                PostError::RepliesLocked | PostError::FriendsOnlyReplies | PostError::NotAuthor,
+            ) => (StatusCode::FORBIDDEN, self.to_string()),
+
             // 404 Not Found
             Self::Auth(AuthError::NonexistentAccount)
-            | Self::Post(PostError::NotFound)
+            | Self::Post(PostError::NotFound | PostError::QuotedNotFound | PostError::NoDraft)
+            | Self::Reaction(ReactionError::PostNotFound | ReactionError::NoReaction)
+            | Self::Bookmark(BookmarkError::PostNotFound | BookmarkError::NotBookmarked)
+            | Self::Draft(DraftError::PostNotFound | DraftError::NotFound)
             | Self::Friendship(FriendshipError::NonexistentUser)
+            | Self::Moderation(ModerationError::NotFound | ModerationError::NoOpenReports)
+            | Self::Webhook(WebhookError::NotFound)
+            | Self::Federation(FederationError::UserNotFound)
+            | Self::Attachment(AttachmentError::PostNotFound | AttachmentError::NotFound)
             | Self::Read(ReadError::NotFound) => (StatusCode::NOT_FOUND, self.to_string()),
 
             // 409 Conflict
@@ -49,22 +110,73 @@ impl IntoResponse for ApiError {
             | Self::Friendship(
                 FriendshipError::AlreadyFriends | FriendshipError::AlreadyRequested,
             )
-            | Self::Post(PostError::DuplicateReply) => (StatusCode::CONFLICT, self.to_string()),
+            | Self::Post(PostError::DuplicateReply)
+            | Self::Moderation(ModerationError::AlreadyReported) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
 
             // 410 Gone
-            Self::Post(PostError::DeletedParent) => (StatusCode::GONE, self.to_string()),
+            Self::Post(PostError::DeletedParent | PostError::DeletedQuote)
+            | Self::Reaction(ReactionError::DeletedPost)
+            | Self::Bookmark(BookmarkError::DeletedPost)
+            | Self::Draft(DraftError::DeletedParent)
+            | Self::Moderation(ModerationError::DeletedPost)
+            | Self::Attachment(AttachmentError::DeletedPost) => {
+                (StatusCode::GONE, self.to_string())
+            }
+
+            // 413 Payload Too Large
+            Self::Attachment(AttachmentError::TooLarge(_)) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, self.to_string())
+            }
+
+            // 415 Unsupported Media Type
+            Self::Attachment(AttachmentError::UnsupportedType) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string())
+            }
 
             // 422 Unprocessable Entity
             Self::Request(_)
             | Self::Friendship(FriendshipError::SelfFriendship)
-            | Self::Post(PostError::SelfReply | PostError::ArchivedParent) => {
-                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
-            }
+            | Self::Post(
+                PostError::SelfReply
+                | PostError::ArchivedParent
+                | PostError::TooDeep(_)
+                | PostError::TooManyReplies(_)
+                | PostError::EmptyBody
+                | PostError::TooManyCharacters(_)
+                | PostError::TooManyBytes(_)
+                | PostError::RejectedContent
+                | PostError::PublishTimeNotInFuture,
+            )
+            | Self::Reaction(ReactionError::ArchivedPost)
+            | Self::Draft(DraftError::TooManyBytes(_))
+            | Self::Webhook(WebhookError::DisallowedUrl(_))
+            | Self::Federation(FederationError::UnsupportedActivity(_))
+            | Self::Moderation(ModerationError::SelfReport)
+            | Self::Attachment(
+                AttachmentError::ArchivedPost
+                | AttachmentError::TooManyAttachments(_)
+                | AttachmentError::MissingFile
+                | AttachmentError::TooManyPixels(_)
+                | AttachmentError::InvalidImage,
+            ) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+
+            // 429 Too Many Requests
+            Self::RateLimited => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
 
             // 500 Internal Server Error
             Self::Auth(AuthError::Internal(_))
             | Self::Friendship(FriendshipError::Internal(_))
             | Self::Post(PostError::Internal(_))
+            | Self::Reaction(ReactionError::Internal(_))
+            | Self::Bookmark(BookmarkError::Internal(_))
+            | Self::Draft(DraftError::Internal(_))
+            | Self::Moderation(ModerationError::Internal(_))
+            | Self::Notification(NotificationError::Internal(_))
+            | Self::Webhook(WebhookError::Internal(_))
+            | Self::Federation(FederationError::Internal(_))
+            | Self::Attachment(AttachmentError::Internal(_))
             | Self::Read(ReadError::Technical(_)) => (StatusCode::INTERNAL_SERVER_ERROR, {
                 log::error!("{self}");
                 String::from("internal server error")
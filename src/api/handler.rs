@@ -1,6 +1,23 @@
+pub mod attachment;
 pub mod auth;
+pub mod bookmark;
+pub mod draft;
+pub mod event;
+pub mod federation;
+pub mod feed;
 pub mod friendship;
+pub mod live;
+pub mod mention;
+pub mod moderation;
+pub mod notification;
 pub mod post;
+pub mod search;
+pub mod tag;
+pub mod timeline;
+pub mod webhook;
+
+/// The number of posts returned per page when the client does not specify a limit.
+const DEFAULT_PAGE_SIZE: i64 = 20;
 
 /// Expands to a handler function return type.
 ///
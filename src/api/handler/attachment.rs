@@ -0,0 +1,208 @@
+use {
+    crate::{
+        api::{dto::responses::ErrorResponse, error::ApiError},
+        domain::attachment::AttachmentSvc,
+        models::attachment::AttachmentVariant,
+        state::AppState,
+    },
+    axum::{
+        Extension, Router,
+        extract::{Path, State},
+        http::{
+            StatusCode,
+            header::{CACHE_CONTROL, CONTENT_TYPE},
+        },
+        response::IntoResponse,
+        routing::get,
+    },
+    std::sync::Arc,
+};
+
+/// Stored images never change, so clients can keep them for as long as they like.
+const CACHE_FOREVER: &str = "private, max-age=31536000, immutable";
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(original, thumbnail))]
+pub struct AttachmentsDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/{attachment_id}", get(original))
+        .route("/{attachment_id}/thumbnail", get(thumbnail))
+}
+
+/// Retrieves the full-size image of an attachment.
+#[utoipa::path(
+    get,
+    tag = "attachments",
+    path = "/{attachment_id}",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            content((Vec<u8> = "image/jpeg"), (Vec<u8> = "image/png")),
+            description = "the image",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "attachment not found, or its post was deleted or is hidden from you",
+        ),
+    ),
+)]
+async fn original(
+    attachment_svc: State<Arc<dyn AttachmentSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(attachment_id): Path<i32>,
+) -> Result<impl IntoResponse, ApiError> {
+    serve(&attachment_svc, requester_id, attachment_id, AttachmentVariant::Original).await
+}
+
+/// Retrieves a reduced copy of an attachment's image, no more than 320 pixels on either side.
+#[utoipa::path(
+    get,
+    tag = "attachments",
+    path = "/{attachment_id}/thumbnail",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            content((Vec<u8> = "image/jpeg"), (Vec<u8> = "image/png")),
+            description = "the thumbnail",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "attachment not found, or its post was deleted or is hidden from you",
+        ),
+    ),
+)]
+async fn thumbnail(
+    attachment_svc: State<Arc<dyn AttachmentSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(attachment_id): Path<i32>,
+) -> Result<impl IntoResponse, ApiError> {
+    serve(&attachment_svc, requester_id, attachment_id, AttachmentVariant::Thumbnail).await
+}
+
+async fn serve(
+    attachment_svc: &Arc<dyn AttachmentSvc>,
+    requester_id: i32,
+    attachment_id: i32,
+    variant: AttachmentVariant,
+) -> Result<impl IntoResponse + use<>, ApiError> {
+    let file = attachment_svc
+        .open(requester_id, attachment_id, variant)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        [(CONTENT_TYPE, file.content_type), (CACHE_CONTROL, String::from(CACHE_FOREVER))],
+        file.bytes,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::attachment::{MockAttachmentSvc, error::AttachmentError},
+            models::attachment::AttachmentFile,
+            test_utils::{http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::Result,
+        axum::{
+            body::{Body, to_bytes},
+            http::{HeaderName, Method, Request},
+        },
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    /// Sends a request for the provided URI as user 255.
+    async fn send_req(state: AppState, uri: &str) -> Result<axum::response::Response> {
+        let app = routes().with_state(state);
+
+        let mut req = Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .body(Body::empty())?;
+        req.extensions_mut().insert(255);
+
+        app.oneshot(req).await.map_err(Into::into)
+    }
+
+    fn header_value(resp: &axum::response::Response, name: HeaderName) -> Option<&str> {
+        resp.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    #[test]
+    fn serves_images_with_their_content_type() -> Result<()> {
+        tokio_test(async {
+            let mut mock_attachment_svc = MockAttachmentSvc::new();
+            mock_attachment_svc
+                .expect_open()
+                .with(eq(255), eq(31), eq(AttachmentVariant::Original))
+                .once()
+                .return_once(|_, _, _| {
+                    Ok(AttachmentFile {
+                        content_type: String::from("image/jpeg"),
+                        bytes: vec![0xFF, 0xD8, 0xFF],
+                    })
+                });
+            mock_attachment_svc
+                .expect_open()
+                .with(eq(255), eq(31), eq(AttachmentVariant::Thumbnail))
+                .once()
+                .return_once(|_, _, _| {
+                    Ok(AttachmentFile {
+                        content_type: String::from("image/png"),
+                        bytes: vec![0x89, b'P'],
+                    })
+                });
+
+            let state =
+                AppState { attachment_svc: Arc::new(mock_attachment_svc), ..Default::default() };
+
+            for (uri, content_type, bytes) in [
+                ("/31", "image/jpeg", vec![0xFF, 0xD8, 0xFF]),
+                ("/31/thumbnail", "image/png", vec![0x89, b'P']),
+            ] {
+                let resp = send_req(state.clone(), uri).await?;
+
+                assert_eq!(StatusCode::OK, resp.status());
+                assert_eq!(Some(content_type), header_value(&resp, CONTENT_TYPE));
+                assert_eq!(Some(CACHE_FOREVER), header_value(&resp, CACHE_CONTROL));
+                assert_eq!(bytes, to_bytes(resp.into_body(), usize::MAX).await?);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn translates_errors() -> Result<()> {
+        tokio_test(async {
+            let mut mock_attachment_svc = MockAttachmentSvc::new();
+            mock_attachment_svc
+                .expect_open()
+                .with(eq(255), eq(32), eq(AttachmentVariant::Thumbnail))
+                .once()
+                .return_once(|_, _, _| Err(AttachmentError::NotFound));
+
+            let state =
+                AppState { attachment_svc: Arc::new(mock_attachment_svc), ..Default::default() };
+
+            let resp = send_req(state, "/32/thumbnail").await?;
+            assert_eq!(StatusCode::NOT_FOUND, resp.status());
+
+            let expected = ErrorResponse { error: String::from("No attachment found") };
+            assert_eq!(expected, deserialize_body::<ErrorResponse>(resp).await?);
+
+            Ok(())
+        })
+    }
+}
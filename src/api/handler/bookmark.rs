@@ -0,0 +1,128 @@
+use {
+    super::{DEFAULT_PAGE_SIZE, api_result},
+    crate::{
+        api::{
+            dto::{requests::PageQuery, responses::BookmarkPageResponse},
+            validated_query::ValidatedQuery,
+        },
+        read_models::BookmarkRead,
+        state::AppState,
+    },
+    axum::{Extension, Json, Router, extract::State, http::StatusCode, routing::get},
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(saved_posts))]
+pub struct BookmarksDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/", get(saved_posts)) }
+
+/// Retrieves a page of the posts the requester has bookmarked, most recently saved first.
+#[utoipa::path(
+    get,
+    tag = "bookmarks",
+    path = "",
+    security(("jwt" = [])),
+    params(PageQuery),
+    responses((
+        status = StatusCode::OK,
+        body = BookmarkPageResponse,
+        description = "a page of your bookmarks",
+    )),
+)]
+async fn saved_posts(
+    bookmark_read: State<Arc<dyn BookmarkRead>>,
+    Extension(requester_id): Extension<i32>,
+    query: ValidatedQuery<PageQuery>,
+) -> api_result!(BookmarkPageResponse) {
+    let PageQuery { cursor, limit } = query.0;
+
+    let page = bookmark_read
+        .saved_by(requester_id, cursor, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            map_into::MapInto as _,
+            models::{bookmark::BookmarkPage, post::PostCursor},
+            read_models::MockBookmarkRead,
+            test_utils::{dummy_data::post_with_author, http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::{Context as _, Result},
+        axum::{body::Body, http::Request},
+        chrono::DateTime,
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    /// Sends a request for bookmarks as the requester.
+    async fn send_req(
+        mock_bookmark_read: MockBookmarkRead,
+        requester_id: i32,
+        uri: &str,
+    ) -> Result<axum::response::Response> {
+        let state = AppState { bookmark_read: Arc::new(mock_bookmark_read), ..Default::default() };
+        let app = routes().with_state(state);
+
+        let mut req = Request::builder().uri(uri).body(Body::empty())?;
+        req.extensions_mut().insert(requester_id);
+
+        Ok(app.oneshot(req).await?)
+    }
+
+    #[test]
+    fn retrieves_a_page_with_tombstones_and_the_next_cursor() -> Result<()> {
+        tokio_test(async {
+            // Deleted posts have no body
+            let [first, second, mut deleted] = post_with_author::all3()?;
+            deleted.body = None;
+            let posts = [first, second, deleted];
+            let posts_vec = posts.to_vec();
+            let saved_at = DateTime::from_timestamp_micros(1_760_000_000_654_321)
+                .context("invalid timestamp")?;
+
+            let mut mock_bookmark_read = MockBookmarkRead::new();
+            mock_bookmark_read
+                .expect_saved_by()
+                .with(eq(9), eq(None), eq(DEFAULT_PAGE_SIZE))
+                .once()
+                .return_once(move |_, _, _| {
+                    Ok(BookmarkPage {
+                        posts: posts_vec,
+                        next_cursor: Some(PostCursor { created_at: saved_at, id: 1324 }),
+                    })
+                });
+
+            let resp = send_req(mock_bookmark_read, 9, "/").await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let resp_body = deserialize_body::<BookmarkPageResponse>(resp).await?;
+            let expected = BookmarkPageResponse {
+                posts: posts.map_into(),
+                next_cursor: Some(String::from("1760000000654321_1324")),
+            };
+            assert_eq!(Some("[deleted]"), resp_body.posts.last().map(|post| post.body.as_str()));
+            assert_eq!(expected, resp_body);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_invalid_limits() -> Result<()> {
+        tokio_test(async {
+            // The read model should not be called
+            let resp = send_req(MockBookmarkRead::new(), 9, "/?limit=0").await?;
+            assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, resp.status());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use {
+    super::api_result,
+    crate::{
+        api::dto::responses::DraftResponse, map_into::MapInto as _, read_models::DraftRead,
+        state::AppState,
+    },
+    axum::{Extension, Json, Router, extract::State, http::StatusCode, routing::get},
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(own_drafts))]
+pub struct DraftsDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/", get(own_drafts)) }
+
+/// Retrieves all of the requester's draft replies, most recently saved first.
+#[utoipa::path(
+    get,
+    tag = "drafts",
+    path = "",
+    security(("jwt" = [])),
+    responses((
+        status = StatusCode::OK,
+        body = Vec<DraftResponse>,
+        description = "a list of your drafts",
+    )),
+)]
+async fn own_drafts(
+    draft_read: State<Arc<dyn DraftRead>>,
+    Extension(requester_id): Extension<i32>,
+) -> api_result!(Vec<DraftResponse>) {
+    Ok((StatusCode::OK, Json(draft_read.drafts_by(requester_id).await?.map_into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            read_models::MockDraftRead,
+            test_utils::{dummy_data, http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::Result,
+        axum::{body::Body, http::Request},
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    #[test]
+    fn lists_the_requesters_drafts() -> Result<()> {
+        tokio_test(async {
+            let draft = dummy_data::draft::number1()?;
+            let expected = vec![DraftResponse::from(draft.clone())];
+
+            let mut mock_draft_read = MockDraftRead::new();
+            mock_draft_read
+                .expect_drafts_by()
+                .with(eq(255))
+                .once()
+                .return_once(|_| Ok(vec![draft]));
+
+            let state = AppState { draft_read: Arc::new(mock_draft_read), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder().uri("/").body(Body::empty())?;
+            req.extensions_mut().insert(255);
+
+            let resp = app.oneshot(req).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+            assert_eq!(expected, deserialize_body::<Vec<DraftResponse>>(resp).await?);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,451 @@
+use {
+    crate::{
+        api::{dto::responses::NotificationResponse, error::ApiError},
+        app_services::background::ShutdownSignal,
+        models::notification::{NewNotification, NotificationWithActor},
+        read_models::{NotificationFeed, NotificationRead, ReadError},
+        state::AppState,
+    },
+    anyhow::Context as _,
+    axum::{
+        Extension, Router,
+        extract::State,
+        http::HeaderMap,
+        response::sse::{Event, KeepAlive, Sse},
+        routing::get,
+    },
+    chrono::{DateTime, TimeDelta, Utc},
+    futures_util::{Stream, stream},
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::Arc,
+        time::Duration,
+    },
+    tokio::sync::broadcast::{Receiver, error::RecvError},
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(notification_stream))]
+pub struct EventsDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/", get(notification_stream)) }
+
+/// How often a comment is sent to keep idle connections from being closed by proxies.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long before the newest notification sent the stream keeps looking for older ones. Creation
+/// times and IDs are assigned when a transaction starts, but rows only become visible when it
+/// commits, so a notification can show up after newer ones as long as its transaction commits
+/// within this window.
+const COMMIT_WINDOW: TimeDelta = TimeDelta::minutes(1);
+
+/// Streams the requester's new notifications as Server-Sent Events.
+///
+/// Each event has the type `notification`, the notification's ID as its event ID, and the
+/// notification as its JSON data. Reconnecting with the `Last-Event-ID` header set first replays
+/// any notifications created since that event. Notifications may arrive out of ID order, and
+/// ones created shortly before the resumed event may be replayed, so events with an already
+/// received ID should be ignored.
+#[utoipa::path(
+    get,
+    tag = "events",
+    path = "",
+    security(("jwt" = [])),
+    params((
+        "Last-Event-ID" = Option<i32>,
+        Header,
+        description = "the ID of the last event received, to resume after a disconnect",
+    )),
+    responses((
+        status = StatusCode::OK,
+        content_type = "text/event-stream",
+        body = NotificationResponse,
+        description = "a stream of notification events",
+    )),
+)]
+async fn notification_stream(
+    notification_read: State<Arc<dyn NotificationRead>>,
+    notification_feed: State<Arc<dyn NotificationFeed>>,
+    State(shutdown): State<ShutdownSignal>,
+    Extension(requester_id): Extension<i32>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, anyhow::Error>>>, ApiError> {
+    // Subscribe before replaying so that nothing created in between is missed
+    let signals = notification_feed.subscribe();
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i32>().ok());
+
+    let resumed_at = match last_event_id {
+        Some(id) => notification_read.created_at(requester_id, id).await?,
+        None => None,
+    };
+
+    let mut stream_state = StreamState {
+        notification_read: notification_read.0,
+        signals,
+        shutdown,
+        recipient_id: requester_id,
+        newest: resumed_at.unwrap_or_else(Utc::now),
+        seen: last_event_id.zip(resumed_at).into_iter().collect(),
+        pending: VecDeque::new(),
+    };
+
+    // Without a resume point, the client is expected to have fetched everything created before it
+    // connected, so that is only marked as seen
+    let backlog = stream_state.fetch_unseen().await?;
+    if last_event_id.is_some() {
+        stream_state.pending = backlog.into();
+    }
+
+    Ok(Sse::new(stream::try_unfold(stream_state, next_event))
+        .keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL)))
+}
+
+struct StreamState {
+    notification_read: Arc<dyn NotificationRead>,
+    signals: Receiver<NewNotification>,
+    /// Ends the stream, since the server waits for every connection to close before shutting down
+    shutdown: ShutdownSignal,
+    recipient_id: i32,
+    /// The creation time of the newest notification seen, which `COMMIT_WINDOW` is counted back
+    /// from
+    newest: DateTime<Utc>,
+    /// The creation times of the notifications within the window that were already sent or known
+    /// to the client
+    seen: HashMap<i32, DateTime<Utc>>,
+    pending: VecDeque<NotificationWithActor>,
+}
+
+impl StreamState {
+    /// Fetches the notifications created within `COMMIT_WINDOW` of the newest one seen that have
+    /// not been seen yet, and records them as seen.
+    async fn fetch_unseen(&mut self) -> Result<Vec<NotificationWithActor>, ReadError> {
+        let notifications = self
+            .notification_read
+            .for_recipient_since(self.recipient_id, window_start(self.newest)?)
+            .await?;
+
+        let unseen: Vec<_> = notifications
+            .into_iter()
+            .filter(|notification| {
+                self.seen
+                    .insert(notification.id, notification.created_at)
+                    .is_none()
+            })
+            .collect();
+
+        if let Some(newest) = unseen
+            .iter()
+            .map(|notification| notification.created_at)
+            .max()
+        {
+            self.newest = self.newest.max(newest);
+        }
+
+        // Anything older than the window is never fetched again
+        let start = window_start(self.newest)?;
+        self.seen.retain(|_, created_at| *created_at >= start);
+
+        Ok(unseen)
+    }
+}
+
+fn window_start(newest: DateTime<Utc>) -> anyhow::Result<DateTime<Utc>> {
+    newest
+        .checked_sub_signed(COMMIT_WINDOW)
+        .context("notification time out of range")
+}
+
+/// Waits for the next notification for the recipient and converts it into an event. Signals only
+/// carry IDs, so the notifications themselves are fetched, which also catches up on any that were
+/// missed. The stream ends when the feed closes or shutdown is requested.
+async fn next_event(mut state: StreamState) -> anyhow::Result<Option<(Event, StreamState)>> {
+    loop {
+        if let Some(notification) = state.pending.pop_front() {
+            let event = Event::default()
+                .id(notification.id.to_string())
+                .event("notification")
+                .json_data(NotificationResponse::from(notification))?;
+
+            return Ok(Some((event, state)));
+        }
+
+        let received = tokio::select! {
+            received = state.signals.recv() => received,
+            () = state.shutdown.requested() => return Ok(None),
+        };
+
+        match received {
+            Ok(signal) if signal.recipient_id != state.recipient_id => continue,
+            // Some signals were dropped, which the fetch catches up on all the same
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return Ok(None),
+        }
+
+        state.pending = state.fetch_unseen().await?.into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::notification::NotificationKind,
+            read_models::{MockNotificationFeed, MockNotificationRead},
+            test_utils::tokio_test,
+        },
+        anyhow::Result,
+        axum::{
+            body::{Body, BodyDataStream},
+            http::{Method, Request, StatusCode},
+        },
+        futures_util::StreamExt as _,
+        mockall::predicate::{always, eq},
+        tokio::{sync::broadcast, time::timeout},
+        tower::ServiceExt as _,
+    };
+
+    /// Creates a notification created `minutes_ago` minutes ago.
+    fn notification(id: i32, minutes_ago: i64) -> NotificationWithActor {
+        NotificationWithActor {
+            id,
+            kind: NotificationKind::Reply,
+            post_id: Some(id.saturating_mul(10)),
+            created_at: Utc::now()
+                .checked_sub_signed(TimeDelta::minutes(minutes_ago))
+                .unwrap_or_default(),
+            read_at: None,
+            actor_username: Some(String::from("someone")),
+        }
+    }
+
+    /// Creates a read model whose fetches since some time return `fetches` in order.
+    fn fetching(
+        requester_id: i32,
+        fetches: Vec<Vec<NotificationWithActor>>,
+    ) -> MockNotificationRead {
+        let mut mock_notification_read = MockNotificationRead::new();
+        let times = fetches.len();
+        let mut remaining = fetches.into_iter();
+        mock_notification_read
+            .expect_for_recipient_since()
+            .with(eq(requester_id), always())
+            .times(times)
+            .returning(move |_, _| Ok(remaining.next().unwrap_or_default()));
+        mock_notification_read
+    }
+
+    /// Opens the event stream as the requester, optionally resuming from `last_event_id`.
+    async fn open_stream(
+        mock_notification_read: MockNotificationRead,
+        signals: Receiver<NewNotification>,
+        shutdown: ShutdownSignal,
+        requester_id: i32,
+        last_event_id: Option<&str>,
+    ) -> Result<BodyDataStream> {
+        let mut mock_notification_feed = MockNotificationFeed::new();
+        mock_notification_feed
+            .expect_subscribe()
+            .once()
+            .return_once(move || signals);
+
+        let state = AppState {
+            notification_read: Arc::new(mock_notification_read),
+            notification_feed: Arc::new(mock_notification_feed),
+            shutdown,
+            ..Default::default()
+        };
+        let app = routes().with_state(state);
+
+        let mut req_builder = Request::builder().method(Method::GET).uri("/");
+        if let Some(id) = last_event_id {
+            req_builder = req_builder.header("Last-Event-ID", id);
+        }
+        let mut req = req_builder.body(Body::empty())?;
+
+        req.extensions_mut().insert(requester_id);
+
+        let resp = app.oneshot(req).await?;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        Ok(resp.into_body().into_data_stream())
+    }
+
+    /// Reads the next chunk of the event stream as text.
+    async fn next_chunk(body: &mut BodyDataStream) -> Result<String> {
+        let bytes = timeout(Duration::from_secs(5), body.next())
+            .await
+            .context("timed out waiting for an event")?
+            .context("event stream ended")??;
+
+        String::from_utf8(bytes.to_vec()).map_err(Into::into)
+    }
+
+    #[test]
+    fn replays_missed_notifications_then_streams_new_ones() -> Result<()> {
+        tokio_test(async {
+            let requester_id = 7;
+            let (sender, receiver) = broadcast::channel(8);
+            let resumed = notification(3, 5);
+            let resumed_at = resumed.created_at;
+
+            let mut mock_notification_read = fetching(
+                requester_id,
+                vec![
+                    vec![resumed.clone(), notification(4, 4)],
+                    vec![notification(4, 4), notification(6, 0)],
+                ],
+            );
+            mock_notification_read
+                .expect_created_at()
+                .with(eq(requester_id), eq(3))
+                .once()
+                .return_once(move |_, _| Ok(Some(resumed_at)));
+
+            let mut body = open_stream(
+                mock_notification_read,
+                receiver,
+                ShutdownSignal::default(),
+                requester_id,
+                Some("3"),
+            )
+            .await?;
+
+            let replayed = next_chunk(&mut body).await?;
+            assert!(replayed.contains("event: notification\n"));
+            assert!(replayed.contains("id: 4\n"));
+            assert!(replayed.contains(r#""postId":40"#));
+
+            // Another user's notification is ignored without querying
+            sender.send(NewNotification { id: 5, recipient_id: requester_id + 1 })?;
+            sender.send(NewNotification { id: 6, recipient_id: requester_id })?;
+
+            let streamed = next_chunk(&mut body).await?;
+            assert!(streamed.contains("id: 6\n"));
+            assert!(streamed.contains(r#""postId":60"#));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn starts_from_the_connection_without_a_resume_point() -> Result<()> {
+        tokio_test(async {
+            let requester_id = 8;
+            let (sender, receiver) = broadcast::channel(8);
+
+            // The notification created before connecting is left to the inbox
+            let mock_notification_read = fetching(
+                requester_id,
+                vec![vec![notification(11, 0)], vec![notification(11, 0), notification(12, 0)]],
+            );
+
+            let mut body = open_stream(
+                mock_notification_read,
+                receiver,
+                ShutdownSignal::default(),
+                requester_id,
+                None,
+            )
+            .await?;
+
+            sender.send(NewNotification { id: 12, recipient_id: requester_id })?;
+
+            let streamed = next_chunk(&mut body).await?;
+            assert!(!streamed.contains("id: 11\n"));
+            assert!(streamed.contains("id: 12\n"));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn delivers_notifications_that_commit_out_of_order() -> Result<()> {
+        tokio_test(async {
+            let requester_id = 9;
+            let (sender, receiver) = broadcast::channel(8);
+
+            // Notification 5 was created first but only becomes visible after notification 6
+            let mock_notification_read = fetching(
+                requester_id,
+                vec![
+                    vec![],
+                    vec![notification(6, 0)],
+                    vec![notification(5, 0), notification(6, 0)],
+                ],
+            );
+
+            let mut body = open_stream(
+                mock_notification_read,
+                receiver,
+                ShutdownSignal::default(),
+                requester_id,
+                None,
+            )
+            .await?;
+
+            sender.send(NewNotification { id: 6, recipient_id: requester_id })?;
+            let first = next_chunk(&mut body).await?;
+            assert!(first.contains("id: 6\n"));
+
+            sender.send(NewNotification { id: 5, recipient_id: requester_id })?;
+            let second = next_chunk(&mut body).await?;
+            assert!(second.contains("id: 5\n"));
+            assert!(!second.contains("id: 6\n"));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn ends_the_stream_when_the_feed_closes() -> Result<()> {
+        tokio_test(async {
+            let (sender, receiver) = broadcast::channel(8);
+            drop(sender);
+
+            let mut body = open_stream(
+                fetching(9, vec![vec![]]),
+                receiver,
+                ShutdownSignal::default(),
+                9,
+                None,
+            )
+            .await?;
+
+            assert!(
+                timeout(Duration::from_secs(5), body.next())
+                    .await
+                    .context("timed out waiting for the stream to end")?
+                    .is_none()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn ends_the_stream_when_shutdown_is_requested() -> Result<()> {
+        tokio_test(async {
+            let (_sender, receiver) = broadcast::channel(8);
+            let (shutdown_tx, shutdown) = ShutdownSignal::channel();
+
+            let mut body =
+                open_stream(fetching(10, vec![vec![]]), receiver, shutdown, 10, None).await?;
+
+            shutdown_tx.send_replace(true);
+
+            assert!(
+                timeout(Duration::from_secs(5), body.next())
+                    .await
+                    .context("timed out waiting for the stream to end")?
+                    .is_none()
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,569 @@
+use {
+    crate::{
+        api::{
+            dto::{
+                activitypub::{ACTIVITY_JSON, JRD_JSON, Outbox, Person, WebFingerResponse},
+                requests::WebFingerQuery,
+                responses::ErrorResponse,
+            },
+            error::ApiError,
+        },
+        app_services::Federation,
+        config::PublicUrl,
+        domain::federation::error::FederationError,
+        models::federation::InboxRequest,
+        read_models::PostWithAuthorRead,
+        state::AppState,
+    },
+    axum::{
+        Json, Router,
+        body::Bytes,
+        extract::{OriginalUri, Path, Query, State},
+        http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+        response::{IntoResponse as _, Response},
+        routing::{get, post},
+    },
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(webfinger))]
+pub struct WebFingerDoc;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(actor, outbox, inbox))]
+pub struct ActorsDoc;
+
+/// Routes to be nested under `/.well-known`.
+pub fn well_known_routes() -> Router<AppState> { Router::new().route("/webfinger", get(webfinger)) }
+
+/// Routes to be nested under `/users`.
+pub fn actor_routes() -> Router<AppState> {
+    Router::new()
+        .route("/{username}", get(actor))
+        .route("/{username}/outbox", get(outbox))
+        .route("/{username}/inbox", post(inbox))
+}
+
+/// Responds with a JSON document of the specified media type.
+fn json_as(content_type: &'static str, document: impl serde::Serialize) -> Response {
+    ([(CONTENT_TYPE, content_type)], Json(document)).into_response()
+}
+
+/// Looks up the ActivityPub actor of an account on this server, so that users elsewhere in the
+/// fediverse can find it by its address.
+#[utoipa::path(
+    get,
+    tag = "federation",
+    path = "/webfinger",
+    params(WebFingerQuery),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = WebFingerResponse,
+            content_type = "application/jrd+json",
+            description = "the account's links",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no such account on this server",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests from the same address",
+        ),
+    ),
+)]
+async fn webfinger(
+    federation: State<Arc<dyn Federation>>,
+    State(public_url): State<PublicUrl>,
+    Query(WebFingerQuery { resource }): Query<WebFingerQuery>,
+) -> Result<Response, ApiError> {
+    let username = resource
+        .strip_prefix("acct:")
+        .and_then(|account| account.rsplit_once('@'))
+        .and_then(|(username, host)| (host == public_url.host()).then_some(username))
+        .ok_or(FederationError::UserNotFound)?;
+
+    let actor = federation.actor(username).await?;
+
+    Ok(json_as(JRD_JSON, WebFingerResponse::new(&public_url, &actor.username)))
+}
+
+/// Retrieves the ActivityPub actor of a user.
+#[utoipa::path(
+    get,
+    tag = "federation",
+    path = "/{username}",
+    params(("username" = String, Path, description = "the user's username")),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Person,
+            content_type = "application/activity+json",
+            description = "the user's actor",
+        ),
+        (status = StatusCode::NOT_FOUND, body = ErrorResponse, description = "no such user"),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests from the same address",
+        ),
+    ),
+)]
+async fn actor(
+    federation: State<Arc<dyn Federation>>,
+    State(public_url): State<PublicUrl>,
+    Path(username): Path<String>,
+) -> Result<Response, ApiError> {
+    let actor = federation.actor(&username).await?;
+
+    Ok(json_as(ACTIVITY_JSON, Person::new(&public_url, actor)))
+}
+
+/// Retrieves a user's most recent public posts as ActivityPub activities.
+#[utoipa::path(
+    get,
+    tag = "federation",
+    path = "/{username}/outbox",
+    params(("username" = String, Path, description = "the user's username")),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Outbox,
+            content_type = "application/activity+json",
+            description = "the user's outbox",
+        ),
+        (status = StatusCode::NOT_FOUND, body = ErrorResponse, description = "no such user"),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests from the same address",
+        ),
+    ),
+)]
+async fn outbox(
+    federation: State<Arc<dyn Federation>>,
+    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    State(public_url): State<PublicUrl>,
+    Path(username): Path<String>,
+) -> Result<Response, ApiError> {
+    let actor = federation.actor(&username).await?;
+
+    // Only posts that anyone may see are federated
+    let posts = post_with_author_read
+        .written_by_username(&actor.username, None)
+        .await?;
+
+    Ok(json_as(ACTIVITY_JSON, Outbox::new(&public_url, &actor.username, posts)))
+}
+
+/// Delivers an ActivityPub activity to a user. Requests must be signed with HTTP signatures by
+/// the activity's actor. Only `Follow` activities are currently supported.
+#[utoipa::path(
+    post,
+    tag = "federation",
+    path = "/{username}/inbox",
+    params(
+        ("username" = String, Path, description = "the user's username"),
+        ("Signature" = String, Header, description = "the request's HTTP signature"),
+    ),
+    request_body(content = Object, content_type = "application/activity+json"),
+    responses(
+        (status = StatusCode::ACCEPTED, description = "activity accepted"),
+        (status = StatusCode::BAD_REQUEST, body = ErrorResponse, description = "malformed activity"),
+        (
+            status = StatusCode::UNAUTHORIZED,
+            body = ErrorResponse,
+            description = "missing or invalid signature",
+        ),
+        (status = StatusCode::NOT_FOUND, body = ErrorResponse, description = "no such user"),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "unsupported activity type",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests from the same address",
+        ),
+    ),
+)]
+async fn inbox(
+    federation: State<Arc<dyn Federation>>,
+    Path(username): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, ApiError> {
+    let request = InboxRequest {
+        path: uri
+            .path_and_query()
+            .map_or_else(|| uri.path().to_owned(), ToString::to_string),
+        headers: headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect(),
+        body: body.to_vec(),
+    };
+
+    federation.receive(&username, request).await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            app_services::{
+                MockFederation, deliver_activity::DeliverActivityHandler,
+                federation_svc::FederationSvc, job_worker::JobWorker,
+            },
+            domain::{
+                federation::signature::{Signature, SignedRequest, signed_post_headers},
+                outbound::OutboundPolicy,
+            },
+            infra::{
+                federation_client::ReqwestFederationClient, federation_repo::PgFederationRepo,
+                job_repo::PgJobRepo, user_repo::PgUserRepo,
+            },
+            models::federation::LocalActor,
+            read_models::MockPostWithAuthorRead,
+            test_utils::{
+                dummy_data::{actor_key, post_with_author},
+                http_bodies::deserialize_body,
+                seed_data::seed_users,
+                tokio_test,
+            },
+        },
+        anyhow::{Context as _, Result},
+        axum::{body::Body, http::Request},
+        chrono::Utc,
+        mockall::predicate::eq,
+        serde_json::{Value, json},
+        sqlx::PgPool,
+        std::{net::SocketAddr, time::Duration},
+        tokio::{net::TcpListener, sync::mpsc, time::timeout},
+        tower::ServiceExt as _,
+    };
+
+    fn app(mock_federation: MockFederation, mock_pwa_read: MockPostWithAuthorRead) -> Router {
+        Router::new()
+            .nest("/.well-known", well_known_routes())
+            .nest("/users", actor_routes())
+            .with_state(AppState {
+                federation: Arc::new(mock_federation),
+                post_with_author_read: Arc::new(mock_pwa_read),
+                ..Default::default()
+            })
+    }
+
+    fn local_actor() -> LocalActor {
+        LocalActor {
+            user_id: 1,
+            username: String::from("drake_conan"),
+            name: String::from("Drake"),
+            public_key_pem: actor_key::number1().public_key_pem,
+        }
+    }
+
+    fn federation_with_actor() -> MockFederation {
+        let mut mock_federation = MockFederation::new();
+        mock_federation
+            .expect_actor()
+            .with(eq("drake_conan"))
+            .returning(|_| Ok(local_actor()));
+        mock_federation
+            .expect_actor()
+            .returning(|_| Err(FederationError::UserNotFound));
+        mock_federation
+    }
+
+    async fn get_uri(app: Router, uri: &str) -> Result<Response> {
+        app.oneshot(Request::get(uri).body(Body::empty())?)
+            .await
+            .map_err(Into::into)
+    }
+
+    fn content_type(resp: &Response) -> Option<&str> {
+        resp.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    #[test]
+    fn finds_local_accounts_with_webfinger() -> Result<()> {
+        tokio_test(async {
+            let app = app(federation_with_actor(), MockPostWithAuthorRead::new());
+
+            let resp = get_uri(
+                app.clone(),
+                "/.well-known/webfinger?resource=acct:drake_conan@spur.example",
+            )
+            .await?;
+            assert_eq!(StatusCode::OK, resp.status());
+            assert_eq!(Some(JRD_JSON), content_type(&resp));
+            let body: Value = deserialize_body(resp).await?;
+            assert_eq!(
+                Some("https://spur.example/users/drake_conan"),
+                body.pointer("/links/0/href").and_then(Value::as_str)
+            );
+
+            for resource in [
+                "acct:drake_conan@elsewhere.example",
+                "acct:nobody@spur.example",
+                "drake_conan@spur.example",
+            ] {
+                let missing =
+                    get_uri(app.clone(), &format!("/.well-known/webfinger?resource={resource}"))
+                        .await?;
+                assert_eq!(StatusCode::NOT_FOUND, missing.status(), "{resource}");
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn serves_actors_as_activity_json() -> Result<()> {
+        tokio_test(async {
+            let app = app(federation_with_actor(), MockPostWithAuthorRead::new());
+
+            let resp = get_uri(app.clone(), "/users/drake_conan").await?;
+            assert_eq!(StatusCode::OK, resp.status());
+            assert_eq!(Some(ACTIVITY_JSON), content_type(&resp));
+            let body: Value = deserialize_body(resp).await?;
+            assert_eq!(
+                Some("https://spur.example/users/drake_conan#main-key"),
+                body.pointer("/publicKey/id").and_then(Value::as_str)
+            );
+
+            let missing = get_uri(app, "/users/nobody").await?;
+            assert_eq!(StatusCode::NOT_FOUND, missing.status());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn lists_only_posts_anyone_may_see_in_outboxes() -> Result<()> {
+        tokio_test(async {
+            let mut mock_pwa_read = MockPostWithAuthorRead::new();
+            mock_pwa_read
+                .expect_written_by_username()
+                .with(eq("drake_conan"), eq(None))
+                .once()
+                .return_once(|_, _| Ok(post_with_author::all3()?.to_vec()));
+
+            let resp =
+                get_uri(app(federation_with_actor(), mock_pwa_read), "/users/drake_conan/outbox")
+                    .await?;
+            assert_eq!(StatusCode::OK, resp.status());
+            assert_eq!(Some(ACTIVITY_JSON), content_type(&resp));
+            let body: Value = deserialize_body(resp).await?;
+            assert_eq!(Some(3), body.pointer("/totalItems").and_then(Value::as_u64));
+            assert_eq!(
+                Some("Note"),
+                body.pointer("/orderedItems/0/object/type")
+                    .and_then(Value::as_str)
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn passes_signed_requests_to_the_inbox() -> Result<()> {
+        tokio_test(async {
+            let mut mock_federation = MockFederation::new();
+            mock_federation
+                .expect_receive()
+                .with(
+                    eq("drake_conan"),
+                    mockall::predicate::function(|request: &InboxRequest| {
+                        request.path == "/users/drake_conan/inbox"
+                            && request.body == br#"{"type":"Follow"}"#
+                            && request
+                                .headers
+                                .contains(&(String::from("signature"), String::from("sig")))
+                    }),
+                )
+                .once()
+                .returning(|_, _| Ok(()));
+            mock_federation
+                .expect_receive()
+                .returning(|_, _| Err(FederationError::InvalidSignature));
+            let app = app(mock_federation, MockPostWithAuthorRead::new());
+
+            let post = |signature: &str| {
+                Request::post("/users/drake_conan/inbox")
+                    .header(CONTENT_TYPE, "application/activity+json")
+                    .header("signature", signature)
+                    .body(Body::from(r#"{"type":"Follow"}"#))
+            };
+
+            let accepted = app.clone().oneshot(post("sig")?).await?;
+            assert_eq!(StatusCode::ACCEPTED, accepted.status());
+
+            let rejected = app.oneshot(post("forged")?).await?;
+            assert_eq!(StatusCode::UNAUTHORIZED, rejected.status());
+
+            Ok(())
+        })
+    }
+
+    /// Serves a remote server with Alice's actor document, signed with the second dummy key, and an
+    /// inbox that forwards the requests it receives.
+    async fn serve_remote()
+    -> Result<(SocketAddr, mpsc::UnboundedReceiver<(Vec<(String, String)>, Bytes)>)> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let alice = format!("http://{addr}/users/alice");
+
+        let remote = Router::new()
+            .route(
+                "/users/alice",
+                get(async move || {
+                    Json(json!({
+                        "@context": ["https://www.w3.org/ns/activitystreams"],
+                        "id": alice,
+                        "type": "Person",
+                        "inbox": format!("{alice}/inbox"),
+                        "publicKey": {
+                            "id": format!("{alice}#main-key"),
+                            "owner": alice,
+                            "publicKeyPem": actor_key::number2().public_key_pem,
+                        },
+                    }))
+                }),
+            )
+            .route(
+                "/users/alice/inbox",
+                post(async move |received_headers: HeaderMap, body: Bytes| {
+                    let headers = received_headers
+                        .iter()
+                        .filter_map(|(name, value)| {
+                            Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                        })
+                        .collect();
+                    if sender.send((headers, body)).is_err() {
+                        log::error!("Test receiver dropped");
+                    }
+                    StatusCode::ACCEPTED
+                }),
+            );
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, remote).await {
+                log::error!("Remote test server failed: {e}");
+            }
+        });
+
+        Ok((addr, receiver))
+    }
+
+    #[sqlx::test]
+    async fn accepts_follows_from_remote_servers_end_to_end(pool: PgPool) -> Result<()> {
+        seed_users(&pool).await?;
+        let (remote_addr, mut remote_inbox) = serve_remote().await?;
+        let alice = format!("http://{remote_addr}/users/alice");
+
+        let client = ReqwestFederationClient::new(OutboundPolicy::AllowLoopback)?;
+        let app = Router::new()
+            .nest("/users", actor_routes())
+            .with_state(AppState {
+                federation: Arc::new(FederationSvc::new(
+                    pool.clone(),
+                    PgUserRepo,
+                    PgFederationRepo,
+                    PgJobRepo,
+                    client.clone(),
+                    PublicUrl::default(),
+                    OutboundPolicy::AllowLoopback,
+                )),
+                ..Default::default()
+            });
+        let worker = JobWorker::new(pool.clone(), PgJobRepo)
+            .with_handler(DeliverActivityHandler::new(pool.clone(), PgFederationRepo, client));
+
+        // Alice's server sends a signed `Follow` to Drake's inbox
+        let follow = serde_json::to_vec(&json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": format!("{alice}#follows/1"),
+            "type": "Follow",
+            "actor": alice,
+            "object": "https://spur.example/users/drake_conan",
+        }))?;
+        let inbox_req = signed_post_headers(
+            &actor_key::number2().private_key_pem,
+            &format!("{alice}#main-key"),
+            "https://spur.example/users/drake_conan/inbox",
+            &follow,
+            Utc::now(),
+        )?
+        .into_iter()
+        .fold(Request::post("/users/drake_conan/inbox"), |req, (name, value)| {
+            req.header(name, value)
+        })
+        .header(CONTENT_TYPE, "application/activity+json")
+        .body(Body::from(follow.clone()))?;
+
+        let resp = app.clone().oneshot(inbox_req).await?;
+        assert_eq!(StatusCode::ACCEPTED, resp.status());
+
+        // The `Accept` is delivered in the background
+        assert_eq!(1, worker.run_due().await?);
+        let (headers, body) = timeout(Duration::from_secs(5), remote_inbox.recv())
+            .await?
+            .context("no activity delivered")?;
+
+        let accept: Value = serde_json::from_slice(&body)?;
+        assert_eq!(Some("Accept"), accept.pointer("/type").and_then(Value::as_str));
+        assert_eq!(Some(&serde_json::from_slice::<Value>(&follow)?), accept.pointer("/object"));
+
+        // Alice's server verifies the delivery with the key from Drake's actor document
+        let actor: Value = deserialize_body(
+            app.oneshot(Request::get("/users/drake_conan").body(Body::empty())?)
+                .await?,
+        )
+        .await?;
+        let public_key_pem = actor
+            .pointer("/publicKey/publicKeyPem")
+            .and_then(Value::as_str)
+            .context("no public key")?;
+        let signature = headers
+            .iter()
+            .find(|(name, _)| name == "signature")
+            .map(|(_, value)| Signature::parse(value))
+            .context("no signature")??;
+
+        assert_eq!(
+            actor.pointer("/publicKey/id").and_then(Value::as_str),
+            Some(signature.key_id.as_str())
+        );
+        signature.verify(
+            public_key_pem,
+            &SignedRequest {
+                method: "POST",
+                path: "/users/alice/inbox",
+                headers: &headers,
+                body: &body,
+            },
+            Utc::now(),
+        )?;
+
+        let followers =
+            sqlx::query_scalar!("SELECT actor_id FROM remote_follower WHERE user_id = 1")
+                .fetch_all(&pool)
+                .await?;
+        assert_eq!(vec![alice], followers);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,428 @@
+use {
+    crate::{
+        api::{
+            dto::{
+                feed::{Feed, FeedFormat, MAX_FEED_ENTRIES},
+                responses::ErrorResponse,
+            },
+            error::ApiError,
+            optional_requester::OptionalRequester,
+        },
+        config::PublicUrl,
+        read_models::{PostWithAuthorRead, ReadError},
+        state::AppState,
+    },
+    anyhow::Context as _,
+    axum::{
+        Router,
+        extract::{Path, State},
+        http::{
+            HeaderMap, StatusCode,
+            header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY},
+        },
+        response::{IntoResponse as _, Response},
+        routing::get,
+    },
+    chrono::DateTime,
+    sha2::{Digest as _, Sha256},
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(user_feed))]
+pub struct UserFeedsDoc;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(thread_feed))]
+pub struct ThreadFeedsDoc;
+
+/// Routes to be nested under `/users`.
+pub fn user_routes() -> Router<AppState> {
+    Router::new().route("/{username}/feed.{format}", get(user_feed))
+}
+
+/// Routes to be nested under `/posts`.
+pub fn thread_routes() -> Router<AppState> {
+    Router::new().route("/{post_id}/feed.{format}", get(thread_feed))
+}
+
+/// Retrieves a feed of the most recent posts written by the specified user, for use in feed
+/// readers.
+#[utoipa::path(
+    get,
+    tag = "feeds",
+    path = "/{username}/feed.{format}",
+    security((), ("jwt" = [])),
+    params(
+        ("username" = String, Path, description = "the author of the posts"),
+        ("format" = FeedFormat, Path, description = "the feed format"),
+        (
+            "If-None-Match" = Option<String>,
+            Header,
+            description = "the ETag of a previously retrieved copy of the feed",
+        ),
+    ),
+    responses(
+        (
+            status = StatusCode::OK,
+            content(
+                (String = "application/rss+xml"),
+                (String = "application/atom+xml"),
+                (String = "application/feed+json"),
+            ),
+            description = "the feed",
+        ),
+        (
+            status = StatusCode::NOT_MODIFIED,
+            description = "the feed has not changed since the copy with the provided ETag",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "user not found",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests without logging in",
+        ),
+    ),
+)]
+async fn user_feed(
+    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    State(public_url): State<PublicUrl>,
+    OptionalRequester(viewer_id): OptionalRequester,
+    Path((username, format)): Path<(String, FeedFormat)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let posts = post_with_author_read
+        .recent_by_username(&username, viewer_id, MAX_FEED_ENTRIES)
+        .await?;
+
+    let feed = Feed::new(
+        &public_url,
+        format!("Posts by {username}"),
+        &format!("/posts/user/{username}"),
+        &format!("/users/{username}/feed"),
+        posts,
+        DateTime::UNIX_EPOCH,
+    );
+
+    feed_response(&feed, format, &headers)
+}
+
+/// Retrieves a feed of a post and its most recent replies, for use in feed readers.
+#[utoipa::path(
+    get,
+    tag = "feeds",
+    path = "/{post_id}/feed.{format}",
+    security((), ("jwt" = [])),
+    params(
+        ("post_id" = i32, Path, description = "the post that starts the thread"),
+        ("format" = FeedFormat, Path, description = "the feed format"),
+        (
+            "If-None-Match" = Option<String>,
+            Header,
+            description = "the ETag of a previously retrieved copy of the feed",
+        ),
+    ),
+    responses(
+        (
+            status = StatusCode::OK,
+            content(
+                (String = "application/rss+xml"),
+                (String = "application/atom+xml"),
+                (String = "application/feed+json"),
+            ),
+            description = "the feed",
+        ),
+        (
+            status = StatusCode::NOT_MODIFIED,
+            description = "the feed has not changed since the copy with the provided ETag",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests without logging in",
+        ),
+    ),
+)]
+async fn thread_feed(
+    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    State(public_url): State<PublicUrl>,
+    OptionalRequester(viewer_id): OptionalRequester,
+    Path((post_id, format)): Path<(i32, FeedFormat)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let root = post_with_author_read.by_post_id(post_id, viewer_id).await?;
+    if root.hidden {
+        return Err(ReadError::NotFound.into());
+    }
+
+    // The post that started the thread takes up one of the entries
+    let replies = post_with_author_read
+        .recent_replies_to(post_id, viewer_id, MAX_FEED_ENTRIES.saturating_sub(1))
+        .await?;
+
+    let title = root.author_username.as_ref().map_or_else(
+        || String::from("Replies to a deleted post"),
+        |author| format!("Replies to {author}'s post"),
+    );
+    let root_updated = root.edited_at.unwrap_or(root.created_at);
+
+    let feed = Feed::new(
+        &public_url,
+        title,
+        &format!("/posts/{post_id}"),
+        &format!("/posts/{post_id}/feed"),
+        // Newest first, so the post that started the thread goes last
+        replies.into_iter().chain([root]),
+        root_updated,
+    );
+
+    feed_response(&feed, format, &headers)
+}
+
+/// Renders the feed with an `ETag` derived from its content, or responds with 304 Not Modified if
+/// the client already has the same content.
+fn feed_response(
+    feed: &Feed,
+    format: FeedFormat,
+    headers: &HeaderMap,
+) -> Result<Response, ApiError> {
+    let body = feed
+        .render(format)
+        .context("failed to render feed")
+        .map_err(ReadError::from)?;
+    let etag = format!("\"{}\"", hex::encode(Sha256::digest(body.as_bytes())));
+
+    let not_modified = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+        });
+
+    // Requesters who are logged in may see posts that others can't
+    let vary = (VARY, AUTHORIZATION.to_string());
+
+    if not_modified {
+        Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag), vary]).into_response())
+    } else {
+        let content_type = (CONTENT_TYPE, String::from(format.content_type()));
+        Ok((StatusCode::OK, [(ETAG, etag), vary, content_type], body).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            read_models::MockPostWithAuthorRead,
+            test_utils::{
+                dummy_data::post_with_author, http_bodies::resp_into_body_text, tokio_test,
+            },
+        },
+        anyhow::Result,
+        axum::{
+            body::Body,
+            http::{Method, Request},
+        },
+        mockall::predicate::eq,
+        serde_json::Value,
+        tower::ServiceExt as _,
+    };
+
+    fn app(mock_pwa_read: MockPostWithAuthorRead) -> Router {
+        Router::new()
+            .nest("/users", user_routes())
+            .nest("/posts", thread_routes())
+            .with_state(AppState {
+                post_with_author_read: Arc::new(mock_pwa_read),
+                ..Default::default()
+            })
+    }
+
+    /// Sends an anonymous request for the provided URI, with an "If-None-Match" header if `etag`
+    /// is `Some`.
+    async fn send_req(app: Router, uri: &str, etag: Option<&str>) -> Result<Response> {
+        let mut req = Request::builder().method(Method::GET).uri(uri);
+        if let Some(tag) = etag {
+            req = req.header(IF_NONE_MATCH, tag);
+        }
+
+        app.oneshot(req.body(Body::empty())?)
+            .await
+            .map_err(Into::into)
+    }
+
+    fn header_value(resp: &Response, name: &str) -> Option<String> {
+        resp.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    }
+
+    #[test]
+    fn serves_user_feeds_in_each_format() -> Result<()> {
+        tokio_test(async {
+            for (format, content_type, expected) in [
+                ("rss", "application/rss+xml; charset=utf-8", "<rss version=\"2.0\""),
+                ("atom", "application/atom+xml; charset=utf-8", "<feed xmlns="),
+                ("json", "application/feed+json; charset=utf-8", "\"version\":"),
+            ] {
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_recent_by_username()
+                    .with(eq("helmet_man"), eq(None), eq(MAX_FEED_ENTRIES))
+                    .once()
+                    .return_once(|_, _, _| Ok(post_with_author::all3()?.to_vec()));
+
+                let resp =
+                    send_req(app(mock_pwa_read), &format!("/users/helmet_man/feed.{format}"), None)
+                        .await?;
+                assert_eq!(StatusCode::OK, resp.status());
+                assert_eq!(Some(content_type), header_value(&resp, "content-type").as_deref());
+                assert!(header_value(&resp, "etag").is_some());
+
+                let body = resp_into_body_text(resp).await?;
+                assert!(body.contains(expected));
+                assert!(body.contains("https://spur.example/users/helmet_man/feed"));
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn responds_not_modified_for_a_matching_etag() -> Result<()> {
+        tokio_test(async {
+            let mut mock_pwa_read = MockPostWithAuthorRead::new();
+            mock_pwa_read
+                .expect_recent_by_username()
+                .times(3)
+                .returning(|_, _, _| Ok(post_with_author::all3()?.to_vec()));
+            let app = app(mock_pwa_read);
+            let uri = "/users/someone/feed.atom";
+
+            let first = send_req(app.clone(), uri, None).await?;
+            let etag = header_value(&first, "etag").unwrap_or_default();
+
+            let if_none_match = format!("\"other\", W/{etag}");
+            let unchanged = send_req(app.clone(), uri, Some(&if_none_match)).await?;
+            assert_eq!(StatusCode::NOT_MODIFIED, unchanged.status());
+            assert_eq!(Some(etag), header_value(&unchanged, "etag"));
+            assert_eq!("", resp_into_body_text(unchanged).await?);
+
+            let changed = send_req(app, uri, Some("\"stale\"")).await?;
+            assert_eq!(StatusCode::OK, changed.status());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn lists_a_post_after_its_replies_in_thread_feeds() -> Result<()> {
+        tokio_test(async {
+            let [root, first_reply, second_reply] = post_with_author::all3()?;
+            let root_id = root.id;
+            let expected_ids = [second_reply.id, first_reply.id, root_id]
+                .map(|id| format!("https://spur.example/posts/{id}"));
+
+            let mut mock_pwa_read = MockPostWithAuthorRead::new();
+            mock_pwa_read
+                .expect_by_post_id()
+                .with(eq(root_id), eq(None))
+                .once()
+                .return_once(|_, _| Ok(root));
+            mock_pwa_read
+                .expect_recent_replies_to()
+                .with(eq(root_id), eq(None), eq(MAX_FEED_ENTRIES - 1))
+                .once()
+                .return_once(|_, _, _| Ok(vec![second_reply, first_reply]));
+
+            let resp =
+                send_req(app(mock_pwa_read), &format!("/posts/{root_id}/feed.json"), None).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let body = serde_json::from_str::<Value>(&resp_into_body_text(resp).await?)?;
+            assert_eq!(
+                Some("Replies to jack54444mack's post"),
+                body.pointer("/title").and_then(Value::as_str)
+            );
+            let ids = body
+                .pointer("/items")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.get("id").and_then(Value::as_str))
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                });
+            assert_eq!(Some(expected_ids.to_vec()), ids);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn does_not_serve_thread_feeds_for_hidden_posts() -> Result<()> {
+        tokio_test(async {
+            let mut root = post_with_author::number1()?;
+            root.hidden = true;
+            let root_id = root.id;
+
+            // Children should not be accessed
+            let mut mock_pwa_read = MockPostWithAuthorRead::new();
+            mock_pwa_read
+                .expect_by_post_id()
+                .with(eq(root_id), eq(None))
+                .once()
+                .return_once(|_, _| Ok(root.placeholder_if_hidden()));
+
+            let resp =
+                send_req(app(mock_pwa_read), &format!("/posts/{root_id}/feed.rss"), None).await?;
+            assert_eq!(StatusCode::NOT_FOUND, resp.status());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn does_not_serve_feeds_for_unknown_users() -> Result<()> {
+        tokio_test(async {
+            let mut mock_pwa_read = MockPostWithAuthorRead::new();
+            mock_pwa_read
+                .expect_recent_by_username()
+                .with(eq("nobody"), eq(None), eq(MAX_FEED_ENTRIES))
+                .once()
+                .return_once(|_, _, _| Err(ReadError::NotFound));
+
+            let resp = send_req(app(mock_pwa_read), "/users/nobody/feed.rss", None).await?;
+            assert_eq!(StatusCode::NOT_FOUND, resp.status());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_unknown_formats() -> Result<()> {
+        tokio_test(async {
+            // Reads should not be accessed
+            let resp =
+                send_req(app(MockPostWithAuthorRead::new()), "/users/someone/feed.xml", None)
+                    .await?;
+            assert_eq!(StatusCode::BAD_REQUEST, resp.status());
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,512 @@
+use {
+    crate::{
+        api::dto::{requests::LiveThreadRequest, responses::LiveThreadEvent},
+        app_services::background::ShutdownSignal,
+        models::post::{PostChange, PostChangeKind},
+        read_models::{PostFeed, PostWithAuthorRead},
+        state::AppState,
+    },
+    axum::{
+        Extension, Router,
+        extract::{
+            State,
+            ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
+        },
+        response::Response,
+        routing::get,
+    },
+    std::{collections::BTreeSet, sync::Arc},
+    tokio::sync::broadcast::{Receiver, error::RecvError},
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(live_threads), components(schemas(LiveThreadRequest, LiveThreadEvent)))]
+pub struct LiveDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/threads", get(live_threads)) }
+
+/// The maximum number of posts that a single connection can be subscribed to at once.
+const MAX_SUBSCRIPTIONS: usize = 50;
+
+/// Opens a WebSocket for watching threads as they change.
+///
+/// Clients send `LiveThreadRequest` messages to subscribe to (or unsubscribe from) post IDs, and
+/// receive `LiveThreadEvent` messages whenever a subscribed post or one of its direct replies is
+/// created, edited, or deleted. Each connection can be subscribed to at most 50 posts at once.
+#[utoipa::path(
+    get,
+    tag = "live",
+    path = "/threads",
+    security(("jwt" = [])),
+    responses((
+        status = StatusCode::SWITCHING_PROTOCOLS,
+        description = "upgraded to a WebSocket connection",
+    )),
+)]
+async fn live_threads(
+    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    post_feed: State<Arc<dyn PostFeed>>,
+    State(shutdown): State<ShutdownSignal>,
+    Extension(requester_id): Extension<i32>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    // Subscribe before upgrading so that nothing is missed once the client can send messages
+    let session = LiveThreadSession {
+        post_with_author_read: post_with_author_read.0,
+        viewer_id: requester_id,
+        changes: post_feed.subscribe(),
+        shutdown,
+        subscriptions: BTreeSet::new(),
+    };
+
+    ws.on_upgrade(|socket| session.run(socket))
+}
+
+struct LiveThreadSession {
+    post_with_author_read: Arc<dyn PostWithAuthorRead>,
+    /// The requester, for marking their own reactions on relayed posts
+    viewer_id: i32,
+    changes: Receiver<PostChange>,
+    /// Closes the connection, since the server waits for every connection to close before shutting
+    /// down
+    shutdown: ShutdownSignal,
+    subscriptions: BTreeSet<i32>,
+}
+
+impl LiveThreadSession {
+    /// Handles messages from the client and relays relevant post changes until either side closes
+    /// the connection or shutdown is requested, in which case the client is told that the server
+    /// is going away.
+    async fn run(mut self, mut socket: WebSocket) {
+        loop {
+            let outgoing = tokio::select! {
+                incoming = socket.recv() => match incoming {
+                    Some(Ok(Message::Text(text))) => Some(self.handle_request(&text)),
+                    Some(Ok(Message::Binary(_))) => Some(LiveThreadEvent::Error {
+                        message: String::from("Messages must be sent as text"),
+                    }),
+                    // Pings are answered automatically
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => None,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                },
+                received = self.changes.recv() => match received {
+                    Ok(change) => self.handle_change(change).await,
+                    Err(RecvError::Lagged(missed)) => Some(LiveThreadEvent::Lagged { missed }),
+                    Err(RecvError::Closed) => break,
+                },
+                () = self.shutdown.requested() => {
+                    let frame = CloseFrame {
+                        code: close_code::AWAY,
+                        reason: "Server shutting down".into(),
+                    };
+                    if let Err(e) = socket.send(Message::Close(Some(frame))).await {
+                        log::debug!("Failed to close live thread connection: {e}");
+                    }
+                    break;
+                },
+            };
+
+            if let Some(event) = outgoing {
+                match serde_json::to_string(&event) {
+                    Ok(json) => {
+                        if socket.send(Message::text(json)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => log::error!("Failed to serialize live thread event: {e}"),
+                }
+            }
+        }
+    }
+
+    /// Updates the subscriptions according to the client's message, reporting the resulting set of
+    /// subscriptions or the reason the message was rejected.
+    fn handle_request(&mut self, text: &str) -> LiveThreadEvent {
+        match serde_json::from_str(text) {
+            Ok(LiveThreadRequest::Subscribe { post_ids }) => {
+                let new_ids = post_ids
+                    .into_iter()
+                    .filter(|id| !self.subscriptions.contains(id))
+                    .collect::<BTreeSet<_>>();
+
+                if self.subscriptions.len().saturating_add(new_ids.len()) > MAX_SUBSCRIPTIONS {
+                    return LiveThreadEvent::Error {
+                        message: format!(
+                            "Cannot subscribe to more than {MAX_SUBSCRIPTIONS} posts per \
+                             connection"
+                        ),
+                    };
+                }
+
+                self.subscriptions.extend(new_ids);
+            }
+            Ok(LiveThreadRequest::Unsubscribe { post_ids }) => {
+                for id in &post_ids {
+                    self.subscriptions.remove(id);
+                }
+            }
+            Err(e) => return LiveThreadEvent::Error { message: format!("Invalid message: {e}") },
+        }
+
+        LiveThreadEvent::Subscribed { post_ids: self.subscriptions.iter().copied().collect() }
+    }
+
+    /// Converts a post change into an event if it affects a subscribed post or one of its direct
+    /// replies that the viewer may see.
+    async fn handle_change(&self, change: PostChange) -> Option<LiveThreadEvent> {
+        let relevant = self.subscriptions.contains(&change.post_id)
+            || change
+                .parent_id
+                .is_some_and(|parent_id| self.subscriptions.contains(&parent_id));
+
+        if !relevant {
+            return None;
+        }
+
+        let fetch_post = async || {
+            self.post_with_author_read
+                .by_post_id(change.post_id, Some(self.viewer_id))
+                .await
+                .inspect_err(|e| {
+                    log::error!("Failed to fetch changed post {}: {e}", change.post_id);
+                })
+                .ok()
+        };
+
+        match change.kind {
+            PostChangeKind::Created => {
+                Some(LiveThreadEvent::Created { post: fetch_post().await?.into() })
+            }
+            PostChangeKind::Edited => {
+                Some(LiveThreadEvent::Edited { post: fetch_post().await?.into() })
+            }
+            // Deleted posts keep their row, so the viewer is checked the same way, and only told
+            // about deletions of posts that they could see
+            PostChangeKind::Deleted => {
+                fetch_post()
+                    .await
+                    .filter(|post| !post.hidden)
+                    .map(|_| LiveThreadEvent::Deleted {
+                        post_id: change.post_id,
+                        parent_id: change.parent_id,
+                    })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::router,
+            app_services::MockAuthenticator,
+            domain::auth::AuthError,
+            models::post::PostWithAuthor,
+            read_models::{MockPostFeed, MockPostWithAuthorRead},
+            test_utils::{
+                dummy_data, tokio_test,
+                ws_client::{TestSocket, connect, recv_json, send_json, serve},
+            },
+        },
+        anyhow::{Context as _, Result, anyhow},
+        axum::http::StatusCode,
+        chrono::Utc,
+        futures_util::StreamExt as _,
+        mockall::predicate::eq,
+        std::{net::SocketAddr, time::Duration},
+        tokio::{sync::broadcast, time::timeout},
+        tokio_tungstenite::tungstenite::{self, protocol::frame::coding::CloseCode},
+    };
+
+    const PATH: &str = "/live/threads";
+    const TOKEN: &str = "live-token";
+
+    /// Serves the full app (including JWT validation) with a valid `TOKEN`, the provided post
+    /// change signals and shutdown signal, and the provided read model.
+    async fn serve_app(
+        changes: Receiver<PostChange>,
+        shutdown: ShutdownSignal,
+        mock_post_with_author_read: MockPostWithAuthorRead,
+    ) -> Result<SocketAddr> {
+        let mut mock_auth = MockAuthenticator::new();
+        mock_auth
+            .expect_validate_token()
+            .with(eq(TOKEN))
+            .returning(|_| Ok(7));
+
+        let mut mock_post_feed = MockPostFeed::new();
+        mock_post_feed
+            .expect_subscribe()
+            .once()
+            .return_once(move || changes);
+
+        let state = AppState {
+            auth: Arc::new(mock_auth),
+            post_feed: Arc::new(mock_post_feed),
+            post_with_author_read: Arc::new(mock_post_with_author_read),
+            shutdown,
+            ..Default::default()
+        };
+
+        serve(router::build(state, "http://localhost:5173")?).await
+    }
+
+    /// Subscribes to `post_ids`, asserting that the connection ends up subscribed to exactly those
+    /// posts.
+    async fn subscribe(socket: &mut TestSocket, mut post_ids: Vec<i32>) -> Result<()> {
+        send_json(socket, &LiveThreadRequest::Subscribe { post_ids: post_ids.clone() }).await?;
+        post_ids.sort_unstable();
+        assert_eq!(LiveThreadEvent::Subscribed { post_ids }, recv_json(socket).await?);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_connections_without_a_valid_token() -> Result<()> {
+        tokio_test(async {
+            let mut mock_auth = MockAuthenticator::new();
+            mock_auth
+                .expect_validate_token()
+                .with(eq("expired"))
+                .once()
+                .return_once(|_| Err(AuthError::TokenValidation));
+
+            let state = AppState { auth: Arc::new(mock_auth), ..Default::default() };
+            let addr = serve(router::build(state, "http://localhost:5173")?).await?;
+
+            for (token, expected_status) in
+                [(None, StatusCode::BAD_REQUEST), (Some("expired"), StatusCode::UNAUTHORIZED)]
+            {
+                match connect(addr, PATH, token).await {
+                    Err(tungstenite::Error::Http(resp)) => {
+                        assert_eq!(expected_status, resp.status());
+                    }
+                    other => return Err(anyhow!("unexpected connection result: {other:?}")),
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn relays_changes_to_subscribed_posts_and_their_replies() -> Result<()> {
+        tokio_test(async {
+            let (sender, receiver) = broadcast::channel(8);
+
+            let reply = dummy_data::post_with_author::number1()?;
+            let edited = dummy_data::post_with_author::number2()?;
+            let (reply_id, reply_parent_id) = (reply.id, reply.parent_id);
+            let edited_id = edited.id;
+            let (reply_clone, edited_clone) = (reply.clone(), edited.clone());
+            let deleted =
+                PostWithAuthor { body: None, deleted_at: Some(Utc::now()), ..reply.clone() };
+
+            let mut mock_post_with_author_read = MockPostWithAuthorRead::new();
+            mock_post_with_author_read
+                .expect_by_post_id()
+                .with(eq(reply_id), eq(Some(7)))
+                .once()
+                .return_once(|_, _| Ok(reply_clone));
+            mock_post_with_author_read
+                .expect_by_post_id()
+                .with(eq(edited_id), eq(Some(7)))
+                .once()
+                .return_once(|_, _| Ok(edited_clone));
+            mock_post_with_author_read
+                .expect_by_post_id()
+                .with(eq(reply_id), eq(Some(7)))
+                .once()
+                .return_once(|_, _| Ok(deleted));
+
+            let addr =
+                serve_app(receiver, ShutdownSignal::default(), mock_post_with_author_read).await?;
+            let mut socket = connect(addr, PATH, Some(TOKEN)).await?;
+
+            let parent_id = reply_parent_id.ok_or_else(|| anyhow!("unexpected None parent ID"))?;
+            subscribe(&mut socket, vec![edited_id, parent_id]).await?;
+
+            // A reply to an unrelated post is skipped without being fetched
+            sender.send(PostChange {
+                kind: PostChangeKind::Created,
+                post_id: 5000,
+                parent_id: Some(4999),
+            })?;
+            sender.send(PostChange {
+                kind: PostChangeKind::Created,
+                post_id: reply_id,
+                parent_id: reply_parent_id,
+            })?;
+            assert_eq!(
+                LiveThreadEvent::Created { post: reply.into() },
+                recv_json(&mut socket).await?
+            );
+
+            sender.send(PostChange {
+                kind: PostChangeKind::Edited,
+                post_id: edited_id,
+                parent_id: edited.parent_id,
+            })?;
+            assert_eq!(
+                LiveThreadEvent::Edited { post: edited.into() },
+                recv_json(&mut socket).await?
+            );
+
+            sender.send(PostChange {
+                kind: PostChangeKind::Deleted,
+                post_id: reply_id,
+                parent_id: reply_parent_id,
+            })?;
+            assert_eq!(
+                LiveThreadEvent::Deleted { post_id: reply_id, parent_id: reply_parent_id },
+                recv_json(&mut socket).await?,
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn skips_deletions_of_posts_hidden_from_the_viewer() -> Result<()> {
+        tokio_test(async {
+            let (sender, receiver) = broadcast::channel(8);
+
+            let hidden = PostWithAuthor {
+                hidden: true,
+                deleted_at: Some(Utc::now()),
+                ..dummy_data::post_with_author::number1()?
+            }
+            .placeholder_if_hidden();
+            let visible = dummy_data::post_with_author::number2()?;
+            let (hidden_id, visible_id) = (hidden.id, visible.id);
+
+            let mut mock_post_with_author_read = MockPostWithAuthorRead::new();
+            mock_post_with_author_read
+                .expect_by_post_id()
+                .with(eq(hidden_id), eq(Some(7)))
+                .once()
+                .return_once(|_, _| Ok(hidden));
+            mock_post_with_author_read
+                .expect_by_post_id()
+                .with(eq(visible_id), eq(Some(7)))
+                .once()
+                .return_once(|_, _| Ok(visible));
+
+            let addr =
+                serve_app(receiver, ShutdownSignal::default(), mock_post_with_author_read).await?;
+            let mut socket = connect(addr, PATH, Some(TOKEN)).await?;
+            subscribe(&mut socket, vec![hidden_id, visible_id]).await?;
+
+            for post_id in [hidden_id, visible_id] {
+                sender.send(PostChange {
+                    kind: PostChangeKind::Deleted,
+                    post_id,
+                    parent_id: None,
+                })?;
+            }
+
+            // Only the deletion of the visible post comes through
+            assert_eq!(
+                LiveThreadEvent::Deleted { post_id: visible_id, parent_id: None },
+                recv_json(&mut socket).await?,
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn enforces_the_subscription_limit() -> Result<()> {
+        tokio_test(async {
+            let (_sender, receiver) = broadcast::channel(8);
+            let addr =
+                serve_app(receiver, ShutdownSignal::default(), MockPostWithAuthorRead::new())
+                    .await?;
+            let mut socket = connect(addr, PATH, Some(TOKEN)).await?;
+
+            let limit = i32::try_from(MAX_SUBSCRIPTIONS)?;
+            subscribe(&mut socket, (1..=limit).collect()).await?;
+
+            // Already subscribed posts do not count against the limit
+            subscribe(&mut socket, (1..=limit).collect()).await?;
+
+            send_json(
+                &mut socket,
+                &LiveThreadRequest::Subscribe { post_ids: vec![limit.saturating_add(1)] },
+            )
+            .await?;
+            assert_eq!(
+                LiveThreadEvent::Error {
+                    message: String::from("Cannot subscribe to more than 50 posts per connection"),
+                },
+                recv_json(&mut socket).await?,
+            );
+
+            send_json(&mut socket, &LiveThreadRequest::Unsubscribe { post_ids: vec![1] }).await?;
+            assert_eq!(
+                LiveThreadEvent::Subscribed { post_ids: (2..=limit).collect() },
+                recv_json(&mut socket).await?,
+            );
+
+            subscribe(&mut socket, (2..=limit.saturating_add(1)).collect()).await?;
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn reports_invalid_messages_and_missed_changes() -> Result<()> {
+        tokio_test(async {
+            let (sender, receiver) = broadcast::channel(1);
+            let addr =
+                serve_app(receiver, ShutdownSignal::default(), MockPostWithAuthorRead::new())
+                    .await?;
+            let mut socket = connect(addr, PATH, Some(TOKEN)).await?;
+
+            send_json(&mut socket, &"hello").await?;
+            assert!(matches!(
+                recv_json(&mut socket).await?,
+                LiveThreadEvent::Error { message } if message.starts_with("Invalid message")
+            ));
+
+            // Overflow the one-signal buffer so that two signals are dropped
+            for post_id in 1..=3 {
+                sender.send(PostChange {
+                    kind: PostChangeKind::Deleted,
+                    post_id,
+                    parent_id: None,
+                })?;
+            }
+            assert_eq!(LiveThreadEvent::Lagged { missed: 2 }, recv_json(&mut socket).await?);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn closes_the_connection_when_shutdown_is_requested() -> Result<()> {
+        tokio_test(async {
+            let (_sender, receiver) = broadcast::channel(8);
+            let (shutdown_tx, shutdown) = ShutdownSignal::channel();
+            let addr = serve_app(receiver, shutdown, MockPostWithAuthorRead::new()).await?;
+            let mut socket = connect(addr, PATH, Some(TOKEN)).await?;
+            subscribe(&mut socket, vec![1]).await?;
+
+            shutdown_tx.send_replace(true);
+
+            let message = timeout(Duration::from_secs(5), socket.next())
+                .await
+                .context("timed out waiting for the socket to close")?
+                .context("socket ended without a close frame")??;
+            match message {
+                tungstenite::Message::Close(Some(frame)) => {
+                    assert_eq!(CloseCode::Away, frame.code);
+                }
+                other => return Err(anyhow!("unexpected message: {other:?}")),
+            }
+
+            Ok(())
+        })
+    }
+}
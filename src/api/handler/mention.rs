@@ -0,0 +1,138 @@
+use {
+    super::{DEFAULT_PAGE_SIZE, api_result},
+    crate::{
+        api::{
+            dto::{requests::PageQuery, responses::MentionPageResponse},
+            validated_query::ValidatedQuery,
+        },
+        read_models::MentionRead,
+        state::AppState,
+    },
+    axum::{Extension, Json, Router, extract::State, http::StatusCode, routing::get},
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(mentioning_posts))]
+pub struct MentionsDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/", get(mentioning_posts)) }
+
+/// Retrieves a page of the posts that mention the requester, most recent first.
+#[utoipa::path(
+    get,
+    tag = "mentions",
+    path = "",
+    security(("jwt" = [])),
+    params(PageQuery),
+    responses((
+        status = StatusCode::OK,
+        body = MentionPageResponse,
+        description = "a page of posts that mention you",
+    )),
+)]
+async fn mentioning_posts(
+    mention_read: State<Arc<dyn MentionRead>>,
+    Extension(requester_id): Extension<i32>,
+    query: ValidatedQuery<PageQuery>,
+) -> api_result!(MentionPageResponse) {
+    let PageQuery { cursor, limit } = query.0;
+
+    let page = mention_read
+        .mentioning(requester_id, cursor, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::dto::responses::{MentionSpanResponse, PostResponse},
+            models::{
+                mention::{MentionPage, MentionSpan},
+                post::PostCursor,
+            },
+            read_models::MockMentionRead,
+            test_utils::{dummy_data::post_with_author, http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::Result,
+        axum::{body::Body, http::Request},
+        mockall::predicate::eq,
+        sqlx::types::Json as SqlxJson,
+        tower::ServiceExt as _,
+    };
+
+    /// Sends a request for mentions as the requester.
+    async fn send_req(
+        mock_mention_read: MockMentionRead,
+        requester_id: i32,
+        uri: &str,
+    ) -> Result<axum::response::Response> {
+        let state = AppState { mention_read: Arc::new(mock_mention_read), ..Default::default() };
+        let app = routes().with_state(state);
+
+        let mut req = Request::builder().uri(uri).body(Body::empty())?;
+        req.extensions_mut().insert(requester_id);
+
+        Ok(app.oneshot(req).await?)
+    }
+
+    #[test]
+    fn retrieves_a_page_with_mention_spans_and_the_next_cursor() -> Result<()> {
+        tokio_test(async {
+            let mut mentioning = post_with_author::number1()?;
+            mentioning.body = Some(String::from("hey @maria_g"));
+            mentioning.mentions = SqlxJson(vec![MentionSpan {
+                start: 4,
+                end: 12,
+                username: String::from("maria_g"),
+            }]);
+            let cursor = PostCursor::of(&mentioning);
+            let mentioning_clone = mentioning.clone();
+
+            let mut mock_mention_read = MockMentionRead::new();
+            mock_mention_read
+                .expect_mentioning()
+                .with(eq(6), eq(Some(cursor)), eq(5))
+                .once()
+                .return_once(move |_, _, _| {
+                    Ok(MentionPage { posts: vec![mentioning_clone], next_cursor: Some(cursor) })
+                });
+
+            let resp =
+                send_req(mock_mention_read, 6, &format!("/?cursor={cursor}&limit=5")).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let resp_body = deserialize_body::<MentionPageResponse>(resp).await?;
+            let expected = MentionPageResponse {
+                posts: vec![PostResponse::from(mentioning)],
+                next_cursor: Some(cursor.to_string()),
+            };
+            assert_eq!(expected, resp_body);
+            assert_eq!(
+                Some(&vec![MentionSpanResponse {
+                    start: 4,
+                    end: 12,
+                    username: String::from("maria_g"),
+                }]),
+                resp_body.posts.first().map(|post| &post.mentions),
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_invalid_limits() -> Result<()> {
+        tokio_test(async {
+            // The read model should not be called
+            let resp = send_req(MockMentionRead::new(), 6, "/?limit=0").await?;
+            assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, resp.status());
+
+            Ok(())
+        })
+    }
+}
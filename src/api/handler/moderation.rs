@@ -0,0 +1,351 @@
+use {
+    super::api_result,
+    crate::{
+        api::{
+            dto::{
+                requests::ResolveReportsRequest,
+                responses::{
+                    ErrorResponse, ModerationActionResponse, ReportedPostResponse, SuccessResponse,
+                },
+            },
+            validated_json::ValidatedJson,
+        },
+        app_services::PostModeration,
+        map_into::MapInto as _,
+        read_models::ModerationRead,
+        state::AppState,
+    },
+    axum::{
+        Extension, Json, Router,
+        extract::{Path, State},
+        http::StatusCode,
+        routing::{get, post},
+    },
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(open_reports, resolve, action_log))]
+pub struct ModerationDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/reports", get(open_reports))
+        .route("/posts/{post_id}/resolve", post(resolve))
+        .route("/actions", get(action_log))
+}
+
+/// Retrieves the moderation queue of posts with open reports. (Moderators only.)
+#[utoipa::path(
+    get,
+    tag = "moderation",
+    path = "/reports",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Vec<ReportedPostResponse>,
+            description = "reported posts, most reported first",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "requester is not a moderator",
+        ),
+    ),
+)]
+async fn open_reports(
+    moderation_read: State<Arc<dyn ModerationRead>>,
+) -> api_result!(Vec<ReportedPostResponse>) {
+    Ok((StatusCode::OK, Json(moderation_read.open_reports().await?.map_into())))
+}
+
+/// Resolves all open reports on a post by dismissing them, archiving the post, or deleting the
+/// post. (Moderators only.)
+#[utoipa::path(
+    post,
+    tag = "moderation",
+    path = "/posts/{post_id}/resolve",
+    security(("jwt" = [])),
+    request_body = ResolveReportsRequest,
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "reports resolved",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "requester is not a moderator",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found or post has no open reports",
+        ),
+    ),
+)]
+async fn resolve(
+    post_moderation: State<Arc<dyn PostModeration>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+    payload: ValidatedJson<ResolveReportsRequest>,
+) -> api_result!(SuccessResponse) {
+    let ResolveReportsRequest { action, note } = payload.0;
+
+    let resolved = post_moderation
+        .resolve(requester_id, post_id, action, note)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(SuccessResponse { message: format!("Resolved {resolved} report(s)") }),
+    ))
+}
+
+/// Retrieves the moderation audit trail. (Moderators only.)
+#[utoipa::path(
+    get,
+    tag = "moderation",
+    path = "/actions",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Vec<ModerationActionResponse>,
+            description = "moderation actions, most recent first",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "requester is not a moderator",
+        ),
+    ),
+)]
+async fn action_log(
+    moderation_read: State<Arc<dyn ModerationRead>>,
+) -> api_result!(Vec<ModerationActionResponse>) {
+    Ok((StatusCode::OK, Json(moderation_read.action_log().await?.map_into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            app_services::MockPostModeration,
+            domain::moderation::error::ModerationError,
+            models::moderation::{
+                ModerationActionKind, ModerationActionRecord, ReportReason, ReportedPost,
+            },
+            read_models::MockModerationRead,
+            test_utils::{
+                http_bodies::{deserialize_body, serialize_body},
+                tokio_test,
+            },
+        },
+        anyhow::Result,
+        axum::{
+            body::Body,
+            http::{Method, Request, header::CONTENT_TYPE},
+        },
+        chrono::Utc,
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    mod open_reports {
+        use super::*;
+
+        #[test]
+        fn retrieves_the_queue() -> Result<()> {
+            tokio_test(async {
+                let now = Utc::now();
+                let reported = vec![ReportedPost {
+                    post_id: 12,
+                    author_username: None,
+                    body: Some(String::from("Buy my stuff")),
+                    report_count: 3,
+                    reasons: vec![ReportReason::Spam, ReportReason::Other],
+                    first_reported_at: now,
+                    last_reported_at: now,
+                }];
+                let reported_clone = reported.clone();
+
+                let mut mock_moderation_read = MockModerationRead::new();
+                mock_moderation_read
+                    .expect_open_reports()
+                    .once()
+                    .return_once(|| Ok(reported_clone));
+
+                let state = AppState {
+                    moderation_read: Arc::new(mock_moderation_read),
+                    ..Default::default()
+                };
+                let app = routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .uri("/reports")
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<Vec<ReportedPostResponse>>(resp).await?;
+                assert_eq!(reported.map_into::<Vec<ReportedPostResponse>>(), resp_body);
+                assert_eq!(
+                    resp_body.first().map(|r| r.author_username.as_str()),
+                    Some("[deleted]")
+                );
+
+                Ok(())
+            })
+        }
+    }
+
+    mod resolve {
+        use super::*;
+
+        /// Sends a request to resolve the reports on the post with the provided ID as the
+        /// requester.
+        async fn send_req(
+            mock_post_moderation: MockPostModeration,
+            requester_id: i32,
+            post_id: i32,
+            payload: &ResolveReportsRequest,
+        ) -> Result<axum::response::Response> {
+            let state =
+                AppState { post_moderation: Arc::new(mock_post_moderation), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri(format!("/posts/{post_id}/resolve"))
+                .header(CONTENT_TYPE, "application/json")
+                .body(serialize_body(payload)?)?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
+        #[test]
+        fn reports_the_number_of_reports_resolved() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, post_id) = (4, 88);
+                let payload = ResolveReportsRequest {
+                    action: ModerationActionKind::Archive,
+                    note: Some(String::from("Off topic")),
+                };
+
+                let mut mock_post_moderation = MockPostModeration::new();
+                mock_post_moderation
+                    .expect_resolve()
+                    .with(
+                        eq(requester_id),
+                        eq(post_id),
+                        eq(ModerationActionKind::Archive),
+                        eq(Some(String::from("Off topic"))),
+                    )
+                    .once()
+                    .return_once(|_, _, _, _| Ok(2));
+
+                let resp = send_req(mock_post_moderation, requester_id, post_id, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<SuccessResponse>(resp).await?;
+                let expected = SuccessResponse { message: String::from("Resolved 2 report(s)") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_empty_notes() -> Result<()> {
+            tokio_test(async {
+                let payload = ResolveReportsRequest {
+                    action: ModerationActionKind::Dismiss,
+                    note: Some(String::new()),
+                };
+
+                // The service should not be called
+                let resp = send_req(MockPostModeration::new(), 4, 88, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let payload =
+                    ResolveReportsRequest { action: ModerationActionKind::Delete, note: None };
+
+                let mut mock_post_moderation = MockPostModeration::new();
+                mock_post_moderation
+                    .expect_resolve()
+                    .once()
+                    .return_once(|_, _, _, _| Err(ModerationError::NoOpenReports));
+
+                let resp = send_req(mock_post_moderation, 4, 89, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected =
+                    ErrorResponse { error: String::from("No open reports on this post") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+    }
+
+    mod action_log {
+        use super::*;
+
+        #[test]
+        fn retrieves_the_audit_trail() -> Result<()> {
+            tokio_test(async {
+                let record = ModerationActionRecord {
+                    id: 5,
+                    post_id: 12,
+                    moderator_username: Some(String::from("spurt")),
+                    action: ModerationActionKind::Delete,
+                    note: None,
+                    reports_resolved: 4,
+                    created_at: Utc::now(),
+                };
+                let record_clone = record.clone();
+
+                let mut mock_moderation_read = MockModerationRead::new();
+                mock_moderation_read
+                    .expect_action_log()
+                    .once()
+                    .return_once(|| Ok(vec![record_clone]));
+
+                let state = AppState {
+                    moderation_read: Arc::new(mock_moderation_read),
+                    ..Default::default()
+                };
+                let app = routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .uri("/actions")
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<Vec<ModerationActionResponse>>(resp).await?;
+                assert_eq!(vec![ModerationActionResponse::from(record)], resp_body);
+
+                Ok(())
+            })
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use {
+    super::{DEFAULT_PAGE_SIZE, api_result},
+    crate::{
+        api::{
+            dto::{
+                requests::{MarkNotificationsReadRequest, PageQuery},
+                responses::{NotificationInboxResponse, SuccessResponse},
+            },
+            validated_json::ValidatedJson,
+            validated_query::ValidatedQuery,
+        },
+        domain::notification::NotificationSvc,
+        map_into::MapInto as _,
+        read_models::NotificationRead,
+        state::AppState,
+    },
+    axum::{
+        Extension, Json, Router,
+        extract::State,
+        http::StatusCode,
+        routing::{get, post},
+    },
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(inbox, mark_read))]
+pub struct NotificationsDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(inbox))
+        .route("/read", post(mark_read))
+}
+
+/// Retrieves a page of the requester's notifications, most recent first, along with the number of
+/// unread notifications.
+#[utoipa::path(
+    get,
+    tag = "notifications",
+    path = "",
+    security(("jwt" = [])),
+    params(PageQuery),
+    responses((
+        status = StatusCode::OK,
+        body = NotificationInboxResponse,
+        description = "a page of your notifications and unread counts",
+    )),
+)]
+async fn inbox(
+    notification_read: State<Arc<dyn NotificationRead>>,
+    Extension(requester_id): Extension<i32>,
+    query: ValidatedQuery<PageQuery>,
+) -> api_result!(NotificationInboxResponse) {
+    let PageQuery { cursor, limit } = query.0;
+
+    let unread = notification_read.unread_counts(requester_id).await?.into();
+    let page = notification_read
+        .for_recipient(requester_id, cursor, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(NotificationInboxResponse {
+            unread,
+            notifications: page.notifications.map_into(),
+            next_cursor: page.next_cursor.map(|next| next.to_string()),
+        }),
+    ))
+}
+
+/// Marks the requester's notifications as read, either all of them or only the specified ones.
+#[utoipa::path(
+    post,
+    tag = "notifications",
+    path = "/read",
+    security(("jwt" = [])),
+    request_body = MarkNotificationsReadRequest,
+    responses((
+        status = StatusCode::OK,
+        body = SuccessResponse,
+        description = "notifications marked as read",
+    )),
+)]
+async fn mark_read(
+    notification_svc: State<Arc<dyn NotificationSvc>>,
+    Extension(requester_id): Extension<i32>,
+    payload: ValidatedJson<MarkNotificationsReadRequest>,
+) -> api_result!(SuccessResponse) {
+    let MarkNotificationsReadRequest { ids } = payload.0;
+    let marked = notification_svc.mark_read(requester_id, ids).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(SuccessResponse { message: format!("Marked {marked} notification(s) as read") }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::dto::responses::{ErrorResponse, UnreadCountsResponse},
+            domain::notification::{MockNotificationSvc, error::NotificationError},
+            models::{
+                notification::{
+                    NotificationKind, NotificationPage, NotificationWithActor, UnreadCounts,
+                },
+                post::PostCursor,
+            },
+            read_models::MockNotificationRead,
+            test_utils::{
+                http_bodies::{deserialize_body, serialize_body},
+                tokio_test,
+            },
+        },
+        anyhow::{Result, anyhow},
+        axum::{
+            body::Body,
+            http::{Method, Request, header::CONTENT_TYPE},
+        },
+        chrono::{DateTime, Utc},
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    mod inbox {
+        use super::*;
+
+        #[test]
+        fn retrieves_a_page_of_notifications_and_unread_counts() -> Result<()> {
+            tokio_test(async {
+                let requester_id = 31;
+                let notifications = vec![
+                    NotificationWithActor {
+                        id: 9,
+                        kind: NotificationKind::Reply,
+                        post_id: Some(44),
+                        created_at: Utc::now(),
+                        read_at: None,
+                        actor_username: Some(String::from("replier")),
+                    },
+                    NotificationWithActor {
+                        id: 8,
+                        kind: NotificationKind::FriendRequest,
+                        post_id: None,
+                        created_at: Utc::now(),
+                        read_at: Some(Utc::now()),
+                        actor_username: None,
+                    },
+                ];
+                let notifications_clone = notifications.clone();
+                // Cursors are only precise to the microsecond
+                let cursor = PostCursor { created_at: DateTime::UNIX_EPOCH, id: 12 };
+                let next_cursor = PostCursor { created_at: DateTime::UNIX_EPOCH, id: 8 };
+
+                let mut mock_notification_read = MockNotificationRead::new();
+                mock_notification_read
+                    .expect_unread_counts()
+                    .with(eq(requester_id))
+                    .once()
+                    .return_once(|_| {
+                        Ok(UnreadCounts {
+                            reply: 1,
+                            friend_request: 0,
+                            friend_accepted: 2,
+                            mention: 4,
+                        })
+                    });
+                mock_notification_read
+                    .expect_for_recipient()
+                    .with(eq(requester_id), eq(Some(cursor)), eq(2))
+                    .once()
+                    .return_once(move |_, _, _| {
+                        Ok(NotificationPage {
+                            notifications: notifications_clone,
+                            next_cursor: Some(next_cursor),
+                        })
+                    });
+
+                let state = AppState {
+                    notification_read: Arc::new(mock_notification_read),
+                    ..Default::default()
+                };
+                let app = routes().with_state(state);
+
+                let mut req = Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/?cursor={cursor}&limit=2"))
+                    .body(Body::empty())?;
+
+                req.extensions_mut().insert(requester_id);
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<NotificationInboxResponse>(resp).await?;
+                let expected = NotificationInboxResponse {
+                    unread: UnreadCountsResponse {
+                        total: 7,
+                        reply: 1,
+                        friend_request: 0,
+                        friend_accepted: 2,
+                        mention: 4,
+                    },
+                    notifications: notifications.map_into(),
+                    next_cursor: Some(next_cursor.to_string()),
+                };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+    }
+
+    mod mark_read {
+        use super::*;
+
+        /// Sends a request to mark notifications as read as the requester.
+        async fn send_req(
+            mock_notification_svc: MockNotificationSvc,
+            requester_id: i32,
+            payload: &MarkNotificationsReadRequest,
+        ) -> Result<axum::response::Response> {
+            let state = AppState {
+                notification_svc: Arc::new(mock_notification_svc),
+                ..Default::default()
+            };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri("/read")
+                .header(CONTENT_TYPE, "application/json")
+                .body(serialize_body(payload)?)?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
+        #[test]
+        fn reports_the_number_of_notifications_marked() -> Result<()> {
+            tokio_test(async {
+                for (ids, marked) in [(Some(vec![4, 6]), 2), (None, 17)] {
+                    let requester_id = 32;
+                    let payload = MarkNotificationsReadRequest { ids: ids.clone() };
+
+                    let mut mock_notification_svc = MockNotificationSvc::new();
+                    mock_notification_svc
+                        .expect_mark_read()
+                        .with(eq(requester_id), eq(ids))
+                        .once()
+                        .return_once(move |_, _| Ok(marked));
+
+                    let resp = send_req(mock_notification_svc, requester_id, &payload).await?;
+                    assert_eq!(resp.status(), StatusCode::OK);
+
+                    let resp_body = deserialize_body::<SuccessResponse>(resp).await?;
+                    let expected = SuccessResponse {
+                        message: format!("Marked {marked} notification(s) as read"),
+                    };
+                    assert_eq!(expected, resp_body);
+                }
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_an_empty_list_of_ids() -> Result<()> {
+            tokio_test(async {
+                let payload = MarkNotificationsReadRequest { ids: Some(Vec::new()) };
+
+                // The service should not be called
+                let resp = send_req(MockNotificationSvc::new(), 33, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let payload = MarkNotificationsReadRequest { ids: None };
+
+                let mut mock_notification_svc = MockNotificationSvc::new();
+                mock_notification_svc
+                    .expect_mark_read()
+                    .once()
+                    .return_once(|_, _| Err(NotificationError::Internal(anyhow!("oops"))));
+
+                let resp = send_req(mock_notification_svc, 34, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected = ErrorResponse { error: String::from("internal server error") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+    }
+}
@@ -1,37 +1,97 @@
 use {
-    super::api_result,
+    super::{DEFAULT_PAGE_SIZE, api_result},
     crate::{
         api::{
             dto::{
-                requests::CreatePostRequest,
-                responses::{ErrorResponse, PostResponse},
+                markdown::check_markdown,
+                requests::{
+                    AttachmentUploadForm, ChildPostsQuery, CreatePostRequest, PageQuery,
+                    ReplyPolicyRequest, ReportPostRequest, SaveDraftRequest, ScheduleDraftRequest,
+                    VisibilityRequest,
+                },
+                responses::{
+                    AttachmentResponse, DraftResponse, ErrorResponse, PostResponse,
+                    QuotePageResponse, SuccessResponse,
+                },
             },
+            error::ApiError,
+            optional_requester::OptionalRequester,
             validated_json::ValidatedJson,
+            validated_query::ValidatedQuery,
+        },
+        app_services::PostModeration,
+        domain::{
+            attachment::{AttachmentSvc, error::AttachmentError},
+            bookmark::BookmarkSvc,
+            draft::DraftSvc,
+            post::PostSvc,
+            reaction::ReactionSvc,
         },
-        domain::post::PostSvc,
         map_into::MapInto as _,
-        read_models::PostWithAuthorRead,
+        models::{post::BodyFormat, reaction::ReactionKind},
+        read_models::{DraftRead, PostWithAuthorRead},
         state::AppState,
     },
     axum::{
         Extension, Json, Router,
-        extract::{Path, State},
+        extract::{DefaultBodyLimit, Multipart, Path, Query, State},
         http::StatusCode,
-        routing::{get, post},
+        routing::{get, post, put},
     },
+    chrono::DateTime,
     std::sync::Arc,
+    validator::{ValidationError, ValidationErrors},
 };
 
 #[derive(utoipa::OpenApi)]
-#[openapi(paths(create_new, by_post_id, child_posts, specific_user_posts, own_posts))]
+#[openapi(paths(
+    create_new,
+    by_post_id,
+    child_posts,
+    quotes,
+    set_reply_policy,
+    set_visibility,
+    report,
+    upload_attachment,
+    react,
+    unreact,
+    save,
+    unsave,
+    draft,
+    save_draft,
+    discard_draft,
+    publish_draft,
+    schedule_draft,
+    specific_user_posts,
+    own_posts,
+))]
 pub struct PostsDoc;
 
-pub fn routes() -> Router<AppState> {
+/// Routes that can be used without logging in, where anonymous requesters only see public posts.
+pub fn public_routes() -> Router<AppState> {
     Router::new()
-        .route("/", post(create_new))
         .route("/{post_id}", get(by_post_id))
         .route("/{post_id}/children", get(child_posts))
+        .route("/{post_id}/quotes", get(quotes))
         .route("/user/{author_username}", get(specific_user_posts))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_new))
+        .route("/{post_id}/reply-policy", put(set_reply_policy))
+        .route("/{post_id}/visibility", put(set_visibility))
+        .route("/{post_id}/report", post(report))
+        .route(
+            "/{post_id}/attachments",
+            // The handler enforces its own, larger limit while reading the upload
+            post(upload_attachment).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/{post_id}/reactions/{kind}", put(react).delete(unreact))
+        .route("/{post_id}/bookmark", put(save).delete(unsave))
+        .route("/{post_id}/draft", get(draft).put(save_draft).delete(discard_draft))
+        .route("/{post_id}/draft/publish", post(publish_draft))
+        .route("/{post_id}/draft/schedule", post(schedule_draft))
         .route("/me", get(own_posts))
 }
 
@@ -47,10 +107,15 @@ pub fn routes() -> Router<AppState> {
             status = StatusCode::CREATED,
             description = "new post created",
         ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "the reply policy of the parent or a post above it forbids replying",
+        ),
         (
             status = StatusCode::NOT_FOUND,
             body = ErrorResponse,
-            description = "parent post not found",
+            description = "parent or quoted post not found",
         ),
         (
             status = StatusCode::CONFLICT,
@@ -60,7 +125,7 @@ pub fn routes() -> Router<AppState> {
         (
             status = StatusCode::GONE,
             body = ErrorResponse,
-            description = "parent post was deleted",
+            description = "parent or quoted post was deleted",
         ),
         (
             status = StatusCode::UNPROCESSABLE_ENTITY,
@@ -75,7 +140,13 @@ async fn create_new(
     payload: ValidatedJson<CreatePostRequest>,
 ) -> api_result!() {
     post_svc
-        .create_new(requester_id, payload.parent_id, &payload.body)
+        .create_new(
+            requester_id,
+            payload.parent_id,
+            &payload.body,
+            payload.format,
+            payload.quoted_post_id,
+        )
         .await?;
 
     Ok(StatusCode::CREATED)
@@ -86,7 +157,7 @@ async fn create_new(
     get,
     tag = "posts",
     path = "/{post_id}",
-    security(("jwt" = [])),
+    security((), ("jwt" = [])),
     responses(
         (
             status = StatusCode::OK,
@@ -97,13 +168,27 @@ async fn create_new(
             body = ErrorResponse,
             description = "requested post not found",
         ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests without logging in",
+        ),
     ),
 )]
 async fn by_post_id(
     post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    OptionalRequester(viewer_id): OptionalRequester,
     Path(post_id): Path<i32>,
 ) -> api_result!(PostResponse) {
-    Ok((StatusCode::OK, Json(post_with_author_read.by_post_id(post_id).await?.into())))
+    Ok((
+        StatusCode::OK,
+        Json(
+            post_with_author_read
+                .by_post_id(post_id, viewer_id)
+                .await?
+                .into(),
+        ),
+    ))
 }
 
 /// Retrieves the children of the post with the provided ID.
@@ -111,136 +196,1266 @@ async fn by_post_id(
     get,
     tag = "posts",
     path = "/{post_id}/children",
-    security(("jwt" = [])),
-    responses((
-        status = StatusCode::OK,
-        body = Vec<PostResponse>,
-        description = "a list of all replies to the specified post",
-    )),
+    security((), ("jwt" = [])),
+    params(ChildPostsQuery),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Vec<PostResponse>,
+            description = "a list of all replies to the specified post",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests without logging in",
+        ),
+    ),
 )]
 async fn child_posts(
     post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    OptionalRequester(viewer_id): OptionalRequester,
     Path(parent_id): Path<i32>,
+    Query(ChildPostsQuery { sort }): Query<ChildPostsQuery>,
 ) -> api_result!(Vec<PostResponse>) {
     Ok((
         StatusCode::OK,
         Json(
             post_with_author_read
-                .children_of(parent_id)
+                .children_of(parent_id, sort, viewer_id)
                 .await?
                 .map_into(),
         ),
     ))
 }
 
-/// Retrieves posts written by the user with the specified username.
+/// Retrieves a page of the posts that quote the post with the provided ID, most recent first.
 #[utoipa::path(
     get,
     tag = "posts",
-    path = "/user/{author_username}",
-    security(("jwt" = [])),
-    responses((
-        status = StatusCode::OK,
-        body = Vec<PostResponse>,
-        description = "a list of all posts written by the specified user",
-    )),
+    path = "/{post_id}/quotes",
+    security((), ("jwt" = [])),
+    params(PageQuery),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = QuotePageResponse,
+            description = "a page of posts that quote the specified post",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests without logging in",
+        ),
+    ),
 )]
-async fn specific_user_posts(
+async fn quotes(
     post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
-    Path(author_username): Path<String>,
-) -> api_result!(Vec<PostResponse>) {
-    Ok((
-        StatusCode::OK,
-        Json(
-            post_with_author_read
-                .written_by_username(&author_username)
-                .await?
-                .map_into(),
+    OptionalRequester(viewer_id): OptionalRequester,
+    Path(post_id): Path<i32>,
+    query: ValidatedQuery<PageQuery>,
+) -> api_result!(QuotePageResponse) {
+    let PageQuery { cursor, limit } = query.0;
+
+    let page = post_with_author_read
+        .quotes_of(post_id, viewer_id, cursor, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+/// Changes who may reply to one of the requester's posts.
+#[utoipa::path(
+    put,
+    tag = "posts",
+    path = "/{post_id}/reply-policy",
+    security(("jwt" = [])),
+    request_body = ReplyPolicyRequest,
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "reply policy updated",
         ),
-    ))
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "the post was written by someone else",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+    ),
+)]
+async fn set_reply_policy(
+    post_svc: State<Arc<dyn PostSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+    payload: ValidatedJson<ReplyPolicyRequest>,
+) -> api_result!(SuccessResponse) {
+    post_svc
+        .set_reply_policy(requester_id, post_id, payload.policy, payload.cascades)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Reply policy updated") })))
 }
 
-/// Retrieves the requester's own posts.
+/// Changes who may see one of the requester's posts.
 #[utoipa::path(
-    get,
+    put,
     tag = "posts",
-    path = "/me",
+    path = "/{post_id}/visibility",
     security(("jwt" = [])),
-    responses((
-        status = StatusCode::OK,
-        body = Vec<PostResponse>,
-        description = "a list of all posts written by you",
-    )),
+    request_body = VisibilityRequest,
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "visibility updated",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "the post was written by someone else",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+    ),
 )]
-async fn own_posts(
-    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+async fn set_visibility(
+    post_svc: State<Arc<dyn PostSvc>>,
     Extension(requester_id): Extension<i32>,
-) -> api_result!(Vec<PostResponse>) {
-    Ok((
-        StatusCode::OK,
-        Json(
-            post_with_author_read
-                .written_by_id(requester_id)
-                .await?
-                .map_into(),
+    Path(post_id): Path<i32>,
+    payload: ValidatedJson<VisibilityRequest>,
+) -> api_result!(SuccessResponse) {
+    post_svc
+        .set_visibility(requester_id, post_id, payload.visibility)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Visibility updated") })))
+}
+
+/// Reports a post to the moderators.
+#[utoipa::path(
+    post,
+    tag = "posts",
+    path = "/{post_id}/report",
+    security(("jwt" = [])),
+    request_body = ReportPostRequest,
+    responses(
+        (
+            status = StatusCode::CREATED,
+            description = "report submitted",
         ),
-    ))
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+        (
+            status = StatusCode::CONFLICT,
+            body = ErrorResponse,
+            description = "already reported this post",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "post was deleted",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "cannot report one's own post",
+        ),
+    ),
+)]
+async fn report(
+    post_moderation: State<Arc<dyn PostModeration>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+    payload: ValidatedJson<ReportPostRequest>,
+) -> api_result!() {
+    post_moderation
+        .report(requester_id, post_id, payload.reason)
+        .await?;
+
+    Ok(StatusCode::CREATED)
 }
 
-#[cfg(test)]
-mod tests {
-    use {
-        super::*,
-        crate::{
-            api::dto::responses::ErrorResponse,
-            domain::post::{MockPostSvc, error::PostError},
-            read_models::{MockPostWithAuthorRead, ReadError},
-            test_utils::{
-                dummy_data::post_with_author,
-                http_bodies::{deserialize_body, serialize_body},
-                tokio_test,
-            },
-        },
-        anyhow::{Result, anyhow},
-        axum::{
-            body::Body,
-            http::{Method, Request, header::CONTENT_TYPE},
-        },
-        mockall::predicate::eq,
-        tower::ServiceExt as _,
-    };
+/// Attaches an image to one of the requester's own posts. The image is re-encoded without its
+/// metadata, and a thumbnail is made from it.
+#[utoipa::path(
+    post,
+    tag = "posts",
+    path = "/{post_id}/attachments",
+    security(("jwt" = [])),
+    request_body(content = AttachmentUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (
+            status = StatusCode::CREATED,
+            body = AttachmentResponse,
+            description = "image attached",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "post belongs to another user",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "post was deleted",
+        ),
+        (
+            status = StatusCode::PAYLOAD_TOO_LARGE,
+            body = ErrorResponse,
+            description = "image file is too large",
+        ),
+        (
+            status = StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            body = ErrorResponse,
+            description = "file is not a supported kind of image",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "no file, unreadable image, image too wide or tall, post archived, or \
+                           post already has the maximum number of attachments",
+        ),
+    ),
+)]
+async fn upload_attachment(
+    attachment_svc: State<Arc<dyn AttachmentSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<AttachmentResponse>), ApiError> {
+    let max_bytes = attachment_svc.max_upload_bytes();
+    let mut data = None;
+
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        // Read in chunks to stop as soon as the limit is exceeded
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await? {
+            if bytes.len().saturating_add(chunk.len()) > max_bytes {
+                return Err(AttachmentError::TooLarge(max_bytes).into());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        data = Some(bytes);
+        break;
+    }
+
+    let attachment = attachment_svc
+        .upload(requester_id, post_id, data.ok_or(AttachmentError::MissingFile)?)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(attachment.into())))
+}
+
+/// Reacts to a post, replacing the requester's existing reaction to it if there is one.
+#[utoipa::path(
+    put,
+    tag = "posts",
+    path = "/{post_id}/reactions/{kind}",
+    security(("jwt" = [])),
+    params(("kind" = ReactionKind, Path, description = "the kind of reaction")),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "reaction set",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "post was deleted",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "cannot react to an archived post",
+        ),
+    ),
+)]
+async fn react(
+    reaction_svc: State<Arc<dyn ReactionSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path((post_id, kind)): Path<(i32, ReactionKind)>,
+) -> api_result!(SuccessResponse) {
+    reaction_svc.react(requester_id, post_id, kind).await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Reaction set") })))
+}
+
+/// Removes the requester's reaction of the specified kind from a post.
+#[utoipa::path(
+    delete,
+    tag = "posts",
+    path = "/{post_id}/reactions/{kind}",
+    security(("jwt" = [])),
+    params(("kind" = ReactionKind, Path, description = "the kind of reaction")),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "reaction removed",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found or no reaction of this kind to remove",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "post was deleted",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "cannot change reactions on an archived post",
+        ),
+    ),
+)]
+async fn unreact(
+    reaction_svc: State<Arc<dyn ReactionSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path((post_id, kind)): Path<(i32, ReactionKind)>,
+) -> api_result!(SuccessResponse) {
+    reaction_svc.unreact(requester_id, post_id, kind).await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Reaction removed") })))
+}
+
+/// Bookmarks a post for the requester to come back to later.
+#[utoipa::path(
+    put,
+    tag = "posts",
+    path = "/{post_id}/bookmark",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "post bookmarked",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "post was deleted",
+        ),
+    ),
+)]
+async fn save(
+    bookmark_svc: State<Arc<dyn BookmarkSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+) -> api_result!(SuccessResponse) {
+    bookmark_svc.save(requester_id, post_id).await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Post bookmarked") })))
+}
+
+/// Removes a post from the requester's bookmarks, even if the post was deleted.
+#[utoipa::path(
+    delete,
+    tag = "posts",
+    path = "/{post_id}/bookmark",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "bookmark removed",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post is not bookmarked",
+        ),
+    ),
+)]
+async fn unsave(
+    bookmark_svc: State<Arc<dyn BookmarkSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+) -> api_result!(SuccessResponse) {
+    bookmark_svc.unsave(requester_id, post_id).await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Bookmark removed") })))
+}
+
+/// Retrieves the requester's draft reply to a post.
+#[utoipa::path(
+    get,
+    tag = "posts",
+    path = "/{post_id}/draft",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = DraftResponse,
+            description = "the draft",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no draft reply to the post",
+        ),
+    ),
+)]
+async fn draft(
+    draft_read: State<Arc<dyn DraftRead>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+) -> api_result!(DraftResponse) {
+    Ok((StatusCode::OK, Json(draft_read.by_parent(requester_id, post_id).await?.into())))
+}
+
+/// Saves the requester's unfinished reply to a post, replacing the previous draft if there is one.
+/// Drafts are not checked like posts until they are published.
+#[utoipa::path(
+    put,
+    tag = "posts",
+    path = "/{post_id}/draft",
+    security(("jwt" = [])),
+    request_body = SaveDraftRequest,
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "draft saved",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "post not found",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "post was deleted",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "draft is too large",
+        ),
+    ),
+)]
+async fn save_draft(
+    draft_svc: State<Arc<dyn DraftSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+    payload: ValidatedJson<SaveDraftRequest>,
+) -> api_result!(SuccessResponse) {
+    draft_svc
+        .save(requester_id, post_id, &payload.body, payload.format)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Draft saved") })))
+}
+
+/// Discards the requester's draft reply to a post.
+#[utoipa::path(
+    delete,
+    tag = "posts",
+    path = "/{post_id}/draft",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "draft discarded",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no draft reply to the post",
+        ),
+    ),
+)]
+async fn discard_draft(
+    draft_svc: State<Arc<dyn DraftSvc>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+) -> api_result!(SuccessResponse) {
+    draft_svc.discard(requester_id, post_id).await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Draft discarded") })))
+}
+
+/// Publishes the requester's draft reply to a post. The draft is checked the same way as a new
+/// post, and is removed once published.
+#[utoipa::path(
+    post,
+    tag = "posts",
+    path = "/{post_id}/draft/publish",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::CREATED,
+            description = "draft published as a new post",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "the reply policy of the parent or a post above it forbids replying",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no draft reply to the post",
+        ),
+        (
+            status = StatusCode::CONFLICT,
+            body = ErrorResponse,
+            description = "duplicate reply to the same post",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "parent post was deleted",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "invalid draft body, or cannot reply to one's own post or an archived post",
+        ),
+    ),
+)]
+async fn publish_draft(
+    post_svc: State<Arc<dyn PostSvc>>,
+    draft_read: State<Arc<dyn DraftRead>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+) -> api_result!() {
+    check_draft_markdown(&draft_read, requester_id, post_id).await?;
+    post_svc.publish_draft(requester_id, post_id).await?;
+
+    Ok(StatusCode::CREATED)
+}
+
+/// Schedules the requester's draft reply to a post to be published later. The draft is checked now
+/// the same way as a new post, and checked again when it is due. If it can no longer be published
+/// then, it is returned to the drafts with the reason.
+#[utoipa::path(
+    post,
+    tag = "posts",
+    path = "/{post_id}/draft/schedule",
+    security(("jwt" = [])),
+    request_body = ScheduleDraftRequest,
+    responses(
+        (
+            status = StatusCode::ACCEPTED,
+            body = SuccessResponse,
+            description = "draft scheduled",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "the reply policy of the parent or a post above it forbids replying",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no draft reply to the post",
+        ),
+        (
+            status = StatusCode::CONFLICT,
+            body = ErrorResponse,
+            description = "duplicate reply to the same post",
+        ),
+        (
+            status = StatusCode::GONE,
+            body = ErrorResponse,
+            description = "parent post was deleted",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "invalid draft body or publish time, or cannot reply to one's own post \
+                or an archived post",
+        ),
+    ),
+)]
+async fn schedule_draft(
+    post_svc: State<Arc<dyn PostSvc>>,
+    draft_read: State<Arc<dyn DraftRead>>,
+    Extension(requester_id): Extension<i32>,
+    Path(post_id): Path<i32>,
+    payload: ValidatedJson<ScheduleDraftRequest>,
+) -> api_result!(SuccessResponse) {
+    let publish_at = DateTime::from_timestamp_millis(payload.publish_at_ms).ok_or_else(|| {
+        invalid_field(
+            "publishAtMs",
+            ValidationError::new("range").with_message("publish time is out of range".into()),
+        )
+    })?;
+
+    check_draft_markdown(&draft_read, requester_id, post_id).await?;
+    post_svc
+        .schedule_draft(requester_id, post_id, publish_at)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(SuccessResponse { message: String::from("Draft scheduled") })))
+}
+
+/// Checks the Markdown of the requester's draft reply to a post, since Markdown is checked in the
+/// API for new posts too.
+async fn check_draft_markdown(
+    draft_read: &Arc<dyn DraftRead>,
+    requester_id: i32,
+    post_id: i32,
+) -> Result<(), ApiError> {
+    let draft = draft_read.by_parent(requester_id, post_id).await?.draft;
+
+    match draft.body_format {
+        BodyFormat::Plain => Ok(()),
+        BodyFormat::Markdown => check_markdown(&draft.body).map_err(|e| invalid_field("body", e)),
+    }
+}
+
+/// Reports a problem with a field the same way that request validation does.
+fn invalid_field(field: &'static str, e: ValidationError) -> ApiError {
+    let mut errors = ValidationErrors::new();
+    errors.add(field, e);
+    errors.into()
+}
+
+/// Retrieves posts written by the user with the specified username.
+#[utoipa::path(
+    get,
+    tag = "posts",
+    path = "/user/{author_username}",
+    security((), ("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Vec<PostResponse>,
+            description = "a list of all posts written by the specified user",
+        ),
+        (
+            status = StatusCode::TOO_MANY_REQUESTS,
+            body = ErrorResponse,
+            description = "too many requests without logging in",
+        ),
+    ),
+)]
+async fn specific_user_posts(
+    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    OptionalRequester(viewer_id): OptionalRequester,
+    Path(author_username): Path<String>,
+) -> api_result!(Vec<PostResponse>) {
+    Ok((
+        StatusCode::OK,
+        Json(
+            post_with_author_read
+                .written_by_username(&author_username, viewer_id)
+                .await?
+                .map_into(),
+        ),
+    ))
+}
+
+/// Retrieves the requester's own posts.
+#[utoipa::path(
+    get,
+    tag = "posts",
+    path = "/me",
+    security(("jwt" = [])),
+    responses((
+        status = StatusCode::OK,
+        body = Vec<PostResponse>,
+        description = "a list of all posts written by you",
+    )),
+)]
+async fn own_posts(
+    post_with_author_read: State<Arc<dyn PostWithAuthorRead>>,
+    Extension(requester_id): Extension<i32>,
+) -> api_result!(Vec<PostResponse>) {
+    Ok((
+        StatusCode::OK,
+        Json(
+            post_with_author_read
+                .written_by_id(requester_id)
+                .await?
+                .map_into(),
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::dto::responses::ErrorResponse,
+            app_services::{Authenticator, MockAuthenticator, MockPostModeration},
+            domain::{
+                bookmark::{MockBookmarkSvc, error::BookmarkError},
+                moderation::error::ModerationError,
+                post::{MockPostSvc, error::PostError},
+                reaction::{MockReactionSvc, error::ReactionError},
+            },
+            models::{
+                moderation::ReportReason,
+                post::{BodyFormat, PostSort},
+            },
+            read_models::{MockPostWithAuthorRead, ReadError},
+            test_utils::{
+                dummy_data::post_with_author,
+                http_bodies::{deserialize_body, serialize_body},
+                tokio_test,
+            },
+        },
+        anyhow::{Result, anyhow},
+        axum::{
+            body::Body,
+            http::{
+                Method, Request,
+                header::{AUTHORIZATION, CONTENT_TYPE},
+            },
+        },
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    /// Authenticator that accepts any token as belonging to `requester_id`.
+    fn authenticated_as(requester_id: i32) -> Arc<dyn Authenticator> {
+        let mut mock_auth = MockAuthenticator::new();
+        mock_auth
+            .expect_validate_token()
+            .once()
+            .return_once(move |_| Ok(requester_id));
+        Arc::new(mock_auth)
+    }
+
+    mod create_new {
+        use super::*;
+
+        #[test]
+        fn reports_successfully_creating_a_post() -> Result<()> {
+            tokio_test(async {
+                let requester_id = 93;
+                let payload = CreatePostRequest {
+                    parent_id: 925,
+                    body: String::from("I want to create **this** post"),
+                    format: BodyFormat::Markdown,
+                    quoted_post_id: Some(31),
+                };
+
+                let mut mock_post_svc = MockPostSvc::new();
+                mock_post_svc
+                    .expect_create_new()
+                    .with(
+                        eq(requester_id),
+                        eq(payload.parent_id),
+                        eq(payload.body.clone()),
+                        eq(BodyFormat::Markdown),
+                        eq(Some(31)),
+                    )
+                    .once()
+                    .return_once(|_, _, _, _, _| Ok(()));
+
+                let state = AppState { post_svc: Arc::new(mock_post_svc), ..Default::default() };
+                let app = routes().with_state(state);
+
+                let mut req = Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(serialize_body(&payload)?)?;
+
+                req.extensions_mut().insert(requester_id);
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::CREATED);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let requester_id = 2052;
+                let payload = CreatePostRequest {
+                    parent_id: 275,
+                    body: String::from("This will fail"),
+                    format: BodyFormat::Plain,
+                    quoted_post_id: None,
+                };
+
+                let mut mock_post_svc = MockPostSvc::new();
+                mock_post_svc
+                    .expect_create_new()
+                    .with(
+                        eq(requester_id),
+                        eq(payload.parent_id),
+                        eq(payload.body.clone()),
+                        eq(BodyFormat::Plain),
+                        eq(None),
+                    )
+                    .once()
+                    .return_once(|_, _, _, _, _| Err(PostError::DeletedParent));
+
+                let state = AppState { post_svc: Arc::new(mock_post_svc), ..Default::default() };
+                let app = routes().with_state(state);
+
+                let mut req = Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(serialize_body(&payload)?)?;
+
+                req.extensions_mut().insert(requester_id);
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::GONE);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected =
+                    ErrorResponse { error: String::from("Cannot reply to a deleted post") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_unsupported_markdown() -> Result<()> {
+            tokio_test(async {
+                let payload = CreatePostRequest {
+                    parent_id: 275,
+                    body: String::from("# A heading"),
+                    format: BodyFormat::Markdown,
+                    quoted_post_id: None,
+                };
+
+                // The service should not be called
+                let state =
+                    AppState { post_svc: Arc::new(MockPostSvc::new()), ..Default::default() };
+                let app = routes().with_state(state);
+
+                let mut req = Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(serialize_body(&payload)?)?;
+
+                req.extensions_mut().insert(20);
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                assert!(
+                    resp_body
+                        .error
+                        .contains("markdown bodies cannot contain headings")
+                );
+
+                Ok(())
+            })
+        }
+    }
+
+    mod by_post_id {
+        use super::*;
+
+        #[test]
+        fn retrieves_the_post() -> Result<()> {
+            tokio_test(async {
+                let requester_id = 61;
+                let [_, post, _] = post_with_author::all3()?;
+                let post_clone = post.clone();
+
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_by_post_id()
+                    .with(eq(post.id), eq(Some(requester_id)))
+                    .once()
+                    .return_once(|_, _| Ok(post_clone));
+
+                let state = AppState {
+                    auth: authenticated_as(requester_id),
+                    post_with_author_read: Arc::new(mock_pwa_read),
+                    ..Default::default()
+                };
+                let app = public_routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .header(AUTHORIZATION, "Bearer some-token")
+                    .uri(format!("/{}", post.id))
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<PostResponse>(resp).await?;
+                assert_eq!(PostResponse::from(post), resp_body);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let post_id = 2414;
+
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_by_post_id()
+                    .with(eq(post_id), eq(None))
+                    .once()
+                    .return_once(|_, _| Err(ReadError::NotFound));
+
+                let state = AppState {
+                    post_with_author_read: Arc::new(mock_pwa_read),
+                    ..Default::default()
+                };
+                let app = public_routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/{post_id}"))
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected = ErrorResponse { error: String::from("Not found") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+    }
+
+    mod child_posts {
+        use super::*;
+
+        #[test]
+        fn retrieves_child_posts() -> Result<()> {
+            tokio_test(async {
+                let requester_id = 63;
+                let parent_id = 92;
+                let posts = post_with_author::all3()?; // Not actually children
+                let posts_vec = posts.to_vec();
+
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_children_of()
+                    .with(eq(parent_id), eq(PostSort::Top), eq(Some(requester_id)))
+                    .once()
+                    .return_once(move |_, _, _| Ok(posts_vec));
+
+                let state = AppState {
+                    auth: authenticated_as(requester_id),
+                    post_with_author_read: Arc::new(mock_pwa_read),
+                    ..Default::default()
+                };
+                let app = public_routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .header(AUTHORIZATION, "Bearer some-token")
+                    .uri(format!("/{parent_id}/children?sort=top"))
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<Vec<PostResponse>>(resp).await?;
+                assert_eq!(posts.map_into::<Vec<PostResponse>>(), resp_body);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let parent_id = 257;
+
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_children_of()
+                    .with(eq(parent_id), eq(PostSort::New), eq(None))
+                    .once()
+                    .return_once(move |_, _, _| {
+                        Err(ReadError::Technical(anyhow!("bad things happened!")))
+                    });
+
+                let state = AppState {
+                    post_with_author_read: Arc::new(mock_pwa_read),
+                    ..Default::default()
+                };
+                let app = public_routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/{parent_id}/children"))
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected = ErrorResponse { error: String::from("internal server error") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+    }
+
+    mod quotes {
+        use {
+            super::*,
+            crate::{
+                api::dto::responses::QuotedPostResponse,
+                models::post::{PostCursor, QuotePage, QuotedPost},
+            },
+            sqlx::types::Json as SqlxJson,
+        };
+
+        #[test]
+        fn retrieves_a_page_with_previews_and_the_next_cursor() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, quoted_post_id) = (65, 999);
+                let mut quoting = post_with_author::number1()?;
+                quoting.quoted = Some(SqlxJson(QuotedPost {
+                    id: quoted_post_id,
+                    author_username: None,
+                    excerpt: Some(String::from("one two three")),
+                    hidden: false,
+                }));
+                let cursor = PostCursor::of(&quoting);
+                let quoting_clone = quoting.clone();
+
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_quotes_of()
+                    .with(eq(quoted_post_id), eq(Some(requester_id)), eq(Some(cursor)), eq(5))
+                    .once()
+                    .return_once(move |_, _, _, _| {
+                        Ok(QuotePage { posts: vec![quoting_clone], next_cursor: Some(cursor) })
+                    });
+
+                let state = AppState {
+                    auth: authenticated_as(requester_id),
+                    post_with_author_read: Arc::new(mock_pwa_read),
+                    ..Default::default()
+                };
+                let app = public_routes().with_state(state);
+
+                let req = Request::builder()
+                    .method(Method::GET)
+                    .header(AUTHORIZATION, "Bearer some-token")
+                    .uri(format!("/{quoted_post_id}/quotes?cursor={cursor}&limit=5"))
+                    .body(Body::empty())?;
+
+                let resp = app.oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<QuotePageResponse>(resp).await?;
+                assert_eq!(
+                    Some(&QuotedPostResponse {
+                        id: quoted_post_id,
+                        author_username: String::from("[deleted]"),
+                        excerpt: String::from("one two three"),
+                    }),
+                    resp_body
+                        .posts
+                        .first()
+                        .and_then(|post| post.quoted.as_ref()),
+                );
+                assert_eq!(
+                    QuotePageResponse {
+                        posts: vec![PostResponse::from(quoting)],
+                        next_cursor: Some(cursor.to_string()),
+                    },
+                    resp_body,
+                );
+
+                Ok(())
+            })
+        }
+    }
+
+    mod set_reply_policy {
+        use {super::*, crate::models::post::ReplyPolicy};
+
+        /// Sends a request to change the reply policy of the post with ID 24.
+        async fn send_req(
+            mock_post_svc: MockPostSvc,
+            requester_id: i32,
+            body: Body,
+        ) -> Result<axum::response::Response> {
+            let state = AppState { post_svc: Arc::new(mock_post_svc), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(Method::PUT)
+                .uri("/24/reply-policy")
+                .header(CONTENT_TYPE, "application/json")
+                .body(body)?;
+
+            req.extensions_mut().insert(requester_id);
+
+            Ok(app.oneshot(req).await?)
+        }
+
+        #[test]
+        fn sets_the_policy_without_cascading_by_default() -> Result<()> {
+            tokio_test(async {
+                let mut mock_post_svc = MockPostSvc::new();
+                mock_post_svc
+                    .expect_set_reply_policy()
+                    .with(eq(255), eq(24), eq(ReplyPolicy::Friends), eq(false))
+                    .once()
+                    .return_once(|_, _, _, _| Ok(()));
+
+                let resp =
+                    send_req(mock_post_svc, 255, Body::from(r#"{"policy":"friends"}"#)).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Reply policy updated") },
+                    deserialize_body::<SuccessResponse>(resp).await?,
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn forbids_changing_other_users_posts() -> Result<()> {
+            tokio_test(async {
+                let mut mock_post_svc = MockPostSvc::new();
+                mock_post_svc
+                    .expect_set_reply_policy()
+                    .with(eq(256), eq(24), eq(ReplyPolicy::Nobody), eq(true))
+                    .once()
+                    .return_once(|_, _, _, _| Err(PostError::NotAuthor));
+
+                let resp = send_req(
+                    mock_post_svc,
+                    256,
+                    serialize_body(&ReplyPolicyRequest {
+                        policy: ReplyPolicy::Nobody,
+                        cascades: true,
+                    })?,
+                )
+                .await?;
+                assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+                Ok(())
+            })
+        }
+    }
 
-    mod create_new {
-        use super::*;
+    mod set_visibility {
+        use {super::*, crate::models::post::PostVisibility};
 
         #[test]
-        fn reports_successfully_creating_a_post() -> Result<()> {
+        fn sets_the_visibility_of_the_requesters_post() -> Result<()> {
             tokio_test(async {
-                let requester_id = 93;
-                let payload = CreatePostRequest {
-                    parent_id: 925,
-                    body: String::from("I want to create this post"),
-                };
-
                 let mut mock_post_svc = MockPostSvc::new();
                 mock_post_svc
-                    .expect_create_new()
-                    .with(eq(requester_id), eq(payload.parent_id), eq(payload.body.clone()))
+                    .expect_set_visibility()
+                    .with(eq(255), eq(24), eq(PostVisibility::Participants))
                     .once()
                     .return_once(|_, _, _| Ok(()));
 
                 let state = AppState { post_svc: Arc::new(mock_post_svc), ..Default::default() };
-                let app = routes().with_state(state);
-
                 let mut req = Request::builder()
-                    .method(Method::POST)
-                    .uri("/")
+                    .method(Method::PUT)
+                    .uri("/24/visibility")
                     .header(CONTENT_TYPE, "application/json")
-                    .body(serialize_body(&payload)?)?;
+                    .body(Body::from(r#"{"visibility":"participants"}"#))?;
+                req.extensions_mut().insert(255);
 
-                req.extensions_mut().insert(requester_id);
+                let resp = routes().with_state(state).oneshot(req).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Visibility updated") },
+                    deserialize_body::<SuccessResponse>(resp).await?,
+                );
 
-                let resp = app.oneshot(req).await?;
+                Ok(())
+            })
+        }
+    }
+
+    mod report {
+        use super::*;
+
+        /// Sends a request to report the post with the provided ID as the requester.
+        async fn send_req(
+            mock_post_moderation: MockPostModeration,
+            requester_id: i32,
+            post_id: i32,
+            payload: &ReportPostRequest,
+        ) -> Result<axum::response::Response> {
+            let state =
+                AppState { post_moderation: Arc::new(mock_post_moderation), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri(format!("/{post_id}/report"))
+                .header(CONTENT_TYPE, "application/json")
+                .body(serialize_body(payload)?)?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
+        #[test]
+        fn reports_successfully_reporting_a_post() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, post_id) = (52, 316);
+                let payload = ReportPostRequest { reason: ReportReason::Spam };
+
+                let mut mock_post_moderation = MockPostModeration::new();
+                mock_post_moderation
+                    .expect_report()
+                    .with(eq(requester_id), eq(post_id), eq(ReportReason::Spam))
+                    .once()
+                    .return_once(|_, _, _| Ok(()));
+
+                let resp = send_req(mock_post_moderation, requester_id, post_id, &payload).await?;
                 assert_eq!(resp.status(), StatusCode::CREATED);
 
                 Ok(())
@@ -250,34 +1465,240 @@ mod tests {
         #[test]
         fn translates_errors() -> Result<()> {
             tokio_test(async {
-                let requester_id = 2052;
-                let payload =
-                    CreatePostRequest { parent_id: 275, body: String::from("This will fail") };
+                let (requester_id, post_id) = (53, 317);
+                let payload = ReportPostRequest { reason: ReportReason::Hate };
 
-                let mut mock_post_svc = MockPostSvc::new();
-                mock_post_svc
-                    .expect_create_new()
-                    .with(eq(requester_id), eq(payload.parent_id), eq(payload.body.clone()))
+                let mut mock_post_moderation = MockPostModeration::new();
+                mock_post_moderation
+                    .expect_report()
+                    .with(eq(requester_id), eq(post_id), eq(ReportReason::Hate))
                     .once()
-                    .return_once(|_, _, _| Err(PostError::DeletedParent));
+                    .return_once(|_, _, _| Err(ModerationError::AlreadyReported));
 
-                let state = AppState { post_svc: Arc::new(mock_post_svc), ..Default::default() };
-                let app = routes().with_state(state);
+                let resp = send_req(mock_post_moderation, requester_id, post_id, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::CONFLICT);
 
-                let mut req = Request::builder()
-                    .method(Method::POST)
-                    .uri("/")
-                    .header(CONTENT_TYPE, "application/json")
-                    .body(serialize_body(&payload)?)?;
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected = ErrorResponse {
+                    error: String::from(
+                        "Already reported this post. A moderator will review it soon.",
+                    ),
+                };
+                assert_eq!(expected, resp_body);
 
-                req.extensions_mut().insert(requester_id);
+                Ok(())
+            })
+        }
+    }
 
-                let resp = app.oneshot(req).await?;
-                assert_eq!(resp.status(), StatusCode::GONE);
+    mod upload_attachment {
+        use {
+            super::*,
+            crate::{domain::attachment::MockAttachmentSvc, models::attachment::Attachment},
+            chrono::Utc,
+        };
+
+        const BOUNDARY: &str = "spur-test-boundary";
+
+        /// Sends a multipart request with one field to attach an image to the post with the
+        /// provided ID as the requester.
+        async fn send_req(
+            mock_attachment_svc: MockAttachmentSvc,
+            requester_id: i32,
+            post_id: i32,
+            field_name: &str,
+            data: &[u8],
+        ) -> Result<axum::response::Response> {
+            let state =
+                AppState { attachment_svc: Arc::new(mock_attachment_svc), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let body = [
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{field_name}\"; \
+                     filename=\"photo.png\"\r\nContent-Type: image/png\r\n\r\n"
+                )
+                .as_bytes(),
+                data,
+                format!("\r\n--{BOUNDARY}--\r\n").as_bytes(),
+            ]
+            .concat();
+
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri(format!("/{post_id}/attachments"))
+                .header(CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+                .body(Body::from(body))?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
+        #[test]
+        fn responds_with_the_new_attachment() -> Result<()> {
+            tokio_test(async {
+                let mut mock_attachment_svc = MockAttachmentSvc::new();
+                mock_attachment_svc
+                    .expect_max_upload_bytes()
+                    .return_const(100_usize);
+                mock_attachment_svc
+                    .expect_upload()
+                    .with(eq(61), eq(320), eq(vec![1, 2, 3]))
+                    .once()
+                    .return_once(|_, post_id, _| {
+                        Ok(Attachment {
+                            id: 9,
+                            post_id,
+                            content_type: String::from("image/png"),
+                            width: 64,
+                            height: 48,
+                            byte_size: 3,
+                            storage_key: String::from("attachments/a.png"),
+                            thumbnail_key: String::from("attachments/a_thumb.png"),
+                            created_at: Utc::now(),
+                        })
+                    });
+
+                let resp = send_req(mock_attachment_svc, 61, 320, "file", &[1, 2, 3]).await?;
+                assert_eq!(resp.status(), StatusCode::CREATED);
+
+                let expected = AttachmentResponse {
+                    id: 9,
+                    content_type: String::from("image/png"),
+                    width: 64,
+                    height: 48,
+                    url: String::from("/attachments/9"),
+                    thumbnail_url: String::from("/attachments/9/thumbnail"),
+                };
+                assert_eq!(expected, deserialize_body::<AttachmentResponse>(resp).await?);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_missing_and_oversized_files_without_processing_them() -> Result<()> {
+            tokio_test(async {
+                for (field_name, data, status) in [
+                    ("image", vec![1, 2, 3], StatusCode::UNPROCESSABLE_ENTITY),
+                    ("file", vec![0; 101], StatusCode::PAYLOAD_TOO_LARGE),
+                ] {
+                    // The upload should not be passed on to the service
+                    let mut mock_attachment_svc = MockAttachmentSvc::new();
+                    mock_attachment_svc
+                        .expect_max_upload_bytes()
+                        .return_const(100_usize);
+
+                    let resp = send_req(mock_attachment_svc, 61, 320, field_name, &data).await?;
+                    assert_eq!(resp.status(), status);
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    mod reactions {
+        use super::*;
+
+        /// Sends a request to react to (or unreact from) the post with the provided ID as the
+        /// requester.
+        async fn send_req(
+            mock_reaction_svc: MockReactionSvc,
+            method: Method,
+            requester_id: i32,
+            uri: &str,
+        ) -> Result<axum::response::Response> {
+            let state =
+                AppState { reaction_svc: Arc::new(mock_reaction_svc), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
+        #[test]
+        fn reports_successfully_reacting_and_unreacting() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, post_id) = (71, 318);
+                let uri = format!("/{post_id}/reactions/laugh");
+
+                let mut mock_react_svc = MockReactionSvc::new();
+                mock_react_svc
+                    .expect_react()
+                    .with(eq(requester_id), eq(post_id), eq(ReactionKind::Laugh))
+                    .once()
+                    .return_once(|_, _, _| Ok(()));
+
+                let react_resp = send_req(mock_react_svc, Method::PUT, requester_id, &uri).await?;
+                assert_eq!(react_resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Reaction set") },
+                    deserialize_body::<SuccessResponse>(react_resp).await?,
+                );
+
+                let mut mock_unreact_svc = MockReactionSvc::new();
+                mock_unreact_svc
+                    .expect_unreact()
+                    .with(eq(requester_id), eq(post_id), eq(ReactionKind::Laugh))
+                    .once()
+                    .return_once(|_, _, _| Ok(()));
+
+                let unreact_resp =
+                    send_req(mock_unreact_svc, Method::DELETE, requester_id, &uri).await?;
+                assert_eq!(unreact_resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Reaction removed") },
+                    deserialize_body::<SuccessResponse>(unreact_resp).await?,
+                );
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_unknown_kinds() -> Result<()> {
+            tokio_test(async {
+                // The service should not be called
+                let resp =
+                    send_req(MockReactionSvc::new(), Method::PUT, 72, "/319/reactions/meh").await?;
+                assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, post_id) = (73, 320);
+
+                let mut mock_reaction_svc = MockReactionSvc::new();
+                mock_reaction_svc
+                    .expect_unreact()
+                    .with(eq(requester_id), eq(post_id), eq(ReactionKind::Sad))
+                    .once()
+                    .return_once(|_, _, _| Err(ReactionError::ArchivedPost));
+
+                let resp = send_req(
+                    mock_reaction_svc,
+                    Method::DELETE,
+                    requester_id,
+                    &format!("/{post_id}/reactions/sad"),
+                )
+                .await?;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
                 let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
                 let expected =
-                    ErrorResponse { error: String::from("Cannot reply to a deleted post") };
+                    ErrorResponse { error: String::from("Cannot react to an archived post") };
                 assert_eq!(expected, resp_body);
 
                 Ok(())
@@ -285,147 +1706,390 @@ mod tests {
         }
     }
 
-    mod by_post_id {
+    mod bookmarks {
         use super::*;
 
+        /// Sends a request to bookmark (or unbookmark) the post with the provided ID as the
+        /// requester.
+        async fn send_req(
+            mock_bookmark_svc: MockBookmarkSvc,
+            method: Method,
+            requester_id: i32,
+            post_id: i32,
+        ) -> Result<axum::response::Response> {
+            let state =
+                AppState { bookmark_svc: Arc::new(mock_bookmark_svc), ..Default::default() };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(method)
+                .uri(format!("/{post_id}/bookmark"))
+                .body(Body::empty())?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
         #[test]
-        fn retrieves_the_post() -> Result<()> {
+        fn reports_successfully_saving_and_unsaving() -> Result<()> {
             tokio_test(async {
-                let [_, post, _] = post_with_author::all3()?;
-                let post_clone = post.clone();
+                let (requester_id, post_id) = (74, 321);
 
-                let mut mock_pwa_read = MockPostWithAuthorRead::new();
-                mock_pwa_read
-                    .expect_by_post_id()
-                    .with(eq(post.id))
+                let mut mock_save_svc = MockBookmarkSvc::new();
+                mock_save_svc
+                    .expect_save()
+                    .with(eq(requester_id), eq(post_id))
                     .once()
-                    .return_once(|_| Ok(post_clone));
+                    .return_once(|_, _| Ok(()));
+
+                let save_resp = send_req(mock_save_svc, Method::PUT, requester_id, post_id).await?;
+                assert_eq!(save_resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Post bookmarked") },
+                    deserialize_body::<SuccessResponse>(save_resp).await?,
+                );
+
+                let mut mock_unsave_svc = MockBookmarkSvc::new();
+                mock_unsave_svc
+                    .expect_unsave()
+                    .with(eq(requester_id), eq(post_id))
+                    .once()
+                    .return_once(|_, _| Ok(()));
 
-                let state = AppState {
-                    post_with_author_read: Arc::new(mock_pwa_read),
-                    ..Default::default()
-                };
-                let app = routes().with_state(state);
+                let unsave_resp =
+                    send_req(mock_unsave_svc, Method::DELETE, requester_id, post_id).await?;
+                assert_eq!(unsave_resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Bookmark removed") },
+                    deserialize_body::<SuccessResponse>(unsave_resp).await?,
+                );
 
-                let req = Request::builder()
-                    .method(Method::GET)
-                    .uri(format!("/{}", post.id))
-                    .body(Body::empty())?;
+                Ok(())
+            })
+        }
 
-                let resp = app.oneshot(req).await?;
-                assert_eq!(resp.status(), StatusCode::OK);
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, post_id) = (75, 322);
 
-                let resp_body = deserialize_body::<PostResponse>(resp).await?;
-                assert_eq!(PostResponse::from(post), resp_body);
+                let mut mock_bookmark_svc = MockBookmarkSvc::new();
+                mock_bookmark_svc
+                    .expect_save()
+                    .with(eq(requester_id), eq(post_id))
+                    .once()
+                    .return_once(|_, _| Err(BookmarkError::DeletedPost));
+
+                let resp = send_req(mock_bookmark_svc, Method::PUT, requester_id, post_id).await?;
+                assert_eq!(resp.status(), StatusCode::GONE);
+
+                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+                let expected =
+                    ErrorResponse { error: String::from("Cannot bookmark a deleted post") };
+                assert_eq!(expected, resp_body);
 
                 Ok(())
             })
         }
+    }
+
+    mod drafts {
+        use {
+            super::*,
+            crate::{
+                domain::draft::{MockDraftSvc, error::DraftError},
+                read_models::MockDraftRead,
+                test_utils::dummy_data,
+            },
+            anyhow::Context as _,
+        };
+
+        /// Sends a request concerning the requester's draft reply to the post with the provided ID.
+        async fn send_req(
+            state: AppState,
+            method: Method,
+            requester_id: i32,
+            uri: &str,
+            body: Body,
+        ) -> Result<axum::response::Response> {
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body)?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
 
         #[test]
-        fn translates_errors() -> Result<()> {
+        fn saves_retrieves_and_discards_drafts() -> Result<()> {
             tokio_test(async {
-                let post_id = 2414;
+                let (requester_id, post_id) = (255, 42);
+                let draft = dummy_data::draft::number1()?;
+                let expected = DraftResponse::from(draft.clone());
+                let payload =
+                    SaveDraftRequest { body: String::from("half of"), format: BodyFormat::Plain };
+
+                let mut mock_draft_svc = MockDraftSvc::new();
+                mock_draft_svc
+                    .expect_save()
+                    .with(
+                        eq(requester_id),
+                        eq(post_id),
+                        eq(payload.body.clone()),
+                        eq(BodyFormat::Plain),
+                    )
+                    .once()
+                    .return_once(|_, _, _, _| Ok(()));
+                mock_draft_svc
+                    .expect_discard()
+                    .with(eq(requester_id), eq(post_id))
+                    .once()
+                    .return_once(|_, _| Ok(()));
 
-                let mut mock_pwa_read = MockPostWithAuthorRead::new();
-                mock_pwa_read
-                    .expect_by_post_id()
-                    .with(eq(post_id))
+                let mut mock_draft_read = MockDraftRead::new();
+                mock_draft_read
+                    .expect_by_parent()
+                    .with(eq(requester_id), eq(post_id))
                     .once()
-                    .return_once(|_| Err(ReadError::NotFound));
+                    .return_once(|_, _| Ok(draft));
 
                 let state = AppState {
-                    post_with_author_read: Arc::new(mock_pwa_read),
+                    draft_svc: Arc::new(mock_draft_svc),
+                    draft_read: Arc::new(mock_draft_read),
                     ..Default::default()
                 };
-                let app = routes().with_state(state);
 
-                let req = Request::builder()
-                    .method(Method::GET)
-                    .uri(format!("/{post_id}"))
-                    .body(Body::empty())?;
+                let save_resp = send_req(
+                    state.clone(),
+                    Method::PUT,
+                    requester_id,
+                    "/42/draft",
+                    serialize_body(&payload)?,
+                )
+                .await?;
+                assert_eq!(save_resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Draft saved") },
+                    deserialize_body::<SuccessResponse>(save_resp).await?,
+                );
+
+                let get_resp =
+                    send_req(state.clone(), Method::GET, requester_id, "/42/draft", Body::empty())
+                        .await?;
+                assert_eq!(get_resp.status(), StatusCode::OK);
+                assert_eq!(expected, deserialize_body::<DraftResponse>(get_resp).await?);
+
+                let discard_resp =
+                    send_req(state, Method::DELETE, requester_id, "/42/draft", Body::empty())
+                        .await?;
+                assert_eq!(discard_resp.status(), StatusCode::OK);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Draft discarded") },
+                    deserialize_body::<SuccessResponse>(discard_resp).await?,
+                );
 
-                let resp = app.oneshot(req).await?;
-                assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+                Ok(())
+            })
+        }
 
-                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
-                let expected = ErrorResponse { error: String::from("Not found") };
-                assert_eq!(expected, resp_body);
+        #[test]
+        fn publishes_drafts() -> Result<()> {
+            tokio_test(async {
+                let (requester_id, post_id) = (255, 42);
+                let draft = dummy_data::draft::number1()?;
+
+                let mut mock_draft_read = MockDraftRead::new();
+                mock_draft_read
+                    .expect_by_parent()
+                    .with(eq(requester_id), eq(post_id))
+                    .once()
+                    .return_once(|_, _| Ok(draft));
+
+                let mut mock_post_svc = MockPostSvc::new();
+                mock_post_svc
+                    .expect_publish_draft()
+                    .with(eq(requester_id), eq(post_id))
+                    .once()
+                    .return_once(|_, _| Ok(()));
+
+                let state = AppState {
+                    post_svc: Arc::new(mock_post_svc),
+                    draft_read: Arc::new(mock_draft_read),
+                    ..Default::default()
+                };
+
+                let resp =
+                    send_req(state, Method::POST, requester_id, "/42/draft/publish", Body::empty())
+                        .await?;
+                assert_eq!(resp.status(), StatusCode::CREATED);
 
                 Ok(())
             })
         }
-    }
-
-    mod child_posts {
-        use super::*;
 
         #[test]
-        fn retrieves_child_posts() -> Result<()> {
+        fn schedules_drafts() -> Result<()> {
             tokio_test(async {
-                let parent_id = 92;
-                let posts = post_with_author::all3()?; // Not actually children
-                let posts_vec = posts.to_vec();
+                let (requester_id, post_id) = (255, 42);
+                let draft = dummy_data::draft::number1()?;
+                let publish_at_ms = 1_800_000_000_000;
+
+                let mut mock_draft_read = MockDraftRead::new();
+                mock_draft_read
+                    .expect_by_parent()
+                    .with(eq(requester_id), eq(post_id))
+                    .once()
+                    .return_once(|_, _| Ok(draft));
 
-                let mut mock_pwa_read = MockPostWithAuthorRead::new();
-                mock_pwa_read
-                    .expect_children_of()
-                    .with(eq(parent_id))
+                let mut mock_post_svc = MockPostSvc::new();
+                mock_post_svc
+                    .expect_schedule_draft()
+                    .with(
+                        eq(requester_id),
+                        eq(post_id),
+                        eq(DateTime::from_timestamp_millis(publish_at_ms)
+                            .context("out of range")?),
+                    )
                     .once()
-                    .return_once(move |_| Ok(posts_vec));
+                    .return_once(|_, _, _| Ok(()));
 
                 let state = AppState {
-                    post_with_author_read: Arc::new(mock_pwa_read),
+                    post_svc: Arc::new(mock_post_svc),
+                    draft_read: Arc::new(mock_draft_read),
                     ..Default::default()
                 };
-                let app = routes().with_state(state);
 
-                let req = Request::builder()
-                    .method(Method::GET)
-                    .uri(format!("/{parent_id}/children"))
-                    .body(Body::empty())?;
+                let resp = send_req(
+                    state,
+                    Method::POST,
+                    requester_id,
+                    "/42/draft/schedule",
+                    serialize_body(&ScheduleDraftRequest { publish_at_ms })?,
+                )
+                .await?;
+                assert_eq!(resp.status(), StatusCode::ACCEPTED);
+                assert_eq!(
+                    SuccessResponse { message: String::from("Draft scheduled") },
+                    deserialize_body::<SuccessResponse>(resp).await?,
+                );
 
-                let resp = app.oneshot(req).await?;
-                assert_eq!(resp.status(), StatusCode::OK);
+                Ok(())
+            })
+        }
 
-                let resp_body = deserialize_body::<Vec<PostResponse>>(resp).await?;
-                assert_eq!(posts.map_into::<Vec<PostResponse>>(), resp_body);
+        #[test]
+        fn rejects_out_of_range_publish_times() -> Result<()> {
+            tokio_test(async {
+                // Neither the draft nor the post service should be looked at
+                let state = AppState {
+                    post_svc: Arc::new(MockPostSvc::new()),
+                    draft_read: Arc::new(MockDraftRead::new()),
+                    ..Default::default()
+                };
+
+                let resp = send_req(
+                    state,
+                    Method::POST,
+                    255,
+                    "/42/draft/schedule",
+                    serialize_body(&ScheduleDraftRequest { publish_at_ms: i64::MAX })?,
+                )
+                .await?;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
                 Ok(())
             })
         }
 
         #[test]
-        fn translates_errors() -> Result<()> {
+        fn rejects_publishing_unsupported_markdown() -> Result<()> {
             tokio_test(async {
-                let parent_id = 257;
+                let mut draft = dummy_data::draft::number1()?;
+                draft.draft.body = String::from("# A heading");
 
-                let mut mock_pwa_read = MockPostWithAuthorRead::new();
-                mock_pwa_read
-                    .expect_children_of()
-                    .with(eq(parent_id))
+                let mut mock_draft_read = MockDraftRead::new();
+                mock_draft_read
+                    .expect_by_parent()
+                    .with(eq(255), eq(42))
                     .once()
-                    .return_once(move |_| {
-                        Err(ReadError::Technical(anyhow!("bad things happened!")))
-                    });
+                    .return_once(|_, _| Ok(draft));
 
+                // The post service should not be called
                 let state = AppState {
-                    post_with_author_read: Arc::new(mock_pwa_read),
+                    post_svc: Arc::new(MockPostSvc::new()),
+                    draft_read: Arc::new(mock_draft_read),
                     ..Default::default()
                 };
-                let app = routes().with_state(state);
 
-                let req = Request::builder()
-                    .method(Method::GET)
-                    .uri(format!("/{parent_id}/children"))
-                    .body(Body::empty())?;
+                let resp =
+                    send_req(state, Method::POST, 255, "/42/draft/publish", Body::empty()).await?;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
-                let resp = app.oneshot(req).await?;
-                assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+                Ok(())
+            })
+        }
 
-                let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
-                let expected = ErrorResponse { error: String::from("internal server error") };
-                assert_eq!(expected, resp_body);
+        #[test]
+        fn translates_errors() -> Result<()> {
+            tokio_test(async {
+                let mut mock_draft_svc = MockDraftSvc::new();
+                mock_draft_svc
+                    .expect_save()
+                    .once()
+                    .return_once(|_, _, _, _| Err(DraftError::DeletedParent));
+                mock_draft_svc
+                    .expect_discard()
+                    .once()
+                    .return_once(|_, _| Err(DraftError::NotFound));
+
+                let mut mock_draft_read = MockDraftRead::new();
+                mock_draft_read
+                    .expect_by_parent()
+                    .once()
+                    .return_once(|_, _| Err(ReadError::NotFound));
+
+                let state = AppState {
+                    draft_svc: Arc::new(mock_draft_svc),
+                    draft_read: Arc::new(mock_draft_read),
+                    ..Default::default()
+                };
+
+                let payload = SaveDraftRequest { body: String::new(), format: BodyFormat::Plain };
+                for (method, uri, body, status, error) in [
+                    (
+                        Method::PUT,
+                        "/43/draft",
+                        serialize_body(&payload)?,
+                        StatusCode::GONE,
+                        "Cannot reply to a deleted post",
+                    ),
+                    (
+                        Method::DELETE,
+                        "/43/draft",
+                        Body::empty(),
+                        StatusCode::NOT_FOUND,
+                        "No draft found",
+                    ),
+                    (
+                        Method::POST,
+                        "/43/draft/publish",
+                        Body::empty(),
+                        StatusCode::NOT_FOUND,
+                        "Not found",
+                    ),
+                ] {
+                    let resp = send_req(state.clone(), method, 256, uri, body).await?;
+                    assert_eq!(resp.status(), status);
+
+                    let expected = ErrorResponse { error: String::from(error) };
+                    assert_eq!(expected, deserialize_body::<ErrorResponse>(resp).await?);
+                }
 
                 Ok(())
             })
@@ -438,6 +2102,7 @@ mod tests {
         #[test]
         fn retrieves_posts_by_a_user() -> Result<()> {
             tokio_test(async {
+                let requester_id = 81;
                 let posts = post_with_author::all3()?;
                 let posts_vec = posts.to_vec();
                 let author_username = String::from("some_user");
@@ -445,18 +2110,20 @@ mod tests {
                 let mut mock_pwa_read = MockPostWithAuthorRead::new();
                 mock_pwa_read
                     .expect_written_by_username()
-                    .with(eq(author_username.clone()))
+                    .with(eq(author_username.clone()), eq(Some(requester_id)))
                     .once()
-                    .return_once(|_| Ok(posts_vec));
+                    .return_once(|_, _| Ok(posts_vec));
 
                 let state = AppState {
+                    auth: authenticated_as(requester_id),
                     post_with_author_read: Arc::new(mock_pwa_read),
                     ..Default::default()
                 };
-                let app = routes().with_state(state);
+                let app = public_routes().with_state(state);
 
                 let req = Request::builder()
                     .method(Method::GET)
+                    .header(AUTHORIZATION, "Bearer some-token")
                     .uri(format!("/user/{author_username}"))
                     .body(Body::empty())?;
 
@@ -478,15 +2145,15 @@ mod tests {
                 let mut mock_pwa_read = MockPostWithAuthorRead::new();
                 mock_pwa_read
                     .expect_written_by_username()
-                    .with(eq(username))
+                    .with(eq(username), eq(None))
                     .once()
-                    .return_once(|_| Err(ReadError::Technical(anyhow!("oh no!"))));
+                    .return_once(|_, _| Err(ReadError::Technical(anyhow!("oh no!"))));
 
                 let state = AppState {
                     post_with_author_read: Arc::new(mock_pwa_read),
                     ..Default::default()
                 };
-                let app = routes().with_state(state);
+                let app = public_routes().with_state(state);
 
                 let req = Request::builder()
                     .method(Method::GET)
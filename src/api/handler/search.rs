@@ -0,0 +1,164 @@
+use {
+    super::{DEFAULT_PAGE_SIZE, api_result},
+    crate::{
+        api::{
+            dto::{requests::SearchQuery, responses::SearchResponse},
+            validated_query::ValidatedQuery,
+        },
+        models::search::SearchKind,
+        read_models::SearchRead,
+        state::AppState,
+    },
+    axum::{Extension, Json, Router, extract::State, http::StatusCode, routing::get},
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(search))]
+pub struct SearchDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/", get(search)) }
+
+/// Searches posts by content or users by username and name, best match first.
+#[utoipa::path(
+    get,
+    tag = "search",
+    path = "",
+    security(("jwt" = [])),
+    params(SearchQuery),
+    responses((
+        status = StatusCode::OK,
+        body = SearchResponse,
+        description = "a page of search results",
+    )),
+)]
+async fn search(
+    search_read: State<Arc<dyn SearchRead>>,
+    Extension(requester_id): Extension<i32>,
+    query: ValidatedQuery<SearchQuery>,
+) -> api_result!(SearchResponse) {
+    let SearchQuery { q, kind, offset: maybe_offset, limit: maybe_limit } = query.0;
+    let (offset, limit) = (maybe_offset.unwrap_or(0), maybe_limit.unwrap_or(DEFAULT_PAGE_SIZE));
+
+    let results = match kind {
+        SearchKind::Posts => search_read
+            .posts(&q, requester_id, offset, limit)
+            .await?
+            .into(),
+        SearchKind::Users => search_read.users(&q, offset, limit).await?.into(),
+    };
+
+    Ok((StatusCode::OK, Json(results)))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::dto::responses::UserSearchResultResponse,
+            map_into::MapInto as _,
+            models::search::{SearchPage, UserSearchResult},
+            read_models::MockSearchRead,
+            test_utils::{dummy_data::post_with_author, http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::Result,
+        axum::{body::Body, http::Request},
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    /// Sends a search request as the requester.
+    async fn send_req(
+        mock_search_read: MockSearchRead,
+        requester_id: i32,
+        uri: &str,
+    ) -> Result<axum::response::Response> {
+        let state = AppState { search_read: Arc::new(mock_search_read), ..Default::default() };
+        let app = routes().with_state(state);
+
+        let mut req = Request::builder().uri(uri).body(Body::empty())?;
+        req.extensions_mut().insert(requester_id);
+
+        Ok(app.oneshot(req).await?)
+    }
+
+    #[test]
+    fn searches_posts_by_default() -> Result<()> {
+        tokio_test(async {
+            let posts = post_with_author::all3()?;
+            let posts_vec = posts.to_vec();
+
+            let mut mock_search_read = MockSearchRead::new();
+            mock_search_read
+                .expect_posts()
+                .with(eq("rust traits"), eq(6), eq(0), eq(DEFAULT_PAGE_SIZE))
+                .once()
+                .return_once(|_, _, _, _| {
+                    Ok(SearchPage { results: posts_vec, next_offset: Some(20) })
+                });
+
+            let resp = send_req(mock_search_read, 6, "/?q=rust%20traits").await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let expected =
+                SearchResponse::Posts { results: posts.map_into(), next_offset: Some(20) };
+            assert_eq!(expected, deserialize_body::<SearchResponse>(resp).await?);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn searches_users_with_the_offset_and_limit() -> Result<()> {
+        tokio_test(async {
+            let mut mock_search_read = MockSearchRead::new();
+            mock_search_read
+                .expect_users()
+                .with(eq("lee"), eq(10), eq(5))
+                .once()
+                .return_once(|_, _, _| {
+                    Ok(SearchPage {
+                        results: vec![UserSearchResult {
+                            username: String::from("you_n_15"),
+                            name: String::from("Eunice Lee"),
+                        }],
+                        next_offset: None,
+                    })
+                });
+
+            let resp =
+                send_req(mock_search_read, 6, "/?q=lee&type=users&offset=10&limit=5").await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let expected = SearchResponse::Users {
+                results: vec![UserSearchResultResponse {
+                    username: String::from("you_n_15"),
+                    name: String::from("Eunice Lee"),
+                }],
+                next_offset: None,
+            };
+            assert_eq!(expected, deserialize_body::<SearchResponse>(resp).await?);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_invalid_queries() -> Result<()> {
+        tokio_test(async {
+            for (uri, expected_status) in [
+                ("/", StatusCode::BAD_REQUEST),
+                ("/?q=a&type=groups", StatusCode::BAD_REQUEST),
+                ("/?q=", StatusCode::UNPROCESSABLE_ENTITY),
+                ("/?q=a&offset=-1", StatusCode::UNPROCESSABLE_ENTITY),
+            ] {
+                // The read model should not be called
+                let resp = send_req(MockSearchRead::new(), 6, uri).await?;
+                assert_eq!(expected_status, resp.status(), "{uri}");
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use {
+    super::{DEFAULT_PAGE_SIZE, api_result},
+    crate::{
+        api::{
+            dto::{
+                requests::{PageQuery, TrendingQuery},
+                responses::{TagPageResponse, TrendingTagResponse},
+            },
+            validated_query::ValidatedQuery,
+        },
+        domain::post::hashtag::normalize_tag,
+        map_into::MapInto as _,
+        read_models::TagRead,
+        state::AppState,
+    },
+    axum::{
+        Extension, Json, Router,
+        extract::{Path, State},
+        http::StatusCode,
+        routing::get,
+    },
+    chrono::TimeDelta,
+    std::sync::Arc,
+};
+
+/// The window used for trending tags when the client does not specify one.
+const DEFAULT_TRENDING_HOURS: i64 = 24;
+/// The number of trending tags returned when the client does not specify a limit.
+const DEFAULT_TRENDING_LIMIT: i64 = 10;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(tagged_posts, trending_tags))]
+pub struct TagsDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/trending", get(trending_tags))
+        .route("/{tag}/posts", get(tagged_posts))
+}
+
+/// Retrieves a page of the posts with a tag, most recent first. Tags are case-insensitive, and a
+/// leading # is optional.
+#[utoipa::path(
+    get,
+    tag = "tags",
+    path = "/{tag}/posts",
+    security(("jwt" = [])),
+    params(("tag" = String, Path, description = "the tag to browse"), PageQuery),
+    responses((
+        status = StatusCode::OK,
+        body = TagPageResponse,
+        description = "a page of posts with the tag",
+    )),
+)]
+async fn tagged_posts(
+    tag_read: State<Arc<dyn TagRead>>,
+    Extension(requester_id): Extension<i32>,
+    Path(tag): Path<String>,
+    query: ValidatedQuery<PageQuery>,
+) -> api_result!(TagPageResponse) {
+    let PageQuery { cursor, limit } = query.0;
+
+    let page = tag_read
+        .tagged(&normalize_tag(&tag), requester_id, cursor, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+/// Retrieves the tags used by the most posts recently, most used first.
+#[utoipa::path(
+    get,
+    tag = "tags",
+    path = "/trending",
+    security(("jwt" = [])),
+    params(TrendingQuery),
+    responses((
+        status = StatusCode::OK,
+        body = Vec<TrendingTagResponse>,
+        description = "the most used recent tags",
+    )),
+)]
+async fn trending_tags(
+    tag_read: State<Arc<dyn TagRead>>,
+    query: ValidatedQuery<TrendingQuery>,
+) -> api_result!(Vec<TrendingTagResponse>) {
+    let TrendingQuery { hours, limit } = query.0;
+
+    let tags = tag_read
+        .trending(
+            TimeDelta::hours(hours.unwrap_or(DEFAULT_TRENDING_HOURS)),
+            limit.unwrap_or(DEFAULT_TRENDING_LIMIT),
+        )
+        .await?;
+
+    Ok((StatusCode::OK, Json(tags.map_into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::tag::{TagPage, TrendingTag},
+            read_models::MockTagRead,
+            test_utils::{dummy_data::post_with_author, http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::Result,
+        axum::{body::Body, http::Request},
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    /// Sends a request as the requester.
+    async fn send_req(
+        mock_tag_read: MockTagRead,
+        requester_id: i32,
+        uri: &str,
+    ) -> Result<axum::response::Response> {
+        let state = AppState { tag_read: Arc::new(mock_tag_read), ..Default::default() };
+        let app = routes().with_state(state);
+
+        let mut req = Request::builder().uri(uri).body(Body::empty())?;
+        req.extensions_mut().insert(requester_id);
+
+        Ok(app.oneshot(req).await?)
+    }
+
+    #[test]
+    fn retrieves_posts_by_normalized_tag() -> Result<()> {
+        tokio_test(async {
+            let posts = post_with_author::all3()?;
+            let posts_vec = posts[..2].to_vec();
+
+            let mut mock_tag_read = MockTagRead::new();
+            mock_tag_read
+                .expect_tagged()
+                .with(eq("rustlang"), eq(12), eq(None), eq(DEFAULT_PAGE_SIZE))
+                .once()
+                .return_once(move |_, _, _, _| Ok(TagPage { posts: posts_vec, next_cursor: None }));
+
+            // %23 is an encoded #
+            let resp = send_req(mock_tag_read, 12, "/%23RustLang/posts").await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let [first, second, _] = posts;
+            let expected =
+                TagPageResponse { posts: vec![first, second].map_into(), next_cursor: None };
+            assert_eq!(expected, deserialize_body::<TagPageResponse>(resp).await?);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn retrieves_trending_tags_with_defaults_or_provided_values() -> Result<()> {
+        tokio_test(async {
+            for (uri, window, limit) in [
+                ("/trending", TimeDelta::hours(DEFAULT_TRENDING_HOURS), DEFAULT_TRENDING_LIMIT),
+                ("/trending?hours=6&limit=2", TimeDelta::hours(6), 2),
+            ] {
+                let mut mock_tag_read = MockTagRead::new();
+                mock_tag_read
+                    .expect_trending()
+                    .with(eq(window), eq(limit))
+                    .once()
+                    .return_once(|_, _| {
+                        Ok(vec![
+                            TrendingTag { name: String::from("async"), post_count: 5 },
+                            TrendingTag { name: String::from("rust"), post_count: 3 },
+                        ])
+                    });
+
+                let resp = send_req(mock_tag_read, 12, uri).await?;
+                assert_eq!(StatusCode::OK, resp.status());
+
+                let expected = vec![
+                    TrendingTagResponse { name: String::from("async"), post_count: 5 },
+                    TrendingTagResponse { name: String::from("rust"), post_count: 3 },
+                ];
+                assert_eq!(expected, deserialize_body::<Vec<TrendingTagResponse>>(resp).await?);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_invalid_trending_windows() -> Result<()> {
+        tokio_test(async {
+            // The read model should not be called
+            for uri in ["/trending?hours=0", "/trending?hours=169", "/trending?limit=51"] {
+                let resp = send_req(MockTagRead::new(), 12, uri).await?;
+                assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, resp.status(), "{uri}");
+            }
+
+            Ok(())
+        })
+    }
+}
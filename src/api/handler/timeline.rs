@@ -0,0 +1,179 @@
+use {
+    super::{DEFAULT_PAGE_SIZE, api_result},
+    crate::{
+        api::{
+            dto::{requests::PageQuery, responses::TimelinePageResponse},
+            validated_query::ValidatedQuery,
+        },
+        read_models::SocialRead,
+        state::AppState,
+    },
+    axum::{Extension, Json, Router, extract::State, http::StatusCode, routing::get},
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(home_timeline))]
+pub struct TimelineDoc;
+
+pub fn routes() -> Router<AppState> { Router::new().route("/", get(home_timeline)) }
+
+/// Retrieves a page of the requester's home timeline: posts by friends, replies to the requester's
+/// posts, and posts in threads that the requester has posted in, most recent first.
+#[utoipa::path(
+    get,
+    tag = "timeline",
+    path = "",
+    security(("jwt" = [])),
+    params(PageQuery),
+    responses((
+        status = StatusCode::OK,
+        body = TimelinePageResponse,
+        description = "a page of your home timeline",
+    )),
+)]
+async fn home_timeline(
+    social_read: State<Arc<dyn SocialRead>>,
+    Extension(requester_id): Extension<i32>,
+    query: ValidatedQuery<PageQuery>,
+) -> api_result!(TimelinePageResponse) {
+    let PageQuery { cursor, limit } = query.0;
+
+    let page = social_read
+        .home_timeline(requester_id, cursor, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .await?;
+
+    Ok((StatusCode::OK, Json(page.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::dto::responses::{ParentSummaryResponse, PostResponse, TimelinePostResponse},
+            models::post::{
+                BodyFormat, ParentSummary, PostCursor, PostVisibility, PostWithAuthor, ReplyPolicy,
+                TimelinePage, TimelinePost,
+            },
+            read_models::MockSocialRead,
+            test_utils::{http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::{Context as _, Result},
+        axum::{body::Body, http::Request},
+        chrono::DateTime,
+        mockall::predicate::eq,
+        sqlx::types::Json,
+        tower::ServiceExt as _,
+    };
+
+    /// Sends a request for the home timeline as the requester.
+    async fn send_req(
+        mock_social_read: MockSocialRead,
+        requester_id: i32,
+        uri: &str,
+    ) -> Result<axum::response::Response> {
+        let state = AppState { social_read: Arc::new(mock_social_read), ..Default::default() };
+        let app = routes().with_state(state);
+
+        let mut req = Request::builder().uri(uri).body(Body::empty())?;
+        req.extensions_mut().insert(requester_id);
+
+        Ok(app.oneshot(req).await?)
+    }
+
+    #[test]
+    fn retrieves_a_page_with_parent_summaries_and_the_next_cursor() -> Result<()> {
+        tokio_test(async {
+            let created_at = DateTime::from_timestamp_micros(1_760_000_000_123_456)
+                .context("invalid timestamp")?;
+            let post = PostWithAuthor {
+                id: 12,
+                author_id: Some(2),
+                parent_id: Some(5),
+                body: Some(String::from("Agreed")),
+                body_format: BodyFormat::Plain,
+                created_at,
+                edited_at: None,
+                archived_at: None,
+                deleted_at: None,
+                descendant_count: 0,
+                reply_policy: ReplyPolicy::Anyone,
+                reply_policy_cascades: false,
+                visibility: PostVisibility::Public,
+                hidden: false,
+
+                author_username: Some(String::from("friend")),
+                reactions: Json(Vec::new()),
+                mentions: Json(Vec::new()),
+                attachments: Json(Vec::new()),
+                quoted: None,
+            };
+            let post_clone = post.clone();
+
+            let mut mock_social_read = MockSocialRead::new();
+            mock_social_read
+                .expect_home_timeline()
+                .with(eq(7), eq(None), eq(DEFAULT_PAGE_SIZE))
+                .once()
+                .return_once(move |_, _, _| {
+                    Ok(TimelinePage {
+                        posts: vec![TimelinePost {
+                            post: post_clone,
+                            parent: Some(ParentSummary {
+                                id: 5,
+                                author_username: None,
+                                excerpt: Some(String::from("Hot take")),
+                            }),
+                        }],
+                        next_cursor: Some(PostCursor { created_at, id: 12 }),
+                    })
+                });
+
+            let resp = send_req(mock_social_read, 7, "/").await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let expected = TimelinePageResponse {
+                posts: vec![TimelinePostResponse {
+                    post: PostResponse::from(post),
+                    parent: Some(ParentSummaryResponse {
+                        id: 5,
+                        author_username: String::from("[deleted]"),
+                        excerpt: String::from("Hot take"),
+                    }),
+                }],
+                next_cursor: Some(String::from("1760000000123456_12")),
+            };
+            assert_eq!(expected, deserialize_body::<TimelinePageResponse>(resp).await?);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn passes_the_cursor_and_limit_through() -> Result<()> {
+        tokio_test(async {
+            let cursor = PostCursor {
+                created_at: DateTime::from_timestamp_micros(1_760_000_000_123_456)
+                    .context("invalid timestamp")?,
+                id: 12,
+            };
+
+            // The cursor must round trip losslessly, including the microseconds
+            let mut mock_social_read = MockSocialRead::new();
+            mock_social_read
+                .expect_home_timeline()
+                .with(eq(7), eq(Some(cursor)), eq(5))
+                .once()
+                .return_once(|_, _, _| Ok(TimelinePage { posts: Vec::new(), next_cursor: None }));
+
+            let resp = send_req(mock_social_read, 7, &format!("/?cursor={cursor}&limit=5")).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let expected = TimelinePageResponse { posts: Vec::new(), next_cursor: None };
+            assert_eq!(expected, deserialize_body::<TimelinePageResponse>(resp).await?);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,395 @@
+use {
+    super::api_result,
+    crate::{
+        api::{
+            dto::{
+                requests::RegisterWebhookRequest,
+                responses::{
+                    ErrorResponse, NewWebhookResponse, SuccessResponse, WebhookDeliveryResponse,
+                    WebhookResponse,
+                },
+            },
+            validated_json::ValidatedJson,
+        },
+        app_services::WebhookManagement,
+        map_into::MapInto as _,
+        read_models::WebhookRead,
+        state::AppState,
+    },
+    axum::{
+        Extension, Json, Router,
+        extract::{Path, State},
+        http::StatusCode,
+        routing::{delete, get},
+    },
+    std::sync::Arc,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(list, register, remove, deliveries))]
+pub struct WebhooksDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list).post(register))
+        .route("/{webhook_id}", delete(remove))
+        .route("/{webhook_id}/deliveries", get(deliveries))
+}
+
+/// Retrieves the requester's webhooks.
+#[utoipa::path(
+    get,
+    tag = "webhooks",
+    path = "",
+    security(("jwt" = [])),
+    responses((
+        status = StatusCode::OK,
+        body = Vec<WebhookResponse>,
+        description = "your webhooks, most recent first",
+    )),
+)]
+async fn list(
+    webhook_read: State<Arc<dyn WebhookRead>>,
+    Extension(requester_id): Extension<i32>,
+) -> api_result!(Vec<WebhookResponse>) {
+    Ok((StatusCode::OK, Json(webhook_read.for_owner(requester_id).await?.map_into())))
+}
+
+/// Registers a webhook. Each event is sent as a JSON `POST` request with an `X-Spur-Signature`
+/// header containing `sha256=` followed by the hex-encoded HMAC-SHA256 of
+/// `{X-Spur-Timestamp}.{body}`, keyed with the returned secret. Failed deliveries are retried with
+/// exponential backoff.
+#[utoipa::path(
+    post,
+    tag = "webhooks",
+    path = "",
+    security(("jwt" = [])),
+    request_body = RegisterWebhookRequest,
+    responses(
+        (
+            status = StatusCode::CREATED,
+            body = NewWebhookResponse,
+            description = "webhook registered (the secret will not be shown again)",
+        ),
+        (
+            status = StatusCode::FORBIDDEN,
+            body = ErrorResponse,
+            description = "all users' events requested by a non-moderator",
+        ),
+        (
+            status = StatusCode::UNPROCESSABLE_ENTITY,
+            body = ErrorResponse,
+            description = "the URL does not use https or its host is not publicly routable",
+        ),
+    ),
+)]
+async fn register(
+    webhook_management: State<Arc<dyn WebhookManagement>>,
+    Extension(requester_id): Extension<i32>,
+    payload: ValidatedJson<RegisterWebhookRequest>,
+) -> api_result!(NewWebhookResponse) {
+    let RegisterWebhookRequest { url, events, all_users } = payload.0;
+
+    let new_webhook = webhook_management
+        .register(requester_id, &url, events, all_users)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(new_webhook.into())))
+}
+
+/// Deletes one of the requester's webhooks, cancelling any pending deliveries.
+#[utoipa::path(
+    delete,
+    tag = "webhooks",
+    path = "/{webhook_id}",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = SuccessResponse,
+            description = "webhook deleted",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no webhook with this ID belongs to the requester",
+        ),
+    ),
+)]
+async fn remove(
+    webhook_management: State<Arc<dyn WebhookManagement>>,
+    Extension(requester_id): Extension<i32>,
+    Path(webhook_id): Path<i32>,
+) -> api_result!(SuccessResponse) {
+    webhook_management.delete(requester_id, webhook_id).await?;
+
+    Ok((StatusCode::OK, Json(SuccessResponse { message: String::from("Webhook deleted") })))
+}
+
+/// Retrieves the most recent deliveries to one of the requester's webhooks.
+#[utoipa::path(
+    get,
+    tag = "webhooks",
+    path = "/{webhook_id}/deliveries",
+    security(("jwt" = [])),
+    responses(
+        (
+            status = StatusCode::OK,
+            body = Vec<WebhookDeliveryResponse>,
+            description = "deliveries, most recent first",
+        ),
+        (
+            status = StatusCode::NOT_FOUND,
+            body = ErrorResponse,
+            description = "no webhook with this ID belongs to the requester",
+        ),
+    ),
+)]
+async fn deliveries(
+    webhook_read: State<Arc<dyn WebhookRead>>,
+    Extension(requester_id): Extension<i32>,
+    Path(webhook_id): Path<i32>,
+) -> api_result!(Vec<WebhookDeliveryResponse>) {
+    Ok((
+        StatusCode::OK,
+        Json(
+            webhook_read
+                .delivery_log(requester_id, webhook_id)
+                .await?
+                .map_into(),
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            app_services::MockWebhookManagement,
+            domain::webhook::error::WebhookError,
+            models::webhook::{
+                NewWebhook, WebhookDeliveryRecord, WebhookDeliveryStatus, WebhookEventKind,
+            },
+            read_models::{MockWebhookRead, ReadError},
+            test_utils::{
+                http_bodies::{deserialize_body, serialize_body},
+                tokio_test,
+            },
+        },
+        anyhow::Result,
+        axum::{
+            body::Body,
+            http::{Method, Request, header::CONTENT_TYPE},
+        },
+        chrono::Utc,
+        mockall::predicate::eq,
+        tower::ServiceExt as _,
+    };
+
+    mod register {
+        use super::*;
+
+        /// Sends a request to register a webhook as the requester.
+        async fn send_req(
+            mock_webhook_management: MockWebhookManagement,
+            requester_id: i32,
+            payload: &RegisterWebhookRequest,
+        ) -> Result<axum::response::Response> {
+            let state = AppState {
+                webhook_management: Arc::new(mock_webhook_management),
+                ..Default::default()
+            };
+            let app = routes().with_state(state);
+
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri("/")
+                .header(CONTENT_TYPE, "application/json")
+                .body(serialize_body(payload)?)?;
+
+            req.extensions_mut().insert(requester_id);
+
+            app.oneshot(req).await.map_err(Into::into)
+        }
+
+        #[test]
+        fn returns_the_secret() -> Result<()> {
+            tokio_test(async {
+                let payload = RegisterWebhookRequest {
+                    url: String::from("https://example.com/hook"),
+                    events: vec![WebhookEventKind::PostCreated],
+                    all_users: false,
+                };
+
+                let mut mock_webhook_management = MockWebhookManagement::new();
+                mock_webhook_management
+                    .expect_register()
+                    .with(
+                        eq(17),
+                        eq("https://example.com/hook"),
+                        eq(vec![WebhookEventKind::PostCreated]),
+                        eq(false),
+                    )
+                    .once()
+                    .return_once(|_, _, _, _| {
+                        Ok(NewWebhook { id: 3, secret: String::from("whsec_abc") })
+                    });
+
+                let resp = send_req(mock_webhook_management, 17, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::CREATED);
+
+                let resp_body = deserialize_body::<NewWebhookResponse>(resp).await?;
+                let expected = NewWebhookResponse { id: 3, secret: String::from("whsec_abc") };
+                assert_eq!(expected, resp_body);
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_invalid_urls_and_empty_events() -> Result<()> {
+            tokio_test(async {
+                for (url, events) in [
+                    ("not a url", vec![WebhookEventKind::PostCreated]),
+                    ("https://example.com/hook", vec![]),
+                ] {
+                    let payload =
+                        RegisterWebhookRequest { url: String::from(url), events, all_users: false };
+
+                    // The service should not be called
+                    let resp = send_req(MockWebhookManagement::new(), 17, &payload).await?;
+                    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+                }
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn forbids_non_moderators_from_receiving_all_users_events() -> Result<()> {
+            tokio_test(async {
+                let payload = RegisterWebhookRequest {
+                    url: String::from("https://example.com/hook"),
+                    events: vec![WebhookEventKind::FriendshipAccepted],
+                    all_users: true,
+                };
+
+                let mut mock_webhook_management = MockWebhookManagement::new();
+                mock_webhook_management
+                    .expect_register()
+                    .once()
+                    .return_once(|_, _, _, _| Err(WebhookError::NotModerator));
+
+                let resp = send_req(mock_webhook_management, 17, &payload).await?;
+                assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+                Ok(())
+            })
+        }
+    }
+
+    mod remove {
+        use super::*;
+
+        #[test]
+        fn deletes_or_reports_missing_webhooks() -> Result<()> {
+            tokio_test(async {
+                for (result, expected_status) in
+                    [(Ok(()), StatusCode::OK), (Err(WebhookError::NotFound), StatusCode::NOT_FOUND)]
+                {
+                    let mut mock_webhook_management = MockWebhookManagement::new();
+                    mock_webhook_management
+                        .expect_delete()
+                        .with(eq(5), eq(40))
+                        .once()
+                        .return_once(|_, _| result);
+
+                    let state = AppState {
+                        webhook_management: Arc::new(mock_webhook_management),
+                        ..Default::default()
+                    };
+                    let app = routes().with_state(state);
+
+                    let mut req = Request::builder()
+                        .method(Method::DELETE)
+                        .uri("/40")
+                        .body(Body::empty())?;
+
+                    req.extensions_mut().insert(5);
+
+                    let resp = app.oneshot(req).await?;
+                    assert_eq!(resp.status(), expected_status);
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    mod deliveries {
+        use super::*;
+
+        #[test]
+        fn retrieves_the_delivery_log_of_owned_webhooks() -> Result<()> {
+            tokio_test(async {
+                let now = Utc::now();
+                let record = WebhookDeliveryRecord {
+                    id: 8,
+                    event_id: 21,
+                    kind: WebhookEventKind::FriendshipRequested,
+                    status: WebhookDeliveryStatus::Failed,
+                    attempts: 8,
+                    last_attempt_at: Some(now),
+                    next_attempt_at: now,
+                    response_status: Some(500),
+                    last_error: None,
+                    event_created_at: now,
+                };
+                let record_clone = record.clone();
+
+                let mut mock_webhook_read = MockWebhookRead::new();
+                mock_webhook_read
+                    .expect_delivery_log()
+                    .with(eq(5), eq(40))
+                    .once()
+                    .return_once(|_, _| Ok(vec![record_clone]));
+                mock_webhook_read
+                    .expect_delivery_log()
+                    .with(eq(5), eq(41))
+                    .once()
+                    .return_once(|_, _| Err(ReadError::NotFound));
+
+                let state =
+                    AppState { webhook_read: Arc::new(mock_webhook_read), ..Default::default() };
+
+                let send_req = async |webhook_id: i32| {
+                    let mut req = Request::builder()
+                        .method(Method::GET)
+                        .uri(format!("/{webhook_id}/deliveries"))
+                        .body(Body::empty())?;
+
+                    req.extensions_mut().insert(5);
+
+                    routes()
+                        .with_state(state.clone())
+                        .oneshot(req)
+                        .await
+                        .map_err(anyhow::Error::from)
+                };
+
+                let resp = send_req(40).await?;
+                assert_eq!(resp.status(), StatusCode::OK);
+
+                let resp_body = deserialize_body::<Vec<WebhookDeliveryResponse>>(resp).await?;
+                assert_eq!(vec![WebhookDeliveryResponse::from(record)], resp_body);
+                // No more attempts will be made
+                assert_eq!(resp_body.first().and_then(|d| d.next_attempt_at_ms), None);
+
+                assert_eq!(send_req(41).await?.status(), StatusCode::NOT_FOUND);
+
+                Ok(())
+            })
+        }
+    }
+}
@@ -1,7 +1,14 @@
 use {
-    crate::{api::error::ApiError, app_services::Authenticator},
+    crate::{
+        api::error::ApiError,
+        app_services::{Authenticator, PostModeration, RateLimiter},
+        config::TrustedProxies,
+        domain::moderation::error::ModerationError,
+    },
     axum::{
-        extract::{Request, State},
+        Extension,
+        extract::{ConnectInfo, Request, State},
+        http::{HeaderMap, HeaderName, header::AUTHORIZATION},
         middleware,
         response::Response,
     },
@@ -9,7 +16,10 @@ use {
         TypedHeader,
         headers::{Authorization, authorization::Bearer},
     },
-    std::sync::Arc,
+    std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        sync::Arc,
+    },
 };
 
 /// Middleware that confirms JWT validity and passes the requester's user ID to the handler via a
@@ -27,13 +37,100 @@ pub async fn validate_jwt(
     Ok(next.run(request).await)
 }
 
+/// Middleware that only allows moderators through. Must be applied after `validate_jwt` so that
+/// the requester's user ID is available.
+pub async fn require_moderator(
+    post_moderation: State<Arc<dyn PostModeration>>,
+    Extension(requester_id): Extension<i32>,
+    request: Request,
+    next: middleware::Next,
+) -> Result<Response, ApiError> {
+    if !post_moderation.is_moderator(requester_id).await? {
+        return Err(ModerationError::NotModerator.into());
+    }
+
+    Ok(next.run(request).await)
+}
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// Middleware that limits how often each IP address may make requests without logging in.
+/// Requests that include a token are let through unlimited, leaving the handler to reject them if
+/// the token is invalid.
+pub async fn limit_anonymous(
+    limiter: State<Arc<dyn RateLimiter>>,
+    trusted_proxies: State<TrustedProxies>,
+    request: Request,
+    next: middleware::Next,
+) -> Result<Response, ApiError> {
+    if !request.headers().contains_key(AUTHORIZATION) {
+        count_request(&**limiter, &trusted_proxies, &request)?;
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware that limits how often each IP address may make requests, sharing the limit of
+/// `limit_anonymous`. For routes that don't take tokens, so that sending one doesn't lift the
+/// limit.
+pub async fn limit_by_address(
+    limiter: State<Arc<dyn RateLimiter>>,
+    trusted_proxies: State<TrustedProxies>,
+    request: Request,
+    next: middleware::Next,
+) -> Result<Response, ApiError> {
+    count_request(&**limiter, &trusted_proxies, &request)?;
+
+    Ok(next.run(request).await)
+}
+
+/// Counts a request against the limit of the client that made it, returning
+/// `ApiError::RateLimited` if the client is over the limit.
+fn count_request(
+    limiter: &dyn RateLimiter,
+    trusted_proxies: &TrustedProxies,
+    request: &Request,
+) -> Result<(), ApiError> {
+    // The connection info is missing when the router is served without it (e.g. in tests)
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |ConnectInfo(addr)| addr.ip());
+
+    if limiter.allow(client_ip(peer, request.headers(), trusted_proxies)) {
+        Ok(())
+    } else {
+        Err(ApiError::RateLimited)
+    }
+}
+
+/// Finds the IP address of the client behind a connection. Requests from trusted proxies are
+/// attributed to the last address in their `X-Forwarded-For` header that isn't another trusted
+/// proxy, since the client can put anything before it. Falls back to the connection's address if
+/// there is no such address.
+fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &TrustedProxies) -> IpAddr {
+    if !trusted_proxies.contains(peer) {
+        return peer;
+    }
+
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .rev()
+        .flat_map(|value| value.to_str().unwrap_or_default().rsplit(','))
+        .map(|entry| entry.trim().parse::<IpAddr>())
+        .find(|entry| !entry.as_ref().is_ok_and(|ip| trusted_proxies.contains(*ip)))
+        .and_then(Result::ok)
+        .unwrap_or(peer)
+}
+
 #[cfg(test)]
 mod tests {
     use {
         super::*,
         crate::{
             api::dto::responses::ErrorResponse,
-            app_services::MockAuthenticator,
+            app_services::{MockAuthenticator, MockPostModeration, MockRateLimiter},
             domain::auth::AuthError,
             state::AppState,
             test_utils::{
@@ -43,9 +140,9 @@ mod tests {
         },
         anyhow::Result,
         axum::{
-            Extension, Json, Router,
+            Json, Router,
             body::Body,
-            http::{Method, Request, StatusCode, header::AUTHORIZATION},
+            http::{Method, Request, StatusCode},
             routing::get,
         },
         mockall::predicate::eq,
@@ -169,4 +266,249 @@ mod tests {
             Ok(())
         })
     }
+
+    /// Makes a GET request to the simple ID reporting endpoint using a router with the moderator
+    /// requirement middleware applied, inserting `requester_id` as if the JWT had been validated.
+    async fn send_moderator_req(
+        requester_id: i32,
+        mock_post_moderation: impl PostModeration + 'static,
+    ) -> Result<Response> {
+        let mut req = Request::builder()
+            .method(Method::GET)
+            .uri(ID_ROUTE)
+            .body(Body::empty())?;
+
+        req.extensions_mut().insert(requester_id);
+
+        Router::new()
+            .route(ID_ROUTE, get(what_is_my_id))
+            .layer(middleware::from_fn_with_state(
+                AppState { post_moderation: Arc::new(mock_post_moderation), ..Default::default() },
+                require_moderator,
+            ))
+            .oneshot(req)
+            .await
+            .map_err(Into::into)
+    }
+
+    #[test]
+    fn allows_moderators_through() -> Result<()> {
+        tokio_test(async {
+            let requester_id = 77;
+
+            let mut mock_post_moderation = MockPostModeration::new();
+            mock_post_moderation
+                .expect_is_moderator()
+                .with(eq(requester_id))
+                .once()
+                .return_once(|_| Ok(true));
+
+            let resp = send_moderator_req(requester_id, mock_post_moderation).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            let resp_body = deserialize_body::<RequesterId>(resp).await?;
+            assert_eq!(RequesterId { requester_id }, resp_body);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn disallows_non_moderators() -> Result<()> {
+        tokio_test(async {
+            let requester_id = 78;
+
+            let mut mock_post_moderation = MockPostModeration::new();
+            mock_post_moderation
+                .expect_is_moderator()
+                .with(eq(requester_id))
+                .once()
+                .return_once(|_| Ok(false));
+
+            let resp = send_moderator_req(requester_id, mock_post_moderation).await?;
+            assert_eq!(StatusCode::FORBIDDEN, resp.status());
+
+            let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+            let expected = ErrorResponse { error: String::from("Moderator access required") };
+            assert_eq!(expected, resp_body);
+
+            Ok(())
+        })
+    }
+
+    /// Makes a GET request from `client` to a route that reports success, using a router with the
+    /// anonymous rate limiting middleware applied. If `token` is `Some`, sends it as a bearer
+    /// token.
+    async fn send_limited_req(
+        client: SocketAddr,
+        token: Option<&str>,
+        forwarded_for: Option<&str>,
+        mock_limiter: MockRateLimiter,
+    ) -> Result<Response> {
+        let mut req = Request::builder().method(Method::GET).uri(ID_ROUTE);
+        if let Some(tk) = token {
+            req = req.header(AUTHORIZATION, format!("Bearer {tk}"));
+        }
+        if let Some(addresses) = forwarded_for {
+            req = req.header(X_FORWARDED_FOR, addresses);
+        }
+        let mut req_body = req.body(Body::empty())?;
+        req_body.extensions_mut().insert(ConnectInfo(client));
+
+        Router::new()
+            .route(ID_ROUTE, get(async || "ok"))
+            .layer(middleware::from_fn_with_state(
+                AppState {
+                    anonymous_rate_limiter: Arc::new(mock_limiter),
+                    trusted_proxies: "10.0.0.0/8, 192.0.2.1".parse()?,
+                    ..Default::default()
+                },
+                limit_anonymous,
+            ))
+            .oneshot(req_body)
+            .await
+            .map_err(Into::into)
+    }
+
+    #[test]
+    fn allows_anonymous_requests_under_the_limit() -> Result<()> {
+        tokio_test(async {
+            let client = SocketAddr::from(([203, 0, 113, 9], 4321));
+
+            let mut mock_limiter = MockRateLimiter::new();
+            mock_limiter
+                .expect_allow()
+                .with(eq(client.ip()))
+                .once()
+                .return_const(true);
+
+            let resp = send_limited_req(client, None, None, mock_limiter).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn disallows_anonymous_requests_over_the_limit() -> Result<()> {
+        tokio_test(async {
+            let client = SocketAddr::from(([203, 0, 113, 9], 4321));
+
+            let mut mock_limiter = MockRateLimiter::new();
+            mock_limiter
+                .expect_allow()
+                .with(eq(client.ip()))
+                .once()
+                .return_const(false);
+
+            let resp = send_limited_req(client, None, None, mock_limiter).await?;
+            assert_eq!(StatusCode::TOO_MANY_REQUESTS, resp.status());
+
+            let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+            let expected = ErrorResponse {
+                error: String::from("Too many requests. Log in or try again later."),
+            };
+            assert_eq!(expected, resp_body);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn does_not_limit_requests_with_a_token() -> Result<()> {
+        tokio_test(async {
+            // The limiter should not be accessed
+            let client = SocketAddr::from(([203, 0, 113, 10], 4321));
+            let resp = send_limited_req(client, Some("tok"), None, MockRateLimiter::new()).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn limits_requests_by_address_even_with_a_token() -> Result<()> {
+        tokio_test(async {
+            let client = SocketAddr::from(([203, 0, 113, 9], 4321));
+
+            let mut mock_limiter = MockRateLimiter::new();
+            mock_limiter
+                .expect_allow()
+                .with(eq(client.ip()))
+                .once()
+                .return_const(false);
+
+            let mut req = Request::builder()
+                .method(Method::GET)
+                .uri(ID_ROUTE)
+                .header(AUTHORIZATION, "Bearer tok")
+                .body(Body::empty())?;
+            req.extensions_mut().insert(ConnectInfo(client));
+
+            let resp = Router::new()
+                .route(ID_ROUTE, get(async || "ok"))
+                .layer(middleware::from_fn_with_state(
+                    AppState {
+                        anonymous_rate_limiter: Arc::new(mock_limiter),
+                        ..Default::default()
+                    },
+                    limit_by_address,
+                ))
+                .oneshot(req)
+                .await?;
+            assert_eq!(StatusCode::TOO_MANY_REQUESTS, resp.status());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn limits_clients_behind_trusted_proxies_by_their_forwarded_address() -> Result<()> {
+        tokio_test(async {
+            let proxy = SocketAddr::from(([10, 0, 0, 2], 4321));
+
+            // The first address could be made up by the client, and the last is another proxy
+            let mut mock_limiter = MockRateLimiter::new();
+            mock_limiter
+                .expect_allow()
+                .with(eq(IpAddr::from([203, 0, 113, 9])))
+                .once()
+                .return_const(true);
+            let chain = "198.51.100.7, 203.0.113.9, 192.0.2.1";
+            let resp = send_limited_req(proxy, None, Some(chain), mock_limiter).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            // Without a usable header, the proxy itself is limited
+            for unusable in [None, Some("not an address")] {
+                let mut proxy_limiter = MockRateLimiter::new();
+                proxy_limiter
+                    .expect_allow()
+                    .with(eq(proxy.ip()))
+                    .once()
+                    .return_const(true);
+                let proxy_resp = send_limited_req(proxy, None, unusable, proxy_limiter).await?;
+                assert_eq!(StatusCode::OK, proxy_resp.status());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn ignores_forwarded_addresses_from_untrusted_peers() -> Result<()> {
+        tokio_test(async {
+            let client = SocketAddr::from(([203, 0, 113, 9], 4321));
+
+            let mut mock_limiter = MockRateLimiter::new();
+            mock_limiter
+                .expect_allow()
+                .with(eq(client.ip()))
+                .once()
+                .return_const(true);
+
+            let resp = send_limited_req(client, None, Some("198.51.100.7"), mock_limiter).await?;
+            assert_eq!(StatusCode::OK, resp.status());
+
+            Ok(())
+        })
+    }
 }
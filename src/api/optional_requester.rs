@@ -0,0 +1,132 @@
+use {
+    super::error::ApiError,
+    crate::app_services::Authenticator,
+    axum::{
+        extract::{FromRef, FromRequestParts},
+        http::{header::AUTHORIZATION, request::Parts},
+        response::{IntoResponse, Response},
+    },
+    axum_extra::{
+        TypedHeader,
+        headers::{Authorization, authorization::Bearer},
+    },
+    std::sync::Arc,
+};
+
+/// Custom extractor for endpoints that can be used with or without logging in. Holds the
+/// requester's user ID if the request has a valid JSON Web Token, or `None` if it has no
+/// "Authorization" header. Requests with a malformed or invalid token are rejected rather than
+/// treated as anonymous.
+#[cfg_attr(test, derive(Debug))]
+pub struct OptionalRequester(pub Option<i32>);
+
+impl<S> FromRequestParts<S> for OptionalRequester
+where
+    S: Send + Sync,
+    Arc<dyn Authenticator>: FromRef<S>,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if !parts.headers.contains_key(AUTHORIZATION) {
+            return Ok(Self(None));
+        }
+
+        let TypedHeader(Authorization(bearer)) =
+            TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+
+        Arc::<dyn Authenticator>::from_ref(state)
+            .validate_token(bearer.token())
+            .map(|requester_id| Self(Some(requester_id)))
+            .map_err(|e| ApiError::from(e).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{app_services::MockAuthenticator, domain::auth::AuthError, test_utils::tokio_test},
+        anyhow::Result,
+        axum::http::{Request, StatusCode},
+        mockall::predicate::eq,
+        std::assert_matches,
+    };
+
+    /// Extracts the requester from a request with the provided "Authorization" header, if any.
+    async fn extract(
+        header_val: Option<&str>,
+        mock_auth: MockAuthenticator,
+    ) -> Result<Result<OptionalRequester, Response>> {
+        let mut req = Request::builder();
+        if let Some(val) = header_val {
+            req = req.header(AUTHORIZATION, val);
+        }
+        let (mut parts, ()) = req.body(())?.into_parts();
+        let auth: Arc<dyn Authenticator> = Arc::new(mock_auth);
+
+        Ok(OptionalRequester::from_request_parts(&mut parts, &auth).await)
+    }
+
+    #[test]
+    fn treats_requests_without_a_token_as_anonymous() -> Result<()> {
+        tokio_test(async {
+            // Auth should not be accessed
+            assert_matches!(
+                extract(None, MockAuthenticator::new()).await?,
+                Ok(OptionalRequester(None))
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn identifies_requesters_with_a_valid_token() -> Result<()> {
+        tokio_test(async {
+            let mut mock_auth = MockAuthenticator::new();
+            mock_auth
+                .expect_validate_token()
+                .with(eq("good"))
+                .once()
+                .return_once(|_| Ok(38));
+
+            assert_matches!(
+                extract(Some("Bearer good"), mock_auth).await?,
+                Ok(OptionalRequester(Some(38)))
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_invalid_tokens_instead_of_treating_them_as_anonymous() -> Result<()> {
+        tokio_test(async {
+            let mut mock_auth = MockAuthenticator::new();
+            mock_auth
+                .expect_validate_token()
+                .with(eq("bad"))
+                .once()
+                .return_once(|_| Err(AuthError::TokenValidation));
+
+            assert_matches!(
+                extract(Some("Bearer bad"), mock_auth).await?,
+                Err(resp) if resp.status() == StatusCode::UNAUTHORIZED
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_malformed_auth_headers() -> Result<()> {
+        tokio_test(async {
+            // Auth should not be accessed
+            assert_matches!(
+                extract(Some("Bearer"), MockAuthenticator::new()).await?,
+                Err(resp) if resp.status() == StatusCode::BAD_REQUEST
+            );
+            Ok(())
+        })
+    }
+}
@@ -2,12 +2,25 @@ use {
     crate::{
         api::{
             handler::{
-                auth,
-                auth::AuthDoc,
+                attachment::{self, AttachmentsDoc},
+                auth::{self, AuthDoc},
+                bookmark::{self, BookmarksDoc},
+                draft::{self, DraftsDoc},
+                event::{self, EventsDoc},
+                federation::{self, ActorsDoc, WebFingerDoc},
+                feed::{self, ThreadFeedsDoc, UserFeedsDoc},
                 friendship::{self, FriendsDoc},
+                live::{self, LiveDoc},
+                mention::{self, MentionsDoc},
+                moderation::{self, ModerationDoc},
+                notification::{self, NotificationsDoc},
                 post::{self, PostsDoc},
+                search::{self, SearchDoc},
+                tag::{self, TagsDoc},
+                timeline::{self, TimelineDoc},
+                webhook::{self, WebhooksDoc},
             },
-            middleware::validate_jwt,
+            middleware::{limit_anonymous, limit_by_address, require_moderator, validate_jwt},
         },
         state::AppState,
     },
@@ -38,7 +51,7 @@ use {
 pub fn build(state: AppState, frontend_url: &str) -> Result<Router> {
     let cors = CorsLayer::new()
         .allow_origin([frontend_url.parse()?])
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
         .allow_headers([CONTENT_TYPE, AUTHORIZATION])
         .allow_credentials(true);
 
@@ -46,6 +59,8 @@ pub fn build(state: AppState, frontend_url: &str) -> Result<Router> {
         .route("/", get(async || Redirect::to("/docs")))
         .route("/ping", get(pong))
         .nest("/auth", auth::routes().with_state(state.clone()))
+        .merge(federation_routes(state.clone()))
+        .merge(optionally_protected_routes(state.clone()))
         .merge(protected_routes(state))
         .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .layer(cors);
@@ -53,11 +68,46 @@ pub fn build(state: AppState, frontend_url: &str) -> Result<Router> {
     Ok(app)
 }
 
+/// ActivityPub and WebFinger routes for other servers, which identify themselves with HTTP
+/// signatures rather than tokens. Each address is limited like anonymous requests are.
+fn federation_routes(state: AppState) -> Router {
+    Router::new()
+        .nest("/.well-known", federation::well_known_routes())
+        .nest("/users", federation::actor_routes())
+        .route_layer(middleware::from_fn_with_state(state.clone(), limit_by_address))
+        .with_state(state)
+}
+
+/// Routes that accept a token but can also be used without one, subject to a stricter rate limit.
+fn optionally_protected_routes(state: AppState) -> Router {
+    Router::new()
+        .nest("/posts", post::public_routes().merge(feed::thread_routes()))
+        .nest("/users", feed::user_routes())
+        .route_layer(middleware::from_fn_with_state(state.clone(), limit_anonymous))
+        .with_state(state)
+}
+
 fn protected_routes(state: AppState) -> Router {
     Router::new()
         .route("/auth/check", get(token_check))
         .nest("/friends", friendship::routes())
         .nest("/posts", post::routes())
+        .nest("/attachments", attachment::routes())
+        .nest("/timeline", timeline::routes())
+        .nest("/bookmarks", bookmark::routes())
+        .nest("/drafts", draft::routes())
+        .nest("/mentions", mention::routes())
+        .nest("/search", search::routes())
+        .nest("/tags", tag::routes())
+        .nest("/notifications", notification::routes())
+        .nest("/events", event::routes())
+        .nest("/live", live::routes())
+        .nest("/webhooks", webhook::routes())
+        .nest(
+            "/moderation",
+            moderation::routes()
+                .route_layer(middleware::from_fn_with_state(state.clone(), require_moderator)),
+        )
         .route_layer(middleware::from_fn_with_state(state.clone(), validate_jwt))
         .with_state(state)
 }
@@ -104,6 +154,22 @@ async fn token_check() -> &'static str { "Your token is valid\n" }
         (path = "/auth", api = AuthDoc),
         (path = "/friends", api = FriendsDoc),
         (path = "/posts", api = PostsDoc),
+        (path = "/attachments", api = AttachmentsDoc),
+        (path = "/timeline", api = TimelineDoc),
+        (path = "/bookmarks", api = BookmarksDoc),
+        (path = "/drafts", api = DraftsDoc),
+        (path = "/mentions", api = MentionsDoc),
+        (path = "/search", api = SearchDoc),
+        (path = "/tags", api = TagsDoc),
+        (path = "/notifications", api = NotificationsDoc),
+        (path = "/events", api = EventsDoc),
+        (path = "/live", api = LiveDoc),
+        (path = "/webhooks", api = WebhooksDoc),
+        (path = "/moderation", api = ModerationDoc),
+        (path = "/users", api = UserFeedsDoc),
+        (path = "/posts", api = ThreadFeedsDoc),
+        (path = "/.well-known", api = WebFingerDoc),
+        (path = "/users", api = ActorsDoc),
     ),
 )]
 pub struct ApiDoc;
@@ -119,6 +185,13 @@ request).
 Protected endpoints (the ones with a lock symbol) require authentication using a JSON Web Token. A \
 token can be acquired via the login or signup endpoints and entered using the \"Authorize\" button.
 
+Some endpoints are optionally protected. They accept a token but can also be used without logging \
+in, in which case only public posts are shown and requests are limited per IP address.
+
+Federation endpoints are for other ActivityPub servers. Users can be followed from elsewhere in the \
+fediverse by their address (`username@host`), and requests to their inboxes must be signed with \
+HTTP signatures.
+
 ### Common error responses
 
 - All endpoints may return:
@@ -127,6 +200,11 @@ token can be acquired via the login or signup endpoints and entered using the \"
 - All protected endpoints may return:
   - 400 Bad Request - authentication header missing
   - 401 Unauthorized - expired or invalid token
+- All optionally protected endpoints may return:
+  - 401 Unauthorized - expired or invalid token (if one is provided)
+  - 429 Too Many Requests - anonymous request limit reached
+- All moderation endpoints may return:
+  - 403 Forbidden - requester is not a moderator
 - All POST endpoints may return:
   - 422 Unprocessable Entity - JSON body failed validation logic (e.g. illegal username characters)
 
@@ -165,10 +243,11 @@ mod tests {
                 dummy_data::dummy_login_request,
                 responses::{ErrorResponse, TokenResponse},
             },
-            app_services::MockAuthenticator,
+            app_services::{MockAuthenticator, MockRateLimiter},
             domain::auth::AuthError,
-            read_models::MockSocialRead,
+            read_models::{MockPostWithAuthorRead, MockSocialRead},
             test_utils::{
+                dummy_data::post_with_author,
                 http_bodies::{deserialize_body, resp_into_body_text, serialize_body},
                 tokio_test,
             },
@@ -273,6 +352,48 @@ mod tests {
                 Ok(())
             })
         }
+
+        #[test]
+        fn allows_anonymous_access_to_optionally_protected_route() -> Result<()> {
+            tokio_test(async {
+                let [post, ..] = post_with_author::all3()?;
+                let post_id = post.id;
+
+                let mut mock_limiter = MockRateLimiter::new();
+                mock_limiter.expect_allow().once().return_const(true);
+
+                let mut mock_pwa_read = MockPostWithAuthorRead::new();
+                mock_pwa_read
+                    .expect_by_post_id()
+                    .with(eq(post_id), eq(None))
+                    .once()
+                    .return_once(|_, _| Ok(post));
+
+                // Auth should not be accessed
+                let state = AppState {
+                    anonymous_rate_limiter: Arc::new(mock_limiter),
+                    post_with_author_read: Arc::new(mock_pwa_read),
+                    ..Default::default()
+                };
+
+                let resp = send_req(state, &format!("/posts/{post_id}"), None).await?;
+                assert_eq!(StatusCode::OK, resp.status());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn still_requires_auth_for_protected_routes_sharing_a_prefix() -> Result<()> {
+            tokio_test(async {
+                // Neither auth nor the limiter should be accessed
+                let resp = send_req(AppState::default(), "/posts/me", None).await?;
+                assert_eq!(StatusCode::BAD_REQUEST, resp.status());
+                let resp_body = resp_into_body_text(resp).await?;
+                assert_eq!("Header of type `authorization` was missing", resp_body);
+                Ok(())
+            })
+        }
     }
 
     mod state_passing {
@@ -461,7 +582,10 @@ mod tests {
                     "https://frontend.example",
                     try_get_header(h, &ACCESS_CONTROL_ALLOW_ORIGIN)?
                 );
-                assert_eq!("GET,POST,OPTIONS", try_get_header(h, &ACCESS_CONTROL_ALLOW_METHODS)?);
+                assert_eq!(
+                    "GET,POST,PUT,DELETE,OPTIONS",
+                    try_get_header(h, &ACCESS_CONTROL_ALLOW_METHODS)?
+                );
                 assert_eq!(
                     "content-type,authorization",
                     try_get_header(h, &ACCESS_CONTROL_ALLOW_HEADERS)?
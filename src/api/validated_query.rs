@@ -0,0 +1,99 @@
+use {
+    super::error::ApiError,
+    axum::{
+        extract::{FromRequestParts, Query},
+        http::request::Parts,
+        response::{IntoResponse, Response},
+    },
+    serde::de::DeserializeOwned,
+    std::ops::Deref,
+    validator::Validate,
+};
+
+/// Custom extractor that validates the query string parameters using `validator::Validate`.
+#[cfg_attr(test, derive(Debug))]
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T> Deref for ValidatedQuery<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl<S, T> FromRequestParts<S> for ValidatedQuery<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Parse the query string using Axum's built-in extractor
+        let Query(params) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        // Validate the fields using custom logic
+        params
+            .validate()
+            .map_err(|e| ApiError::from(e).into_response())?;
+
+        Ok(Self(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            api::dto::{requests::PageQuery, responses::ErrorResponse},
+            test_utils::{http_bodies::deserialize_body, tokio_test},
+        },
+        anyhow::{Result, anyhow},
+        axum::http::{Request, StatusCode},
+        std::assert_matches,
+    };
+
+    /// Extracts the query parameters from a request to the provided URI.
+    async fn extract(uri: &str) -> Result<Result<ValidatedQuery<PageQuery>, Response>> {
+        let (mut parts, ()) = Request::builder().uri(uri).body(())?.into_parts();
+        Ok(ValidatedQuery::<PageQuery>::from_request_parts(&mut parts, &()).await)
+    }
+
+    #[test]
+    fn allows_valid_query_parameters() -> Result<()> {
+        tokio_test(async {
+            let result = extract("/anything?limit=5&cursor=1760000000000000_12").await?;
+            assert_matches!(
+                result,
+                Ok(ValidatedQuery(PageQuery { limit: Some(5), cursor: Some(cursor) }))
+                    if cursor.id == 12
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn disallows_invalid_query_parameters() -> Result<()> {
+        tokio_test(async {
+            let Err(resp) = extract("/anything?limit=0").await? else {
+                return Err(anyhow!("unexpected Ok for invalid query parameters"));
+            };
+
+            assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, resp.status());
+            let resp_body = deserialize_body::<ErrorResponse>(resp).await?;
+            assert_eq!(
+                ErrorResponse { error: String::from("limit: limit must be between 1 and 100") },
+                resp_body,
+            );
+
+            // Malformed cursors are rejected before validation
+            let Err(cursor_resp) = extract("/anything?cursor=yesterday").await? else {
+                return Err(anyhow!("unexpected Ok for malformed cursor"));
+            };
+            assert_eq!(StatusCode::BAD_REQUEST, cursor_resp.status());
+
+            Ok(())
+        })
+    }
+}
@@ -1,11 +1,33 @@
-use crate::{
-    domain::{auth::AuthError, friendship::error::FriendshipError},
-    models::user::UserRegistration,
+use {
+    crate::{
+        domain::{
+            auth::AuthError, federation::error::FederationError,
+            friendship::error::FriendshipError, moderation::error::ModerationError,
+            webhook::error::WebhookError,
+        },
+        models::{
+            federation::{InboxRequest, LocalActor},
+            moderation::{ModerationActionKind, ReportReason},
+            user::UserRegistration,
+            webhook::{NewWebhook, WebhookEventKind},
+        },
+    },
+    std::net::IpAddr,
 };
 
 pub mod authenticator_svc;
+pub mod background;
+pub mod deliver_activity;
+pub mod federation_svc;
+pub mod fixed_window_limiter;
+pub mod job_worker;
 pub mod mutate_friendship_by_username_svc;
+pub mod post_moderation_svc;
+pub mod prune_history;
+pub mod scheduled_post_publisher;
 pub mod uow;
+pub mod webhook_dispatcher;
+pub mod webhook_management_svc;
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
@@ -37,3 +59,93 @@ pub trait Authenticator: Send + Sync {
     async fn login(&self, email: &str, pw: &str) -> Result<String, AuthError>;
     fn validate_token(&self, token: &str) -> Result<i32, AuthError>;
 }
+
+#[cfg_attr(test, mockall::automock)]
+pub trait RateLimiter: Send + Sync {
+    /// Counts a request from the client, returning whether it is within the client's limit.
+    fn allow(&self, client: IpAddr) -> bool;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait PostModeration: Send + Sync {
+    /// Opens a report on a post so that it appears in the moderation queue.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the post does not exist, has been deleted, was written by the reporter,
+    /// or already has an open report from the reporter.
+    async fn report(
+        &self,
+        reporter_id: i32,
+        post_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), ModerationError>;
+
+    /// Determines whether the user with the provided ID is a moderator.
+    async fn is_moderator(&self, user_id: i32) -> Result<bool, ModerationError>;
+
+    /// Closes all open reports on a post using the specified action, archiving or deleting the post
+    /// if the action calls for it, and records the decision in the audit trail. Returns the number
+    /// of reports closed.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the requester is not a moderator, the post does not exist, or the post
+    /// has no open reports. (In which case nothing is mutated.)
+    async fn resolve(
+        &self,
+        moderator_id: i32,
+        post_id: i32,
+        action: ModerationActionKind,
+        note: Option<String>,
+    ) -> Result<u64, ModerationError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait WebhookManagement: Send + Sync {
+    /// Registers a webhook that will be sent the specified kinds of events, returning its ID and
+    /// the secret used to sign its payloads. Webhooks are only sent events involving their owner
+    /// unless `all_users` is set.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `all_users` is set and the owner is not a moderator.
+    async fn register(
+        &self,
+        owner_id: i32,
+        url: &str,
+        events: Vec<WebhookEventKind>,
+        all_users: bool,
+    ) -> Result<NewWebhook, WebhookError>;
+
+    /// Deletes one of the owner's webhooks along with its delivery log.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the webhook does not exist or belongs to someone else.
+    async fn delete(&self, owner_id: i32, id: i32) -> Result<(), WebhookError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait Federation: Send + Sync {
+    /// Fetches a user as an ActivityPub actor, generating the key pair that it signs requests with
+    /// the first time.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the user does not exist.
+    async fn actor(&self, username: &str) -> Result<LocalActor, FederationError>;
+
+    /// Handles an activity sent to a user's inbox. Only `Follow` activities are supported, for
+    /// which the sender is recorded as a follower and sent an `Accept` in the background.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the user does not exist, the activity is malformed or unsupported, or
+    /// the request is not signed by the actor that sent the activity. (In which case nothing is
+    /// mutated.)
+    async fn receive(&self, username: &str, request: InboxRequest) -> Result<(), FederationError>;
+}
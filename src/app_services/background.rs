@@ -0,0 +1,183 @@
+use {
+    std::time::Duration,
+    tokio::{sync::watch, task::JoinSet},
+};
+
+/// How long to wait for workers to finish what they are doing after being told to shut down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A worker that repeatedly polls for work in the background.
+#[async_trait::async_trait]
+pub trait PollingWorker: Send + Sync + 'static {
+    /// Identifies the worker in logs.
+    const NAME: &'static str;
+
+    /// How long to wait before polling again when no more work is immediately available.
+    const POLL_INTERVAL: Duration;
+
+    /// Does a unit of work, returning whether more work is immediately available.
+    async fn poll(&self) -> anyhow::Result<bool>;
+}
+
+/// Tells a background worker or long-lived connection when to stop.
+#[derive(Clone)]
+pub struct ShutdownSignal(watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    fn is_requested(&self) -> bool { *self.0.borrow() }
+
+    /// Waits until shutdown is requested.
+    pub async fn requested(&mut self) {
+        // An error means the sender was dropped, which only happens when shutting down anyway
+        if self.0.wait_for(|&requested| requested).await.is_err() {
+            log::debug!("Shutdown sender dropped");
+        }
+    }
+}
+
+#[cfg(test)]
+impl ShutdownSignal {
+    /// Creates a signal along with the sender that requests shutdown.
+    pub fn channel() -> (watch::Sender<bool>, Self) {
+        let shutdown_tx = watch::Sender::new(false);
+        let signal = Self(shutdown_tx.subscribe());
+        (shutdown_tx, signal)
+    }
+}
+
+/// A signal that is never sent.
+#[cfg(test)]
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        static NEVER: std::sync::LazyLock<watch::Sender<bool>> =
+            std::sync::LazyLock::new(|| watch::Sender::new(false));
+
+        Self(NEVER.subscribe())
+    }
+}
+
+/// The background workers running alongside the server.
+pub struct Workers {
+    shutdown_tx: watch::Sender<bool>,
+    tasks: JoinSet<()>,
+}
+
+impl Workers {
+    pub(crate) fn new() -> Self {
+        Self { shutdown_tx: watch::Sender::new(false), tasks: JoinSet::new() }
+    }
+
+    /// Starts polling with a worker until shutdown is requested.
+    pub(crate) fn spawn(&mut self, worker: impl PollingWorker) {
+        self.tasks
+            .spawn(poll_until_shutdown(worker, self.shutdown_signal()));
+    }
+
+    /// Returns a signal for anything else that should stop when the workers do.
+    pub(crate) fn shutdown_signal(&self) -> ShutdownSignal {
+        ShutdownSignal(self.shutdown_tx.subscribe())
+    }
+
+    /// Wraps `signal` so that shutdown is requested as soon as it completes, without waiting for
+    /// anything to stop. The server waits for every connection to close before returning, so
+    /// long-lived connections need to be told to close before then.
+    pub fn request_shutdown_after<F>(&self, signal: F) -> impl Future<Output = ()> + use<F>
+    where
+        F: Future<Output = ()>,
+    {
+        let shutdown_tx = self.shutdown_tx.clone();
+
+        async move {
+            signal.await;
+            shutdown_tx.send_replace(true);
+        }
+    }
+
+    /// Tells every worker to stop and waits for them to finish their in-progress work, abandoning
+    /// any that are still running after a timeout.
+    pub async fn shut_down(mut self) {
+        self.shutdown_tx.send_replace(true);
+
+        let finished = tokio::time::timeout(SHUTDOWN_TIMEOUT, async {
+            while let Some(result) = self.tasks.join_next().await {
+                if let Err(e) = result {
+                    log::error!("Background worker panicked: {e}");
+                }
+            }
+        })
+        .await;
+
+        match finished {
+            Ok(()) => log::info!("Background workers stopped"),
+            Err(_) => log::warn!("Background workers did not stop in time, abandoning them"),
+        }
+    }
+}
+
+/// Polls with the worker until shutdown is requested, waiting between polls whenever no more work
+/// is immediately available. Errors are logged and treated as having no more work. Work that has
+/// already started is always finished before stopping.
+async fn poll_until_shutdown<W: PollingWorker>(worker: W, mut shutdown: ShutdownSignal) {
+    while !shutdown.is_requested() {
+        let more = worker.poll().await.unwrap_or_else(|e| {
+            log::error!("{} failed: {e:#}", W::NAME);
+            false
+        });
+
+        if !more {
+            tokio::select! {
+                () = tokio::time::sleep(W::POLL_INTERVAL) => {}
+                () = shutdown.requested() => {}
+            }
+        }
+    }
+
+    log::info!("{} stopped", W::NAME);
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::tokio_test,
+        anyhow::Result,
+        std::sync::{
+            Arc,
+            atomic::{AtomicU32, Ordering::SeqCst},
+        },
+    };
+
+    /// Counts its polls, reporting more work for the first few only.
+    struct CountingWorker(Arc<AtomicU32>);
+
+    #[async_trait::async_trait]
+    impl PollingWorker for CountingWorker {
+        const NAME: &'static str = "Counting worker";
+        const POLL_INTERVAL: Duration = Duration::from_mins(1);
+
+        async fn poll(&self) -> Result<bool> { Ok(self.0.fetch_add(1, SeqCst) < 2) }
+    }
+
+    #[test]
+    fn polls_until_shutdown_then_waits_for_the_workers() -> Result<()> {
+        tokio_test(async {
+            let ticks = Arc::new(AtomicU32::new(0));
+
+            let mut workers = Workers::new();
+            workers.spawn(CountingWorker(Arc::clone(&ticks)));
+
+            tokio::time::timeout(Duration::from_secs(5), async {
+                while ticks.load(SeqCst) < 3 {
+                    tokio::task::yield_now().await;
+                }
+            })
+            .await?;
+
+            // Shutting down interrupts the wait for the (long) poll interval
+            tokio::time::timeout(Duration::from_secs(5), workers.shut_down()).await?;
+            assert_eq!(3, ticks.load(SeqCst));
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use {
+    crate::{
+        app_services::{job_worker::JobHandler, uow::UnitOfWork},
+        domain::{
+            federation::{FederationClient, FederationRepo, signature::signed_post_headers},
+            job::{Job, RetryPolicy},
+        },
+    },
+    anyhow::{Result, ensure},
+    chrono::{TimeDelta, Utc},
+    serde::{Deserialize, Serialize},
+};
+
+/// Sends an activity from a local user's actor to a remote inbox, signed with the user's key.
+#[derive(Serialize, Deserialize)]
+pub struct DeliverActivity {
+    pub user_id: i32,
+    /// The ID of the user's key, which the receiver fetches to verify the signature.
+    pub key_id: String,
+    pub inbox: String,
+    pub activity: serde_json::Value,
+}
+
+impl Job for DeliverActivity {
+    const KIND: &'static str = "deliver_activity";
+
+    // Remote servers can be down for a while, so attempts are spread out over about a day
+    const RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 8,
+        base_delay: TimeDelta::minutes(1),
+        max_delay: TimeDelta::hours(6),
+    };
+}
+
+pub struct DeliverActivityHandler<U, F, C> {
+    uow: U,
+    federation_repo: F,
+    client: C,
+}
+
+impl<U, F, C> DeliverActivityHandler<U, F, C> {
+    pub const fn new(uow: U, federation_repo: F, client: C) -> Self {
+        Self { uow, federation_repo, client }
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, F, C> JobHandler for DeliverActivityHandler<U, F, C>
+where
+    U: UnitOfWork + 'static,
+    F: FederationRepo + 'static,
+    C: FederationClient + 'static,
+{
+    type Job = DeliverActivity;
+
+    async fn handle(&self, job: DeliverActivity) -> Result<()> {
+        // The key is deleted along with the user, in which case the activity is no longer theirs
+        // to send
+        let Some(key) = self
+            .federation_repo
+            .key_for(self.uow.single_exec(), job.user_id)
+            .await?
+        else {
+            log::info!("Dropping activity for deleted user {}", job.user_id);
+            return Ok(());
+        };
+
+        let body = serde_json::to_string(&job.activity)?;
+        let headers = signed_post_headers(
+            &key.private_key_pem,
+            &job.key_id,
+            &job.inbox,
+            body.as_bytes(),
+            Utc::now(),
+        )?;
+
+        let status = self.client.post(&job.inbox, headers, body).await?;
+        ensure!((200..300).contains(&status), "{} responded with {status}", job.inbox);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::federation::{
+                MockFederationClient,
+                signature::{Signature, SignedRequest},
+            },
+            test_utils::{
+                dummy_data::actor_key, fake_db::FakeUow, mock_repos::MockFederationRepo, tokio_test,
+            },
+        },
+        anyhow::Context as _,
+        mockall::predicate::{always, eq},
+        serde_json::json,
+    };
+
+    const KEY_ID: &str = "https://spur.example/users/drake#main-key";
+    const INBOX: &str = "https://remote.example/users/alice/inbox";
+
+    fn job() -> DeliverActivity {
+        DeliverActivity {
+            user_id: 1,
+            key_id: String::from(KEY_ID),
+            inbox: String::from(INBOX),
+            activity: json!({ "type": "Accept" }),
+        }
+    }
+
+    fn repo_with_key() -> MockFederationRepo {
+        MockFederationRepo {
+            key_for: Some(Box::new(|user_id| {
+                assert_eq!(1, user_id);
+                Ok(Some(actor_key::number1()))
+            })),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a client that checks the signature of the posted activity and responds with
+    /// `status`.
+    fn verifying_client(status: u16) -> MockFederationClient {
+        let mut client = MockFederationClient::new();
+        client
+            .expect_post()
+            .with(eq(INBOX), always(), eq(String::from(r#"{"type":"Accept"}"#)))
+            .times(1)
+            .returning(move |_, sent_headers, body| {
+                let headers: Vec<_> = sent_headers
+                    .into_iter()
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .collect();
+                let signature_header = headers
+                    .iter()
+                    .find(|(name, _)| name == "signature")
+                    .map(|(_, value)| value.as_str())
+                    .context("no signature header")?;
+                let signature = Signature::parse(signature_header)?;
+
+                assert_eq!(KEY_ID, signature.key_id);
+                signature.verify(
+                    &actor_key::number1().public_key_pem,
+                    &SignedRequest {
+                        method: "POST",
+                        path: "/users/alice/inbox",
+                        headers: &headers,
+                        body: body.as_bytes(),
+                    },
+                    Utc::now(),
+                )?;
+
+                Ok(status)
+            });
+        client
+    }
+
+    #[test]
+    fn signs_and_posts_the_activity() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+
+            DeliverActivityHandler::new(fake_uow, repo_with_key(), verifying_client(202))
+                .handle(job())
+                .await
+        })
+    }
+
+    #[test]
+    fn fails_on_unsuccessful_responses_so_that_it_is_retried() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+
+            let result =
+                DeliverActivityHandler::new(fake_uow, repo_with_key(), verifying_client(503))
+                    .handle(job())
+                    .await;
+            assert!(result.is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn drops_activities_of_deleted_users() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+            let repo =
+                MockFederationRepo { key_for: Some(Box::new(|_| Ok(None))), ..Default::default() };
+
+            // The client has no expectations, so posting would panic
+            DeliverActivityHandler::new(fake_uow, repo, MockFederationClient::new())
+                .handle(job())
+                .await
+        })
+    }
+}
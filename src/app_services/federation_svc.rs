@@ -0,0 +1,691 @@
+use {
+    crate::{
+        app_services::{
+            Federation,
+            deliver_activity::DeliverActivity,
+            uow::{Tx as _, UnitOfWork},
+        },
+        config::PublicUrl,
+        domain::{
+            federation::{
+                FederationClient, FederationRepo, KEY_FRAGMENT, actor_path,
+                error::FederationError,
+                generate_key,
+                signature::{Signature, SignedRequest},
+            },
+            job::{JobRepo, NewJob},
+            outbound::OutboundPolicy,
+            user::UserRepo,
+        },
+        models::federation::{ActorKey, InboxRequest, LocalActor},
+    },
+    chrono::Utc,
+    serde_json::{Value, json},
+    url::Url,
+};
+
+pub struct FederationSvc<Uo, Us, F, J, C> {
+    uow: Uo,
+    user_repo: Us,
+    federation_repo: F,
+    job_repo: J,
+    client: C,
+    public_url: PublicUrl,
+    outbound_policy: OutboundPolicy,
+}
+
+impl<Uo, Us, F, J, C> FederationSvc<Uo, Us, F, J, C> {
+    pub const fn new(
+        uow: Uo,
+        user_repo: Us,
+        federation_repo: F,
+        job_repo: J,
+        client: C,
+        public_url: PublicUrl,
+        outbound_policy: OutboundPolicy,
+    ) -> Self {
+        Self { uow, user_repo, federation_repo, job_repo, client, public_url, outbound_policy }
+    }
+}
+
+/// Whether two URLs have the same scheme, host, and port.
+fn same_origin(first: &str, second: &str) -> bool {
+    let origin = |url: &str| Url::parse(url).ok().map(|parsed| parsed.origin());
+    origin(first).is_some_and(|first_origin| Some(first_origin) == origin(second))
+}
+
+/// Fetches a user's key pair, generating one if they do not have one yet. Generating a key takes a
+/// while, so it is done outside of any transaction. If concurrent requests both generate a key,
+/// only the first one stored is kept, and both return it.
+async fn key_for(
+    uow: &impl UnitOfWork,
+    federation_repo: &impl FederationRepo,
+    user_id: i32,
+) -> Result<ActorKey, FederationError> {
+    if let Some(key) = federation_repo.key_for(uow.single_exec(), user_id).await? {
+        return Ok(key);
+    }
+
+    let generated = tokio::task::spawn_blocking(generate_key)
+        .await
+        .map_err(anyhow::Error::from)??;
+    federation_repo
+        .insert_key(uow.single_exec(), user_id, &generated)
+        .await?;
+
+    // Keys are deleted along with their users, so the user must have been deleted in the meantime
+    federation_repo
+        .key_for(uow.single_exec(), user_id)
+        .await?
+        .ok_or(FederationError::UserNotFound)
+}
+
+#[async_trait::async_trait]
+impl<Uo, Us, F, J, C> Federation for FederationSvc<Uo, Us, F, J, C>
+where
+    Uo: UnitOfWork,
+    Us: UserRepo,
+    F: FederationRepo,
+    J: JobRepo,
+    C: FederationClient,
+{
+    async fn actor(&self, username: &str) -> Result<LocalActor, FederationError> {
+        let user = self
+            .user_repo
+            .get_by_username(self.uow.single_exec(), username)
+            .await?
+            .ok_or(FederationError::UserNotFound)?;
+        let key = key_for(&self.uow, &self.federation_repo, user.id).await?;
+
+        Ok(LocalActor {
+            user_id: user.id,
+            username: user.username,
+            name: user.name,
+            public_key_pem: key.public_key_pem,
+        })
+    }
+
+    async fn receive(&self, username: &str, request: InboxRequest) -> Result<(), FederationError> {
+        let activity: Value = serde_json::from_slice(&request.body)
+            .map_err(|_| FederationError::InvalidActivity("body is not JSON"))?;
+        let field = |pointer| activity.pointer(pointer).and_then(Value::as_str);
+
+        let kind = field("/type").ok_or(FederationError::InvalidActivity("missing type"))?;
+        if kind != "Follow" {
+            return Err(FederationError::UnsupportedActivity(kind.to_owned()));
+        }
+
+        let actor_id = self.public_url.join(&actor_path(username));
+        // The object may be embedded rather than referred to by ID
+        if field("/object").or_else(|| field("/object/id")) != Some(actor_id.as_str()) {
+            return Err(FederationError::InvalidActivity("object is not the inbox's owner"));
+        }
+        let follower_id =
+            field("/actor").ok_or(FederationError::InvalidActivity("missing actor"))?;
+
+        let signature = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("signature"))
+            .ok_or(FederationError::InvalidSignature)
+            .and_then(|(_, value)| Signature::parse(value))?;
+
+        // Keys are fetched from their owners' actor documents so that the activity's actor can be
+        // checked against the owner. Only keys on the actor's own server are fetched, and only if
+        // requests may be sent there.
+        let actor_url = signature
+            .key_id
+            .split_once('#')
+            .map_or(signature.key_id.as_str(), |(url, _)| url);
+        if !same_origin(actor_url, follower_id) {
+            return Err(FederationError::InvalidSignature);
+        }
+        self.outbound_policy.check_url(actor_url).map_err(|e| {
+            log::warn!("Refusing to fetch actor {actor_url} to verify a signature: {e}");
+            FederationError::InvalidSignature
+        })?;
+        let follower = self.client.fetch_actor(actor_url).await.map_err(|e| {
+            log::warn!("Failed to fetch actor {actor_url} to verify a signature: {e:#}");
+            FederationError::InvalidSignature
+        })?;
+        if follower.id != follower_id
+            || follower.public_key.id != signature.key_id
+            || follower.public_key.owner != follower.id
+        {
+            return Err(FederationError::InvalidSignature);
+        }
+        // Activities are delivered to the inbox later on
+        self.outbound_policy
+            .check_url(&follower.inbox)
+            .map_err(|_| {
+                FederationError::InvalidActivity("the actor's inbox may not be contacted")
+            })?;
+
+        signature.verify(
+            &follower.public_key.public_key_pem,
+            &SignedRequest {
+                method: "POST",
+                path: &request.path,
+                headers: &request.headers,
+                body: &request.body,
+            },
+            Utc::now(),
+        )?;
+
+        let accept = json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": format!("{actor_id}#accepts/{}", hex::encode(rand::random::<[u8; 16]>())),
+            "type": "Accept",
+            "actor": actor_id,
+            "object": activity,
+        });
+
+        // Makes sure that the key the `Accept` is signed with exists before it is sent
+        let user = self
+            .user_repo
+            .get_by_username(self.uow.single_exec(), username)
+            .await?
+            .ok_or(FederationError::UserNotFound)?;
+        key_for(&self.uow, &self.federation_repo, user.id).await?;
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        let user_id = self
+            .user_repo
+            .get_by_username_exclusive(tx.exec(), username)
+            .await?
+            .ok_or(FederationError::UserNotFound)?
+            .id;
+
+        self.federation_repo
+            .add_follower(tx.exec(), user_id, &follower)
+            .await?;
+
+        self.job_repo
+            .enqueue(
+                tx.exec(),
+                &NewJob::new(&DeliverActivity {
+                    user_id,
+                    key_id: [actor_id.as_str(), KEY_FRAGMENT].concat(),
+                    inbox: follower.inbox.clone(),
+                    activity: accept,
+                })?,
+            )
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::federation::{MockFederationClient, signature::signed_post_headers},
+            models::federation::{RemoteActor, RemotePublicKey},
+            test_utils::{
+                dummy_data::{actor_key, user},
+                fake_db::FakeUow,
+                mock_repos::{MockFederationRepo, MockJobRepo, MockUserRepo},
+                tokio_test,
+            },
+        },
+        anyhow::{Result, anyhow},
+        std::sync::{
+            Arc, Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    const FOLLOWER_ID: &str = "https://remote.example/users/alice";
+    const FOLLOWER_INBOX: &str = "https://remote.example/users/alice/inbox";
+
+    fn follower() -> RemoteActor {
+        RemoteActor {
+            id: String::from(FOLLOWER_ID),
+            inbox: String::from(FOLLOWER_INBOX),
+            public_key: RemotePublicKey {
+                id: format!("{FOLLOWER_ID}#main-key"),
+                owner: String::from(FOLLOWER_ID),
+                public_key_pem: actor_key::number2().public_key_pem,
+            },
+        }
+    }
+
+    /// A `Follow` of the first dummy user from the follower, signed with the follower's key.
+    fn signed_follow() -> Result<InboxRequest> {
+        let body = serde_json::to_vec(&json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": format!("{FOLLOWER_ID}#follows/1"),
+            "type": "Follow",
+            "actor": FOLLOWER_ID,
+            "object": format!("https://spur.example/users/{}", user::number1().username),
+        }))?;
+
+        signed(&user::number1().username, body)
+    }
+
+    /// Signs a POST of the body to the user's inbox with the follower's key.
+    fn signed(username: &str, body: Vec<u8>) -> Result<InboxRequest> {
+        signed_as(username, &format!("{FOLLOWER_ID}#main-key"), body)
+    }
+
+    /// Signs a POST of the body to the user's inbox with the follower's key, identified by
+    /// `key_id`.
+    fn signed_as(username: &str, key_id: &str, body: Vec<u8>) -> Result<InboxRequest> {
+        let path = format!("/users/{username}/inbox");
+        let headers = signed_post_headers(
+            &actor_key::number2().private_key_pem,
+            key_id,
+            &format!("https://spur.example{path}"),
+            &body,
+            Utc::now(),
+        )?
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect();
+
+        Ok(InboxRequest { path, headers, body })
+    }
+
+    fn user_repo() -> MockUserRepo {
+        let by_username = |username: &str| {
+            let user = user::number1();
+            Ok((username == user.username).then_some(user))
+        };
+
+        MockUserRepo {
+            get_by_username: Some(Box::new(by_username)),
+            get_by_username_exclusive: Some(Box::new(by_username)),
+            ..Default::default()
+        }
+    }
+
+    fn client_fetching(actor: RemoteActor) -> MockFederationClient {
+        let mut client = MockFederationClient::new();
+        client
+            .expect_fetch_actor()
+            .withf(|url| url == FOLLOWER_ID)
+            .returning(move |_| Ok(actor.clone()));
+        client
+    }
+
+    fn svc(
+        uow: FakeUow,
+        federation_repo: MockFederationRepo,
+        job_repo: MockJobRepo,
+        client: MockFederationClient,
+    ) -> impl Federation {
+        FederationSvc::new(
+            uow,
+            user_repo(),
+            federation_repo,
+            job_repo,
+            client,
+            PublicUrl::default(),
+            OutboundPolicy::PublicOnly,
+        )
+    }
+
+    #[test]
+    fn describes_users_with_their_existing_keys() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+            let repo = MockFederationRepo {
+                key_for: Some(Box::new(|_| Ok(Some(actor_key::number1())))),
+                ..Default::default()
+            };
+            let user = user::number1();
+
+            let actor = svc(fake_uow, repo, MockJobRepo::default(), MockFederationClient::new())
+                .actor(&user.username)
+                .await?;
+
+            assert_eq!(
+                LocalActor {
+                    user_id: user.id,
+                    username: user.username,
+                    name: user.name,
+                    public_key_pem: actor_key::number1().public_key_pem,
+                },
+                actor
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn generates_keys_for_users_without_them() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+            let inserted = Arc::new(Mutex::new(None));
+            let inserted_clone = Arc::clone(&inserted);
+            let stored = Arc::clone(&inserted);
+            let repo = MockFederationRepo {
+                key_for: Some(Box::new(move |_| {
+                    Ok(stored
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .clone()
+                        .map(|(_, key)| key))
+                })),
+                insert_key: Some(Box::new(move |user_id, key| {
+                    *inserted_clone.lock().map_err(|_| anyhow!("poisoned"))? =
+                        Some((user_id, key.clone()));
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let actor = svc(fake_uow, repo, MockJobRepo::default(), MockFederationClient::new())
+                .actor(&user::number1().username)
+                .await?;
+
+            assert_eq!(
+                Some((user::number1().id, actor.public_key_pem)),
+                inserted
+                    .lock()
+                    .map_err(|_| anyhow!("poisoned"))?
+                    .clone()
+                    .map(|(user_id, key)| (user_id, key.public_key_pem))
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn keeps_keys_stored_by_concurrent_requests() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+            // Another request stores its key while this one generates one
+            let lookups = Arc::new(AtomicUsize::new(0));
+            let lookups_clone = Arc::clone(&lookups);
+            let repo = MockFederationRepo {
+                key_for: Some(Box::new(move |_| {
+                    Ok((lookups_clone.fetch_add(1, Ordering::SeqCst) > 0).then(actor_key::number1))
+                })),
+                insert_key: Some(Box::new(|_, _| Ok(()))),
+                ..Default::default()
+            };
+
+            let actor = svc(fake_uow, repo, MockJobRepo::default(), MockFederationClient::new())
+                .actor(&user::number1().username)
+                .await?;
+
+            assert_eq!(actor_key::number1().public_key_pem, actor.public_key_pem);
+            assert_eq!(2, lookups.load(Ordering::SeqCst));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn fails_to_describe_nonexistent_users() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+
+            let result = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                MockFederationClient::new(),
+            )
+            .actor("nobody")
+            .await;
+
+            assert_eq!(Err(FederationError::UserNotFound), result);
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn records_followers_and_queues_an_accept() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let enqueued = Arc::new(Mutex::new(Vec::new()));
+            let enqueued_clone = Arc::clone(&enqueued);
+
+            let repo = MockFederationRepo {
+                key_for: Some(Box::new(|_| Ok(Some(actor_key::number1())))),
+                add_follower: Some(Box::new(|user_id, actor| {
+                    assert_eq!(user::number1().id, user_id);
+                    assert_eq!(&follower(), actor);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+            let job_repo = MockJobRepo {
+                enqueue: Some(Box::new(move |job| {
+                    enqueued_clone
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .push(job.clone());
+                    Ok(Some(1))
+                })),
+                ..Default::default()
+            };
+
+            let request = signed_follow()?;
+            let follow: Value = serde_json::from_slice(&request.body)?;
+            svc(fake_uow, repo, job_repo, client_fetching(follower()))
+                .receive(&user::number1().username, request)
+                .await?;
+
+            let jobs = enqueued.lock().map_err(|_| anyhow!("poisoned"))?.clone();
+            let [job] = jobs.as_slice() else {
+                return Err(anyhow!("expected one job, got {jobs:?}"));
+            };
+            let delivery: DeliverActivity = serde_json::from_value(job.payload.clone())?;
+            let actor_id = format!("https://spur.example/users/{}", user::number1().username);
+
+            assert_eq!("deliver_activity", job.kind);
+            assert_eq!(user::number1().id, delivery.user_id);
+            assert_eq!(format!("{actor_id}#main-key"), delivery.key_id);
+            assert_eq!(FOLLOWER_INBOX, delivery.inbox);
+            assert_eq!(Some("Accept"), delivery.activity.pointer("/type").and_then(Value::as_str));
+            assert_eq!(
+                Some(actor_id.as_str()),
+                delivery.activity.pointer("/actor").and_then(Value::as_str)
+            );
+            assert_eq!(Some(&follow), delivery.activity.pointer("/object"));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_follows_signed_with_another_key() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let impostor = RemoteActor {
+                public_key: RemotePublicKey {
+                    public_key_pem: actor_key::number1().public_key_pem,
+                    ..follower().public_key
+                },
+                ..follower()
+            };
+
+            let result = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                client_fetching(impostor),
+            )
+            .receive(&user::number1().username, signed_follow()?)
+            .await;
+
+            assert_eq!(Err(FederationError::InvalidSignature), result);
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_follows_on_behalf_of_other_actors() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let body = serde_json::to_vec(&json!({
+                "type": "Follow",
+                "actor": "https://remote.example/users/bob",
+                "object": format!("https://spur.example/users/{}", user::number1().username),
+            }))?;
+
+            let result = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                client_fetching(follower()),
+            )
+            .receive(&user::number1().username, signed(&user::number1().username, body)?)
+            .await;
+
+            assert_eq!(Err(FederationError::InvalidSignature), result);
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn only_fetches_keys_from_the_actors_own_public_server() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            // The key should not be fetched
+            let federation = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                MockFederationClient::new(),
+            );
+            let username = user::number1().username;
+            let follow_from = |actor: &str| {
+                serde_json::to_vec(&json!({
+                    "type": "Follow",
+                    "actor": actor,
+                    "object": format!("https://spur.example/users/{username}"),
+                }))
+            };
+
+            for (actor, key_id) in [
+                (FOLLOWER_ID, "https://other.example/users/alice#main-key"),
+                (FOLLOWER_ID, "http://remote.example/users/alice#main-key"),
+                ("https://127.0.0.1/users/alice", "https://127.0.0.1/users/alice#main-key"),
+                ("https://localhost/users/alice", "https://localhost/users/alice#main-key"),
+                ("https://10.0.0.5/users/alice", "https://10.0.0.5/users/alice#main-key"),
+            ] {
+                assert_eq!(
+                    Err(FederationError::InvalidSignature),
+                    federation
+                        .receive(&username, signed_as(&username, key_id, follow_from(actor)?)?)
+                        .await,
+                    "{key_id}"
+                );
+            }
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_followers_with_internal_inboxes() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let internal = RemoteActor {
+                inbox: String::from("https://169.254.169.254/latest/meta-data"),
+                ..follower()
+            };
+
+            let result = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                client_fetching(internal),
+            )
+            .receive(&user::number1().username, signed_follow()?)
+            .await;
+
+            assert_eq!(Err(FederationError::InvalidActivity("")), result);
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_unsupported_and_misaddressed_activities() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, _) = FakeUow::with_probe()?;
+            let federation = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                MockFederationClient::new(),
+            );
+            let username = user::number1().username;
+
+            let like = serde_json::to_vec(&json!({ "type": "Like", "actor": FOLLOWER_ID }))?;
+            assert_eq!(
+                Err(FederationError::UnsupportedActivity(String::from("Like"))),
+                federation
+                    .receive(&username, signed(&username, like)?)
+                    .await
+            );
+
+            let other_user = serde_json::to_vec(&json!({
+                "type": "Follow",
+                "actor": FOLLOWER_ID,
+                "object": "https://spur.example/users/someone_else",
+            }))?;
+            assert_eq!(
+                Err(FederationError::InvalidActivity("")),
+                federation
+                    .receive(&username, signed(&username, other_user)?)
+                    .await
+            );
+
+            let unsigned = InboxRequest { headers: Vec::new(), ..signed_follow()? };
+            assert_eq!(
+                Err(FederationError::InvalidSignature),
+                federation.receive(&username, unsigned).await
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn fails_to_receive_for_nonexistent_users() -> Result<()> {
+        tokio_test(async {
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let body = serde_json::to_vec(&json!({
+                "type": "Follow",
+                "actor": FOLLOWER_ID,
+                "object": "https://spur.example/users/nobody",
+            }))?;
+
+            let result = svc(
+                fake_uow,
+                MockFederationRepo::default(),
+                MockJobRepo::default(),
+                client_fetching(follower()),
+            )
+            .receive("nobody", signed("nobody", body)?)
+            .await;
+
+            assert_eq!(Err(FederationError::UserNotFound), result);
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use {
+    crate::app_services::RateLimiter,
+    std::{
+        collections::HashMap,
+        net::IpAddr,
+        sync::{Mutex, PoisonError},
+        time::{Duration, Instant},
+    },
+};
+
+/// Once this many clients are being tracked, clients whose windows have ended are forgotten.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Allows each client a fixed number of requests per window of time, starting a client's window
+/// with its first request. Counts are kept in memory, so they are per server and reset on restart.
+pub struct FixedWindowLimiter {
+    max_requests: u32,
+    window: Duration,
+    clients: Mutex<HashMap<IpAddr, ClientWindow>>,
+}
+
+struct ClientWindow {
+    started_at: Instant,
+    requests: u32,
+}
+
+impl FixedWindowLimiter {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self { max_requests, window, clients: Mutex::new(HashMap::new()) }
+    }
+
+    fn allow_at(&self, client: IpAddr, now: Instant) -> bool {
+        // A panic elsewhere while holding the lock can't leave the counts inconsistent
+        let mut clients = self.clients.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if clients.len() >= PRUNE_THRESHOLD && !clients.contains_key(&client) {
+            clients.retain(|_, w| now.saturating_duration_since(w.started_at) < self.window);
+        }
+
+        let current = clients
+            .entry(client)
+            .or_insert(ClientWindow { started_at: now, requests: 0 });
+
+        if now.saturating_duration_since(current.started_at) >= self.window {
+            *current = ClientWindow { started_at: now, requests: 0 };
+        }
+
+        let allowed = current.requests < self.max_requests;
+        if allowed {
+            current.requests = current.requests.saturating_add(1);
+        }
+        drop(clients);
+
+        allowed
+    }
+}
+
+impl RateLimiter for FixedWindowLimiter {
+    fn allow(&self, client: IpAddr) -> bool { self.allow_at(client, Instant::now()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        anyhow::{Context as _, Result},
+        std::net::Ipv4Addr,
+    };
+
+    #[test]
+    fn limits_each_client_separately_until_its_window_ends() -> Result<()> {
+        let limiter = FixedWindowLimiter::new(2, Duration::from_mins(1));
+        let (first, second) =
+            (IpAddr::from(Ipv4Addr::new(10, 0, 0, 1)), IpAddr::from(Ipv4Addr::LOCALHOST));
+        let start = Instant::now();
+        let later = |secs| {
+            start
+                .checked_add(Duration::from_secs(secs))
+                .context("out of range")
+        };
+
+        assert!(limiter.allow_at(first, start));
+        assert!(limiter.allow_at(first, later(10)?));
+        assert!(!limiter.allow_at(first, later(20)?));
+        assert!(limiter.allow_at(second, later(20)?));
+
+        // The first client's window started with its first request
+        assert!(limiter.allow_at(first, later(60)?));
+        assert!(limiter.allow_at(first, later(61)?));
+        assert!(!limiter.allow_at(first, later(62)?));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+use {
+    crate::{
+        app_services::{background::PollingWorker, uow::UnitOfWork},
+        domain::{
+            RepoError,
+            job::{Job, JobRepo, RetryPolicy},
+        },
+        models::job::ClaimedJob,
+    },
+    anyhow::{Context as _, anyhow},
+    chrono::{DateTime, TimeDelta, Utc},
+    futures_util::future,
+    std::{collections::HashMap, time::Duration},
+};
+
+/// The maximum number of jobs claimed (and run concurrently) at once.
+const BATCH_SIZE: i64 = 10;
+
+/// How long claimed jobs are reserved for this worker.
+const LEASE: TimeDelta = TimeDelta::minutes(5);
+
+/// How long a job may run before it is considered failed. Shorter than the lease so that a job is
+/// never run by two workers at once.
+const JOB_TIMEOUT: Duration = Duration::from_mins(4);
+
+/// Runs one kind of job.
+#[async_trait::async_trait]
+pub trait JobHandler: Send + Sync + 'static {
+    type Job: Job;
+
+    /// Runs the job. Returning `Err` causes the job to be retried according to its retry policy.
+    async fn handle(&self, job: Self::Job) -> anyhow::Result<()>;
+}
+
+/// A `JobHandler` with its job type erased so that handlers for different kinds of jobs can be
+/// stored together.
+#[async_trait::async_trait]
+trait ErasedJobHandler: Send + Sync {
+    fn retry_policy(&self) -> RetryPolicy;
+    async fn handle_payload(&self, payload: serde_json::Value) -> anyhow::Result<()>;
+}
+
+#[async_trait::async_trait]
+impl<H: JobHandler> ErasedJobHandler for H {
+    fn retry_policy(&self) -> RetryPolicy { H::Job::RETRY_POLICY }
+
+    async fn handle_payload(&self, payload: serde_json::Value) -> anyhow::Result<()> {
+        let job = serde_json::from_value(payload)
+            .with_context(|| format!("failed to deserialize {} job", H::Job::KIND))?;
+
+        self.handle(job).await
+    }
+}
+
+/// Background worker that runs queued jobs using the handlers registered for their kinds.
+pub struct JobWorker<U, R> {
+    uow: U,
+    repo: R,
+    handlers: HashMap<&'static str, Box<dyn ErasedJobHandler>>,
+}
+
+impl<U, R> JobWorker<U, R>
+where
+    U: UnitOfWork,
+    R: JobRepo,
+{
+    pub fn new(uow: U, repo: R) -> Self { Self { uow, repo, handlers: HashMap::new() } }
+
+    /// Registers the handler for a kind of job, replacing any existing one.
+    pub fn with_handler(mut self, handler: impl JobHandler) -> Self {
+        const fn kind<H: JobHandler>(_: &H) -> &'static str { H::Job::KIND }
+
+        self.handlers.insert(kind(&handler), Box::new(handler));
+        self
+    }
+
+    /// Claims a batch of due jobs and runs each of them, returning the number claimed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if claiming the jobs or recording the results fails.
+    pub async fn run_due(&self) -> Result<usize, RepoError> {
+        let jobs = self
+            .repo
+            .claim_due(self.uow.single_exec(), BATCH_SIZE, LEASE)
+            .await?;
+
+        let results = future::join_all(jobs.iter().map(|job| self.run_one(job))).await;
+
+        for (job, result) in jobs.iter().zip(results) {
+            let recorded = match result {
+                Ok(()) => {
+                    self.repo
+                        .complete(self.uow.single_exec(), job.id, job.attempts)
+                        .await?
+                }
+                Err((e, retry_at)) => {
+                    let error = format!("{e:#}");
+
+                    if retry_at.is_none() {
+                        log::error!("Dead-lettering {} job {}: {error}", job.kind, job.id);
+                    }
+
+                    self.repo
+                        .fail(self.uow.single_exec(), job.id, job.attempts, &error, retry_at)
+                        .await?
+                }
+            };
+
+            // Another worker claimed the job after the lease expired, so its result counts instead
+            if !recorded {
+                log::warn!("Lost the lease on {} job {}, discarding the result", job.kind, job.id);
+            }
+        }
+
+        Ok(jobs.len())
+    }
+
+    /// Runs a single job, returning the error and when to retry (if ever) on failure.
+    async fn run_one(
+        &self,
+        job: &ClaimedJob,
+    ) -> Result<(), (anyhow::Error, Option<DateTime<Utc>>)> {
+        // Jobs without handlers can never succeed, so they are dead-lettered immediately
+        let handler = self
+            .handlers
+            .get(job.kind.as_str())
+            .ok_or_else(|| (anyhow!("no handler registered for {} jobs", job.kind), None))?;
+
+        let result = tokio::time::timeout(JOB_TIMEOUT, handler.handle_payload(job.payload.clone()))
+            .await
+            .unwrap_or_else(|_| Err(anyhow!("timed out after {JOB_TIMEOUT:?}")));
+
+        result.map_err(|e| (e, handler.retry_policy().next_attempt_at(job.attempts)))
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, R> PollingWorker for JobWorker<U, R>
+where
+    U: UnitOfWork + 'static,
+    R: JobRepo + 'static,
+{
+    const NAME: &'static str = "Job worker";
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    async fn poll(&self) -> anyhow::Result<bool> {
+        let claimed = self.run_due().await?;
+        Ok(i64::try_from(claimed).is_ok_and(|n| n >= BATCH_SIZE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::{fake_db::fake_pool, mock_repos::MockJobRepo, tokio_test},
+        anyhow::Result,
+        serde::{Deserialize, Serialize},
+        serde_json::json,
+        std::sync::{Arc, Mutex},
+    };
+
+    #[derive(Serialize, Deserialize)]
+    struct Divide {
+        dividend: i32,
+        divisor: i32,
+    }
+
+    impl Job for Divide {
+        const KIND: &'static str = "divide";
+        const RETRY_POLICY: RetryPolicy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: TimeDelta::minutes(1),
+            max_delay: TimeDelta::minutes(10),
+        };
+    }
+
+    /// Records the quotients of the jobs it runs, failing on division by zero.
+    struct DivideHandler(Arc<Mutex<Vec<i32>>>);
+
+    #[async_trait::async_trait]
+    impl JobHandler for DivideHandler {
+        type Job = Divide;
+
+        async fn handle(&self, job: Divide) -> Result<()> {
+            let quotient = job
+                .dividend
+                .checked_div(job.divisor)
+                .context("division by zero")?;
+            self.0
+                .lock()
+                .map_err(|_| anyhow!("poisoned"))?
+                .push(quotient);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Outcome {
+        Completed,
+        Retried(String),
+        Dead(String),
+    }
+
+    fn claimed(id: i32, kind: &str, payload: serde_json::Value, attempts: i32) -> ClaimedJob {
+        ClaimedJob { id, kind: String::from(kind), payload, attempts }
+    }
+
+    #[test]
+    fn runs_jobs_and_retries_or_dead_letters_failures() -> Result<()> {
+        tokio_test(async {
+            let jobs = vec![
+                claimed(1, "divide", json!({ "dividend": 12, "divisor": 4 }), 1),
+                claimed(2, "divide", json!({ "dividend": 1, "divisor": 0 }), 1),
+                claimed(3, "divide", json!({ "dividend": 1, "divisor": 0 }), 3),
+                claimed(4, "divide", json!({ "nonsense": true }), 1),
+                claimed(5, "unknown", json!({}), 1),
+            ];
+
+            let outcomes = Arc::new(Mutex::new(Vec::new()));
+            let (complete_outcomes, fail_outcomes) = (Arc::clone(&outcomes), Arc::clone(&outcomes));
+            let retry_times = Arc::new(Mutex::new(Vec::<DateTime<Utc>>::new()));
+            let retry_times_clone = Arc::clone(&retry_times);
+
+            let mock_repo = MockJobRepo {
+                claim_due: Some(Box::new(move |limit, lease| {
+                    assert_eq!((BATCH_SIZE, LEASE), (limit, lease));
+                    Ok(jobs.clone())
+                })),
+                complete: Some(Box::new(move |id, attempt| {
+                    assert_eq!(1, attempt);
+                    complete_outcomes
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .push((id, Outcome::Completed));
+                    Ok(true)
+                })),
+                fail: Some(Box::new(move |id, attempt, error, retry_at| {
+                    assert_eq!(if id == 3 { 3 } else { 1 }, attempt);
+                    let outcome = if let Some(at) = retry_at {
+                        retry_times_clone
+                            .lock()
+                            .map_err(|_| anyhow!("poisoned"))?
+                            .push(at);
+                        Outcome::Retried(error.to_owned())
+                    } else {
+                        Outcome::Dead(error.to_owned())
+                    };
+                    fail_outcomes
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .push((id, outcome));
+                    Ok(true)
+                })),
+                ..Default::default()
+            };
+
+            let quotients = Arc::new(Mutex::new(Vec::new()));
+            let before = Utc::now();
+
+            let claimed_count = JobWorker::new(fake_pool()?, mock_repo)
+                .with_handler(DivideHandler(Arc::clone(&quotients)))
+                .run_due()
+                .await?;
+
+            assert_eq!(5, claimed_count);
+            assert_eq!(vec![3], *quotients.lock().map_err(|_| anyhow!("poisoned"))?);
+
+            let mut recorded =
+                std::mem::take(&mut *outcomes.lock().map_err(|_| anyhow!("poisoned"))?);
+            recorded.sort_by_key(|(id, _)| *id);
+            assert_eq!(
+                vec![
+                    (1, Outcome::Completed),
+                    (2, Outcome::Retried(String::from("division by zero"))),
+                    // Out of attempts
+                    (3, Outcome::Dead(String::from("division by zero"))),
+                    // Malformed payloads are retried in case a handler fix is deployed
+                    (
+                        4,
+                        Outcome::Retried(String::from(
+                            "failed to deserialize divide job: missing field `dividend`"
+                        ))
+                    ),
+                    (5, Outcome::Dead(String::from("no handler registered for unknown jobs"))),
+                ],
+                recorded,
+            );
+
+            // Retried after the policy's base delay
+            for at in retry_times.lock().map_err(|_| anyhow!("poisoned"))?.iter() {
+                assert_eq!(1, at.signed_duration_since(before).num_minutes());
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -7,28 +7,41 @@ use crate::{
         friendship::{
             FriendshipRepo, FriendshipStatus, error::FriendshipError, user_id_pair::UserIdPair,
         },
+        notification::NotificationRepo,
         user::UserRepo,
+        webhook::WebhookRepo,
     },
+    models::{notification::NotificationKind, webhook::WebhookEvent},
 };
 
-pub struct MutateFriendshipByUsernameSvc<Uo, Us, F> {
+pub struct MutateFriendshipByUsernameSvc<Uo, Us, F, N, W> {
     uow: Uo,
     user_repo: Us,
     friendship_repo: F,
+    notification_repo: N,
+    webhook_repo: W,
 }
 
-impl<Uo, Us, F> MutateFriendshipByUsernameSvc<Uo, Us, F> {
-    pub const fn new(uow: Uo, user_repo: Us, friendship_repo: F) -> Self {
-        Self { uow, user_repo, friendship_repo }
+impl<Uo, Us, F, N, W> MutateFriendshipByUsernameSvc<Uo, Us, F, N, W> {
+    pub const fn new(
+        uow: Uo,
+        user_repo: Us,
+        friendship_repo: F,
+        notification_repo: N,
+        webhook_repo: W,
+    ) -> Self {
+        Self { uow, user_repo, friendship_repo, notification_repo, webhook_repo }
     }
 }
 
 #[async_trait::async_trait]
-impl<Uo, Us, F> MutateFriendshipByUsername for MutateFriendshipByUsernameSvc<Uo, Us, F>
+impl<Uo, Us, F, N, W> MutateFriendshipByUsername for MutateFriendshipByUsernameSvc<Uo, Us, F, N, W>
 where
     Uo: UnitOfWork,
     Us: UserRepo,
     F: FriendshipRepo,
+    N: NotificationRepo,
+    W: WebhookRepo,
 {
     async fn add_friend_by_username(
         &self,
@@ -58,6 +71,24 @@ where
             // Already a pending request in the opposite direction, so accept it
             FriendshipStatus::PendingFrom(_) => {
                 self.friendship_repo.accept_request(tx.exec(), &ids).await?;
+                self.notification_repo
+                    .insert_new(
+                        tx.exec(),
+                        recipient_id,
+                        sender_id,
+                        NotificationKind::FriendAccepted,
+                        None,
+                    )
+                    .await?;
+                self.webhook_repo
+                    .enqueue(
+                        tx.exec(),
+                        &WebhookEvent::FriendshipAccepted {
+                            requester_id: recipient_id,
+                            accepter_id: sender_id,
+                        },
+                    )
+                    .await?;
                 Ok(true)
             }
             // No existing relationship, create a new request
@@ -65,6 +96,24 @@ where
                 self.friendship_repo
                     .new_request(tx.exec(), &ids, sender_id)
                     .await?;
+                self.notification_repo
+                    .insert_new(
+                        tx.exec(),
+                        recipient_id,
+                        sender_id,
+                        NotificationKind::FriendRequest,
+                        None,
+                    )
+                    .await?;
+                self.webhook_repo
+                    .enqueue(
+                        tx.exec(),
+                        &WebhookEvent::FriendshipRequested {
+                            requester_id: sender_id,
+                            recipient_id,
+                        },
+                    )
+                    .await?;
                 Ok(false)
             }
         };
@@ -81,7 +130,7 @@ mod tests {
         crate::test_utils::{
             dummy_data,
             fake_db::FakeUow,
-            mock_repos::{MockFriendshipRepo, MockUserRepo},
+            mock_repos::{MockFriendshipRepo, MockNotificationRepo, MockUserRepo, MockWebhookRepo},
             tokio_test,
         },
         anyhow::Result,
@@ -122,6 +171,8 @@ mod tests {
                     fake_uow,
                     mock_user_repo,
                     mock_friendship_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
                 );
                 let result = friendship_svc
                     .add_friend_by_username(my_id, &my_friend.username)
@@ -165,6 +216,8 @@ mod tests {
                     fake_uow,
                     mock_user_repo,
                     mock_friendship_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
                 );
                 let result = friendship_svc
                     .add_friend_by_username(my_id, &desired_friend.username)
@@ -206,12 +259,41 @@ mod tests {
                     ..Default::default()
                 };
 
+                let mock_notification_repo = MockNotificationRepo {
+                    insert_new: Some(Box::new(
+                        move |passed_recipient_id, passed_actor_id, passed_kind, passed_post_id| {
+                            assert_eq!(added_me.id, passed_recipient_id);
+                            assert_eq!(my_id, passed_actor_id);
+                            assert_eq!(NotificationKind::FriendAccepted, passed_kind);
+                            assert_eq!(None, passed_post_id);
+                            Ok(())
+                        },
+                    )),
+                    ..Default::default()
+                };
+
+                let mock_webhook_repo = MockWebhookRepo {
+                    enqueue: Some(Box::new(move |passed_event| {
+                        assert_eq!(
+                            &WebhookEvent::FriendshipAccepted {
+                                requester_id: added_me.id,
+                                accepter_id: my_id,
+                            },
+                            passed_event,
+                        );
+                        Ok(())
+                    })),
+                    ..Default::default()
+                };
+
                 let (fake_uow, probe) = FakeUow::with_probe()?;
 
                 let friendship_svc = MutateFriendshipByUsernameSvc::new(
                     fake_uow,
                     mock_user_repo,
                     mock_friendship_repo,
+                    mock_notification_repo,
+                    mock_webhook_repo,
                 );
                 let result = friendship_svc
                     .add_friend_by_username(my_id, &added_me.username)
@@ -254,12 +336,41 @@ mod tests {
                     ..Default::default()
                 };
 
+                let mock_notification_repo = MockNotificationRepo {
+                    insert_new: Some(Box::new(
+                        move |passed_recipient_id, passed_actor_id, passed_kind, passed_post_id| {
+                            assert_eq!(does_not_know_me.id, passed_recipient_id);
+                            assert_eq!(my_id, passed_actor_id);
+                            assert_eq!(NotificationKind::FriendRequest, passed_kind);
+                            assert_eq!(None, passed_post_id);
+                            Ok(())
+                        },
+                    )),
+                    ..Default::default()
+                };
+
+                let mock_webhook_repo = MockWebhookRepo {
+                    enqueue: Some(Box::new(move |passed_event| {
+                        assert_eq!(
+                            &WebhookEvent::FriendshipRequested {
+                                requester_id: my_id,
+                                recipient_id: does_not_know_me.id,
+                            },
+                            passed_event,
+                        );
+                        Ok(())
+                    })),
+                    ..Default::default()
+                };
+
                 let (fake_uow, probe) = FakeUow::with_probe()?;
 
                 let friendship_svc = MutateFriendshipByUsernameSvc::new(
                     fake_uow,
                     mock_user_svc,
                     mock_friendship_repo,
+                    mock_notification_repo,
+                    mock_webhook_repo,
                 );
                 let result = friendship_svc
                     .add_friend_by_username(my_id, &does_not_know_me.username)
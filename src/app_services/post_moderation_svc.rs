@@ -0,0 +1,406 @@
+use crate::{
+    app_services::{
+        PostModeration,
+        uow::{Tx as _, UnitOfWork},
+    },
+    domain::{
+        moderation::{ModerationRepo, error::ModerationError},
+        post::PostRepo,
+    },
+    models::moderation::{ModerationActionKind, ReportReason},
+};
+
+pub struct PostModerationSvc<U, P, M> {
+    uow: U,
+    post_repo: P,
+    moderation_repo: M,
+}
+
+impl<U, P, M> PostModerationSvc<U, P, M> {
+    pub const fn new(uow: U, post_repo: P, moderation_repo: M) -> Self {
+        Self { uow, post_repo, moderation_repo }
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, P, M> PostModeration for PostModerationSvc<U, P, M>
+where
+    U: UnitOfWork,
+    P: PostRepo,
+    M: ModerationRepo,
+{
+    async fn report(
+        &self,
+        reporter_id: i32,
+        post_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), ModerationError> {
+        // Disallow reporting nonexistent, deleted, or one's own posts. Posts that the reporter may
+        // not see count as nonexistent.
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        let post = self
+            .post_repo
+            .get_by_id_exclusive(tx.exec(), post_id)
+            .await?
+            .ok_or(ModerationError::NotFound)?;
+
+        if !self
+            .post_repo
+            .is_visible_to(tx.exec(), post_id, reporter_id)
+            .await?
+        {
+            return Err(ModerationError::NotFound);
+        }
+
+        if post.deleted_at.is_some() {
+            return Err(ModerationError::DeletedPost);
+        }
+        if post
+            .author_id
+            .is_some_and(|author_id| author_id == reporter_id)
+        {
+            return Err(ModerationError::SelfReport);
+        }
+
+        self.moderation_repo
+            .insert_report(tx.exec(), post_id, reporter_id, reason)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn is_moderator(&self, user_id: i32) -> Result<bool, ModerationError> {
+        self.moderation_repo
+            .is_moderator(self.uow.single_exec(), user_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn resolve(
+        &self,
+        moderator_id: i32,
+        post_id: i32,
+        action: ModerationActionKind,
+        note: Option<String>,
+    ) -> Result<u64, ModerationError> {
+        let mut tx = self.uow.begin_uow().await?;
+
+        if !self
+            .moderation_repo
+            .is_moderator(tx.exec(), moderator_id)
+            .await?
+        {
+            return Err(ModerationError::NotModerator);
+        }
+
+        // Lock the post so that the reports and the post's state are resolved together
+        self.post_repo
+            .get_by_id_exclusive(tx.exec(), post_id)
+            .await?
+            .ok_or(ModerationError::NotFound)?;
+
+        let action_id = self
+            .moderation_repo
+            .record_action(tx.exec(), post_id, moderator_id, action, note.as_deref())
+            .await?;
+
+        let resolved = self
+            .moderation_repo
+            .resolve_open_reports(tx.exec(), post_id, action_id)
+            .await?;
+
+        // Dropping the transaction rolls back the recorded action
+        if resolved == 0 {
+            return Err(ModerationError::NoOpenReports);
+        }
+
+        match action {
+            ModerationActionKind::Dismiss => {}
+            ModerationActionKind::Archive => self.post_repo.archive(tx.exec(), post_id).await?,
+            ModerationActionKind::Delete => self.post_repo.soft_delete(tx.exec(), post_id).await?,
+        }
+
+        tx.commit_uow().await?;
+
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::RepoError,
+            test_utils::{
+                dummy_data,
+                fake_db::FakeUow,
+                mock_repos::{MockModerationRepo, MockPostRepo},
+                tokio_test,
+            },
+        },
+        anyhow::{Context as _, Result},
+        chrono::Utc,
+        std::{
+            assert_matches,
+            sync::{
+                Arc,
+                atomic::{AtomicBool, Ordering::SeqCst},
+            },
+        },
+    };
+
+    mod report {
+        use super::*;
+
+        #[test]
+        fn disallows_reporting_unacceptable_posts() -> Result<()> {
+            tokio_test(async {
+                let mut deleted = dummy_data::post::number1()?;
+                deleted.deleted_at = Some(Utc::now());
+                let own = dummy_data::post::number2()?;
+                let own_author_id = own.author_id.context("unexpected None author ID")?;
+
+                for (post, reporter_id, expected) in [
+                    (None, 5, ModerationError::NotFound),
+                    (Some(deleted), 5, ModerationError::DeletedPost),
+                    (Some(own), own_author_id, ModerationError::SelfReport),
+                ] {
+                    let mock_post_repo = MockPostRepo {
+                        get_by_id_exclusive: Some(Box::new(move |_| Ok(post.clone()))),
+                        is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                        ..Default::default()
+                    };
+
+                    let (fake_uow, probe) = FakeUow::with_probe()?;
+                    let result = PostModerationSvc::new(
+                        fake_uow,
+                        mock_post_repo,
+                        MockModerationRepo::default(),
+                    )
+                    .report(reporter_id, 24, ReportReason::Spam)
+                    .await;
+
+                    assert_matches!(result, Err(e) if e == expected);
+                    assert!(!probe.commit_called());
+                }
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn reports_and_commits_if_all_conditions_are_met() -> Result<()> {
+            tokio_test(async {
+                let post = dummy_data::post::number1()?;
+                let post_id = post.id;
+                let reporter_id = post.author_id.context("unexpected None author ID")? + 1;
+
+                let mock_post_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                        assert_eq!(post_id, passed_id);
+                        Ok(Some(post.clone()))
+                    })),
+                    is_visible_to: Some(Box::new(move |passed_id, passed_viewer_id| {
+                        assert_eq!(post_id, passed_id);
+                        assert_eq!(reporter_id, passed_viewer_id);
+                        Ok(true)
+                    })),
+                    ..Default::default()
+                };
+
+                let mock_moderation_repo = MockModerationRepo {
+                    insert_report: Some(Box::new(
+                        move |passed_post_id, passed_reporter_id, passed_reason| {
+                            assert_eq!(post_id, passed_post_id);
+                            assert_eq!(reporter_id, passed_reporter_id);
+                            assert_eq!(ReportReason::Harassment, passed_reason);
+                            Ok(())
+                        },
+                    )),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostModerationSvc::new(fake_uow, mock_post_repo, mock_moderation_repo)
+                    .report(reporter_id, post_id, ReportReason::Harassment)
+                    .await;
+
+                assert_matches!(result, Ok(()));
+                assert!(probe.commit_called());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn treats_posts_hidden_from_the_reporter_as_nonexistent() -> Result<()> {
+            tokio_test(async {
+                let mock_post_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(|_| Ok(Some(dummy_data::post::number1()?)))),
+                    is_visible_to: Some(Box::new(|_, _| Ok(false))),
+                    ..Default::default()
+                };
+
+                // The moderation repo should not be called
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result =
+                    PostModerationSvc::new(fake_uow, mock_post_repo, MockModerationRepo::default())
+                        .report(5, 24, ReportReason::Spam)
+                        .await;
+
+                assert_matches!(result, Err(ModerationError::NotFound));
+                assert!(!probe.commit_called());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn translates_duplicate_reports() -> Result<()> {
+            tokio_test(async {
+                let mock_post_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(|_| Ok(Some(dummy_data::post::number1()?)))),
+                    is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                    ..Default::default()
+                };
+
+                let mock_moderation_repo = MockModerationRepo {
+                    insert_report: Some(Box::new(|_, _, _| {
+                        Err(RepoError::UniqueViolation(String::from("post_report_open_unique")))
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostModerationSvc::new(fake_uow, mock_post_repo, mock_moderation_repo)
+                    .report(1, 24, ReportReason::Other)
+                    .await;
+
+                assert_matches!(result, Err(ModerationError::AlreadyReported));
+                assert!(!probe.commit_called());
+
+                Ok(())
+            })
+        }
+    }
+
+    mod resolve {
+        use super::*;
+
+        /// Creates a mock moderation repo in which the requester is a moderator and
+        /// `open_reports` reports are closed by the action.
+        fn moderation_repo_with_open_reports(open_reports: u64) -> MockModerationRepo {
+            MockModerationRepo {
+                is_moderator: Some(Box::new(|_| Ok(true))),
+                record_action: Some(Box::new(|_, _, _, _| Ok(77))),
+                resolve_open_reports: Some(Box::new(move |_, action_id| {
+                    assert_eq!(77, action_id);
+                    Ok(open_reports)
+                })),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn disallows_non_moderators() -> Result<()> {
+            tokio_test(async {
+                let mock_moderation_repo = MockModerationRepo {
+                    is_moderator: Some(Box::new(|passed_id| {
+                        assert_eq!(8, passed_id);
+                        Ok(false)
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result =
+                    PostModerationSvc::new(fake_uow, MockPostRepo::default(), mock_moderation_repo)
+                        .resolve(8, 24, ModerationActionKind::Delete, None)
+                        .await;
+
+                assert_matches!(result, Err(ModerationError::NotModerator));
+                assert!(!probe.commit_called());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn does_not_commit_if_there_are_no_open_reports() -> Result<()> {
+            tokio_test(async {
+                let mock_post_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(|_| Ok(Some(dummy_data::post::number1()?)))),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostModerationSvc::new(
+                    fake_uow,
+                    mock_post_repo,
+                    moderation_repo_with_open_reports(0),
+                )
+                .resolve(8, 24, ModerationActionKind::Delete, None)
+                .await;
+
+                assert_matches!(result, Err(ModerationError::NoOpenReports));
+                assert!(!probe.commit_called());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn applies_the_action_to_the_post_and_commits() -> Result<()> {
+            tokio_test(async {
+                for action in [
+                    ModerationActionKind::Dismiss,
+                    ModerationActionKind::Archive,
+                    ModerationActionKind::Delete,
+                ] {
+                    let archived = Arc::new(AtomicBool::new(false));
+                    let deleted = Arc::new(AtomicBool::new(false));
+                    let (archived_clone, deleted_clone) = (archived.clone(), deleted.clone());
+
+                    let mock_post_repo = MockPostRepo {
+                        get_by_id_exclusive: Some(Box::new(|passed_id| {
+                            assert_eq!(24, passed_id);
+                            Ok(Some(dummy_data::post::number1()?))
+                        })),
+                        archive: Some(Box::new(move |passed_id| {
+                            assert_eq!(24, passed_id);
+                            archived_clone.store(true, SeqCst);
+                            Ok(())
+                        })),
+                        soft_delete: Some(Box::new(move |passed_id| {
+                            assert_eq!(24, passed_id);
+                            deleted_clone.store(true, SeqCst);
+                            Ok(())
+                        })),
+                        ..Default::default()
+                    };
+
+                    let (fake_uow, probe) = FakeUow::with_probe()?;
+                    let result = PostModerationSvc::new(
+                        fake_uow,
+                        mock_post_repo,
+                        moderation_repo_with_open_reports(3),
+                    )
+                    .resolve(8, 24, action, Some(String::from("Reviewed")))
+                    .await;
+
+                    assert_matches!(result, Ok(3));
+                    assert!(probe.commit_called());
+                    assert_eq!(archived.load(SeqCst), action == ModerationActionKind::Archive);
+                    assert_eq!(deleted.load(SeqCst), action == ModerationActionKind::Delete);
+                }
+
+                Ok(())
+            })
+        }
+    }
+}
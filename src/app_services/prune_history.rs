@@ -0,0 +1,190 @@
+use {
+    crate::{
+        app_services::{
+            job_worker::JobHandler,
+            uow::{Tx as _, UnitOfWork},
+        },
+        domain::{
+            job::{Job, JobRepo, NewJob},
+            webhook::WebhookRepo,
+        },
+    },
+    anyhow::{Context as _, Result},
+    chrono::{DateTime, NaiveTime, TimeDelta, Utc},
+    serde::{Deserialize, Serialize},
+};
+
+/// How long successful jobs are kept.
+const SUCCEEDED_JOB_RETENTION: TimeDelta = TimeDelta::days(7);
+
+/// How long dead-lettered jobs are kept for inspection.
+const DEAD_JOB_RETENTION: TimeDelta = TimeDelta::days(30);
+
+/// How long webhook events and their delivery logs are kept once no deliveries are pending.
+const WEBHOOK_EVENT_RETENTION: TimeDelta = TimeDelta::days(30);
+
+/// Daily job that deletes old finished jobs and webhook events.
+#[derive(Serialize, Deserialize)]
+pub struct PruneHistory {}
+
+impl Job for PruneHistory {
+    const KIND: &'static str = "prune_history";
+}
+
+impl PruneHistory {
+    /// Creates the run for the day of `day`, deduplicated so that each day has at most one pending
+    /// run no matter how many times it is enqueued.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the job cannot be serialized.
+    pub fn for_day(day: DateTime<Utc>) -> Result<NewJob> {
+        let date = day.date_naive();
+
+        Ok(NewJob::new(&Self {})?
+            .run_at(date.and_time(NaiveTime::MIN).and_utc())
+            .dedupe_key(format!("{}:{date}", Self::KIND)))
+    }
+}
+
+pub struct PruneHistoryHandler<U, J, W> {
+    uow: U,
+    job_repo: J,
+    webhook_repo: W,
+}
+
+impl<U, J, W> PruneHistoryHandler<U, J, W> {
+    pub const fn new(uow: U, job_repo: J, webhook_repo: W) -> Self {
+        Self { uow, job_repo, webhook_repo }
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, J, W> JobHandler for PruneHistoryHandler<U, J, W>
+where
+    U: UnitOfWork + 'static,
+    J: JobRepo + 'static,
+    W: WebhookRepo + 'static,
+{
+    type Job = PruneHistory;
+
+    async fn handle(&self, _job: PruneHistory) -> Result<()> {
+        let now = Utc::now();
+        let before = |retention| {
+            now.checked_sub_signed(retention)
+                .context("cutoff out of range")
+        };
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        let jobs = self
+            .job_repo
+            .prune_finished(
+                tx.exec(),
+                before(SUCCEEDED_JOB_RETENTION)?,
+                before(DEAD_JOB_RETENTION)?,
+            )
+            .await?;
+
+        let webhook_events = self
+            .webhook_repo
+            .prune_finished(tx.exec(), before(WEBHOOK_EVENT_RETENTION)?)
+            .await?;
+
+        // Scheduling the next run in the same transaction keeps the chain from ever breaking
+        let tomorrow = now
+            .checked_add_signed(TimeDelta::days(1))
+            .context("tomorrow out of range")?;
+        self.job_repo
+            .enqueue(tx.exec(), &PruneHistory::for_day(tomorrow)?)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        log::info!("Pruned {jobs} finished jobs and {webhook_events} webhook events");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::{
+            fake_db::FakeUow,
+            mock_repos::{MockJobRepo, MockWebhookRepo},
+            tokio_test,
+        },
+        anyhow::anyhow,
+        chrono::TimeZone as _,
+        std::sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn runs_at_the_start_of_each_day_only_once() -> Result<()> {
+        let afternoon = Utc
+            .with_ymd_and_hms(2026, 10, 18, 15, 30, 0)
+            .single()
+            .context("invalid time")?;
+
+        let job = PruneHistory::for_day(afternoon)?;
+
+        assert_eq!("prune_history", job.kind);
+        assert_eq!(Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).single(), job.run_at);
+        assert_eq!(Some("prune_history:2026-10-18"), job.dedupe_key.as_deref());
+
+        Ok(())
+    }
+
+    #[test]
+    fn prunes_and_schedules_the_next_run_in_one_transaction() -> Result<()> {
+        tokio_test(async {
+            let now = Utc::now();
+            let enqueued = Arc::new(Mutex::new(Vec::new()));
+            let enqueued_clone = Arc::clone(&enqueued);
+
+            let mock_job_repo = MockJobRepo {
+                prune_finished: Some(Box::new(|succeeded_before, dead_before| {
+                    // Measured from after the handler's "now" so that whole days are not truncated
+                    let later = Utc::now();
+                    assert_eq!(7, later.signed_duration_since(succeeded_before).num_days());
+                    assert_eq!(30, later.signed_duration_since(dead_before).num_days());
+                    Ok(3)
+                })),
+                enqueue: Some(Box::new(move |job| {
+                    enqueued_clone
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .push(job.dedupe_key.clone());
+                    Ok(Some(1))
+                })),
+                ..Default::default()
+            };
+
+            let mock_webhook_repo = MockWebhookRepo {
+                prune_finished: Some(Box::new(|before| {
+                    assert_eq!(30, Utc::now().signed_duration_since(before).num_days());
+                    Ok(2)
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            PruneHistoryHandler::new(fake_uow, mock_job_repo, mock_webhook_repo)
+                .handle(PruneHistory {})
+                .await?;
+
+            let tomorrow = now
+                .checked_add_signed(TimeDelta::days(1))
+                .context("out of range")?;
+            assert_eq!(
+                vec![Some(format!("prune_history:{}", tomorrow.date_naive()))],
+                *enqueued.lock().map_err(|_| anyhow!("poisoned"))?,
+            );
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+}
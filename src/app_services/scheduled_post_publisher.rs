@@ -0,0 +1,96 @@
+use {
+    crate::{
+        app_services::background::PollingWorker,
+        domain::post::{PostSvc, ScheduledOutcome},
+    },
+    std::{sync::Arc, time::Duration},
+};
+
+/// Background worker that publishes scheduled posts once they are due.
+pub struct ScheduledPostPublisher {
+    post_svc: Arc<dyn PostSvc>,
+}
+
+impl ScheduledPostPublisher {
+    pub const fn new(post_svc: Arc<dyn PostSvc>) -> Self { Self { post_svc } }
+}
+
+#[async_trait::async_trait]
+impl PollingWorker for ScheduledPostPublisher {
+    const NAME: &'static str = "Scheduled post publisher";
+    const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    async fn poll(&self) -> anyhow::Result<bool> {
+        let Some(outcome) = self.post_svc.publish_next_scheduled().await? else {
+            return Ok(false);
+        };
+
+        match outcome {
+            ScheduledOutcome::Published { post_id } => {
+                log::info!("Published scheduled post {post_id}");
+            }
+            ScheduledOutcome::Returned { post_id, reason } => {
+                log::info!("Returned scheduled post {post_id} to the drafts: {reason}");
+            }
+            ScheduledOutcome::Discarded { post_id } => {
+                log::info!("Discarded scheduled post {post_id} of a deleted user");
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::post::{MockPostSvc, error::PostError},
+            test_utils::tokio_test,
+        },
+        anyhow::{Result, anyhow},
+    };
+
+    #[test]
+    fn keeps_polling_only_while_posts_are_due() -> Result<()> {
+        tokio_test(async {
+            let mut mock_post_svc = MockPostSvc::new();
+            let mut seq = mockall::Sequence::new();
+            mock_post_svc
+                .expect_publish_next_scheduled()
+                .once()
+                .in_sequence(&mut seq)
+                .return_once(|| Ok(Some(ScheduledOutcome::Published { post_id: 8 })));
+            mock_post_svc
+                .expect_publish_next_scheduled()
+                .once()
+                .in_sequence(&mut seq)
+                .return_once(|| {
+                    Ok(Some(ScheduledOutcome::Returned {
+                        post_id: 9,
+                        reason: PostError::DeletedParent,
+                    }))
+                });
+            mock_post_svc
+                .expect_publish_next_scheduled()
+                .once()
+                .in_sequence(&mut seq)
+                .return_once(|| Ok(None));
+            mock_post_svc
+                .expect_publish_next_scheduled()
+                .once()
+                .in_sequence(&mut seq)
+                .return_once(|| Err(PostError::Internal(anyhow!("connection lost"))));
+
+            let publisher = ScheduledPostPublisher::new(Arc::new(mock_post_svc));
+
+            assert!(publisher.poll().await?);
+            assert!(publisher.poll().await?);
+            assert!(!publisher.poll().await?);
+            assert!(publisher.poll().await.is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,279 @@
+use {
+    crate::{
+        app_services::{background::PollingWorker, uow::UnitOfWork},
+        domain::{
+            RepoError,
+            webhook::{self, RETRY_POLICY, WebhookClient, WebhookDeliveryRepo},
+        },
+        models::webhook::{DeliveryAttempt, PendingDelivery, WebhookDeliveryStatus},
+    },
+    anyhow::Context as _,
+    chrono::{TimeDelta, Utc},
+    futures_util::future,
+    serde_json::json,
+    std::time::Duration,
+};
+
+/// The maximum number of deliveries claimed (and sent concurrently) at once.
+const BATCH_SIZE: i64 = 10;
+
+/// How long claimed deliveries are reserved for this worker. Must comfortably exceed the client's
+/// request timeout.
+const LEASE: TimeDelta = TimeDelta::minutes(1);
+
+/// Background worker that sends queued webhook deliveries and records the results.
+pub struct WebhookDispatcher<U, R, C> {
+    uow: U,
+    repo: R,
+    client: C,
+}
+
+impl<U, R, C> WebhookDispatcher<U, R, C>
+where
+    U: UnitOfWork,
+    R: WebhookDeliveryRepo,
+    C: WebhookClient,
+{
+    pub const fn new(uow: U, repo: R, client: C) -> Self { Self { uow, repo, client } }
+
+    /// Claims a batch of due deliveries and attempts to send each of them, returning the number
+    /// claimed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if claiming the deliveries or recording the results fails.
+    pub async fn dispatch_due(&self) -> Result<usize, RepoError> {
+        let deliveries = self
+            .repo
+            .claim_due(self.uow.single_exec(), BATCH_SIZE, LEASE)
+            .await?;
+
+        let attempts =
+            future::join_all(deliveries.iter().map(|delivery| self.attempt(delivery))).await;
+
+        for (delivery, attempt) in deliveries.iter().zip(attempts) {
+            self.repo
+                .record_attempt(self.uow.single_exec(), delivery.id, &attempt)
+                .await?;
+        }
+
+        Ok(deliveries.len())
+    }
+
+    /// Sends a single delivery and determines what should happen to it next.
+    async fn attempt(&self, delivery: &PendingDelivery) -> DeliveryAttempt {
+        let (response_status, error) = match self.send(delivery).await {
+            Ok(status) if (200..300).contains(&status) => {
+                return DeliveryAttempt {
+                    status: WebhookDeliveryStatus::Succeeded,
+                    response_status: Some(i32::from(status)),
+                    error: None,
+                    next_attempt_at: Utc::now(),
+                };
+            }
+            Ok(status) => (Some(i32::from(status)), None),
+            Err(e) => (None, Some(format!("{e:#}"))),
+        };
+
+        let (status, next_attempt_at) = RETRY_POLICY
+            .next_attempt_at(delivery.attempts.saturating_add(1))
+            .map_or_else(
+                || (WebhookDeliveryStatus::Failed, Utc::now()),
+                |at| (WebhookDeliveryStatus::Pending, at),
+            );
+
+        DeliveryAttempt { status, response_status, error, next_attempt_at }
+    }
+
+    /// Wraps the event's data in an envelope, signs it, and sends it, returning the response's
+    /// status code.
+    async fn send(&self, delivery: &PendingDelivery) -> anyhow::Result<u16> {
+        let body = serde_json::to_string(&json!({
+            "id": delivery.event_id,
+            "type": delivery.kind,
+            "createdAtMs": delivery.event_created_at.timestamp_millis(),
+            "data": delivery.payload,
+        }))
+        .context("failed to serialize webhook envelope")?;
+
+        let timestamp = Utc::now().timestamp();
+        let signature = webhook::sign(&delivery.secret, timestamp, &body)?;
+
+        let headers = vec![
+            ("X-Spur-Event", delivery.kind.as_str().to_owned()),
+            ("X-Spur-Delivery", delivery.id.to_string()),
+            ("X-Spur-Timestamp", timestamp.to_string()),
+            ("X-Spur-Signature", format!("sha256={signature}")),
+        ];
+
+        self.client.post(&delivery.url, headers, body).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, R, C> PollingWorker for WebhookDispatcher<U, R, C>
+where
+    U: UnitOfWork + 'static,
+    R: WebhookDeliveryRepo + 'static,
+    C: WebhookClient + 'static,
+{
+    const NAME: &'static str = "Webhook dispatcher";
+    const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    async fn poll(&self) -> anyhow::Result<bool> {
+        let claimed = self.dispatch_due().await?;
+        Ok(i64::try_from(claimed).is_ok_and(|n| n >= BATCH_SIZE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::webhook::MockWebhookClient,
+            models::webhook::WebhookEventKind,
+            test_utils::{fake_db::fake_pool, mock_repos::MockWebhookDeliveryRepo, tokio_test},
+        },
+        anyhow::{Result, anyhow},
+        std::sync::{Arc, Mutex},
+    };
+
+    fn pending_delivery(id: i32, attempts: i32) -> PendingDelivery {
+        PendingDelivery {
+            id,
+            url: format!("https://example.com/hooks/{id}"),
+            secret: String::from("whsec_test"),
+            event_id: id.saturating_mul(10),
+            kind: WebhookEventKind::FriendshipRequested,
+            payload: json!({ "requesterId": 1, "recipientId": 2 }),
+            event_created_at: Utc::now(),
+            attempts,
+        }
+    }
+
+    /// Runs a single dispatch with the provided deliveries and client, returning the recorded
+    /// attempts by delivery ID.
+    async fn dispatch(
+        deliveries: Vec<PendingDelivery>,
+        client: MockWebhookClient,
+    ) -> Result<Vec<(i32, DeliveryAttempt)>> {
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let recorded_clone = Arc::clone(&recorded);
+
+        let mock_repo = MockWebhookDeliveryRepo {
+            claim_due: Some(Box::new(move |limit, lease| {
+                assert_eq!((BATCH_SIZE, LEASE), (limit, lease));
+                Ok(deliveries.clone())
+            })),
+            record_attempt: Some(Box::new(move |id, attempt| {
+                recorded_clone
+                    .lock()
+                    .map_err(|_| anyhow!("poisoned"))?
+                    .push((id, attempt.clone()));
+                Ok(())
+            })),
+        };
+
+        WebhookDispatcher::new(fake_pool()?, mock_repo, client)
+            .dispatch_due()
+            .await?;
+
+        let mut attempts = recorded.lock().map_err(|_| anyhow!("poisoned"))?.clone();
+        attempts.sort_by_key(|(id, _)| *id);
+        Ok(attempts)
+    }
+
+    #[test]
+    fn sends_signed_envelopes_and_records_successes() -> Result<()> {
+        tokio_test(async {
+            let mut client = MockWebhookClient::new();
+            client
+                .expect_post()
+                .times(1)
+                .returning(|url, headers, body| {
+                    assert_eq!("https://example.com/hooks/3", url);
+
+                    let header = |name| {
+                        headers
+                            .iter()
+                            .find(|(key, _)| *key == name)
+                            .map(|(_, value)| value.clone())
+                            .context("missing header")
+                    };
+                    assert_eq!("friendship.requested", header("X-Spur-Event")?);
+                    assert_eq!("3", header("X-Spur-Delivery")?);
+
+                    let timestamp = header("X-Spur-Timestamp")?.parse()?;
+                    let expected_signature = webhook::sign("whsec_test", timestamp, &body)?;
+                    assert_eq!(format!("sha256={expected_signature}"), header("X-Spur-Signature")?);
+
+                    let envelope = serde_json::from_str::<serde_json::Value>(&body)?;
+                    assert_eq!(Some(&json!(30)), envelope.get("id"));
+                    assert_eq!(Some(&json!("friendship.requested")), envelope.get("type"));
+                    assert_eq!(
+                        Some(&json!({ "requesterId": 1, "recipientId": 2 })),
+                        envelope.get("data"),
+                    );
+
+                    Ok(204)
+                });
+
+            let attempts = dispatch(vec![pending_delivery(3, 0)], client).await?;
+
+            assert_eq!(attempts.len(), 1);
+            let (id, attempt) = attempts.first().context("no attempt recorded")?;
+            assert_eq!(3, *id);
+            assert_eq!(WebhookDeliveryStatus::Succeeded, attempt.status);
+            assert_eq!(Some(204), attempt.response_status);
+            assert_eq!(None, attempt.error);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn schedules_retries_with_backoff_until_the_attempts_run_out() -> Result<()> {
+        tokio_test(async {
+            let mut client = MockWebhookClient::new();
+            client.expect_post().times(3).returning(|url, _, _| {
+                if url.ends_with("/1") { Ok(500) } else { Err(anyhow!("connection refused")) }
+            });
+
+            let before = Utc::now();
+            let attempts = dispatch(
+                vec![
+                    pending_delivery(1, 0),
+                    pending_delivery(2, 3),
+                    pending_delivery(3, RETRY_POLICY.max_attempts.saturating_sub(1)),
+                ],
+                client,
+            )
+            .await?;
+
+            let summary = attempts
+                .iter()
+                .map(|(id, attempt)| {
+                    (*id, attempt.status, attempt.response_status, attempt.error.as_deref())
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    (1, WebhookDeliveryStatus::Pending, Some(500), None),
+                    (2, WebhookDeliveryStatus::Pending, None, Some("connection refused")),
+                    (3, WebhookDeliveryStatus::Failed, None, Some("connection refused")),
+                ],
+                summary,
+            );
+
+            // The delay grows with the number of failed attempts
+            let delays = attempts
+                .iter()
+                .map(|(_, attempt)| (attempt.next_attempt_at - before).num_seconds())
+                .collect::<Vec<_>>();
+            assert!(matches!(delays.as_slice(), [30..=31, 240..=241, _]));
+
+            Ok(())
+        })
+    }
+}
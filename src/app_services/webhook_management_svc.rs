@@ -0,0 +1,254 @@
+use crate::{
+    app_services::{
+        WebhookManagement,
+        uow::{Tx as _, UnitOfWork},
+    },
+    domain::{
+        moderation::ModerationRepo,
+        outbound::OutboundPolicy,
+        webhook::{WebhookRepo, error::WebhookError},
+    },
+    models::webhook::{NewWebhook, WebhookEventKind},
+};
+
+pub struct WebhookManagementSvc<U, W, M> {
+    uow: U,
+    webhook_repo: W,
+    moderation_repo: M,
+    outbound_policy: OutboundPolicy,
+}
+
+impl<U, W, M> WebhookManagementSvc<U, W, M> {
+    pub const fn new(
+        uow: U,
+        webhook_repo: W,
+        moderation_repo: M,
+        outbound_policy: OutboundPolicy,
+    ) -> Self {
+        Self { uow, webhook_repo, moderation_repo, outbound_policy }
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, W, M> WebhookManagement for WebhookManagementSvc<U, W, M>
+where
+    U: UnitOfWork,
+    W: WebhookRepo,
+    M: ModerationRepo,
+{
+    async fn register(
+        &self,
+        owner_id: i32,
+        url: &str,
+        events: Vec<WebhookEventKind>,
+        all_users: bool,
+    ) -> Result<NewWebhook, WebhookError> {
+        // The host is checked again when sending, after it has been resolved
+        self.outbound_policy.check_url(url)?;
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        // Only moderators can see activity involving users other than themselves
+        if all_users
+            && !self
+                .moderation_repo
+                .is_moderator(tx.exec(), owner_id)
+                .await?
+        {
+            return Err(WebhookError::NotModerator);
+        }
+
+        let secret = format!("whsec_{}", hex::encode(rand::random::<[u8; 32]>()));
+
+        let id = self
+            .webhook_repo
+            .insert_new(tx.exec(), owner_id, url, &secret, &events, all_users)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(NewWebhook { id, secret })
+    }
+
+    async fn delete(&self, owner_id: i32, id: i32) -> Result<(), WebhookError> {
+        if self
+            .webhook_repo
+            .delete(self.uow.single_exec(), owner_id, id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(WebhookError::NotFound)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::{
+            fake_db::FakeUow,
+            mock_repos::{MockModerationRepo, MockWebhookRepo},
+            tokio_test,
+        },
+        anyhow::Result,
+        std::{
+            assert_matches,
+            sync::{Arc, Mutex},
+        },
+    };
+
+    mod register {
+        use super::*;
+
+        #[test]
+        fn disallows_non_moderators_from_receiving_all_users_events() -> Result<()> {
+            tokio_test(async {
+                let mock_moderation_repo = MockModerationRepo {
+                    is_moderator: Some(Box::new(|passed_id| {
+                        assert_eq!(6, passed_id);
+                        Ok(false)
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = WebhookManagementSvc::new(
+                    fake_uow,
+                    MockWebhookRepo::default(),
+                    mock_moderation_repo,
+                    OutboundPolicy::PublicOnly,
+                )
+                .register(6, "https://example.com", vec![WebhookEventKind::PostCreated], true)
+                .await;
+
+                assert_matches!(result, Err(WebhookError::NotModerator));
+                assert!(!probe.commit_called());
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn rejects_urls_of_internal_hosts() -> Result<()> {
+            tokio_test(async {
+                for url in [
+                    "http://example.com/hook",
+                    "https://localhost:8080/hook",
+                    "https://169.254.169.254/latest/meta-data",
+                    "https://10.0.0.5/hook",
+                    "https://[::1]/hook",
+                ] {
+                    // The repos have no expectations, so using them would panic
+                    let (fake_uow, probe) = FakeUow::with_probe()?;
+                    let result = WebhookManagementSvc::new(
+                        fake_uow,
+                        MockWebhookRepo::default(),
+                        MockModerationRepo::default(),
+                        OutboundPolicy::PublicOnly,
+                    )
+                    .register(6, url, vec![WebhookEventKind::PostCreated], false)
+                    .await;
+
+                    assert_matches!(result, Err(WebhookError::DisallowedUrl(_)), "{url}");
+                    assert!(!probe.commit_called());
+                }
+
+                Ok(())
+            })
+        }
+
+        #[test]
+        fn generates_a_secret_and_commits() -> Result<()> {
+            tokio_test(async {
+                for (all_users, is_moderator) in [(false, None), (true, Some(true))] {
+                    let stored_secret = Arc::new(Mutex::new(String::new()));
+                    let stored_secret_clone = stored_secret.clone();
+
+                    let mock_webhook_repo = MockWebhookRepo {
+                        insert_new: Some(Box::new(
+                            move |owner_id, url, secret, events, passed_all_users| {
+                                assert_eq!(6, owner_id);
+                                assert_eq!("https://example.com/hook", url);
+                                assert_eq!(vec![WebhookEventKind::FriendshipAccepted], events);
+                                assert_eq!(all_users, passed_all_users);
+                                if let Ok(mut stored) = stored_secret_clone.lock() {
+                                    stored.push_str(secret);
+                                }
+                                Ok(12)
+                            },
+                        )),
+                        ..Default::default()
+                    };
+
+                    // Moderator status is only checked when receiving all users' events
+                    let mock_moderation_repo = MockModerationRepo {
+                        is_moderator: is_moderator
+                            .map(|is_mod| Box::new(move |_| Ok(is_mod)) as Box<_>),
+                        ..Default::default()
+                    };
+
+                    let (fake_uow, probe) = FakeUow::with_probe()?;
+                    let new_webhook = WebhookManagementSvc::new(
+                        fake_uow,
+                        mock_webhook_repo,
+                        mock_moderation_repo,
+                        OutboundPolicy::PublicOnly,
+                    )
+                    .register(
+                        6,
+                        "https://example.com/hook",
+                        vec![WebhookEventKind::FriendshipAccepted],
+                        all_users,
+                    )
+                    .await?;
+
+                    assert_eq!(12, new_webhook.id);
+                    assert!(new_webhook.secret.starts_with("whsec_"));
+                    assert_eq!(70, new_webhook.secret.len());
+                    assert_eq!(
+                        Some(new_webhook.secret),
+                        stored_secret.lock().ok().map(|stored| stored.clone())
+                    );
+                    assert!(probe.commit_called());
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        #[test]
+        fn reports_missing_webhooks() -> Result<()> {
+            tokio_test(async {
+                for (existed, expected) in [(true, None), (false, Some(WebhookError::NotFound))] {
+                    let mock_webhook_repo = MockWebhookRepo {
+                        delete: Some(Box::new(move |owner_id, id| {
+                            assert_eq!((3, 40), (owner_id, id));
+                            Ok(existed)
+                        })),
+                        ..Default::default()
+                    };
+
+                    let (fake_uow, _) = FakeUow::with_probe()?;
+                    let result = WebhookManagementSvc::new(
+                        fake_uow,
+                        mock_webhook_repo,
+                        MockModerationRepo::default(),
+                        OutboundPolicy::PublicOnly,
+                    )
+                    .delete(3, 40)
+                    .await;
+
+                    assert_eq!(result.err(), expected);
+                }
+
+                Ok(())
+            })
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod friendship;
+mod moderator;
 mod post;
 mod time_utils;
 mod user;
@@ -26,6 +27,7 @@ fn main() -> Result<()> {
         } else {
             user::seed(&pool).await?; // Users must be seeded first
             friendship::seed(&pool).await?;
+            moderator::seed(&pool).await?;
             post::seed(&pool).await?;
         }
 
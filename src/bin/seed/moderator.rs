@@ -0,0 +1,14 @@
+use {anyhow::Result, sqlx::PgPool};
+
+/// Inserts seed moderators into the database. Assumes a user with ID 1 already exists.
+///
+/// User 1 is the "Spurt" character, who is the only moderator to start.
+pub async fn seed(pool: &PgPool) -> Result<()> {
+    sqlx::query!("INSERT INTO moderator (user_id) VALUES (1)")
+        .execute(pool)
+        .await?;
+
+    log::info!("Seeded moderators");
+
+    Ok(())
+}
@@ -1,7 +1,17 @@
+pub mod attachment;
 pub mod auth;
+pub mod bookmark;
+pub mod draft;
+pub mod federation;
 pub mod friendship;
+pub mod job;
+pub mod moderation;
+pub mod notification;
+pub mod outbound;
 pub mod post;
+pub mod reaction;
 pub mod user;
+pub mod webhook;
 
 #[derive(Debug, thiserror::Error)]
 pub enum RepoError {
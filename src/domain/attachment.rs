@@ -0,0 +1,120 @@
+pub mod error;
+pub mod image;
+pub mod service;
+
+use {
+    crate::{
+        domain::{RepoError, attachment::error::AttachmentError},
+        models::attachment::{Attachment, AttachmentFile, AttachmentVariant, NewAttachment},
+    },
+    anyhow::Result,
+    chrono::{DateTime, TimeDelta, Utc},
+    sqlx::PgExecutor,
+    std::sync::Arc,
+};
+
+/// The maximum number of images that can be attached to a single post.
+pub const MAX_ATTACHMENTS_PER_POST: i64 = 4;
+
+/// How long an attachment can stay pending before its upload is assumed to have been abandoned,
+/// such as by a crash while its images were being written, and it stops counting towards its
+/// post's limit.
+pub const ABANDONED_UPLOAD_AGE: TimeDelta = TimeDelta::hours(1);
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait AttachmentSvc: Send + Sync {
+    /// The largest upload, in bytes, that will be accepted.
+    fn max_upload_bytes(&self) -> usize;
+
+    /// Processes an uploaded image and attaches it to one of the uploader's posts.
+    async fn upload(
+        &self,
+        uploader_id: i32,
+        post_id: i32,
+        data: Vec<u8>,
+    ) -> Result<Attachment, AttachmentError>;
+
+    /// Retrieves one version of an attachment's image, unless its post has been deleted, is
+    /// scheduled, or may not be seen by the viewer.
+    async fn open(
+        &self,
+        viewer_id: i32,
+        id: i32,
+        variant: AttachmentVariant,
+    ) -> Result<AttachmentFile, AttachmentError>;
+}
+
+#[async_trait::async_trait]
+pub trait AttachmentRepo: Send + Sync {
+    /// Inserts a new attachment whose images have yet to be stored, reserving its place on its
+    /// post. It stays hidden until it is marked as stored.
+    async fn insert_pending(
+        &self,
+        exec: impl PgExecutor<'_>,
+        attachment: &NewAttachment,
+    ) -> Result<Attachment, RepoError>;
+
+    /// Marks a pending attachment's images as stored, which reveals it.
+    async fn mark_stored(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError>;
+
+    /// Deletes an attachment. Has no effect if there is no attachment with the ID.
+    async fn delete(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError>;
+
+    /// Counts the attachments on a post, including pending ones other than those created before
+    /// `abandoned_before`.
+    async fn count_for_post(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<i64, RepoError>;
+
+    /// Fetches an attachment by ID, `None` if it doesn't exist or is pending, or if its post has
+    /// been deleted, is scheduled, or may not be seen by the viewer.
+    async fn get_visible(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        viewer_id: i32,
+    ) -> Result<Option<Attachment>, RepoError>;
+}
+
+/// Stores opaque binary objects under string keys, such as in a local directory or an
+/// S3-compatible bucket.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Stores an object, replacing any existing object with the same key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the object could not be written.
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<()>;
+
+    /// Fetches an object, `None` if there is no object with the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the object exists but could not be read.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Deletes an object. Has no effect if there is no object with the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the object exists but could not be deleted.
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Allows the store to be chosen at runtime.
+#[async_trait::async_trait]
+impl<T: BlobStore + ?Sized> BlobStore for Arc<T> {
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<()> {
+        (**self).put(key, content_type, bytes).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> { (**self).get(key).await }
+
+    async fn delete(&self, key: &str) -> Result<()> { (**self).delete(key).await }
+}
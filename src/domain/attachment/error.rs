@@ -0,0 +1,71 @@
+use {crate::domain::RepoError, anyhow::anyhow, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error("No post found")]
+    PostNotFound,
+
+    #[error("Cannot attach images to a deleted post")]
+    DeletedPost,
+
+    #[error("Cannot attach images to an archived post")]
+    ArchivedPost,
+
+    #[error("Cannot attach images to another user's post")]
+    NotAuthor,
+
+    #[error("A post cannot have more than {0} attachments")]
+    TooManyAttachments(i64),
+
+    #[error("No file was uploaded")]
+    MissingFile,
+
+    #[error("Images cannot be larger than {0} bytes")]
+    TooLarge(usize),
+
+    #[error("Only JPEG, PNG, GIF, and WebP images are supported")]
+    UnsupportedType,
+
+    #[error("Images cannot be wider or taller than {0} pixels")]
+    TooManyPixels(u32),
+
+    #[error("The image could not be read")]
+    InvalidImage,
+
+    #[error("No attachment found")]
+    NotFound,
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<RepoError> for AttachmentError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::UniqueViolation(v) => {
+                Self::Internal(anyhow!("Unexpected unique violation: {v}"))
+            }
+            RepoError::CheckViolation(v) => {
+                Self::Internal(anyhow!("Unexpected check violation: {v}"))
+            }
+            RepoError::Technical(err) => Self::Internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for AttachmentError {
+    /// Compares the string representation of `e` for `Internal(e)`. Otherwise, just checks that the
+    /// variant is the same.
+    fn eq(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match self {
+            Self::Internal(self_e) => {
+                matches!(other,
+                    Self::Internal(other_e) if self_e.to_string() == other_e.to_string())
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
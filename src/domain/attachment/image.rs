@@ -0,0 +1,218 @@
+use {
+    crate::domain::attachment::error::AttachmentError,
+    image::{
+        DynamicImage, ImageDecoder as _, ImageFormat, ImageReader,
+        codecs::{jpeg::JpegEncoder, png::PngEncoder},
+    },
+    std::io::Cursor,
+};
+
+/// The longest side of a thumbnail in pixels.
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// The quality used when re-encoding JPEG images, out of 100.
+const JPEG_QUALITY: u8 = 85;
+
+/// The maximum size of an uploaded image.
+#[derive(Clone, Copy)]
+pub struct ImageLimits {
+    /// The maximum size of the upload in bytes.
+    pub max_bytes: usize,
+    /// The maximum width and height in pixels.
+    pub max_dimension: u32,
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self { Self { max_bytes: 10 * 1024 * 1024, max_dimension: 8192 } }
+}
+
+/// An uploaded image that has been checked and re-encoded, along with its thumbnail.
+#[cfg_attr(test, derive(Debug))]
+pub struct ProcessedImage {
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+/// Checks and re-encodes an uploaded image.
+///
+/// The format is sniffed from the data itself rather than trusting the client. The image is fully
+/// decoded and encoded again, which drops all metadata (including EXIF location data) after the
+/// EXIF orientation has been applied to the pixels. JPEG images stay JPEG, while PNG, GIF, and
+/// WebP images become PNG, so only the first frame of an animation is kept.
+///
+/// This is CPU-intensive and should not be run directly on an async task.
+///
+/// # Errors
+///
+/// Returns `Err` if the upload is too large, is not an image in a supported format, has
+/// dimensions outside the limits, or cannot be decoded.
+pub fn process(data: &[u8], limits: ImageLimits) -> Result<ProcessedImage, AttachmentError> {
+    if data.len() > limits.max_bytes {
+        return Err(AttachmentError::TooLarge(limits.max_bytes));
+    }
+
+    let format = image::guess_format(data).map_err(|_| AttachmentError::UnsupportedType)?;
+    let (content_type, extension) = match format {
+        ImageFormat::Jpeg => ("image/jpeg", "jpg"),
+        ImageFormat::Png | ImageFormat::Gif | ImageFormat::WebP => ("image/png", "png"),
+        _ => return Err(AttachmentError::UnsupportedType),
+    };
+
+    // Check the dimensions from the header before allocating anything for the pixels
+    let mut decoder = ImageReader::with_format(Cursor::new(data), format)
+        .into_decoder()
+        .map_err(|_| AttachmentError::InvalidImage)?;
+    let (width, height) = decoder.dimensions();
+    if width > limits.max_dimension || height > limits.max_dimension {
+        return Err(AttachmentError::TooManyPixels(limits.max_dimension));
+    }
+
+    let orientation = decoder
+        .orientation()
+        .map_err(|_| AttachmentError::InvalidImage)?;
+    let mut image =
+        DynamicImage::from_decoder(decoder).map_err(|_| AttachmentError::InvalidImage)?;
+    image.apply_orientation(orientation);
+
+    let thumbnail = if image.width() > THUMBNAIL_SIZE || image.height() > THUMBNAIL_SIZE {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        image.clone()
+    };
+
+    Ok(ProcessedImage {
+        content_type,
+        extension,
+        width: image.width(),
+        height: image.height(),
+        bytes: encode(&image, format)?,
+        thumbnail: encode(&thumbnail, format)?,
+    })
+}
+
+/// Encodes an image as JPEG if it was originally JPEG, or PNG otherwise.
+fn encode(image: &DynamicImage, original_format: ImageFormat) -> Result<Vec<u8>, AttachmentError> {
+    let mut buf = Vec::new();
+
+    let result = if original_format == ImageFormat::Jpeg {
+        // JPEG has no alpha channel
+        DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY))
+    } else {
+        image.write_with_encoder(PngEncoder::new(&mut buf))
+    };
+
+    result
+        .map(|()| buf)
+        .map_err(|e| AttachmentError::Internal(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        anyhow::Result,
+        image::{Rgb, RgbImage, Rgba, RgbaImage},
+        std::assert_matches,
+    };
+
+    fn png(width: u32, height: u32) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([9, 8, 7, 255])))
+            .write_with_encoder(PngEncoder::new(&mut buf))?;
+        Ok(buf)
+    }
+
+    /// Encodes a JPEG with an EXIF segment containing only an orientation tag.
+    fn jpeg_with_exif_orientation(width: u32, height: u32, orientation: u8) -> Result<Vec<u8>> {
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 100, 50])))
+            .write_with_encoder(JpegEncoder::new(&mut jpeg))?;
+
+        #[rustfmt::skip]
+        let app1 = [
+            0xFF, 0xE1, 0x00, 0x22, // APP1 marker and segment length
+            b'E', b'x', b'i', b'f', 0x00, 0x00,
+            b'M', b'M', 0x00, 0x2A, 0x00, 0x00, 0x00, 0x08, // Big-endian TIFF header
+            0x00, 0x01, // One IFD entry
+            0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, orientation, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, // No next IFD
+        ];
+
+        // Insert the segment right after the start-of-image marker
+        let (soi, rest) = jpeg.split_at(2);
+        Ok([soi, &app1, rest].concat())
+    }
+
+    #[test]
+    fn rejects_uploads_that_are_not_supported_images() {
+        let limits = ImageLimits::default();
+
+        assert_matches!(process(b"just some text", limits), Err(AttachmentError::UnsupportedType));
+        // BMP is an image format, but not one that is allowed
+        assert_matches!(process(b"BM\0\0\0\0", limits), Err(AttachmentError::UnsupportedType));
+        // The right magic bytes, but not a real PNG
+        assert_matches!(
+            process(b"\x89PNG\r\n\x1a\nnot really", limits),
+            Err(AttachmentError::InvalidImage)
+        );
+    }
+
+    #[test]
+    fn enforces_byte_and_dimension_limits() -> Result<()> {
+        let image = png(40, 10)?;
+
+        assert_matches!(
+            process(&image, ImageLimits { max_bytes: 10, max_dimension: 100 }),
+            Err(AttachmentError::TooLarge(10))
+        );
+        assert_matches!(
+            process(&image, ImageLimits { max_bytes: 10_000, max_dimension: 39 }),
+            Err(AttachmentError::TooManyPixels(39))
+        );
+        assert_matches!(
+            process(&image, ImageLimits { max_bytes: 10_000, max_dimension: 40 }),
+            Ok(_)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_small_images_and_shrinks_thumbnails_of_large_ones() -> Result<()> {
+        let small = process(&png(40, 10)?, ImageLimits::default())?;
+        assert_eq!(
+            ("image/png", "png", 40, 10),
+            (small.content_type, small.extension, small.width, small.height)
+        );
+        assert_eq!(small.bytes, small.thumbnail);
+
+        let large = process(&png(1280, 640)?, ImageLimits::default())?;
+        assert_eq!((1280, 640), (large.width, large.height));
+        let thumbnail = image::load_from_memory(&large.thumbnail)?;
+        assert_eq!((THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2), (thumbnail.width(), thumbnail.height()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn applies_and_strips_exif_metadata() -> Result<()> {
+        // Orientation 6 means the image must be rotated 90 degrees clockwise to display correctly
+        let upload = jpeg_with_exif_orientation(40, 10, 6)?;
+        assert!(upload.windows(4).any(|w| w == b"Exif"));
+
+        let processed = process(&upload, ImageLimits::default())?;
+        assert_eq!(
+            ("image/jpeg", 10, 40),
+            (processed.content_type, processed.width, processed.height)
+        );
+        assert!(!processed.bytes.windows(4).any(|w| w == b"Exif"));
+        assert!(!processed.thumbnail.windows(4).any(|w| w == b"Exif"));
+
+        Ok(())
+    }
+}
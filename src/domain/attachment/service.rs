@@ -0,0 +1,524 @@
+use {
+    crate::{
+        app_services::uow::{Tx as _, UnitOfWork},
+        domain::{
+            attachment::{
+                ABANDONED_UPLOAD_AGE, AttachmentError, AttachmentRepo, AttachmentSvc, BlobStore,
+                MAX_ATTACHMENTS_PER_POST,
+                image::{ImageLimits, process},
+            },
+            post::PostRepo,
+        },
+        models::{
+            attachment::{Attachment, AttachmentFile, AttachmentVariant, NewAttachment},
+            post::Post,
+        },
+    },
+    anyhow::{Context as _, anyhow},
+    chrono::Utc,
+    sqlx::PgExecutor,
+};
+
+pub struct AttachmentDomainSvc<U, R, P, B> {
+    uow: U,
+    repo: R,
+    post_repo: P,
+    blob_store: B,
+    limits: ImageLimits,
+}
+
+impl<U, R, P, B> AttachmentDomainSvc<U, R, P, B> {
+    pub const fn new(uow: U, repo: R, post_repo: P, blob_store: B, limits: ImageLimits) -> Self {
+        Self { uow, repo, post_repo, blob_store, limits }
+    }
+}
+
+impl<U, R, P, B> AttachmentDomainSvc<U, R, P, B>
+where
+    U: UnitOfWork,
+    R: AttachmentRepo,
+    P: PostRepo,
+    B: BlobStore,
+{
+    /// Checks that the uploader may attach another image to the post. Pending attachments count
+    /// towards the limit unless their upload has been abandoned.
+    async fn check_attachable(
+        &self,
+        exec: impl PgExecutor<'_>,
+        found: Option<Post>,
+        uploader_id: i32,
+    ) -> Result<(), AttachmentError> {
+        let post = found.ok_or(AttachmentError::PostNotFound)?;
+
+        if post.deleted_at.is_some() {
+            return Err(AttachmentError::DeletedPost);
+        }
+        if post.archived_at.is_some() {
+            return Err(AttachmentError::ArchivedPost);
+        }
+        if post.author_id != Some(uploader_id) {
+            return Err(AttachmentError::NotAuthor);
+        }
+
+        let abandoned_before = Utc::now()
+            .checked_sub_signed(ABANDONED_UPLOAD_AGE)
+            .context("current time out of range")?;
+        if self
+            .repo
+            .count_for_post(exec, post.id, abandoned_before)
+            .await?
+            >= MAX_ATTACHMENTS_PER_POST
+        {
+            return Err(AttachmentError::TooManyAttachments(MAX_ATTACHMENTS_PER_POST));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, R, P, B> AttachmentSvc for AttachmentDomainSvc<U, R, P, B>
+where
+    U: UnitOfWork,
+    R: AttachmentRepo,
+    P: PostRepo,
+    B: BlobStore,
+{
+    fn max_upload_bytes(&self) -> usize { self.limits.max_bytes }
+
+    async fn upload(
+        &self,
+        uploader_id: i32,
+        post_id: i32,
+        data: Vec<u8>,
+    ) -> Result<Attachment, AttachmentError> {
+        // Checked before the image is processed, so that uploads that are bound to be rejected
+        // don't tie up a blocking thread, and again once the post is locked
+        let unlocked = self
+            .post_repo
+            .get_by_id(self.uow.single_exec(), post_id)
+            .await?;
+        self.check_attachable(self.uow.single_exec(), unlocked, uploader_id)
+            .await?;
+
+        // Decoding and encoding images takes too long to do on the async runtime
+        let limits = self.limits;
+        let image = tokio::task::spawn_blocking(move || process(&data, limits))
+            .await
+            .context("image processing task failed")??;
+
+        let base_key = format!("attachments/{}", hex::encode(rand::random::<[u8; 16]>()));
+        let new_attachment = NewAttachment {
+            post_id,
+            content_type: image.content_type.to_owned(),
+            width: i32::try_from(image.width).context("image width out of range")?,
+            height: i32::try_from(image.height).context("image height out of range")?,
+            byte_size: i32::try_from(image.bytes.len()).context("image size out of range")?,
+            storage_key: format!("{base_key}.{}", image.extension),
+            thumbnail_key: format!("{base_key}_thumb.{}", image.extension),
+        };
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        let locked = self
+            .post_repo
+            .get_by_id_exclusive(tx.exec(), post_id)
+            .await?;
+        self.check_attachable(tx.exec(), locked, uploader_id)
+            .await?;
+
+        let attachment = self.repo.insert_pending(tx.exec(), &new_attachment).await?;
+        tx.commit_uow().await?;
+
+        // The images are written once the post is no longer locked, so that a slow blob store
+        // doesn't hold up everything else that touches the post. The pending attachment keeps its
+        // place in the meantime.
+        let result = async {
+            self.blob_store
+                .put(&attachment.storage_key, image.content_type, image.bytes)
+                .await?;
+            self.blob_store
+                .put(&attachment.thumbnail_key, image.content_type, image.thumbnail)
+                .await?;
+
+            self.repo
+                .mark_stored(self.uow.single_exec(), attachment.id)
+                .await?;
+
+            Ok::<_, AttachmentError>(())
+        }
+        .await;
+
+        // Don't leave behind blobs or an attachment that will never be shown
+        if let Err(error) = result {
+            for key in [&attachment.storage_key, &attachment.thumbnail_key] {
+                if let Err(e) = self.blob_store.delete(key).await {
+                    log::warn!("Failed to clean up blob {key}: {e:#}");
+                }
+            }
+            if let Err(e) = self
+                .repo
+                .delete(self.uow.single_exec(), attachment.id)
+                .await
+            {
+                log::warn!("Failed to clean up pending attachment {}: {e:#}", attachment.id);
+            }
+
+            return Err(error);
+        }
+
+        Ok(attachment)
+    }
+
+    async fn open(
+        &self,
+        viewer_id: i32,
+        id: i32,
+        variant: AttachmentVariant,
+    ) -> Result<AttachmentFile, AttachmentError> {
+        let attachment = self
+            .repo
+            .get_visible(self.uow.single_exec(), id, viewer_id)
+            .await?
+            .ok_or(AttachmentError::NotFound)?;
+
+        let key = match variant {
+            AttachmentVariant::Original => attachment.storage_key,
+            AttachmentVariant::Thumbnail => attachment.thumbnail_key,
+        };
+
+        let bytes = self
+            .blob_store
+            .get(&key)
+            .await?
+            .ok_or_else(|| anyhow!("blob {key} missing for attachment {id}"))?;
+
+        Ok(AttachmentFile { content_type: attachment.content_type, bytes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::{RepoError, attachment::MockBlobStore},
+            test_utils::{
+                dummy_data,
+                fake_db::FakeUow,
+                mock_repos::{MockAttachmentRepo, MockPostRepo},
+                time::within_five_seconds,
+                tokio_test,
+            },
+        },
+        anyhow::Result,
+        image::{DynamicImage, ImageFormat, RgbImage},
+        mockall::predicate::eq,
+        std::{
+            assert_matches,
+            io::Cursor,
+            sync::{
+                Arc,
+                atomic::{AtomicBool, Ordering::SeqCst},
+            },
+        },
+    };
+
+    fn png(width: u32, height: u32) -> Result<Vec<u8>> {
+        let mut buf = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut buf, ImageFormat::Png)?;
+        Ok(buf.into_inner())
+    }
+
+    /// Creates a post repo that finds `post`, both before and after the image is processed.
+    fn mock_post_repo(post_id: i32, post: Option<Post>) -> MockPostRepo {
+        let post_clone = post.clone();
+        MockPostRepo {
+            get_by_id: Some(Box::new(move |passed_id| {
+                assert_eq!(post_id, passed_id);
+                Ok(post_clone.clone())
+            })),
+            get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                assert_eq!(post_id, passed_id);
+                Ok(post.clone())
+            })),
+            ..Default::default()
+        }
+    }
+
+    fn stored_attachment(new: &NewAttachment) -> Attachment {
+        Attachment {
+            id: 7,
+            post_id: new.post_id,
+            content_type: new.content_type.clone(),
+            width: new.width,
+            height: new.height,
+            byte_size: new.byte_size,
+            storage_key: new.storage_key.clone(),
+            thumbnail_key: new.thumbnail_key.clone(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn rejects_uploads_that_are_not_supported_images() -> Result<()> {
+        tokio_test(async {
+            let mock_repo = MockAttachmentRepo {
+                count_for_post: Some(Box::new(|_, _| Ok(0))),
+                ..Default::default()
+            };
+
+            // No attachment should be recorded, and the blob store should not be touched
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = AttachmentDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_post_repo(24, Some(dummy_data::post::number1()?)),
+                MockBlobStore::new(),
+                ImageLimits::default(),
+            )
+            .upload(255, 24, b"just some text".to_vec())
+            .await;
+
+            assert_matches!(result, Err(AttachmentError::UnsupportedType));
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_uploads_to_posts_that_cannot_have_them() -> Result<()> {
+        tokio_test(async {
+            let live = dummy_data::post::number1()?;
+            let mut deleted = live.clone();
+            deleted.deleted_at = Some(Utc::now());
+            let mut archived = live.clone();
+            archived.archived_at = Some(Utc::now());
+
+            for (uploader_id, post, count, expected) in [
+                (255, None, 0, AttachmentError::PostNotFound),
+                (255, Some(deleted), 0, AttachmentError::DeletedPost),
+                (255, Some(archived), 0, AttachmentError::ArchivedPost),
+                (256, Some(live.clone()), 0, AttachmentError::NotAuthor),
+                (
+                    255,
+                    Some(live.clone()),
+                    MAX_ATTACHMENTS_PER_POST,
+                    AttachmentError::TooManyAttachments(MAX_ATTACHMENTS_PER_POST),
+                ),
+            ] {
+                let mock_repo = MockAttachmentRepo {
+                    count_for_post: Some(Box::new(move |post_id, _| {
+                        assert_eq!(24, post_id);
+                        Ok(count)
+                    })),
+                    ..Default::default()
+                };
+
+                // The post is checked before the image is processed, so the upload isn't even
+                // looked at, and the blob store should not be called
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = AttachmentDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    mock_post_repo(24, post),
+                    MockBlobStore::new(),
+                    ImageLimits::default(),
+                )
+                .upload(uploader_id, 24, b"not even an image".to_vec())
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn stores_both_versions_and_records_the_attachment() -> Result<()> {
+        tokio_test(async {
+            let mut mock_blob_store = MockBlobStore::new();
+            mock_blob_store
+                .expect_put()
+                .withf(|key, content_type, _| {
+                    key.starts_with("attachments/") && content_type == "image/png"
+                })
+                .times(2)
+                .returning(|_, _, _| Ok(()));
+
+            let mock_repo = MockAttachmentRepo {
+                count_for_post: Some(Box::new(|_, abandoned_before| {
+                    // Uploads pending for longer than this no longer count
+                    let abandoned_after = abandoned_before
+                        .checked_add_signed(ABANDONED_UPLOAD_AGE)
+                        .context("out of range")?;
+                    assert!(within_five_seconds(abandoned_after, Utc::now()));
+                    Ok(MAX_ATTACHMENTS_PER_POST.saturating_sub(1))
+                })),
+                insert_pending: Some(Box::new(|new| {
+                    assert_eq!(
+                        (24, "image/png", 4, 2),
+                        (new.post_id, new.content_type.as_str(), new.width, new.height)
+                    );
+                    assert_eq!(
+                        Some(new.thumbnail_key.as_str()),
+                        new.storage_key
+                            .strip_suffix(".png")
+                            .map(|base| format!("{base}_thumb.png"))
+                            .as_deref()
+                    );
+                    Ok(stored_attachment(new))
+                })),
+                mark_stored: Some(Box::new(|id| {
+                    assert_eq!(7, id);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let attachment = AttachmentDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_post_repo(24, Some(dummy_data::post::number1()?)),
+                mock_blob_store,
+                ImageLimits::default(),
+            )
+            .upload(255, 24, png(4, 2)?)
+            .await?;
+
+            assert_eq!(7, attachment.id);
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn writes_no_blobs_if_the_attachment_cannot_be_recorded() -> Result<()> {
+        tokio_test(async {
+            // The blob store should not be called
+            let mock_repo = MockAttachmentRepo {
+                count_for_post: Some(Box::new(|_, _| Ok(0))),
+                insert_pending: Some(Box::new(|_| Err(RepoError::Technical(anyhow!("db down"))))),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = AttachmentDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_post_repo(24, Some(dummy_data::post::number1()?)),
+                MockBlobStore::new(),
+                ImageLimits::default(),
+            )
+            .upload(255, 24, png(4, 2)?)
+            .await;
+
+            assert_matches!(result, Err(AttachmentError::Internal(_)));
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn cleans_up_if_the_images_cannot_be_stored() -> Result<()> {
+        tokio_test(async {
+            let mut mock_blob_store = MockBlobStore::new();
+            mock_blob_store
+                .expect_put()
+                .once()
+                .returning(|_, _, _| Err(anyhow!("disk full")));
+            mock_blob_store
+                .expect_delete()
+                .withf(|key| key.starts_with("attachments/"))
+                .times(2)
+                .returning(|_| Ok(()));
+
+            let deleted = Arc::new(AtomicBool::new(false));
+            let deleted_clone = deleted.clone();
+            let mock_repo = MockAttachmentRepo {
+                count_for_post: Some(Box::new(|_, _| Ok(0))),
+                insert_pending: Some(Box::new(|new| Ok(stored_attachment(new)))),
+                delete: Some(Box::new(move |id| {
+                    assert_eq!(7, id);
+                    deleted_clone.store(true, SeqCst);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            // The reservation was already committed, so it's deleted separately
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = AttachmentDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_post_repo(24, Some(dummy_data::post::number1()?)),
+                mock_blob_store,
+                ImageLimits::default(),
+            )
+            .upload(255, 24, png(4, 2)?)
+            .await;
+
+            assert_matches!(result, Err(AttachmentError::Internal(_)));
+            assert!(probe.commit_called());
+            assert!(deleted.load(SeqCst));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn opens_the_requested_version() -> Result<()> {
+        tokio_test(async {
+            let attachment = stored_attachment(&NewAttachment {
+                post_id: 24,
+                content_type: String::from("image/jpeg"),
+                width: 640,
+                height: 480,
+                byte_size: 1234,
+                storage_key: String::from("attachments/a.jpg"),
+                thumbnail_key: String::from("attachments/a_thumb.jpg"),
+            });
+
+            let mut mock_blob_store = MockBlobStore::new();
+            mock_blob_store
+                .expect_get()
+                .with(eq("attachments/a_thumb.jpg"))
+                .once()
+                .returning(|_| Ok(Some(vec![1, 2, 3])));
+
+            let mock_repo = MockAttachmentRepo {
+                get_visible: Some(Box::new(move |id, viewer_id| {
+                    assert_eq!(255, viewer_id);
+                    Ok((id == attachment.id).then(|| attachment.clone()))
+                })),
+                ..Default::default()
+            };
+
+            let svc = AttachmentDomainSvc::new(
+                FakeUow::with_probe()?.0,
+                mock_repo,
+                MockPostRepo::default(),
+                mock_blob_store,
+                ImageLimits::default(),
+            );
+
+            assert_eq!(
+                AttachmentFile { content_type: String::from("image/jpeg"), bytes: vec![1, 2, 3] },
+                svc.open(255, 7, AttachmentVariant::Thumbnail).await?
+            );
+            assert_matches!(
+                svc.open(255, 8, AttachmentVariant::Original).await,
+                Err(AttachmentError::NotFound)
+            );
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,36 @@
+pub mod error;
+pub mod service;
+
+use {
+    crate::domain::{RepoError, bookmark::error::BookmarkError},
+    sqlx::PgExecutor,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait BookmarkSvc: Send + Sync {
+    /// Bookmarks a post for the user. Has no effect if the post is already bookmarked.
+    async fn save(&self, user_id: i32, post_id: i32) -> Result<(), BookmarkError>;
+
+    /// Removes a post from the user's bookmarks.
+    async fn unsave(&self, user_id: i32, post_id: i32) -> Result<(), BookmarkError>;
+}
+
+#[async_trait::async_trait]
+pub trait BookmarkRepo: Send + Sync {
+    /// Bookmarks a post for the user, keeping the original save time if it is already bookmarked.
+    async fn insert(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+    ) -> Result<(), RepoError>;
+
+    /// Deletes a bookmark, returning whether it existed.
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+    ) -> Result<bool, RepoError>;
+}
@@ -0,0 +1,47 @@
+use {crate::domain::RepoError, anyhow::anyhow, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum BookmarkError {
+    #[error("No post found")]
+    PostNotFound,
+
+    #[error("Cannot bookmark a deleted post")]
+    DeletedPost,
+
+    #[error("Post is not bookmarked")]
+    NotBookmarked,
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<RepoError> for BookmarkError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::UniqueViolation(v) => {
+                Self::Internal(anyhow!("Unexpected unique violation: {v}"))
+            }
+            RepoError::CheckViolation(v) => {
+                Self::Internal(anyhow!("Unexpected check violation: {v}"))
+            }
+            RepoError::Technical(err) => Self::Internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for BookmarkError {
+    /// Compares the string representation of `e` for `Internal(e)`. Otherwise, just checks that the
+    /// variant is the same.
+    fn eq(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match self {
+            Self::Internal(self_e) => {
+                matches!(other,
+                    Self::Internal(other_e) if self_e.to_string() == other_e.to_string())
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
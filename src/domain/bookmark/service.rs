@@ -0,0 +1,206 @@
+use crate::{
+    app_services::uow::{Tx as _, UnitOfWork},
+    domain::{
+        bookmark::{BookmarkError, BookmarkRepo, BookmarkSvc},
+        post::PostRepo,
+    },
+};
+
+pub struct BookmarkDomainSvc<U, R, P> {
+    uow: U,
+    repo: R,
+    post_repo: P,
+}
+
+impl<U, R, P> BookmarkDomainSvc<U, R, P> {
+    pub const fn new(uow: U, repo: R, post_repo: P) -> Self { Self { uow, repo, post_repo } }
+}
+
+#[async_trait::async_trait]
+impl<U, R, P> BookmarkSvc for BookmarkDomainSvc<U, R, P>
+where
+    U: UnitOfWork,
+    R: BookmarkRepo,
+    P: PostRepo,
+{
+    async fn save(&self, user_id: i32, post_id: i32) -> Result<(), BookmarkError> {
+        // Locking the post keeps it from being deleted between the check and the insert
+        let mut tx = self.uow.begin_uow().await?;
+
+        let post = self
+            .post_repo
+            .get_by_id_exclusive(tx.exec(), post_id)
+            .await?
+            .ok_or(BookmarkError::PostNotFound)?;
+
+        // Posts that the user may not see count as nonexistent
+        if !self
+            .post_repo
+            .is_visible_to(tx.exec(), post_id, user_id)
+            .await?
+        {
+            return Err(BookmarkError::PostNotFound);
+        }
+
+        if post.deleted_at.is_some() {
+            return Err(BookmarkError::DeletedPost);
+        }
+
+        self.repo.insert(tx.exec(), user_id, post_id).await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn unsave(&self, user_id: i32, post_id: i32) -> Result<(), BookmarkError> {
+        // Deleted posts can be unsaved too so that their tombstones can be cleared
+        if self
+            .repo
+            .delete(self.uow.single_exec(), user_id, post_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(BookmarkError::NotBookmarked)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::post::Post,
+            test_utils::{
+                dummy_data,
+                fake_db::FakeUow,
+                mock_repos::{MockBookmarkRepo, MockPostRepo},
+                tokio_test,
+            },
+        },
+        anyhow::Result,
+        chrono::Utc,
+        std::assert_matches,
+    };
+
+    fn mock_post_repo(post_id: i32, post: Option<Post>) -> MockPostRepo {
+        MockPostRepo {
+            get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                assert_eq!(post_id, passed_id);
+                Ok(post.clone())
+            })),
+            is_visible_to: Some(Box::new(move |passed_id, passed_viewer_id| {
+                assert_eq!(post_id, passed_id);
+                assert_eq!(5, passed_viewer_id);
+                Ok(true)
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn disallows_saving_nonexistent_or_deleted_posts() -> Result<()> {
+        tokio_test(async {
+            let mut deleted = dummy_data::post::number1()?;
+            deleted.deleted_at = Some(Utc::now());
+
+            for (post, expected) in
+                [(None, BookmarkError::PostNotFound), (Some(deleted), BookmarkError::DeletedPost)]
+            {
+                // The bookmark repo should not be called
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = BookmarkDomainSvc::new(
+                    fake_uow,
+                    MockBookmarkRepo::default(),
+                    mock_post_repo(24, post),
+                )
+                .save(5, 24)
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn treats_posts_hidden_from_the_user_as_nonexistent() -> Result<()> {
+        tokio_test(async {
+            let mock_post_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(false))),
+                ..mock_post_repo(24, Some(dummy_data::post::number1()?))
+            };
+
+            // The bookmark repo should not be called
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result =
+                BookmarkDomainSvc::new(fake_uow, MockBookmarkRepo::default(), mock_post_repo)
+                    .save(5, 24)
+                    .await;
+
+            assert_matches!(result, Err(BookmarkError::PostNotFound));
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn saves_archived_posts() -> Result<()> {
+        tokio_test(async {
+            let mut archived = dummy_data::post::number1()?;
+            archived.archived_at = Some(Utc::now());
+
+            let mock_repo = MockBookmarkRepo {
+                insert: Some(Box::new(|passed_user_id, passed_post_id| {
+                    assert_eq!(5, passed_user_id);
+                    assert_eq!(24, passed_post_id);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result =
+                BookmarkDomainSvc::new(fake_uow, mock_repo, mock_post_repo(24, Some(archived)))
+                    .save(5, 24)
+                    .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn unsaves_only_existing_bookmarks() -> Result<()> {
+        tokio_test(async {
+            for (existed, expected) in [(true, Ok(())), (false, Err(BookmarkError::NotBookmarked))]
+            {
+                let mock_repo = MockBookmarkRepo {
+                    delete: Some(Box::new(move |passed_user_id, passed_post_id| {
+                        assert_eq!(5, passed_user_id);
+                        assert_eq!(24, passed_post_id);
+                        Ok(existed)
+                    })),
+                    ..Default::default()
+                };
+
+                // The post's state does not matter for unsaving
+                let (fake_uow, _) = FakeUow::with_probe()?;
+                let result = BookmarkDomainSvc::new(fake_uow, mock_repo, MockPostRepo::default())
+                    .unsave(5, 24)
+                    .await;
+
+                assert_eq!(expected, result);
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,58 @@
+pub mod error;
+pub mod service;
+
+use {
+    crate::{
+        domain::{RepoError, draft::error::DraftError},
+        models::{draft::Draft, post::BodyFormat},
+    },
+    sqlx::PgExecutor,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait DraftSvc: Send + Sync {
+    /// Saves the author's draft reply to a post, replacing the existing draft if there is one.
+    async fn save(
+        &self,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+    ) -> Result<(), DraftError>;
+
+    /// Discards the author's draft reply to a post.
+    async fn discard(&self, author_id: i32, parent_id: i32) -> Result<(), DraftError>;
+}
+
+#[async_trait::async_trait]
+pub trait DraftRepo: Send + Sync {
+    /// Inserts a draft, or updates the body of the existing draft for the same author and parent,
+    /// replacing any recorded publish error. Returns the draft as stored.
+    async fn upsert(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        publish_error: Option<&str>,
+    ) -> Result<Draft, RepoError>;
+
+    /// Fetches a draft, blocking concurrent writes to it until the surrounding transaction
+    /// completes.
+    async fn get_exclusive(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<Option<Draft>, RepoError>;
+
+    /// Deletes a draft, returning whether it existed.
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<bool, RepoError>;
+}
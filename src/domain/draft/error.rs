@@ -0,0 +1,50 @@
+use {crate::domain::RepoError, anyhow::anyhow, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum DraftError {
+    #[error("No post found")]
+    PostNotFound,
+
+    #[error("Cannot reply to a deleted post")]
+    DeletedParent,
+
+    #[error("Drafts cannot be longer than {0} bytes")]
+    TooManyBytes(usize),
+
+    #[error("No draft found")]
+    NotFound,
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<RepoError> for DraftError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::UniqueViolation(v) => {
+                Self::Internal(anyhow!("Unexpected unique violation: {v}"))
+            }
+            RepoError::CheckViolation(v) => {
+                Self::Internal(anyhow!("Unexpected check violation: {v}"))
+            }
+            RepoError::Technical(err) => Self::Internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for DraftError {
+    /// Compares the string representation of `e` for `Internal(e)`. Otherwise, just checks that the
+    /// variant is the same.
+    fn eq(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match self {
+            Self::Internal(self_e) => {
+                matches!(other,
+                    Self::Internal(other_e) if self_e.to_string() == other_e.to_string())
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
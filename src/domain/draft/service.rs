@@ -0,0 +1,198 @@
+use crate::{
+    app_services::uow::{Tx as _, UnitOfWork},
+    domain::{
+        draft::{DraftError, DraftRepo, DraftSvc},
+        post::PostRepo,
+    },
+    models::post::BodyFormat,
+};
+
+pub struct DraftDomainSvc<U, R, P> {
+    uow: U,
+    repo: R,
+    post_repo: P,
+    max_bytes: usize,
+}
+
+impl<U, R, P> DraftDomainSvc<U, R, P> {
+    /// Creates a draft service that refuses drafts larger than `max_bytes`. Other limits on the
+    /// body are only enforced when a draft is published.
+    pub const fn new(uow: U, repo: R, post_repo: P, max_bytes: usize) -> Self {
+        Self { uow, repo, post_repo, max_bytes }
+    }
+}
+
+#[async_trait::async_trait]
+impl<U, R, P> DraftSvc for DraftDomainSvc<U, R, P>
+where
+    U: UnitOfWork,
+    R: DraftRepo,
+    P: PostRepo,
+{
+    async fn save(
+        &self,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+    ) -> Result<(), DraftError> {
+        if body.len() > self.max_bytes {
+            return Err(DraftError::TooManyBytes(self.max_bytes));
+        }
+
+        // Archived parents and one's own posts are allowed here so that autosaving never loses
+        // work. Publishing checks the parent fully.
+        let mut tx = self.uow.begin_uow().await?;
+
+        let parent = self
+            .post_repo
+            .get_by_id_exclusive(tx.exec(), parent_id)
+            .await?
+            .ok_or(DraftError::PostNotFound)?;
+
+        if parent.deleted_at.is_some() {
+            return Err(DraftError::DeletedParent);
+        }
+
+        self.repo
+            .upsert(tx.exec(), author_id, parent_id, body, body_format, None)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn discard(&self, author_id: i32, parent_id: i32) -> Result<(), DraftError> {
+        if self
+            .repo
+            .delete(self.uow.single_exec(), author_id, parent_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(DraftError::NotFound)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::{draft::Draft, post::Post},
+            test_utils::{
+                dummy_data,
+                fake_db::FakeUow,
+                mock_repos::{MockDraftRepo, MockPostRepo},
+                tokio_test,
+            },
+        },
+        anyhow::Result,
+        chrono::Utc,
+        std::assert_matches,
+    };
+
+    fn mock_post_repo(post_id: i32, post: Option<Post>) -> MockPostRepo {
+        MockPostRepo {
+            get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                assert_eq!(post_id, passed_id);
+                Ok(post.clone())
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn disallows_drafts_that_are_too_large_or_reply_to_missing_posts() -> Result<()> {
+        tokio_test(async {
+            let mut deleted = dummy_data::post::number1()?;
+            deleted.deleted_at = Some(Utc::now());
+
+            for (body, post, expected) in [
+                ("a".repeat(11), Some(dummy_data::post::number1()?), DraftError::TooManyBytes(10)),
+                (String::from("Hi"), None, DraftError::PostNotFound),
+                (String::from("Hi"), Some(deleted), DraftError::DeletedParent),
+            ] {
+                // The draft repo should not be called
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = DraftDomainSvc::new(
+                    fake_uow,
+                    MockDraftRepo::default(),
+                    mock_post_repo(24, post),
+                    10,
+                )
+                .save(5, 24, &body, BodyFormat::Plain)
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn saves_drafts_of_unfinished_replies_to_archived_posts() -> Result<()> {
+        tokio_test(async {
+            let mut archived = dummy_data::post::number1()?;
+            archived.archived_at = Some(Utc::now());
+
+            let mock_repo = MockDraftRepo {
+                upsert: Some(Box::new(|author_id, parent_id, body, body_format, publish_error| {
+                    assert_eq!(
+                        (5, 24, "", BodyFormat::Markdown, None),
+                        (author_id, parent_id, body, body_format, publish_error)
+                    );
+                    Ok(Draft {
+                        author_id,
+                        parent_id,
+                        body: body.to_owned(),
+                        body_format,
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                        publish_error: None,
+                    })
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result =
+                DraftDomainSvc::new(fake_uow, mock_repo, mock_post_repo(24, Some(archived)), 10)
+                    .save(5, 24, "", BodyFormat::Markdown)
+                    .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn discards_only_existing_drafts() -> Result<()> {
+        tokio_test(async {
+            for (existed, expected) in [(true, Ok(())), (false, Err(DraftError::NotFound))] {
+                let mock_repo = MockDraftRepo {
+                    delete: Some(Box::new(move |author_id, parent_id| {
+                        assert_eq!((5, 24), (author_id, parent_id));
+                        Ok(existed)
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, _) = FakeUow::with_probe()?;
+                let result = DraftDomainSvc::new(fake_uow, mock_repo, MockPostRepo::default(), 10)
+                    .discard(5, 24)
+                    .await;
+
+                assert_eq!(expected, result);
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,133 @@
+pub mod error;
+pub mod signature;
+
+use {
+    crate::{
+        domain::RepoError,
+        models::federation::{ActorKey, RemoteActor},
+    },
+    anyhow::Result,
+    rsa::{
+        RsaPrivateKey,
+        pkcs8::{EncodePrivateKey as _, EncodePublicKey as _, LineEnding},
+        rand_core::OsRng,
+    },
+    sqlx::PgExecutor,
+};
+
+/// Appended to an actor's ID to identify its key.
+pub const KEY_FRAGMENT: &str = "#main-key";
+
+/// The size of generated keys in bits, which is what other ActivityPub servers generally use.
+const KEY_BITS: usize = 2048;
+
+/// The path of a user's actor document. Joined to the public URL, this is also the actor's ID.
+pub fn actor_path(username: &str) -> String { format!("/users/{username}") }
+
+/// Generates a new key pair for a user's actor. This takes a while, so it should not be run
+/// directly on the async runtime.
+///
+/// # Errors
+///
+/// Returns `Err` if the key cannot be generated or encoded.
+pub fn generate_key() -> Result<ActorKey> {
+    let private_key = RsaPrivateKey::new(&mut OsRng, KEY_BITS)?;
+
+    Ok(ActorKey {
+        public_key_pem: private_key
+            .to_public_key()
+            .to_public_key_pem(LineEnding::LF)?,
+        private_key_pem: private_key.to_pkcs8_pem(LineEnding::LF)?.to_string(),
+    })
+}
+
+#[async_trait::async_trait]
+pub trait FederationRepo: Send + Sync {
+    /// Fetches the key pair of a user's actor, if one has been generated.
+    async fn key_for(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+    ) -> Result<Option<ActorKey>, RepoError>;
+
+    /// Stores the key pair of a user's actor, doing nothing if the user already has one.
+    async fn insert_key(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        key: &ActorKey,
+    ) -> Result<(), RepoError>;
+
+    /// Records that a remote actor follows a user, updating its inbox if it already did.
+    async fn add_follower(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        follower: &RemoteActor,
+    ) -> Result<(), RepoError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait FederationClient: Send + Sync {
+    /// Fetches the actor document at the URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the request fails, the response is not successful, or the body is not an
+    /// actor document.
+    async fn fetch_actor(&self, url: &str) -> Result<RemoteActor>;
+
+    /// Sends an activity to an inbox with the provided additional headers, returning the
+    /// response's status code.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no response is received (e.g. the connection fails or times out).
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: String,
+    ) -> Result<u16>;
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{signature::*, *},
+        anyhow::Context as _,
+        chrono::Utc,
+    };
+
+    #[test]
+    fn generates_keys_that_verify_their_signatures() -> Result<()> {
+        let key = generate_key()?;
+        let now = Utc::now();
+        let body = br#"{"type":"Accept"}"#;
+
+        let headers: Vec<_> = signed_post_headers(
+            &key.private_key_pem,
+            "https://spur.example/users/drake#main-key",
+            "https://remote.example/inbox",
+            body,
+            now,
+        )?
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect();
+        let signature_header = headers
+            .iter()
+            .find(|(name, _)| name == "signature")
+            .map(|(_, value)| value.as_str())
+            .context("no signature header")?;
+
+        Signature::parse(signature_header)?.verify(
+            &key.public_key_pem,
+            &SignedRequest { method: "POST", path: "/inbox", headers: &headers, body },
+            now,
+        )?;
+
+        Ok(())
+    }
+}
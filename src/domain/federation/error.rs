@@ -0,0 +1,53 @@
+use {crate::domain::RepoError, anyhow::anyhow, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum FederationError {
+    #[error("No user found")]
+    UserNotFound,
+
+    #[error("Missing or invalid HTTP signature")]
+    InvalidSignature,
+
+    #[error("Invalid activity: {0}")]
+    InvalidActivity(&'static str),
+
+    #[error("Unsupported activity type: {0}")]
+    UnsupportedActivity(String),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<RepoError> for FederationError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::UniqueViolation(v) => {
+                Self::Internal(anyhow!("Unexpected unique violation: {v}"))
+            }
+            RepoError::CheckViolation(v) if v == "text_non_empty" => {
+                Self::Internal(anyhow!("Empty field made it past activity validation: {v}"))
+            }
+            RepoError::CheckViolation(v) => {
+                Self::Internal(anyhow!("Unexpected check violation: {v}"))
+            }
+            RepoError::Technical(err) => Self::Internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for FederationError {
+    /// Compares the string representation of `e` for `Internal(e)`. Otherwise, just checks that the
+    /// variant is the same.
+    fn eq(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match self {
+            Self::Internal(self_e) => {
+                matches!(other,
+                    Self::Internal(other_e) if self_e.to_string() == other_e.to_string())
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
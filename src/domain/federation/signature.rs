@@ -0,0 +1,367 @@
+//! HTTP signatures as used across the fediverse, following draft-cavage-http-signatures with
+//! `rsa-sha256` keys.
+
+use {
+    super::error::FederationError,
+    anyhow::{Context as _, Result},
+    base64::{Engine as _, prelude::BASE64_STANDARD},
+    chrono::{DateTime, TimeDelta, Utc},
+    rsa::{
+        RsaPrivateKey, RsaPublicKey,
+        pkcs1v15::{Signature as RsaSignature, SigningKey, VerifyingKey},
+        pkcs8::{DecodePrivateKey as _, DecodePublicKey as _},
+        signature::{RandomizedSigner as _, SignatureEncoding as _, Verifier as _},
+    },
+    sha2::{Digest as _, Sha256},
+};
+
+/// The headers covered by the signatures of outgoing requests, all of which must also be covered
+/// by the signatures of incoming ones.
+pub const SIGNED_HEADERS: [&str; 4] = ["(request-target)", "host", "date", "digest"];
+
+/// How far the `Date` of an incoming request may be from the current time, which limits how long
+/// a captured request can be replayed for.
+const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::minutes(5);
+
+/// An HTTP request whose signature is being verified.
+pub struct SignedRequest<'a> {
+    pub method: &'a str,
+    /// The path and query that the request was sent to.
+    pub path: &'a str,
+    pub headers: &'a [(String, String)],
+    pub body: &'a [u8],
+}
+
+impl SignedRequest<'_> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    }
+}
+
+/// Builds the string that is signed from the listed headers of a request, or `None` if one of them
+/// is missing.
+fn signing_string<'h>(
+    method: &str,
+    path: &str,
+    names: impl IntoIterator<Item = &'h str>,
+    header: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    names
+        .into_iter()
+        .map(|name| {
+            if name == "(request-target)" {
+                Some(format!("(request-target): {} {path}", method.to_ascii_lowercase()))
+            } else {
+                header(name).map(|value| format!("{name}: {value}"))
+            }
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|lines| lines.join("\n"))
+}
+
+/// Computes the value of the `Digest` header for a request body.
+pub fn digest(body: &[u8]) -> String {
+    ["SHA-256=", &BASE64_STANDARD.encode(Sha256::digest(body))].concat()
+}
+
+/// Formats a time as an HTTP date (e.g. `Sun, 06 Nov 1994 08:49:37 GMT`).
+pub fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Builds the headers that sign a POST of `body` to `url` with an actor's key, including the
+/// `Signature` header itself.
+///
+/// # Errors
+///
+/// Returns `Err` if the URL is not absolute or the key cannot be decoded or used.
+pub fn signed_post_headers(
+    private_key_pem: &str,
+    key_id: &str,
+    url: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Result<Vec<(&'static str, String)>> {
+    let (host, path) = url
+        .split_once("://")
+        .and_then(|(_, rest)| {
+            rest.find('/')
+                .map_or(Some((rest, "/")), |i| rest.split_at_checked(i))
+        })
+        .context("inbox URL is not absolute")?;
+
+    let headers = [("host", host.to_owned()), ("date", http_date(now)), ("digest", digest(body))];
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(header_name, _)| *header_name == name)
+            .map(|(_, value)| value.clone())
+    };
+    let to_sign =
+        signing_string("POST", path, SIGNED_HEADERS, header).context("signed header not set")?;
+
+    let key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)?;
+    let signature = SigningKey::<Sha256>::new(key)
+        .try_sign_with_rng(&mut rsa::rand_core::OsRng, to_sign.as_bytes())?;
+
+    let signature_header = format!(
+        r#"keyId="{key_id}",algorithm="rsa-sha256",headers="{}",signature="{}""#,
+        SIGNED_HEADERS.join(" "),
+        BASE64_STANDARD.encode(signature.to_bytes()),
+    );
+
+    Ok(headers
+        .into_iter()
+        .chain([("signature", signature_header)])
+        .collect())
+}
+
+/// The parsed `Signature` header of an incoming request.
+#[cfg_attr(test, derive(Debug))]
+pub struct Signature {
+    /// The URL of the key that the request claims to be signed with.
+    pub key_id: String,
+    headers: Vec<String>,
+    bytes: Vec<u8>,
+}
+
+impl Signature {
+    /// Parses a `Signature` header. The algorithm is assumed to be `rsa-sha256` if it is given as
+    /// `hs2019`, as it is by some servers, since only RSA keys are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSignature` if the header is malformed or uses an unsupported algorithm.
+    pub fn parse(header: &str) -> Result<Self, FederationError> {
+        let mut key_id = None;
+        let mut headers = None;
+        let mut signature = None;
+
+        for (name, quoted) in header
+            .split(',')
+            .filter_map(|param| param.trim().split_once('='))
+        {
+            let value = quoted.trim_matches('"');
+            match name {
+                "keyId" => key_id = Some(value),
+                "headers" => headers = Some(value),
+                "signature" => signature = Some(value),
+                "algorithm" if !matches!(value, "rsa-sha256" | "hs2019") => {
+                    return Err(FederationError::InvalidSignature);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            key_id: key_id.ok_or(FederationError::InvalidSignature)?.to_owned(),
+            // The spec defaults to only the date being signed, which is rejected during
+            // verification
+            headers: headers
+                .unwrap_or("date")
+                .split_whitespace()
+                .map(str::to_ascii_lowercase)
+                .collect(),
+            bytes: BASE64_STANDARD
+                .decode(signature.ok_or(FederationError::InvalidSignature)?)
+                .map_err(|_| FederationError::InvalidSignature)?,
+        })
+    }
+
+    /// Verifies that the signature was made with the key, covers all of [`SIGNED_HEADERS`], and
+    /// that the signed `Digest` and `Date` match the body and the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSignature` if any of the checks fail.
+    pub fn verify(
+        &self,
+        public_key_pem: &str,
+        request: &SignedRequest,
+        now: DateTime<Utc>,
+    ) -> Result<(), FederationError> {
+        let covers_required = SIGNED_HEADERS
+            .iter()
+            .all(|required| self.headers.iter().any(|name| name == required));
+
+        let digest_matches = request.header("digest").is_some_and(|value| {
+            let expected = digest(request.body);
+            value.split(',').any(|d| d.trim() == expected)
+        });
+
+        let date_is_fresh = request
+            .header("date")
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+            .is_some_and(|date| now.signed_duration_since(date).abs() <= MAX_CLOCK_SKEW);
+
+        if !(covers_required && digest_matches && date_is_fresh) {
+            return Err(FederationError::InvalidSignature);
+        }
+
+        let signed = signing_string(
+            request.method,
+            request.path,
+            self.headers.iter().map(String::as_str),
+            |name| request.header(name).map(str::to_owned),
+        )
+        .ok_or(FederationError::InvalidSignature)?;
+
+        let key = RsaPublicKey::from_public_key_pem(public_key_pem)
+            .map_err(|_| FederationError::InvalidSignature)?;
+        let signature = RsaSignature::try_from(self.bytes.as_slice())
+            .map_err(|_| FederationError::InvalidSignature)?;
+
+        VerifyingKey::<Sha256>::new(key)
+            .verify(signed.as_bytes(), &signature)
+            .map_err(|_| FederationError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::test_utils::dummy_data::actor_key};
+
+    const INBOX: &str = "https://remote.example/users/alice/inbox";
+    const KEY_ID: &str = "https://spur.example/users/drake#main-key";
+    const BODY: &[u8] = br#"{"type":"Follow"}"#;
+
+    /// Signs a POST of [`BODY`] to [`INBOX`] with the first dummy key, returning the parsed
+    /// signature and the headers as received by the inbox.
+    fn signed_post(now: DateTime<Utc>) -> Result<(Signature, Vec<(String, String)>)> {
+        let headers: Vec<_> =
+            signed_post_headers(&actor_key::number1().private_key_pem, KEY_ID, INBOX, BODY, now)?
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value))
+                .collect();
+
+        let signature_header = headers
+            .iter()
+            .find(|(name, _)| name == "signature")
+            .map(|(_, value)| value.as_str())
+            .context("no signature header")?;
+
+        Ok((Signature::parse(signature_header)?, headers))
+    }
+
+    fn request<'a>(headers: &'a [(String, String)], body: &'a [u8]) -> SignedRequest<'a> {
+        SignedRequest { method: "POST", path: "/users/alice/inbox", headers, body }
+    }
+
+    #[test]
+    fn digests_with_base64_sha256() {
+        // Independently computed with `openssl dgst -sha256 -binary | base64`
+        assert_eq!("SHA-256=47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=", digest(b""));
+    }
+
+    #[test]
+    fn verifies_its_own_signatures() -> Result<()> {
+        let now = Utc::now();
+        let (signature, headers) = signed_post(now)?;
+
+        assert_eq!(KEY_ID, signature.key_id);
+        assert_eq!(
+            Some("remote.example"),
+            headers
+                .iter()
+                .find(|(name, _)| name == "host")
+                .map(|(_, value)| value.as_str())
+        );
+        signature.verify(&actor_key::number1().public_key_pem, &request(&headers, BODY), now)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_signatures_from_other_keys() -> Result<()> {
+        let now = Utc::now();
+        let (signature, headers) = signed_post(now)?;
+
+        assert_eq!(
+            Err(FederationError::InvalidSignature),
+            signature.verify(&actor_key::number2().public_key_pem, &request(&headers, BODY), now)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_modified_bodies() -> Result<()> {
+        let now = Utc::now();
+        let (signature, headers) = signed_post(now)?;
+
+        assert_eq!(
+            Err(FederationError::InvalidSignature),
+            signature.verify(
+                &actor_key::number1().public_key_pem,
+                &request(&headers, br#"{"type":"Undo"}"#),
+                now
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_stale_dates() -> Result<()> {
+        let now = Utc::now();
+        let (signature, headers) = signed_post(now)?;
+        let verify_at = |offset| {
+            let then = now.checked_add_signed(offset).context("out of range")?;
+            Ok::<_, anyhow::Error>(signature.verify(
+                &actor_key::number1().public_key_pem,
+                &request(&headers, BODY),
+                then,
+            ))
+        };
+
+        // Clocks that are a little off are tolerated
+        assert_eq!(Ok(()), verify_at(TimeDelta::minutes(1))?);
+        assert_eq!(Ok(()), verify_at(TimeDelta::minutes(-1))?);
+
+        for offset in [TimeDelta::minutes(10), TimeDelta::minutes(-10), TimeDelta::days(1)] {
+            assert_eq!(Err(FederationError::InvalidSignature), verify_at(offset)?, "{offset}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_signatures_not_covering_the_digest() -> Result<()> {
+        let now = Utc::now();
+        let (_, headers) = signed_post(now)?;
+        let signature = Signature::parse(
+            r#"keyId="https://spur.example/users/drake#main-key",headers="(request-target) host date",signature="AAAA""#,
+        )?;
+
+        assert_eq!(
+            Err(FederationError::InvalidSignature),
+            signature.verify(&actor_key::number1().public_key_pem, &request(&headers, BODY), now)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_and_unsupported_headers() {
+        assert!(Signature::parse(r#"headers="date",signature="AAAA""#).is_err());
+        assert!(Signature::parse(r#"keyId="https://a.example/key",headers="date""#).is_err());
+        assert!(
+            Signature::parse(r#"keyId="https://a.example/key",signature="not base64!""#).is_err()
+        );
+        assert!(
+            Signature::parse(
+                r#"keyId="https://a.example/key",algorithm="hmac-sha256",signature="AAAA""#
+            )
+            .is_err()
+        );
+        assert!(
+            Signature::parse(
+                r#"keyId="https://a.example/key",algorithm="hs2019",signature="AAAA""#
+            )
+            .is_ok()
+        );
+    }
+}
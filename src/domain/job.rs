@@ -0,0 +1,205 @@
+use {
+    crate::{domain::RepoError, models::job::ClaimedJob},
+    anyhow::{Context as _, Result},
+    chrono::{DateTime, TimeDelta, Utc},
+    serde::{Serialize, de::DeserializeOwned},
+    sqlx::PgExecutor,
+};
+
+/// How many times to attempt something and how long to wait between attempts.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, after which no more are made.
+    pub max_attempts: i32,
+    /// The delay before the first retry, which doubles with each subsequent failure.
+    pub base_delay: TimeDelta,
+    /// The longest that a retry will ever be delayed.
+    pub max_delay: TimeDelta,
+}
+
+impl RetryPolicy {
+    pub const DEFAULT: Self = Self {
+        max_attempts: 5,
+        base_delay: TimeDelta::seconds(10),
+        max_delay: TimeDelta::hours(1),
+    };
+
+    /// Determines how long to wait before retrying something that has failed `attempts` times.
+    pub fn delay(&self, attempts: i32) -> TimeDelta {
+        let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or(0);
+
+        self.base_delay
+            .checked_mul(2_i32.saturating_pow(exponent))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// Determines when to retry something that has failed `attempts` times, or `None` if it has
+    /// run out of attempts.
+    pub fn next_attempt_at(&self, attempts: i32) -> Option<DateTime<Utc>> {
+        (attempts < self.max_attempts).then(|| {
+            Utc::now()
+                .checked_add_signed(self.delay(attempts))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        })
+    }
+}
+
+/// Work to be done in the background after the transaction that enqueued it commits.
+pub trait Job: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Uniquely identifies the kind of job so that the worker can find its handler.
+    const KIND: &'static str;
+
+    /// How failures of this kind of job are retried before it is dead-lettered.
+    const RETRY_POLICY: RetryPolicy = RetryPolicy::DEFAULT;
+}
+
+/// A job ready to be inserted into the queue.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct NewJob {
+    pub kind: &'static str,
+    pub payload: serde_json::Value,
+    /// When the job should first be run, or as soon as possible if `None`.
+    pub run_at: Option<DateTime<Utc>>,
+    /// If set, the job is not enqueued if another job with the same key has ever been enqueued.
+    pub dedupe_key: Option<String>,
+}
+
+impl NewJob {
+    /// Serializes a job to be run as soon as possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the job cannot be serialized.
+    pub fn new<J: Job>(job: &J) -> Result<Self> {
+        Ok(Self {
+            kind: J::KIND,
+            payload: serde_json::to_value(job)
+                .with_context(|| format!("failed to serialize {} job", J::KIND))?,
+            run_at: None,
+            dedupe_key: None,
+        })
+    }
+
+    /// Delays the job until the specified time.
+    pub const fn run_at(mut self, run_at: DateTime<Utc>) -> Self {
+        self.run_at = Some(run_at);
+        self
+    }
+
+    /// Prevents the job from being enqueued if another with the same key already has been.
+    pub fn dedupe_key(mut self, key: String) -> Self {
+        self.dedupe_key = Some(key);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+pub trait JobRepo: Send + Sync {
+    /// Adds a job to the queue, returning its ID, or `None` if it was a duplicate. Must be called
+    /// in the same transaction as the change that requires the job so that jobs are never lost
+    /// or run for rolled back changes.
+    async fn enqueue(
+        &self,
+        exec: impl PgExecutor<'_>,
+        job: &NewJob,
+    ) -> Result<Option<i32>, RepoError>;
+
+    /// Claims up to `limit` jobs that are due, oldest first, counting the claim as an attempt.
+    /// Claimed jobs are not due again until `lease` has passed, so concurrent workers skip them,
+    /// and they are retried if the worker dies before finishing them.
+    async fn claim_due(
+        &self,
+        exec: impl PgExecutor<'_>,
+        limit: i64,
+        lease: TimeDelta,
+    ) -> Result<Vec<ClaimedJob>, RepoError>;
+
+    /// Marks a job as successfully finished, returning `false` without changing it if the claim
+    /// for attempt number `attempt` is no longer held (the lease expired and the job was claimed
+    /// again).
+    async fn complete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: i32,
+    ) -> Result<bool, RepoError>;
+
+    /// Records a failed attempt at a job, scheduling it to be retried at `retry_at`, or
+    /// dead-lettering it if `retry_at` is `None`. Returns `false` without changing the job if the
+    /// claim for attempt number `attempt` is no longer held.
+    async fn fail(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: i32,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, RepoError>;
+
+    /// Deletes jobs that succeeded before `succeeded_before` or were dead-lettered before
+    /// `dead_before`, returning the number deleted.
+    async fn prune_finished(
+        &self,
+        exec: impl PgExecutor<'_>,
+        succeeded_before: DateTime<Utc>,
+        dead_before: DateTime<Utc>,
+    ) -> Result<u64, RepoError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde::Deserialize, serde_json::json};
+
+    #[test]
+    fn doubles_the_delay_up_to_the_maximum() {
+        let policy = RetryPolicy {
+            max_attempts: 8,
+            base_delay: TimeDelta::seconds(30),
+            max_delay: TimeDelta::hours(6),
+        };
+
+        let delays =
+            [1, 2, 3, 7, 10, 40, 0, -1].map(|attempts| policy.delay(attempts).num_seconds());
+        assert_eq!(delays, [30, 60, 120, 1920, 15360, 21600, 30, 30]);
+    }
+
+    #[test]
+    fn stops_retrying_after_the_maximum_attempts() {
+        let policy = RetryPolicy::DEFAULT;
+        let before = Utc::now();
+
+        let next = policy
+            .next_attempt_at(2)
+            .map(|at| at.signed_duration_since(before).num_seconds());
+        assert_eq!(next, Some(20));
+        assert_eq!(policy.next_attempt_at(policy.max_attempts), None);
+    }
+
+    #[test]
+    fn serializes_jobs_with_their_kind() -> Result<()> {
+        #[derive(Serialize, Deserialize)]
+        struct Greet {
+            name: String,
+        }
+
+        impl Job for Greet {
+            const KIND: &'static str = "greet";
+        }
+
+        let later = Utc::now();
+        let new_job = NewJob::new(&Greet { name: String::from("spurt") })?
+            .run_at(later)
+            .dedupe_key(String::from("greet:spurt"));
+
+        let expected = NewJob {
+            kind: "greet",
+            payload: json!({ "name": "spurt" }),
+            run_at: Some(later),
+            dedupe_key: Some(String::from("greet:spurt")),
+        };
+        assert_eq!(expected, new_job);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+pub mod error;
+
+use {
+    crate::{
+        domain::RepoError,
+        models::moderation::{ModerationActionKind, ReportReason},
+    },
+    sqlx::PgExecutor,
+};
+
+#[async_trait::async_trait]
+pub trait ModerationRepo: Send + Sync {
+    /// Opens a new report on a post.
+    async fn insert_report(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        reporter_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), RepoError>;
+
+    /// Opens a report on a post on behalf of the system rather than a user, such as when a content
+    /// filter flags it.
+    async fn insert_flag(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), RepoError>;
+
+    /// Determines whether the user with the provided ID is a moderator.
+    async fn is_moderator(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+    ) -> Result<bool, RepoError>;
+
+    /// Adds an entry to the moderation audit trail, returning its ID.
+    async fn record_action(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        moderator_id: i32,
+        action: ModerationActionKind,
+        note: Option<&str>,
+    ) -> Result<i32, RepoError>;
+
+    /// Closes all open reports on a post by linking them to the provided action, returning the
+    /// number of reports closed.
+    async fn resolve_open_reports(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        action_id: i32,
+    ) -> Result<u64, RepoError>;
+}
@@ -0,0 +1,62 @@
+use {crate::domain::RepoError, anyhow::anyhow, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum ModerationError {
+    #[error("No post found")]
+    NotFound,
+
+    #[error("Cannot report a deleted post")]
+    DeletedPost,
+
+    #[error("Cannot report one's own post")]
+    SelfReport,
+
+    #[error("Already reported this post. A moderator will review it soon.")]
+    AlreadyReported,
+
+    #[error("No open reports on this post")]
+    NoOpenReports,
+
+    #[error("Moderator access required")]
+    NotModerator,
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<RepoError> for ModerationError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::UniqueViolation(v) if v == "post_report_open_unique" => {
+                Self::AlreadyReported
+            }
+            RepoError::UniqueViolation(v) => {
+                Self::Internal(anyhow!("Unexpected unique violation: {v}"))
+            }
+            RepoError::CheckViolation(v) if v == "text_non_empty" => {
+                Self::Internal(anyhow!("Empty field made it past request validation: {v}"))
+            }
+            RepoError::CheckViolation(v) => {
+                Self::Internal(anyhow!("Unexpected check violation: {v}"))
+            }
+            RepoError::Technical(err) => Self::Internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for ModerationError {
+    /// Compares the string representation of `e` for `Internal(e)`. Otherwise, just checks that the
+    /// variant is the same.
+    fn eq(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match self {
+            Self::Internal(self_e) => {
+                matches!(other,
+                    Self::Internal(other_e) if self_e.to_string() == other_e.to_string())
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
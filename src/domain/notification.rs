@@ -0,0 +1,47 @@
+pub mod error;
+pub mod service;
+
+use {
+    crate::{
+        domain::{RepoError, notification::error::NotificationError},
+        models::notification::NotificationKind,
+    },
+    sqlx::PgExecutor,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait NotificationSvc: Send + Sync {
+    /// Marks the recipient's notifications as read, returning the number of notifications that were
+    /// previously unread. If `ids` is `None`, all of the recipient's notifications are marked.
+    /// Otherwise, only the specified notifications are marked, and IDs belonging to other users are
+    /// ignored.
+    async fn mark_read(
+        &self,
+        recipient_id: i32,
+        ids: Option<Vec<i32>>,
+    ) -> Result<u64, NotificationError>;
+}
+
+#[async_trait::async_trait]
+pub trait NotificationRepo: Send + Sync {
+    /// Creates a new unread notification. `post_id` should be provided for events that involve a
+    /// specific post.
+    async fn insert_new(
+        &self,
+        exec: impl PgExecutor<'_>,
+        recipient_id: i32,
+        actor_id: i32,
+        kind: NotificationKind,
+        post_id: Option<i32>,
+    ) -> Result<(), RepoError>;
+
+    /// Marks the recipient's unread notifications as read, optionally limited to the provided
+    /// IDs, returning the number of notifications marked.
+    async fn mark_read(
+        &self,
+        exec: impl PgExecutor<'_>,
+        recipient_id: i32,
+        ids: Option<&[i32]>,
+    ) -> Result<u64, RepoError>;
+}
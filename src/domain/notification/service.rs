@@ -0,0 +1,64 @@
+use crate::{
+    app_services::uow::UnitOfWork,
+    domain::notification::{NotificationRepo, NotificationSvc, error::NotificationError},
+};
+
+pub struct NotificationDomainSvc<U, R> {
+    uow: U,
+    repo: R,
+}
+
+impl<U, R> NotificationDomainSvc<U, R> {
+    pub const fn new(uow: U, repo: R) -> Self { Self { uow, repo } }
+}
+
+#[async_trait::async_trait]
+impl<U, R> NotificationSvc for NotificationDomainSvc<U, R>
+where
+    U: UnitOfWork,
+    R: NotificationRepo,
+{
+    async fn mark_read(
+        &self,
+        recipient_id: i32,
+        ids: Option<Vec<i32>>,
+    ) -> Result<u64, NotificationError> {
+        self.repo
+            .mark_read(self.uow.single_exec(), recipient_id, ids.as_deref())
+            .await
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::{fake_db::FakeUow, mock_repos::MockNotificationRepo, tokio_test},
+        anyhow::Result,
+        std::assert_matches,
+    };
+
+    #[test]
+    fn passes_through_the_recipient_and_ids() -> Result<()> {
+        tokio_test(async {
+            let mock_repo = MockNotificationRepo {
+                mark_read: Some(Box::new(|passed_recipient_id, passed_ids| {
+                    assert_eq!(18, passed_recipient_id);
+                    assert_eq!(Some([3, 5, 8].as_slice()), passed_ids);
+                    Ok(2)
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, _) = FakeUow::with_probe()?;
+            let result = NotificationDomainSvc::new(fake_uow, mock_repo)
+                .mark_read(18, Some(vec![3, 5, 8]))
+                .await;
+
+            assert_matches!(result, Ok(2));
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,188 @@
+//! Checks on URLs that the server sends requests to on behalf of users or remote servers, so that
+//! they cannot be used to reach hosts inside the deployment.
+
+use {
+    std::net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    thiserror::Error,
+    url::{Host, Url},
+};
+
+/// The reason that requests may not be sent to a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{0}")]
+pub struct DisallowedUrl(pub &'static str);
+
+/// Which hosts requests may be sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutboundPolicy {
+    /// Only `https` URLs of publicly routable hosts.
+    #[default]
+    PublicOnly,
+    /// Additionally, `http` URLs of loopback hosts, for testing against local servers.
+    AllowLoopback,
+}
+
+impl OutboundPolicy {
+    /// Checks that requests may be sent to a URL without resolving its host, which still has to
+    /// be checked with [`Self::allows_ip`] once it is resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DisallowedUrl` if the URL is malformed, does not use `https` (or `http` for an
+    /// allowed loopback host), or its host is an address or name that is not publicly routable.
+    pub fn check_url(self, url: &str) -> Result<(), DisallowedUrl> {
+        let parsed = Url::parse(url).map_err(|_| DisallowedUrl("not a valid URL"))?;
+
+        let loopback = match parsed.host() {
+            Some(Host::Domain(domain)) => {
+                let name = domain.trim_end_matches('.').to_ascii_lowercase();
+                name == "localhost" || name.ends_with(".localhost")
+            }
+            Some(Host::Ipv4(ip)) => {
+                self.check_ip(IpAddr::V4(ip))?;
+                ip.is_loopback()
+            }
+            Some(Host::Ipv6(ip)) => {
+                self.check_ip(IpAddr::V6(ip))?;
+                ip.to_canonical().is_loopback()
+            }
+            None => return Err(DisallowedUrl("the URL has no host")),
+        };
+
+        match (parsed.scheme(), loopback, self) {
+            (_, true, Self::PublicOnly) => Err(DisallowedUrl("the host is not publicly routable")),
+            ("https", _, _) | ("http", true, Self::AllowLoopback) => Ok(()),
+            _ => Err(DisallowedUrl("the URL must use https")),
+        }
+    }
+
+    /// Whether requests may be sent to an IP address.
+    pub fn allows_ip(self, ip: IpAddr) -> bool {
+        match ip.to_canonical() {
+            IpAddr::V4(v4) if v4.is_loopback() => self == Self::AllowLoopback,
+            IpAddr::V6(v6) if v6.is_loopback() => self == Self::AllowLoopback,
+            IpAddr::V4(v4) => is_public_v4(v4),
+            IpAddr::V6(v6) => is_public_v6(v6),
+        }
+    }
+
+    fn check_ip(self, ip: IpAddr) -> Result<(), DisallowedUrl> {
+        if self.allows_ip(ip) {
+            Ok(())
+        } else {
+            Err(DisallowedUrl("the host is not publicly routable"))
+        }
+    }
+}
+
+const fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "This network" (0.0.0.0/8), which reaches the local host on some systems
+        || first == 0
+        // Carrier-grade NAT (100.64.0.0/10)
+        || (first == 100 && (second & 0b1100_0000) == 64)
+        // Reserved, including the benchmarking range (198.18.0.0/15) and 240.0.0.0/4
+        || (first == 198 && (second & 0b1111_1110) == 18)
+        || first >= 240)
+}
+
+const fn is_public_v6(ip: Ipv6Addr) -> bool {
+    match ip.segments() {
+        // 6to4 addresses (2002::/16) are relayed to the IPv4 address that follows the prefix
+        [0x2002, high, low, ..] => {
+            let ([a, b], [c, d]) = (high.to_be_bytes(), low.to_be_bytes());
+            is_public_v4(Ipv4Addr::new(a, b, c, d))
+        }
+        // Teredo addresses (2001::/32) are relayed to an obfuscated IPv4 address, and NAT64
+        // addresses (64:ff9b::/96) embed one too, either of which may be a private one
+        [0x2001, 0, ..] | [0x64, 0xFF9B, ..] => false,
+        _ => {
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_https_urls_of_public_hosts() {
+        for url in [
+            "https://example.com/hook",
+            "https://93.184.215.14:8443/hook",
+            "https://[2606:2800:21f:cb07:6820:80da:af6b:8b2c]/hook",
+            // 6to4 for a public IPv4 address
+            "https://[2002:5db8:d70e::1]/hook",
+        ] {
+            assert_eq!(Ok(()), OutboundPolicy::PublicOnly.check_url(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn rejects_internal_hosts_and_plain_http() {
+        for url in [
+            "http://example.com/hook",
+            "ftp://example.com/hook",
+            "https://localhost:8080/hook",
+            "https://api.localhost/hook",
+            "https://127.0.0.1/hook",
+            "https://10.0.0.5/hook",
+            "https://172.16.3.4/hook",
+            "https://192.168.1.1/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://0.0.0.0/hook",
+            "https://100.64.0.1/hook",
+            "https://[::1]/hook",
+            "https://[::]/hook",
+            "https://[fe80::1]/hook",
+            "https://[fd00::1]/hook",
+            "https://[::ffff:127.0.0.1]/hook",
+            "https://[::ffff:10.0.0.1]/hook",
+            "https://[64:ff9b::a00:1]/hook",
+            "https://[2002:7f00:1::1]/hook",
+            "https://[2002:a9fe:a9fe::]/hook",
+            "https://[2001:0:4136:e378:8000:63bf:3fff:fdd2]/hook",
+            "not a url",
+        ] {
+            assert!(OutboundPolicy::PublicOnly.check_url(url).is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn allows_loopback_only_when_configured() {
+        for url in ["http://127.0.0.1:3000/hook", "http://localhost:3000/hook", "https://[::1]/"] {
+            assert!(OutboundPolicy::PublicOnly.check_url(url).is_err(), "{url}");
+            assert_eq!(Ok(()), OutboundPolicy::AllowLoopback.check_url(url), "{url}");
+        }
+
+        // Other internal hosts are still rejected
+        for url in ["http://example.com/hook", "https://10.0.0.5/hook", "http://db:5432"] {
+            assert!(OutboundPolicy::AllowLoopback.check_url(url).is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn checks_resolved_addresses() {
+        let policy = OutboundPolicy::PublicOnly;
+
+        assert!(policy.allows_ip(IpAddr::from([93, 184, 215, 14])));
+        assert!(!policy.allows_ip(IpAddr::from([127, 0, 0, 1])));
+        assert!(!policy.allows_ip(IpAddr::from([172, 17, 0, 2])));
+        assert!(!policy.allows_ip(IpAddr::from([169, 254, 169, 254])));
+        assert!(!policy.allows_ip(IpAddr::from(Ipv6Addr::LOCALHOST)));
+        assert!(OutboundPolicy::AllowLoopback.allows_ip(IpAddr::from([127, 0, 0, 1])));
+    }
+}
@@ -1,37 +1,211 @@
+pub mod body;
+pub mod content_filter;
 pub mod error;
+pub mod hashtag;
+pub mod mention;
 pub mod service;
+pub mod thread;
 
 use {
     crate::{
-        domain::{RepoError, post::error::PostError},
-        models::post::Post,
+        domain::{
+            RepoError,
+            post::{error::PostError, mention::Mention},
+        },
+        models::post::{BodyFormat, Post, PostVisibility, ReplyPolicy},
     },
+    chrono::{DateTime, Utc},
     sqlx::PgExecutor,
 };
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait PostSvc: Send + Sync {
-    /// Attempts to create a new post.
-    async fn create_new(&self, author_id: i32, parent_id: i32, body: &str)
-    -> Result<(), PostError>;
+    /// Attempts to create a new post, optionally quoting another post from anywhere.
+    async fn create_new(
+        &self,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        quoted_post_id: Option<i32>,
+    ) -> Result<(), PostError>;
+
+    /// Attempts to publish the author's draft reply to a post as a new post, discarding the draft
+    /// if successful. The draft is subject to the same checks as any other new post.
+    async fn publish_draft(&self, author_id: i32, parent_id: i32) -> Result<(), PostError>;
+
+    /// Attempts to schedule the author's draft reply to a post to be published at `publish_at`,
+    /// discarding the draft if successful. The draft is checked now, and again when it is due.
+    async fn schedule_draft(
+        &self,
+        author_id: i32,
+        parent_id: i32,
+        publish_at: DateTime<Utc>,
+    ) -> Result<(), PostError>;
+
+    /// Attempts to change who may reply to one of the requester's posts, and whether the policy
+    /// also applies to replies anywhere below the post.
+    async fn set_reply_policy(
+        &self,
+        requester_id: i32,
+        post_id: i32,
+        policy: ReplyPolicy,
+        cascades: bool,
+    ) -> Result<(), PostError>;
+
+    /// Attempts to change who may see one of the requester's posts.
+    async fn set_visibility(
+        &self,
+        requester_id: i32,
+        post_id: i32,
+        visibility: PostVisibility,
+    ) -> Result<(), PostError>;
+
+    /// Publishes the scheduled post that has been due the longest, if any. If it no longer passes
+    /// the checks for a new post, it is returned to its author's drafts with the reason instead.
+    ///
+    /// # Errors
+    ///
+    /// Will only return `Err` for technical failures, in which case nothing is mutated.
+    async fn publish_next_scheduled(&self) -> Result<Option<ScheduledOutcome>, PostError>;
+}
+
+/// What happened to a scheduled post once it was due.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum ScheduledOutcome {
+    /// The post is now visible.
+    Published { post_id: i32 },
+    /// The post could not be published, so it was deleted and returned to the drafts.
+    Returned { post_id: i32, reason: PostError },
+    /// The author's account was deleted, so the post was too.
+    Discarded { post_id: i32 },
+}
+
+/// A reply policy other than [`ReplyPolicy::Anyone`] that governs replies to a post, set either on
+/// the post itself or on an ancestor for all of its descendants.
+#[cfg_attr(test, derive(Debug, Clone))]
+pub struct ReplyRestriction {
+    pub policy: ReplyPolicy,
+    /// Whether the would-be replier wrote the post that set the policy or is a friend of its
+    /// author.
+    pub replier_is_author_or_friend: bool,
 }
 
 #[async_trait::async_trait]
 pub trait PostRepo: Send + Sync {
+    /// Inserts a new post, returning its ID.
     async fn insert_new(
         &self,
         exec: impl PgExecutor<'_>,
         author_id: i32,
         parent_id: i32,
         body: &str,
+        body_format: BodyFormat,
+        quoted_post_id: Option<i32>,
+    ) -> Result<i32, RepoError>;
+
+    /// Inserts a post that stays hidden until it is published by `publish`, returning its ID.
+    async fn insert_scheduled(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        publish_at: DateTime<Utc>,
+    ) -> Result<i32, RepoError>;
+
+    /// Records the mentions in a post that refer to existing users, ignoring the rest. Returns the
+    /// IDs of the mentioned users, each only once.
+    async fn insert_mentions(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        mentions: &[Mention<'_>],
+    ) -> Result<Vec<i32>, RepoError>;
+
+    /// Tags a post with each of the provided normalized tags, creating any tags that don't exist
+    /// yet.
+    async fn insert_tags(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        tags: &[String],
     ) -> Result<(), RepoError>;
 
-    /// Fetches a post by ID, blocking concurrent writes to the same post until the surrounding
-    /// transaction completes.
+    /// Fetches a published post by ID without locking it. Scheduled posts are treated as
+    /// nonexistent.
+    async fn get_by_id(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+    ) -> Result<Option<Post>, RepoError>;
+
+    /// Fetches a published post by ID, blocking concurrent writes to the same post until the
+    /// surrounding transaction completes. Scheduled posts are treated as nonexistent.
     async fn get_by_id_exclusive(
         &self,
         exec: impl PgExecutor<'_>,
         id: i32,
     ) -> Result<Option<Post>, RepoError>;
+
+    /// Checks whether the user with the provided ID may see the post with the provided ID.
+    async fn is_visible_to(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        viewer_id: i32,
+    ) -> Result<bool, RepoError>;
+
+    /// Fetches the reply policies that govern replies by `replier_id` to the post with the provided
+    /// ID: the post's own policy and those of its ancestors that cascade, other than
+    /// [`ReplyPolicy::Anyone`].
+    async fn reply_restrictions(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        replier_id: i32,
+    ) -> Result<Vec<ReplyRestriction>, RepoError>;
+
+    /// Sets who may reply to a post and whether the policy also applies to its descendants.
+    async fn set_reply_policy(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        policy: ReplyPolicy,
+        cascades: bool,
+    ) -> Result<(), RepoError>;
+
+    /// Sets who may see a post.
+    async fn set_visibility(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        visibility: PostVisibility,
+    ) -> Result<(), RepoError>;
+
+    /// Fetches the scheduled post that has been due the longest, locking it until the surrounding
+    /// transaction completes. Posts already locked by another transaction are skipped so that
+    /// concurrent publishers never wait on each other.
+    async fn claim_due_scheduled(
+        &self,
+        exec: impl PgExecutor<'_>,
+    ) -> Result<Option<Post>, RepoError>;
+
+    /// Makes a scheduled post visible, dating it to when it was published. Has no effect if the
+    /// post is already published.
+    async fn publish(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError>;
+
+    /// Permanently deletes a post that has not been published yet. Has no effect on published
+    /// posts.
+    async fn delete_scheduled(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError>;
+
+    /// Marks a post as archived so that it can no longer be replied to. Has no effect if the post
+    /// is already archived.
+    async fn archive(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError>;
+
+    /// Marks a post as deleted and removes its content. Has no effect if the post is already
+    /// deleted.
+    async fn soft_delete(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError>;
 }
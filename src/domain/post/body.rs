@@ -0,0 +1,90 @@
+use {
+    crate::domain::post::error::PostError, unicode_normalization::UnicodeNormalization as _,
+    unicode_segmentation::UnicodeSegmentation as _,
+};
+
+/// Characters that render as nothing and have no legitimate use in a post body.
+const INVISIBLE: [char; 6] = [
+    '\u{00AD}', // Soft hyphen
+    '\u{180E}', // Mongolian vowel separator
+    '\u{200B}', // Zero-width space
+    '\u{2060}', // Word joiner
+    '\u{2061}', // Function application
+    '\u{FEFF}', // Zero-width no-break space (byte order mark)
+];
+
+/// Zero-width characters that legitimately join or separate characters (for example in emoji
+/// sequences and some scripts), but never need to appear more than once in a row.
+const JOINERS: [char; 2] = ['\u{200C}', '\u{200D}'];
+
+/// The maximum size of a post body.
+#[derive(Clone, Copy)]
+pub struct BodyLimits {
+    /// The maximum number of user-perceived characters (extended grapheme clusters).
+    pub max_graphemes: usize,
+    /// The maximum number of bytes once encoded as UTF-8.
+    pub max_bytes: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self { Self { max_graphemes: 5000, max_bytes: 20_000 } }
+}
+
+impl BodyLimits {
+    /// Checks a normalized body against the limits.
+    pub fn check(&self, body: &str) -> Result<(), PostError> {
+        if body.is_empty() {
+            return Err(PostError::EmptyBody);
+        }
+        if body.len() > self.max_bytes {
+            return Err(PostError::TooManyBytes(self.max_bytes));
+        }
+        if body.graphemes(true).count() > self.max_graphemes {
+            return Err(PostError::TooManyCharacters(self.max_graphemes));
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a post body to Unicode NFC, drops invisible characters, collapses runs of zero-width
+/// joiners and non-joiners, and trims surrounding whitespace.
+pub fn normalize_body(body: &str) -> String {
+    let mut normalized = String::with_capacity(body.len());
+    let mut prev = None;
+
+    for c in body.nfc() {
+        if INVISIBLE.contains(&c) || (JOINERS.contains(&c) && prev.is_some_and(|p| p == c)) {
+            continue;
+        }
+        normalized.push(c);
+        prev = Some(c);
+    }
+
+    normalized.trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::assert_matches};
+
+    #[test]
+    fn normalizes_composition_and_strips_invisible_characters() {
+        // "e" followed by a combining acute accent becomes a single "é"
+        assert_eq!("café", normalize_body("cafe\u{0301}"));
+        assert_eq!("spam", normalize_body("\u{200B} s\u{200B}p\u{FEFF}a\u{00AD}m \u{2060}\n"));
+        // A family emoji keeps its joiners, but repeated joiners are collapsed
+        assert_eq!("👩\u{200D}👧", normalize_body("👩\u{200D}\u{200D}\u{200D}👧"));
+    }
+
+    #[test]
+    fn counts_graphemes_and_bytes() {
+        let limits = BodyLimits { max_graphemes: 3, max_bytes: 16 };
+
+        // Two graphemes, though each is several chars and bytes
+        assert_matches!(limits.check("👩\u{200D}👧e\u{0301}"), Ok(()));
+        assert_matches!(limits.check("abcd"), Err(PostError::TooManyCharacters(3)));
+        assert_matches!(limits.check("👩\u{200D}👧👩\u{200D}👧"), Err(PostError::TooManyBytes(16)));
+        assert_matches!(limits.check(""), Err(PostError::EmptyBody));
+    }
+}
@@ -0,0 +1,90 @@
+use {crate::models::moderation::ReportReason, std::collections::HashSet};
+
+/// What should happen to a post according to a content filter.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy)]
+pub enum FilterVerdict {
+    /// Create the post as usual.
+    Allow,
+    /// Create the post, but open a report on it for moderators to review.
+    Flag(ReportReason),
+    /// Refuse to create the post.
+    Reject,
+}
+
+/// Screens post bodies before they are created.
+pub trait ContentFilter: Send + Sync {
+    /// Decides what to do with a normalized post body.
+    fn check(&self, body: &str) -> FilterVerdict;
+}
+
+/// A content filter that matches whole words against two lists, ignoring case. Rejected words
+/// take precedence over flagged ones.
+#[derive(Default)]
+pub struct WordListFilter {
+    rejected: HashSet<String>,
+    flagged: HashSet<String>,
+}
+
+impl WordListFilter {
+    pub fn new<R, F>(rejected: R, flagged: F) -> Self
+    where
+        R: IntoIterator,
+        R::Item: AsRef<str>,
+        F: IntoIterator,
+        F::Item: AsRef<str>,
+    {
+        let normalize = |word: &str| Some(word.trim().to_lowercase()).filter(|w| !w.is_empty());
+
+        Self {
+            rejected: rejected
+                .into_iter()
+                .filter_map(|w| normalize(w.as_ref()))
+                .collect(),
+            flagged: flagged
+                .into_iter()
+                .filter_map(|w| normalize(w.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Builds a filter from comma-separated word lists, such as those read from configuration.
+    pub fn from_csv(rejected: &str, flagged: &str) -> Self {
+        Self::new(rejected.split(','), flagged.split(','))
+    }
+}
+
+impl ContentFilter for WordListFilter {
+    fn check(&self, body: &str) -> FilterVerdict {
+        let mut verdict = FilterVerdict::Allow;
+
+        for word in body
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .map(str::to_lowercase)
+        {
+            if self.rejected.contains(&word) {
+                return FilterVerdict::Reject;
+            }
+            if self.flagged.contains(&word) {
+                verdict = FilterVerdict::Flag(ReportReason::Other);
+            }
+        }
+
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_whole_words_ignoring_case() {
+        let filter = WordListFilter::from_csv("Scam, ", "crypto");
+
+        assert_eq!(FilterVerdict::Allow, filter.check("A scampi recipe"));
+        assert_eq!(FilterVerdict::Allow, WordListFilter::default().check("SCAM"));
+        assert_eq!(FilterVerdict::Flag(ReportReason::Other), filter.check("Buy CRYPTO now"));
+        assert_eq!(FilterVerdict::Reject, filter.check("crypto... it's a SCAM!"));
+    }
+}
@@ -17,6 +17,45 @@ pub enum PostError {
     #[error("Cannot reply to one's own post")]
     SelfReply,
 
+    #[error("Replies to this post are locked")]
+    RepliesLocked,
+
+    #[error("Only the author's friends can reply to this post")]
+    FriendsOnlyReplies,
+
+    #[error("Cannot change who can reply to another user's post")]
+    NotAuthor,
+
+    #[error("Replies cannot be nested more than {0} levels deep")]
+    TooDeep(i32),
+
+    #[error("A post cannot have more than {0} replies")]
+    TooManyReplies(i32),
+
+    #[error("Post body cannot be empty")]
+    EmptyBody,
+
+    #[error("Post body cannot be longer than {0} characters")]
+    TooManyCharacters(usize),
+
+    #[error("Post body cannot be longer than {0} bytes")]
+    TooManyBytes(usize),
+
+    #[error("Post body violates the content policy")]
+    RejectedContent,
+
+    #[error("Quoted post not found")]
+    QuotedNotFound,
+
+    #[error("Cannot quote a deleted post")]
+    DeletedQuote,
+
+    #[error("No draft found")]
+    NoDraft,
+
+    #[error("Scheduled posts must be published in the future")]
+    PublishTimeNotInFuture,
+
     #[error(transparent)]
     Internal(#[from] anyhow::Error),
 }
@@ -0,0 +1,69 @@
+use {
+    lazy_regex::{Regex, lazy_regex},
+    std::sync::LazyLock,
+};
+
+// A # directly after a word character is part of something else, like a URL fragment or an HTML
+// character reference, rather than a tag.
+static HASHTAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| lazy_regex!(r"(?:^|[^\p{L}\p{N}_&#])#([\p{L}\p{N}_]+)").clone());
+
+/// The longest tag (in characters) that is recognized. Anything longer is ignored.
+const MAX_TAG_CHARS: usize = 50;
+
+/// Converts a tag to the form in which it is stored, so that tags that differ only in case (or in
+/// a leading #) are treated as the same tag.
+pub fn normalize_tag(tag: &str) -> String { tag.trim_start_matches('#').to_lowercase() }
+
+/// Finds every distinct `#tag` in `body` in order of first appearance, normalized. Tags made up
+/// entirely of digits (like `#1`) are ignored.
+pub fn parse_hashtags(body: &str) -> Vec<String> {
+    let mut tags = Vec::<String>::new();
+
+    for tag in HASHTAG_RE
+        .captures_iter(body)
+        .filter_map(|caps| caps.get(1))
+        .map(|tag| tag.as_str())
+        .filter(|tag| !tag.chars().all(char::is_numeric) && tag.chars().count() <= MAX_TAG_CHARS)
+        .map(normalize_tag)
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_distinct_normalized_tags_in_order() {
+        assert_eq!(
+            vec!["rust", "async_await", "café", "2025recap"],
+            parse_hashtags("#Rust (#async_await) #RUST, #Café and #2025recap! #rust"),
+        );
+    }
+
+    #[test]
+    fn ignores_things_that_are_not_tags() {
+        for body in [
+            "issue #42",
+            "https://example.com/page#section",
+            "&#128512; is an emoji",
+            "## heading",
+            "# lonely",
+            &format!("#{}", "a".repeat(MAX_TAG_CHARS + 1)),
+        ] {
+            assert_eq!(Vec::<String>::new(), parse_hashtags(body), "{body}");
+        }
+    }
+
+    #[test]
+    fn normalizes_tags_from_paths() {
+        assert_eq!("rustlang", normalize_tag("#RustLang"));
+        assert_eq!("rustlang", normalize_tag("rustlang"));
+    }
+}
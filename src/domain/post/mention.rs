@@ -0,0 +1,68 @@
+use {
+    lazy_regex::{Regex, lazy_regex},
+    std::sync::LazyLock,
+};
+
+// Usernames use the same characters as `USERNAME_RE`. An @ directly after one of those characters
+// (or another @) is part of something else, like an email address, rather than a mention.
+static MENTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| lazy_regex!("(?:^|[^A-Za-z0-9_@-])@([A-Za-z0-9_-]+)").clone());
+
+/// An `@username` in a post body, which may or may not refer to an existing user.
+#[cfg_attr(test, derive(Debug, Clone, Copy, PartialEq, Eq))]
+pub struct Mention<'a> {
+    /// The username without the @.
+    pub username: &'a str,
+    /// The byte offset of the @ in the body.
+    pub start: usize,
+    /// The byte offset just after the end of the username in the body.
+    pub end: usize,
+}
+
+/// Finds every `@username` in `body` in order of appearance.
+pub fn parse_mentions(body: &str) -> Vec<Mention<'_>> {
+    MENTION_RE
+        .captures_iter(body)
+        .filter_map(|caps| caps.get(1))
+        .map(|username| Mention {
+            username: username.as_str(),
+            start: username.start().saturating_sub(1),
+            end: username.end(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_mentions_with_their_byte_offsets() {
+        assert_eq!(
+            vec![
+                Mention { username: "alice", start: 0, end: 6 },
+                Mention { username: "bob_2-b", start: 11, end: 19 },
+                Mention { username: "alice", start: 29, end: 35 },
+            ],
+            parse_mentions("@alice and @bob_2-b, 🗺️ @alice!"),
+        );
+    }
+
+    #[test]
+    fn finds_adjacent_mentions_separated_by_punctuation() {
+        assert_eq!(
+            vec![
+                Mention { username: "a", start: 1, end: 3 },
+                Mention { username: "b", start: 4, end: 6 },
+            ],
+            parse_mentions("(@a,@b)"),
+        );
+    }
+
+    #[test]
+    fn ignores_email_addresses_and_bare_at_signs() {
+        for body in ["write to someone@example.com", "@ nobody", "@@double", "email@@double", "@"] {
+            assert_eq!(Vec::<Mention<'_>>::new(), parse_mentions(body), "{body}");
+        }
+    }
+}
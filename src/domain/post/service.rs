@@ -1,181 +1,1583 @@
-use crate::{
-    app_services::uow::{Tx as _, UnitOfWork},
-    domain::post::{PostError, PostRepo, PostSvc},
+use {
+    crate::{
+        app_services::uow::{Tx, UnitOfWork},
+        domain::{
+            draft::DraftRepo,
+            moderation::ModerationRepo,
+            notification::NotificationRepo,
+            post::{
+                PostError, PostRepo, PostSvc, ScheduledOutcome,
+                body::{BodyLimits, normalize_body},
+                content_filter::{ContentFilter, FilterVerdict},
+                hashtag::parse_hashtags,
+                mention::parse_mentions,
+                thread::ThreadLimits,
+            },
+            webhook::WebhookRepo,
+        },
+        models::{
+            notification::NotificationKind,
+            post::{BodyFormat, Post, PostVisibility, ReplyPolicy},
+            webhook::WebhookEvent,
+        },
+    },
+    chrono::{DateTime, Utc},
 };
 
-pub struct PostDomainSvc<U, R> {
+pub struct PostDomainSvc<U, R, N, W, M, D, F> {
     uow: U,
     repo: R,
+    notification_repo: N,
+    webhook_repo: W,
+    moderation_repo: M,
+    draft_repo: D,
+    content_filter: F,
+    body_limits: BodyLimits,
+    thread_limits: ThreadLimits,
+}
+
+impl<U, R, N, W, M, D, F> PostDomainSvc<U, R, N, W, M, D, F> {
+    #[expect(clippy::too_many_arguments, reason = "One argument per dependency")]
+    pub const fn new(
+        uow: U,
+        repo: R,
+        notification_repo: N,
+        webhook_repo: W,
+        moderation_repo: M,
+        draft_repo: D,
+        content_filter: F,
+        body_limits: BodyLimits,
+        thread_limits: ThreadLimits,
+    ) -> Self {
+        Self {
+            uow,
+            repo,
+            notification_repo,
+            webhook_repo,
+            moderation_repo,
+            draft_repo,
+            content_filter,
+            body_limits,
+            thread_limits,
+        }
+    }
 }
 
-impl<U, R> PostDomainSvc<U, R> {
-    pub const fn new(uow: U, repo: R) -> Self { Self { uow, repo } }
+impl<U, R, N, W, M, D, F> PostDomainSvc<U, R, N, W, M, D, F>
+where
+    U: UnitOfWork,
+    R: PostRepo,
+    N: NotificationRepo,
+    W: WebhookRepo,
+    M: ModerationRepo,
+    D: DraftRepo,
+    F: ContentFilter,
+{
+    /// Normalizes a body and checks it against the limits and the content filter, returning the
+    /// normalized body and the filter's verdict unless it was rejected.
+    fn screen(&self, body: &str) -> Result<(String, FilterVerdict), PostError> {
+        let normalized = normalize_body(body);
+        self.body_limits.check(&normalized)?;
+
+        match self.content_filter.check(&normalized) {
+            FilterVerdict::Reject => Err(PostError::RejectedContent),
+            verdict => Ok((normalized, verdict)),
+        }
+    }
+
+    /// Fetches and locks the post being replied to, checking that the author may reply to it.
+    async fn check_parent(
+        &self,
+        tx: &mut impl Tx,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<Post, PostError> {
+        // Disallow writing posts in response to nonexistent, deleted, archived, or one's own posts,
+        // or to posts already at the thread depth or reply count limits. Posts that the author may
+        // not see count as nonexistent.
+
+        let parent = self
+            .repo
+            .get_by_id_exclusive(tx.exec(), parent_id)
+            .await?
+            .ok_or(PostError::NotFound)?;
+
+        if !self
+            .repo
+            .is_visible_to(tx.exec(), parent_id, author_id)
+            .await?
+        {
+            return Err(PostError::NotFound);
+        }
+
+        if parent.deleted_at.is_some() {
+            return Err(PostError::DeletedParent);
+        }
+        if parent.archived_at.is_some() {
+            return Err(PostError::ArchivedParent);
+        }
+        if parent
+            .author_id
+            .is_some_and(|parent_author_id| parent_author_id == author_id)
+        {
+            return Err(PostError::SelfReply);
+        }
+        self.thread_limits.check(&parent)?;
+
+        // Respect the parent's reply policy and those of any ancestors that extend theirs to all
+        // descendants. The parent is locked, so its policy can't change until the reply is in.
+        let restrictions = self
+            .repo
+            .reply_restrictions(tx.exec(), parent_id, author_id)
+            .await?;
+
+        if restrictions
+            .iter()
+            .any(|r| matches!(r.policy, ReplyPolicy::Nobody))
+        {
+            return Err(PostError::RepliesLocked);
+        }
+        if restrictions
+            .iter()
+            .any(|r| matches!(r.policy, ReplyPolicy::Friends) && !r.replier_is_author_or_friend)
+        {
+            return Err(PostError::FriendsOnlyReplies);
+        }
+
+        Ok(parent)
+    }
+
+    /// Fetches the post being quoted, checking that it can be quoted. Scheduled posts and posts
+    /// hidden from the author count as nonexistent, so only posts that the author can see are
+    /// quotable. The quoted post is only read, not locked, so that replies never wait on a second
+    /// post lock while holding the parent's. The quote's foreign key check keeps the row in place.
+    async fn check_quoted(
+        &self,
+        tx: &mut impl Tx,
+        author_id: i32,
+        quoted_post_id: i32,
+    ) -> Result<(), PostError> {
+        let quoted = self
+            .repo
+            .get_by_id(tx.exec(), quoted_post_id)
+            .await?
+            .ok_or(PostError::QuotedNotFound)?;
+
+        if !self
+            .repo
+            .is_visible_to(tx.exec(), quoted_post_id, author_id)
+            .await?
+        {
+            return Err(PostError::QuotedNotFound);
+        }
+
+        if quoted.deleted_at.is_some() {
+            return Err(PostError::DeletedQuote);
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a reply whose body has already been screened, along with its flag, notifications,
+    /// mentions, tags, and webhook event. Does not commit the transaction.
+    #[expect(clippy::too_many_arguments, reason = "Each argument is part of the reply")]
+    async fn insert_reply(
+        &self,
+        tx: &mut impl Tx,
+        author_id: i32,
+        parent_id: i32,
+        body: String,
+        body_format: BodyFormat,
+        quoted_post_id: Option<i32>,
+        verdict: FilterVerdict,
+    ) -> Result<(), PostError> {
+        let parent = self.check_parent(tx, author_id, parent_id).await?;
+        if let Some(quoted_id) = quoted_post_id {
+            self.check_quoted(tx, author_id, quoted_id).await?;
+        }
+
+        let new_post_id = self
+            .repo
+            .insert_new(tx.exec(), author_id, parent_id, &body, body_format, quoted_post_id)
+            .await?;
+
+        self.announce(tx, new_post_id, author_id, &parent, body, verdict)
+            .await
+    }
+
+    /// Records everything that follows from a reply becoming visible: its flag, notifications,
+    /// mentions, tags, and webhook event. Does not commit the transaction.
+    async fn announce(
+        &self,
+        tx: &mut impl Tx,
+        new_post_id: i32,
+        author_id: i32,
+        parent: &Post,
+        body: String,
+        verdict: FilterVerdict,
+    ) -> Result<(), PostError> {
+        if let FilterVerdict::Flag(reason) = verdict {
+            self.moderation_repo
+                .insert_flag(tx.exec(), new_post_id, reason)
+                .await?;
+        }
+
+        // Let the parent's author know about the reply (unless their account has been deleted).
+        // Nobody is told about a post that they may not see.
+        if let Some(parent_author_id) = parent.author_id
+            && self
+                .repo
+                .is_visible_to(tx.exec(), new_post_id, parent_author_id)
+                .await?
+        {
+            self.notification_repo
+                .insert_new(
+                    tx.exec(),
+                    parent_author_id,
+                    author_id,
+                    NotificationKind::Reply,
+                    Some(new_post_id),
+                )
+                .await?;
+        }
+
+        // Let mentioned users know too, except for the author and the parent's author, who is
+        // already being notified about the reply
+        let mentions = parse_mentions(&body);
+        if !mentions.is_empty() {
+            let mentioned_ids = self
+                .repo
+                .insert_mentions(tx.exec(), new_post_id, &mentions)
+                .await?;
+
+            for mentioned_id in mentioned_ids {
+                if mentioned_id == author_id
+                    || parent.author_id == Some(mentioned_id)
+                    || !self
+                        .repo
+                        .is_visible_to(tx.exec(), new_post_id, mentioned_id)
+                        .await?
+                {
+                    continue;
+                }
+
+                self.notification_repo
+                    .insert_new(
+                        tx.exec(),
+                        mentioned_id,
+                        author_id,
+                        NotificationKind::Mention,
+                        Some(new_post_id),
+                    )
+                    .await?;
+            }
+        }
+
+        let tags = parse_hashtags(&body);
+        if !tags.is_empty() {
+            self.repo.insert_tags(tx.exec(), new_post_id, &tags).await?;
+        }
+
+        self.webhook_repo
+            .enqueue(
+                tx.exec(),
+                &WebhookEvent::PostCreated {
+                    post_id: new_post_id,
+                    author_id,
+                    parent_id: parent.id,
+                    body,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Publishes a scheduled post if it still passes every check that a new post would, doing
+    /// everything that creating it would have done. Does not commit the transaction.
+    async fn go_live(
+        &self,
+        tx: &mut impl Tx,
+        post: &Post,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+    ) -> Result<(), PostError> {
+        let (normalized, verdict) = self.screen(body)?;
+        let parent = self.check_parent(tx, author_id, parent_id).await?;
+
+        self.repo.publish(tx.exec(), post.id).await?;
+
+        self.announce(tx, post.id, author_id, &parent, normalized, verdict)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
-impl<U, R> PostSvc for PostDomainSvc<U, R>
+impl<U, R, N, W, M, D, F> PostSvc for PostDomainSvc<U, R, N, W, M, D, F>
 where
     U: UnitOfWork,
     R: PostRepo,
+    N: NotificationRepo,
+    W: WebhookRepo,
+    M: ModerationRepo,
+    D: DraftRepo,
+    F: ContentFilter,
 {
     async fn create_new(
         &self,
         author_id: i32,
         parent_id: i32,
         body: &str,
+        body_format: BodyFormat,
+        quoted_post_id: Option<i32>,
+    ) -> Result<(), PostError> {
+        // Screen the body before touching the database
+        let (normalized, verdict) = self.screen(body)?;
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        self.insert_reply(
+            &mut tx,
+            author_id,
+            parent_id,
+            normalized,
+            body_format,
+            quoted_post_id,
+            verdict,
+        )
+        .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn publish_draft(&self, author_id: i32, parent_id: i32) -> Result<(), PostError> {
+        // Locking the draft keeps an autosave from changing it while it is being published
+        let mut tx = self.uow.begin_uow().await?;
+
+        let draft = self
+            .draft_repo
+            .get_exclusive(tx.exec(), author_id, parent_id)
+            .await?
+            .ok_or(PostError::NoDraft)?;
+
+        let (normalized, verdict) = self.screen(&draft.body)?;
+
+        self.insert_reply(
+            &mut tx,
+            author_id,
+            parent_id,
+            normalized,
+            draft.body_format,
+            None,
+            verdict,
+        )
+        .await?;
+
+        self.draft_repo
+            .delete(tx.exec(), author_id, parent_id)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn schedule_draft(
+        &self,
+        author_id: i32,
+        parent_id: i32,
+        publish_at: DateTime<Utc>,
+    ) -> Result<(), PostError> {
+        if publish_at <= Utc::now() {
+            return Err(PostError::PublishTimeNotInFuture);
+        }
+
+        let mut tx = self.uow.begin_uow().await?;
+
+        let draft = self
+            .draft_repo
+            .get_exclusive(tx.exec(), author_id, parent_id)
+            .await?
+            .ok_or(PostError::NoDraft)?;
+
+        // Checking now catches most problems while the author is still around to fix them. The
+        // flag, notifications, and so on wait until the post is published.
+        let (normalized, _) = self.screen(&draft.body)?;
+        self.check_parent(&mut tx, author_id, parent_id).await?;
+
+        self.repo
+            .insert_scheduled(
+                tx.exec(),
+                author_id,
+                parent_id,
+                &normalized,
+                draft.body_format,
+                publish_at,
+            )
+            .await?;
+
+        self.draft_repo
+            .delete(tx.exec(), author_id, parent_id)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn set_reply_policy(
+        &self,
+        requester_id: i32,
+        post_id: i32,
+        policy: ReplyPolicy,
+        cascades: bool,
+    ) -> Result<(), PostError> {
+        let mut tx = self.uow.begin_uow().await?;
+
+        let post = self
+            .repo
+            .get_by_id_exclusive(tx.exec(), post_id)
+            .await?
+            .ok_or(PostError::NotFound)?;
+
+        if post.author_id != Some(requester_id) {
+            return Err(PostError::NotAuthor);
+        }
+
+        self.repo
+            .set_reply_policy(tx.exec(), post_id, policy, cascades)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn set_visibility(
+        &self,
+        requester_id: i32,
+        post_id: i32,
+        visibility: PostVisibility,
     ) -> Result<(), PostError> {
-        // Disallow writing posts in response to nonexistent, deleted, archived, or one's own posts
+        let mut tx = self.uow.begin_uow().await?;
+
+        let post = self
+            .repo
+            .get_by_id_exclusive(tx.exec(), post_id)
+            .await?
+            .ok_or(PostError::NotFound)?;
+
+        if post.author_id != Some(requester_id) {
+            return Err(PostError::NotAuthor);
+        }
+
+        self.repo
+            .set_visibility(tx.exec(), post_id, visibility)
+            .await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn publish_next_scheduled(&self) -> Result<Option<ScheduledOutcome>, PostError> {
+        let mut tx = self.uow.begin_uow().await?;
+
+        let Some(post) = self.repo.claim_due_scheduled(tx.exec()).await? else {
+            return Ok(None);
+        };
+
+        // With the author's account deleted, there is no one to publish it for or return it to
+        let (Some(author_id), Some(parent_id)) = (post.author_id, post.parent_id) else {
+            self.repo.delete_scheduled(tx.exec(), post.id).await?;
+            tx.commit_uow().await?;
+            return Ok(Some(ScheduledOutcome::Discarded { post_id: post.id }));
+        };
+
+        let body = post.body.clone().unwrap_or_default();
+
+        // Things may have changed since the post was scheduled, so it is checked again
+        let outcome = match self
+            .go_live(&mut tx, &post, author_id, parent_id, &body)
+            .await
+        {
+            Ok(()) => ScheduledOutcome::Published { post_id: post.id },
+            Err(e @ PostError::Internal(_)) => return Err(e),
+            Err(reason) => {
+                self.repo.delete_scheduled(tx.exec(), post.id).await?;
+                self.draft_repo
+                    .upsert(
+                        tx.exec(),
+                        author_id,
+                        parent_id,
+                        &body,
+                        post.body_format,
+                        Some(&reason.to_string()),
+                    )
+                    .await?;
+
+                ScheduledOutcome::Returned { post_id: post.id, reason }
+            }
+        };
+
+        tx.commit_uow().await?;
+
+        Ok(Some(outcome))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::{
+                RepoError,
+                post::{ReplyRestriction, content_filter::WordListFilter},
+            },
+            models::{draft::Draft, moderation::ReportReason},
+            test_utils::{
+                dummy_data,
+                fake_db::FakeUow,
+                mock_repos::{
+                    MockDraftRepo, MockModerationRepo, MockNotificationRepo, MockPostRepo,
+                    MockWebhookRepo,
+                },
+                tokio_test,
+            },
+        },
+        anyhow::{Context as _, Result, anyhow},
+        chrono::TimeDelta,
+        std::{
+            assert_matches,
+            sync::{Arc, Mutex},
+        },
+    };
+
+    async fn run_unacceptable_parent_test(
+        author_id: i32,
+        parent_post: Option<Post>,
+        expected_post_error: PostError,
+    ) -> Result<()> {
+        let parent_post_id = parent_post.as_ref().map_or(543, |post| post.id);
+
+        let mock_repo = MockPostRepo {
+            is_visible_to: Some(Box::new(|_, _| Ok(true))),
+            get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                assert_eq!(parent_post_id, passed_id);
+                Ok(parent_post.clone())
+            })),
+            ..Default::default()
+        };
+
+        let (fake_uow, probe) = FakeUow::with_probe()?;
+        let result = PostDomainSvc::new(
+            fake_uow,
+            mock_repo,
+            MockNotificationRepo::default(),
+            MockWebhookRepo::default(),
+            MockModerationRepo::default(),
+            MockDraftRepo::default(),
+            WordListFilter::default(),
+            BodyLimits::default(),
+            ThreadLimits::default(),
+        )
+        .create_new(author_id, parent_post_id, "My parent is unacceptable", BodyFormat::Plain, None)
+        .await;
+
+        assert_matches!(result, Err(e) if e == expected_post_error);
+        assert!(!probe.commit_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn disallows_replying_to_a_nonexistent_post() -> Result<()> {
+        tokio_test(async { run_unacceptable_parent_test(20, None, PostError::NotFound).await })
+    }
+
+    #[test]
+    fn disallows_replying_to_a_deleted_post() -> Result<()> {
+        tokio_test(async {
+            let mut deleted_parent = dummy_data::post::number1()?;
+            deleted_parent.deleted_at = Some(Utc::now());
+
+            run_unacceptable_parent_test(
+                deleted_parent.author_id.unwrap_or(41) + 1,
+                Some(deleted_parent),
+                PostError::DeletedParent,
+            )
+            .await
+        })
+    }
+
+    #[test]
+    fn disallows_replying_to_an_archived_post() -> Result<()> {
+        tokio_test(async {
+            let mut archived_parent = dummy_data::post::number1()?;
+            archived_parent.archived_at = Some(Utc::now());
+
+            run_unacceptable_parent_test(
+                archived_parent.author_id.unwrap_or(43) - 1,
+                Some(archived_parent),
+                PostError::ArchivedParent,
+            )
+            .await
+        })
+    }
+
+    #[test]
+    fn disallows_replying_to_ones_own_post() -> Result<()> {
+        tokio_test(async {
+            let self_reply_parent = dummy_data::post::number1()?;
+
+            run_unacceptable_parent_test(
+                self_reply_parent
+                    .author_id
+                    .context("unexpected None author ID")?,
+                Some(self_reply_parent),
+                PostError::SelfReply,
+            )
+            .await
+        })
+    }
+
+    #[test]
+    fn disallows_replies_beyond_the_thread_limits() -> Result<()> {
+        tokio_test(async {
+            let limits = ThreadLimits::default();
+
+            let mut deepest_parent = dummy_data::post::number1()?;
+            deepest_parent.depth = limits.max_depth;
+            run_unacceptable_parent_test(
+                deepest_parent.author_id.unwrap_or(41) + 1,
+                Some(deepest_parent),
+                PostError::TooDeep(limits.max_depth),
+            )
+            .await?;
+
+            let mut fullest_parent = dummy_data::post::number1()?;
+            fullest_parent.child_count = limits.max_children;
+            run_unacceptable_parent_test(
+                fullest_parent.author_id.unwrap_or(41) + 1,
+                Some(fullest_parent),
+                PostError::TooManyReplies(limits.max_children),
+            )
+            .await
+        })
+    }
+
+    #[test]
+    fn disallows_replies_that_a_reply_policy_forbids() -> Result<()> {
+        let restriction = |policy, replier_is_author_or_friend| ReplyRestriction {
+            policy,
+            replier_is_author_or_friend,
+        };
+
+        tokio_test(async {
+            for (restrictions, expected_post_error) in [
+                (vec![restriction(ReplyPolicy::Nobody, true)], PostError::RepliesLocked),
+                (vec![restriction(ReplyPolicy::Friends, false)], PostError::FriendsOnlyReplies),
+                // A lock anywhere above wins over any other restriction
+                (
+                    vec![
+                        restriction(ReplyPolicy::Friends, false),
+                        restriction(ReplyPolicy::Nobody, true),
+                    ],
+                    PostError::RepliesLocked,
+                ),
+                // Every friends-only policy has to be satisfied
+                (
+                    vec![
+                        restriction(ReplyPolicy::Friends, true),
+                        restriction(ReplyPolicy::Friends, false),
+                    ],
+                    PostError::FriendsOnlyReplies,
+                ),
+            ] {
+                let parent_post = dummy_data::post::number1()?;
+                let parent_post_id = parent_post.id;
+
+                let mock_repo = MockPostRepo {
+                    is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                    get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(parent_post.clone())))),
+                    reply_restrictions: Some(Box::new(move |passed_post_id, passed_replier_id| {
+                        assert_eq!((parent_post_id, 7), (passed_post_id, passed_replier_id));
+                        Ok(restrictions.clone())
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .create_new(7, parent_post_id, "Let me in", BodyFormat::Plain, None)
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected_post_error);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn sets_reply_policies_only_on_the_requesters_own_posts() -> Result<()> {
+        tokio_test(async {
+            let post = dummy_data::post::number1()?;
+            let (post_id, author_id) = (post.id, post.author_id.context("unexpected None")?);
+
+            for (requester_id, found, expected) in [
+                (author_id, true, Ok(())),
+                (author_id + 1, true, Err(PostError::NotAuthor)),
+                (author_id, false, Err(PostError::NotFound)),
+            ] {
+                let post_clone = post.clone();
+                let mock_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                        assert_eq!(post_id, passed_id);
+                        Ok(found.then(|| post_clone.clone()))
+                    })),
+                    set_reply_policy: Some(Box::new(
+                        move |passed_id, passed_policy, passed_cascades| {
+                            assert_eq!(
+                                (post_id, ReplyPolicy::Friends, true),
+                                (passed_id, passed_policy, passed_cascades),
+                            );
+                            Ok(())
+                        },
+                    )),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .set_reply_policy(requester_id, post_id, ReplyPolicy::Friends, true)
+                .await;
+
+                assert_eq!(expected.is_ok(), probe.commit_called());
+                assert_eq!(expected, result);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn sets_visibility_only_on_the_requesters_own_posts() -> Result<()> {
+        tokio_test(async {
+            let post = dummy_data::post::number1()?;
+            let (post_id, author_id) = (post.id, post.author_id.context("unexpected None")?);
+
+            for (requester_id, expected) in
+                [(author_id, Ok(())), (author_id + 1, Err(PostError::NotAuthor))]
+            {
+                let post_clone = post.clone();
+                let mock_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(post_clone.clone())))),
+                    set_visibility: Some(Box::new(move |passed_id, passed_visibility| {
+                        assert_eq!(
+                            (post_id, PostVisibility::Friends),
+                            (passed_id, passed_visibility)
+                        );
+                        Ok(())
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .set_visibility(requester_id, post_id, PostVisibility::Friends)
+                .await;
+
+                assert_eq!(expected.is_ok(), probe.commit_called());
+                assert_eq!(expected, result);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn treats_posts_hidden_from_the_author_as_nonexistent() -> Result<()> {
+        tokio_test(async {
+            let parent_post = dummy_data::post::number1()?;
+            let quoted_post = dummy_data::post::number2()?;
+            let (parent_post_id, quoted_post_id) = (parent_post.id, quoted_post.id);
+
+            for (hidden_post_id, expected_post_error) in
+                [(parent_post_id, PostError::NotFound), (quoted_post_id, PostError::QuotedNotFound)]
+            {
+                let (parent_clone, quoted_clone) = (parent_post.clone(), quoted_post.clone());
+                let mock_repo = MockPostRepo {
+                    get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                        assert_eq!(parent_post_id, passed_id);
+                        Ok(Some(parent_clone.clone()))
+                    })),
+                    get_by_id: Some(Box::new(move |passed_id| {
+                        assert_eq!(quoted_post_id, passed_id);
+                        Ok(Some(quoted_clone.clone()))
+                    })),
+                    is_visible_to: Some(Box::new(move |passed_id, passed_viewer_id| {
+                        assert_eq!(7, passed_viewer_id);
+                        Ok(passed_id != hidden_post_id)
+                    })),
+                    reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .create_new(7, parent_post_id, "Psst", BodyFormat::Plain, Some(quoted_post_id))
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected_post_error);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    async fn run_unacceptable_quote_test(
+        quoted_post: Option<Post>,
+        expected_post_error: PostError,
+    ) -> Result<()> {
+        let parent_post = dummy_data::post::number1()?;
+        let parent_post_id = parent_post.id;
+        let quoted_post_id = quoted_post.as_ref().map_or(777, |post| post.id);
+
+        let mock_repo = MockPostRepo {
+            is_visible_to: Some(Box::new(|_, _| Ok(true))),
+            get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                assert_eq!(parent_post_id, passed_id);
+                Ok(Some(parent_post.clone()))
+            })),
+            get_by_id: Some(Box::new(move |passed_id| {
+                assert_eq!(quoted_post_id, passed_id);
+                Ok(quoted_post.clone())
+            })),
+            reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+            ..Default::default()
+        };
+
+        let (fake_uow, probe) = FakeUow::with_probe()?;
+        let result = PostDomainSvc::new(
+            fake_uow,
+            mock_repo,
+            MockNotificationRepo::default(),
+            MockWebhookRepo::default(),
+            MockModerationRepo::default(),
+            MockDraftRepo::default(),
+            WordListFilter::default(),
+            BodyLimits::default(),
+            ThreadLimits::default(),
+        )
+        .create_new(7, parent_post_id, "Look at this", BodyFormat::Plain, Some(quoted_post_id))
+        .await;
+
+        assert_matches!(result, Err(e) if e == expected_post_error);
+        assert!(!probe.commit_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn disallows_quoting_a_nonexistent_post() -> Result<()> {
+        tokio_test(async { run_unacceptable_quote_test(None, PostError::QuotedNotFound).await })
+    }
+
+    #[test]
+    fn disallows_quoting_a_deleted_post() -> Result<()> {
+        tokio_test(async {
+            let mut deleted_quote = dummy_data::post::number2()?;
+            deleted_quote.deleted_at = Some(Utc::now());
+
+            run_unacceptable_quote_test(Some(deleted_quote), PostError::DeletedQuote).await
+        })
+    }
+
+    #[test]
+    fn quotes_posts_from_other_threads() -> Result<()> {
+        tokio_test(async {
+            let parent_post = dummy_data::post::number1()?;
+            // Archived posts can't be replied to, but they can still be quoted
+            let mut quoted_post = dummy_data::post::number2()?;
+            quoted_post.archived_at = Some(Utc::now());
+            let (parent_post_id, quoted_post_id) = (parent_post.id, quoted_post.id);
+
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                // Only the parent is locked
+                get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                    assert_eq!(parent_post_id, passed_id);
+                    Ok(Some(parent_post.clone()))
+                })),
+                get_by_id: Some(Box::new(move |passed_id| {
+                    assert_eq!(quoted_post_id, passed_id);
+                    Ok(Some(quoted_post.clone()))
+                })),
+                insert_new: Some(Box::new(move |_, _, _, _, passed_quoted_post_id| {
+                    assert_eq!(Some(quoted_post_id), passed_quoted_post_id);
+                    Ok(4)
+                })),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(|_, _, _, _| Ok(()))),
+                ..Default::default()
+            };
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .create_new(7, parent_post_id, "See also", BodyFormat::Plain, Some(quoted_post_id))
+            .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn creates_post_notifies_parent_author_and_commits_if_all_conditions_are_met() -> Result<()> {
+        tokio_test(async {
+            let parent_post = dummy_data::post::number1()?;
+            let parent_post_id = parent_post.id;
+            let parent_author_id = parent_post.author_id.context("unexpected None author ID")?;
+            let new_post_author_id = parent_author_id + 15;
+            let new_post_body = "This is a new post that should work";
+            let new_post_id = 8080;
+
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                    assert_eq!(parent_post_id, passed_id);
+                    Ok(Some(parent_post.clone()))
+                })),
+                insert_new: Some(Box::new(
+                    move |passed_author_id,
+                          passed_parent_id,
+                          passed_post_body,
+                          passed_format,
+                          _| {
+                        assert_eq!(new_post_author_id, passed_author_id);
+                        assert_eq!(parent_post_id, passed_parent_id);
+                        assert_eq!(new_post_body, passed_post_body);
+                        assert_eq!(BodyFormat::Markdown, passed_format);
+                        Ok(new_post_id)
+                    },
+                )),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(
+                    move |passed_recipient_id, passed_actor_id, passed_kind, passed_post_id| {
+                        assert_eq!(parent_author_id, passed_recipient_id);
+                        assert_eq!(new_post_author_id, passed_actor_id);
+                        assert_eq!(NotificationKind::Reply, passed_kind);
+                        assert_eq!(Some(new_post_id), passed_post_id);
+                        Ok(())
+                    },
+                )),
+                ..Default::default()
+            };
+
+            let mock_webhook_repo = MockWebhookRepo {
+                enqueue: Some(Box::new(move |passed_event| {
+                    assert_eq!(
+                        &WebhookEvent::PostCreated {
+                            post_id: new_post_id,
+                            author_id: new_post_author_id,
+                            parent_id: parent_post_id,
+                            body: String::from(new_post_body),
+                        },
+                        passed_event,
+                    );
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .create_new(
+                new_post_author_id,
+                parent_post_id,
+                new_post_body,
+                BodyFormat::Markdown,
+                None,
+            )
+            .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn records_mentions_and_notifies_mentioned_users_other_than_the_author_and_parent_author()
+    -> Result<()> {
+        tokio_test(async {
+            let parent_post = dummy_data::post::number1()?;
+            let parent_author_id = parent_post.author_id.context("unexpected None author ID")?;
+            let new_post_author_id = parent_author_id + 1;
+            let new_post_body = "@alice @me @parent_author @alice @nobody";
+            let new_post_id = 606;
+
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(parent_post.clone())))),
+                insert_new: Some(Box::new(move |_, _, _, _, _| Ok(new_post_id))),
+                insert_mentions: Some(Box::new(move |passed_post_id, passed_mentions| {
+                    assert_eq!(new_post_id, passed_post_id);
+                    assert_eq!(
+                        vec!["alice", "me", "parent_author", "alice", "nobody"],
+                        passed_mentions
+                            .iter()
+                            .map(|m| m.username)
+                            .collect::<Vec<_>>(),
+                    );
+                    // Alice, the author, and the parent's author exist
+                    Ok(vec![77, new_post_author_id, parent_author_id])
+                })),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let notified = Arc::new(Mutex::new(Vec::new()));
+            let notified_clone = Arc::clone(&notified);
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(
+                    move |passed_recipient_id, passed_actor_id, passed_kind, passed_post_id| {
+                        assert_eq!(new_post_author_id, passed_actor_id);
+                        assert_eq!(Some(new_post_id), passed_post_id);
+                        notified_clone
+                            .lock()
+                            .map_err(|_| anyhow!("poisoned"))?
+                            .push((passed_recipient_id, passed_kind));
+                        Ok(())
+                    },
+                )),
+                ..Default::default()
+            };
+
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .create_new(new_post_author_id, 24, new_post_body, BodyFormat::Plain, None)
+            .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+            assert_eq!(
+                vec![(parent_author_id, NotificationKind::Reply), (77, NotificationKind::Mention)],
+                *notified.lock().map_err(|_| anyhow!("poisoned"))?,
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn only_notifies_users_who_may_see_the_new_post() -> Result<()> {
+        tokio_test(async {
+            let parent_post = dummy_data::post::number1()?;
+            let parent_author_id = parent_post.author_id.context("unexpected None author ID")?;
+            let new_post_author_id = parent_author_id + 1;
+            let new_post_id = 616;
+
+            // Only user 78 may see the new post, say because it is for the author's friends
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(move |passed_id, passed_viewer_id| {
+                    Ok(passed_id != new_post_id || passed_viewer_id == 78)
+                })),
+                get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(parent_post.clone())))),
+                insert_new: Some(Box::new(move |_, _, _, _, _| Ok(new_post_id))),
+                insert_mentions: Some(Box::new(|_, _| Ok(vec![77, 78]))),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let notified = Arc::new(Mutex::new(Vec::new()));
+            let notified_clone = Arc::clone(&notified);
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(move |passed_recipient_id, _, passed_kind, _| {
+                    notified_clone
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .push((passed_recipient_id, passed_kind));
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .create_new(new_post_author_id, 24, "@alice @bob", BodyFormat::Plain, None)
+            .await;
 
-        let mut tx = self.uow.begin_uow().await?;
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+            assert_eq!(
+                vec![(78, NotificationKind::Mention)],
+                *notified.lock().map_err(|_| anyhow!("poisoned"))?,
+            );
 
-        let parent = self
-            .repo
-            .get_by_id_exclusive(tx.exec(), parent_id)
-            .await?
-            .ok_or(PostError::NotFound)?;
+            Ok(())
+        })
+    }
 
-        if parent.deleted_at.is_some() {
-            return Err(PostError::DeletedParent);
-        }
-        if parent.archived_at.is_some() {
-            return Err(PostError::ArchivedParent);
-        }
-        if parent
-            .author_id
-            .is_some_and(|parent_author_id| parent_author_id == author_id)
-        {
-            return Err(PostError::SelfReply);
-        }
+    #[test]
+    fn tags_the_new_post_with_its_hashtags() -> Result<()> {
+        tokio_test(async {
+            let parent_post = dummy_data::post::number1()?;
+            let new_post_author_id =
+                parent_post.author_id.context("unexpected None author ID")? + 3;
+            let new_post_id = 707;
 
-        self.repo
-            .insert_new(tx.exec(), author_id, parent_id, body)
-            .await?;
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(parent_post.clone())))),
+                insert_new: Some(Box::new(move |_, _, _, _, _| Ok(new_post_id))),
+                insert_tags: Some(Box::new(move |passed_post_id, passed_tags| {
+                    assert_eq!(new_post_id, passed_post_id);
+                    assert_eq!(&[String::from("til"), String::from("rust")], passed_tags);
+                    Ok(())
+                })),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
 
-        tx.commit_uow().await?;
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(|_, _, _, _| Ok(()))),
+                ..Default::default()
+            };
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
 
-        Ok(())
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .create_new(
+                new_post_author_id,
+                24,
+                "#TIL about #Rust, #rust and #til",
+                BodyFormat::Plain,
+                None,
+            )
+            .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use {
-        super::*,
-        crate::{
-            domain::RepoError,
-            models::post::Post,
-            test_utils::{dummy_data, fake_db::FakeUow, mock_repos::MockPostRepo, tokio_test},
-        },
-        anyhow::{Context as _, Result, anyhow},
-        chrono::Utc,
-        std::assert_matches,
-    };
+    #[test]
+    fn skips_notification_if_the_parent_author_was_deleted() -> Result<()> {
+        tokio_test(async {
+            let mut orphaned_parent = dummy_data::post::number1()?;
+            orphaned_parent.author_id = None;
 
-    async fn run_unacceptable_parent_test(
-        author_id: i32,
-        parent_post: Option<Post>,
-        expected_post_error: PostError,
-    ) -> Result<()> {
-        let parent_post_id = parent_post.as_ref().map_or(543, |post| post.id);
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(orphaned_parent.clone())))),
+                insert_new: Some(Box::new(|_, _, _, _, _| Ok(31))),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
 
-        let mock_repo = MockPostRepo {
-            get_by_id_exclusive: Some(Box::new(move |passed_id| {
-                assert_eq!(parent_post_id, passed_id);
-                Ok(parent_post.clone())
-            })),
-            ..Default::default()
-        };
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
 
-        let (fake_uow, probe) = FakeUow::with_probe()?;
-        let result = PostDomainSvc::new(fake_uow, mock_repo)
-            .create_new(author_id, parent_post_id, "My parent is unacceptable")
+            // The notification repo should not be called
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                MockNotificationRepo::default(),
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .create_new(7, 1, "Replying to no one in particular", BodyFormat::Plain, None)
             .await;
 
-        assert_matches!(result, Err(e) if e == expected_post_error);
-        assert!(!probe.commit_called());
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
 
-        Ok(())
+            Ok(())
+        })
     }
 
     #[test]
-    fn disallows_replying_to_a_nonexistent_post() -> Result<()> {
-        tokio_test(async { run_unacceptable_parent_test(20, None, PostError::NotFound).await })
+    fn rejects_bodies_that_are_empty_too_long_or_filtered() -> Result<()> {
+        tokio_test(async {
+            let limits = BodyLimits { max_graphemes: 10, max_bytes: 100 };
+
+            for (body, expected) in [
+                ("\u{200B} \u{FEFF}\n", PostError::EmptyBody),
+                ("eleven char", PostError::TooManyCharacters(10)),
+                (&"🦀".repeat(26), PostError::TooManyBytes(100)),
+                ("Forbidden!", PostError::RejectedContent),
+            ] {
+                // None of the repos should be called
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    MockPostRepo::default(),
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::from_csv("forbidden", ""),
+                    limits,
+                    ThreadLimits::default(),
+                )
+                .create_new(7, 1, body, BodyFormat::Plain, None)
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
     }
 
     #[test]
-    fn disallows_replying_to_a_deleted_post() -> Result<()> {
+    fn creates_normalized_posts_and_flags_them_for_review() -> Result<()> {
         tokio_test(async {
-            let mut deleted_parent = dummy_data::post::number1()?;
-            deleted_parent.deleted_at = Some(Utc::now());
+            let mut parent_post = dummy_data::post::number1()?;
+            parent_post.author_id = None;
+            let new_post_id = 23;
 
-            run_unacceptable_parent_test(
-                deleted_parent.author_id.unwrap_or(41) + 1,
-                Some(deleted_parent),
-                PostError::DeletedParent,
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(parent_post.clone())))),
+                insert_new: Some(Box::new(move |_, _, passed_post_body, _, _| {
+                    assert_eq!("Buy café crypto", passed_post_body);
+                    Ok(new_post_id)
+                })),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let mock_moderation_repo = MockModerationRepo {
+                insert_flag: Some(Box::new(move |passed_post_id, passed_reason| {
+                    assert_eq!(new_post_id, passed_post_id);
+                    assert_eq!(ReportReason::Other, passed_reason);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                MockNotificationRepo::default(),
+                mock_webhook_repo,
+                mock_moderation_repo,
+                MockDraftRepo::default(),
+                WordListFilter::from_csv("", "crypto"),
+                BodyLimits::default(),
+                ThreadLimits::default(),
             )
-            .await
+            .create_new(7, 1, "  Buy cafe\u{0301} \u{200B}crypto\n", BodyFormat::Plain, None)
+            .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
         })
     }
 
     #[test]
-    fn disallows_replying_to_an_archived_post() -> Result<()> {
+    fn publishes_drafts_as_checked_replies_and_deletes_them() -> Result<()> {
         tokio_test(async {
-            let mut archived_parent = dummy_data::post::number1()?;
-            archived_parent.archived_at = Some(Utc::now());
+            let parent_post = dummy_data::post::number1()?;
+            let draft = dummy_data::draft::number1()?.draft;
+            let (author_id, parent_id) = (draft.author_id + 1, parent_post.id);
 
-            run_unacceptable_parent_test(
-                archived_parent.author_id.unwrap_or(43) - 1,
-                Some(archived_parent),
-                PostError::ArchivedParent,
+            let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |passed_id| {
+                    assert_eq!(parent_id, passed_id);
+                    Ok(Some(parent_post.clone()))
+                })),
+                insert_new: Some(Box::new(
+                    move |passed_author_id,
+                          passed_parent_id,
+                          passed_post_body,
+                          passed_format,
+                          _| {
+                        assert_eq!(
+                            (author_id, parent_id, "*half* of a reply", BodyFormat::Markdown),
+                            (passed_author_id, passed_parent_id, passed_post_body, passed_format),
+                        );
+                        Ok(77)
+                    },
+                )),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let mock_draft_repo = MockDraftRepo {
+                get_exclusive: Some(Box::new(move |passed_author_id, passed_parent_id| {
+                    assert_eq!((author_id, parent_id), (passed_author_id, passed_parent_id));
+                    Ok(Some(draft.clone()))
+                })),
+                delete: Some(Box::new(move |passed_author_id, passed_parent_id| {
+                    assert_eq!((author_id, parent_id), (passed_author_id, passed_parent_id));
+                    Ok(true)
+                })),
+                ..Default::default()
+            };
+
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(|_, _, _, _| Ok(()))),
+                ..Default::default()
+            };
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                mock_draft_repo,
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
             )
-            .await
+            .publish_draft(author_id, parent_id)
+            .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
         })
     }
 
     #[test]
-    fn disallows_replying_to_ones_own_post() -> Result<()> {
+    fn disallows_publishing_missing_or_unacceptable_drafts() -> Result<()> {
         tokio_test(async {
-            let self_reply_parent = dummy_data::post::number1()?;
+            let mut empty = dummy_data::draft::number1()?.draft;
+            empty.body = String::from(" \n");
+            let self_reply = dummy_data::draft::number1()?.draft;
 
-            run_unacceptable_parent_test(
-                self_reply_parent
-                    .author_id
-                    .context("unexpected None author ID")?,
-                Some(self_reply_parent),
-                PostError::SelfReply,
-            )
-            .await
+            for (draft, expected) in [
+                (None, PostError::NoDraft),
+                (Some(empty), PostError::EmptyBody),
+                (Some(self_reply), PostError::SelfReply),
+            ] {
+                let mock_repo = MockPostRepo {
+                    is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                    get_by_id_exclusive: Some(Box::new(|_| Ok(Some(dummy_data::post::number1()?)))),
+                    ..Default::default()
+                };
+
+                // The draft should never be deleted
+                let mock_draft_repo = MockDraftRepo {
+                    get_exclusive: Some(Box::new(move |_, _| Ok(draft.clone()))),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    mock_draft_repo,
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .publish_draft(255, 24)
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
         })
     }
 
     #[test]
-    fn creates_post_and_commits_if_all_conditions_are_met() -> Result<()> {
+    fn schedules_checked_drafts_without_announcing_them() -> Result<()> {
         tokio_test(async {
             let parent_post = dummy_data::post::number1()?;
-            let parent_post_id = parent_post.id;
-            let new_post_author_id =
-                parent_post.author_id.context("unexpected None author ID")? + 15;
-            let new_post_body = "This is a new post that should work";
+            let draft = dummy_data::draft::number1()?.draft;
+            let publish_at = Utc::now()
+                .checked_add_signed(TimeDelta::hours(3))
+                .context("out of range")?;
 
             let mock_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
                 get_by_id_exclusive: Some(Box::new(move |passed_id| {
-                    assert_eq!(parent_post_id, passed_id);
+                    assert_eq!(24, passed_id);
                     Ok(Some(parent_post.clone()))
                 })),
-                insert_new: Some(Box::new(
-                    move |passed_author_id, passed_parent_id, passed_post_body| {
-                        assert_eq!(new_post_author_id, passed_author_id);
-                        assert_eq!(parent_post_id, passed_parent_id);
-                        assert_eq!(new_post_body, passed_post_body);
-                        Ok(())
+                insert_scheduled: Some(Box::new(
+                    move |passed_author_id, passed_parent_id, passed_body, passed_format, at| {
+                        assert_eq!(
+                            (7, 24, "*half* of a reply", BodyFormat::Markdown, publish_at),
+                            (passed_author_id, passed_parent_id, passed_body, passed_format, at),
+                        );
+                        Ok(78)
                     },
                 )),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let mock_draft_repo = MockDraftRepo {
+                get_exclusive: Some(Box::new(move |_, _| Ok(Some(draft.clone())))),
+                delete: Some(Box::new(|passed_author_id, passed_parent_id| {
+                    assert_eq!((7, 24), (passed_author_id, passed_parent_id));
+                    Ok(true)
+                })),
+                ..Default::default()
             };
 
+            // Nothing is announced until the post is published
             let (fake_uow, probe) = FakeUow::with_probe()?;
-            let result = PostDomainSvc::new(fake_uow, mock_repo)
-                .create_new(new_post_author_id, parent_post_id, new_post_body)
-                .await;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                MockNotificationRepo::default(),
+                MockWebhookRepo::default(),
+                MockModerationRepo::default(),
+                mock_draft_repo,
+                WordListFilter::from_csv("", "reply"),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .schedule_draft(7, 24, publish_at)
+            .await;
 
             assert_matches!(result, Ok(()));
             assert!(probe.commit_called());
@@ -184,6 +1586,199 @@ mod tests {
         })
     }
 
+    #[test]
+    fn disallows_scheduling_in_the_past() -> Result<()> {
+        tokio_test(async {
+            // None of the repos should be called
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                MockPostRepo::default(),
+                MockNotificationRepo::default(),
+                MockWebhookRepo::default(),
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .schedule_draft(7, 24, Utc::now())
+            .await;
+
+            assert_matches!(result, Err(PostError::PublishTimeNotInFuture));
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    /// A post by user 7 replying to `dummy_data::post::number1`, scheduled for earlier.
+    fn due_post(body: &str) -> Result<Post> {
+        let mut post = dummy_data::post::number2()?;
+        post.author_id = Some(7);
+        post.parent_id = Some(24);
+        post.body = Some(String::from(body));
+        Ok(post)
+    }
+
+    #[test]
+    fn publishes_due_posts_and_announces_them() -> Result<()> {
+        tokio_test(async {
+            let published = Arc::new(Mutex::new(Vec::new()));
+            let published_clone = Arc::clone(&published);
+            let post = due_post("Now you see me")?;
+
+            let mock_repo = MockPostRepo {
+                claim_due_scheduled: Some(Box::new(move || Ok(Some(post.clone())))),
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(|_| Ok(Some(dummy_data::post::number1()?)))),
+                publish: Some(Box::new(move |passed_id| {
+                    published_clone
+                        .lock()
+                        .map_err(|_| anyhow!("poisoned"))?
+                        .push(passed_id);
+                    Ok(())
+                })),
+                reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                ..Default::default()
+            };
+
+            let mock_notification_repo = MockNotificationRepo {
+                insert_new: Some(Box::new(|recipient_id, actor_id, kind, post_id| {
+                    assert_eq!(
+                        (255, 7, NotificationKind::Reply, Some(999)),
+                        (recipient_id, actor_id, kind, post_id)
+                    );
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let mock_webhook_repo =
+                MockWebhookRepo { enqueue: Some(Box::new(|_| Ok(()))), ..Default::default() };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                mock_notification_repo,
+                mock_webhook_repo,
+                MockModerationRepo::default(),
+                MockDraftRepo::default(),
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .publish_next_scheduled()
+            .await;
+
+            assert_eq!(Some(ScheduledOutcome::Published { post_id: 999 }), result?);
+            assert_eq!(vec![999], *published.lock().map_err(|_| anyhow!("poisoned"))?);
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn returns_posts_that_can_no_longer_be_published_to_the_drafts() -> Result<()> {
+        tokio_test(async {
+            let post = due_post("Too late")?;
+            let mut deleted_parent = dummy_data::post::number1()?;
+            deleted_parent.deleted_at = Some(Utc::now());
+
+            let mock_repo = MockPostRepo {
+                claim_due_scheduled: Some(Box::new(move || Ok(Some(post.clone())))),
+                is_visible_to: Some(Box::new(|_, _| Ok(true))),
+                get_by_id_exclusive: Some(Box::new(move |_| Ok(Some(deleted_parent.clone())))),
+                delete_scheduled: Some(Box::new(|passed_id| {
+                    assert_eq!(999, passed_id);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let mock_draft_repo = MockDraftRepo {
+                upsert: Some(Box::new(|author_id, parent_id, body, body_format, publish_error| {
+                    assert_eq!(
+                        (7, 24, "Too late", Some("Cannot reply to a deleted post")),
+                        (author_id, parent_id, body, publish_error)
+                    );
+                    Ok(Draft {
+                        author_id,
+                        parent_id,
+                        body: body.to_owned(),
+                        body_format,
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                        publish_error: publish_error.map(str::to_owned),
+                    })
+                })),
+                ..Default::default()
+            };
+
+            // Nothing should be announced
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result = PostDomainSvc::new(
+                fake_uow,
+                mock_repo,
+                MockNotificationRepo::default(),
+                MockWebhookRepo::default(),
+                MockModerationRepo::default(),
+                mock_draft_repo,
+                WordListFilter::default(),
+                BodyLimits::default(),
+                ThreadLimits::default(),
+            )
+            .publish_next_scheduled()
+            .await;
+
+            let expected =
+                ScheduledOutcome::Returned { post_id: 999, reason: PostError::DeletedParent };
+            assert_eq!(Some(expected), result?);
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn discards_due_posts_of_deleted_users_and_waits_when_none_are_due() -> Result<()> {
+        tokio_test(async {
+            let mut orphan = due_post("Anyone there?")?;
+            orphan.author_id = None;
+
+            for (claimed, expected) in
+                [(Some(orphan), Some(ScheduledOutcome::Discarded { post_id: 999 })), (None, None)]
+            {
+                let mock_repo = MockPostRepo {
+                    claim_due_scheduled: Some(Box::new(move || Ok(claimed.clone()))),
+                    delete_scheduled: Some(Box::new(|_| Ok(()))),
+                    ..Default::default()
+                };
+
+                let (fake_uow, _) = FakeUow::with_probe()?;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .publish_next_scheduled()
+                .await;
+
+                assert_eq!(expected, result?);
+            }
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn translates_repo_errors() -> Result<()> {
         struct TestCase {
@@ -246,6 +1841,7 @@ mod tests {
             .enumerate()
             {
                 let mock_post_repo = MockPostRepo {
+                    is_visible_to: Some(Box::new(|_, _| Ok(true))),
                     get_by_id_exclusive: Some(Box::new(move |passed_id| {
                         assert_eq!(case.parent_id, passed_id);
                         // Alternating between the first and second dummy posts because the third is
@@ -257,19 +1853,31 @@ mod tests {
                         }))
                     })),
                     insert_new: Some(Box::new(
-                        move |passed_author_id, passed_parent_id, passed_post_body| {
+                        move |passed_author_id, passed_parent_id, passed_post_body, _, _| {
                             assert_eq!(case.author_id, passed_author_id);
                             assert_eq!(case.parent_id, passed_parent_id);
                             assert_eq!(case.post_body, passed_post_body);
                             Err(case.repo_error.clone())
                         },
                     )),
+                    reply_restrictions: Some(Box::new(|_, _| Ok(Vec::new()))),
+                    ..Default::default()
                 };
 
                 let (fake_uow, probe) = FakeUow::with_probe()?;
-                let result = PostDomainSvc::new(fake_uow, mock_post_repo)
-                    .create_new(case.author_id, case.parent_id, case.post_body)
-                    .await;
+                let result = PostDomainSvc::new(
+                    fake_uow,
+                    mock_post_repo,
+                    MockNotificationRepo::default(),
+                    MockWebhookRepo::default(),
+                    MockModerationRepo::default(),
+                    MockDraftRepo::default(),
+                    WordListFilter::default(),
+                    BodyLimits::default(),
+                    ThreadLimits::default(),
+                )
+                .create_new(case.author_id, case.parent_id, case.post_body, BodyFormat::Plain, None)
+                .await;
 
                 assert_matches!(result, Err(e) if e == case.post_error);
                 assert!(!probe.commit_called());
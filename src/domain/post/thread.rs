@@ -0,0 +1,52 @@
+use crate::{domain::post::error::PostError, models::post::Post};
+
+/// The maximum size of a tree of replies.
+#[derive(Clone, Copy)]
+pub struct ThreadLimits {
+    /// The maximum number of levels below the root post that a reply can be.
+    pub max_depth: i32,
+    /// The maximum number of replies directly below a single post.
+    pub max_children: i32,
+}
+
+impl Default for ThreadLimits {
+    fn default() -> Self { Self { max_depth: 64, max_children: 1000 } }
+}
+
+impl ThreadLimits {
+    /// Checks whether the post being replied to has room for another reply.
+    pub const fn check(self, parent: &Post) -> Result<(), PostError> {
+        if parent.depth >= self.max_depth {
+            return Err(PostError::TooDeep(self.max_depth));
+        }
+        if parent.child_count >= self.max_children {
+            return Err(PostError::TooManyReplies(self.max_children));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::test_utils::dummy_data, anyhow::Result, std::assert_matches};
+
+    #[test]
+    fn allows_replies_up_to_the_limits() -> Result<()> {
+        let limits = ThreadLimits { max_depth: 3, max_children: 2 };
+        let mut parent = dummy_data::post::number1()?;
+
+        for (depth, child_count) in [(0, 0), (2, 1)] {
+            (parent.depth, parent.child_count) = (depth, child_count);
+            assert_matches!(limits.check(&parent), Ok(()));
+        }
+
+        (parent.depth, parent.child_count) = (3, 0);
+        assert_matches!(limits.check(&parent), Err(PostError::TooDeep(3)));
+
+        (parent.depth, parent.child_count) = (0, 2);
+        assert_matches!(limits.check(&parent), Err(PostError::TooManyReplies(2)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+pub mod error;
+pub mod service;
+
+use {
+    crate::{
+        domain::{RepoError, reaction::error::ReactionError},
+        models::reaction::ReactionKind,
+    },
+    sqlx::PgExecutor,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait ReactionSvc: Send + Sync {
+    /// Reacts to a post, replacing the user's existing reaction to it if there is one.
+    async fn react(
+        &self,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), ReactionError>;
+
+    /// Removes the user's reaction of the specified kind from a post.
+    async fn unreact(
+        &self,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), ReactionError>;
+}
+
+#[async_trait::async_trait]
+pub trait ReactionRepo: Send + Sync {
+    /// Sets the user's reaction to a post, replacing any existing one.
+    async fn upsert(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), RepoError>;
+
+    /// Deletes the user's reaction to a post if it is of the specified kind, returning whether
+    /// there was such a reaction.
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<bool, RepoError>;
+}
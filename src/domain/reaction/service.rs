@@ -0,0 +1,264 @@
+use crate::{
+    app_services::uow::{Tx, UnitOfWork},
+    domain::{
+        post::PostRepo,
+        reaction::{ReactionError, ReactionRepo, ReactionSvc},
+    },
+    models::reaction::ReactionKind,
+};
+
+pub struct ReactionDomainSvc<U, R, P> {
+    uow: U,
+    repo: R,
+    post_repo: P,
+}
+
+impl<U, R, P> ReactionDomainSvc<U, R, P> {
+    pub const fn new(uow: U, repo: R, post_repo: P) -> Self { Self { uow, repo, post_repo } }
+}
+
+#[async_trait::async_trait]
+impl<U, R, P> ReactionSvc for ReactionDomainSvc<U, R, P>
+where
+    U: UnitOfWork,
+    R: ReactionRepo,
+    P: PostRepo,
+{
+    async fn react(
+        &self,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), ReactionError> {
+        let mut tx = self.uow.begin_uow().await?;
+
+        ensure_open(&self.post_repo, &mut tx, user_id, post_id).await?;
+        self.repo.upsert(tx.exec(), user_id, post_id, kind).await?;
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+
+    async fn unreact(
+        &self,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), ReactionError> {
+        let mut tx = self.uow.begin_uow().await?;
+
+        ensure_open(&self.post_repo, &mut tx, user_id, post_id).await?;
+        if !self.repo.delete(tx.exec(), user_id, post_id, kind).await? {
+            return Err(ReactionError::NoReaction);
+        }
+
+        tx.commit_uow().await?;
+
+        Ok(())
+    }
+}
+
+/// Checks that the post exists, that the user may see it, and that its reactions can still change.
+/// Posts that the user may not see count as nonexistent. Archived posts are frozen
+/// as they are, and deleted posts no longer show reactions at all. The post is only read, not
+/// locked, so that reacting never waits on replies and other writes that lock it.
+async fn ensure_open(
+    post_repo: &impl PostRepo,
+    tx: &mut impl Tx,
+    user_id: i32,
+    post_id: i32,
+) -> Result<(), ReactionError> {
+    let post = post_repo
+        .get_by_id(tx.exec(), post_id)
+        .await?
+        .ok_or(ReactionError::PostNotFound)?;
+
+    if !post_repo.is_visible_to(tx.exec(), post_id, user_id).await? {
+        return Err(ReactionError::PostNotFound);
+    }
+
+    if post.deleted_at.is_some() {
+        return Err(ReactionError::DeletedPost);
+    }
+    if post.archived_at.is_some() {
+        return Err(ReactionError::ArchivedPost);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::RepoError,
+            models::post::Post,
+            test_utils::{
+                dummy_data,
+                fake_db::FakeUow,
+                mock_repos::{MockPostRepo, MockReactionRepo},
+                tokio_test,
+            },
+        },
+        anyhow::{Result, anyhow},
+        chrono::Utc,
+        std::assert_matches,
+    };
+
+    fn mock_post_repo(post_id: i32, post: Option<Post>) -> MockPostRepo {
+        MockPostRepo {
+            get_by_id: Some(Box::new(move |passed_id| {
+                assert_eq!(post_id, passed_id);
+                Ok(post.clone())
+            })),
+            is_visible_to: Some(Box::new(move |passed_id, passed_viewer_id| {
+                assert_eq!(post_id, passed_id);
+                assert_eq!(3, passed_viewer_id);
+                Ok(true)
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_allows_reacting_to_existing_open_posts() -> Result<()> {
+        tokio_test(async {
+            let mut deleted = dummy_data::post::number1()?;
+            deleted.deleted_at = Some(Utc::now());
+            let mut archived = dummy_data::post::number1()?;
+            archived.archived_at = Some(Utc::now());
+
+            for (post, expected) in [
+                (None, ReactionError::PostNotFound),
+                (Some(deleted), ReactionError::DeletedPost),
+                (Some(archived), ReactionError::ArchivedPost),
+            ] {
+                // The reaction repo should not be called
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result = ReactionDomainSvc::new(
+                    fake_uow,
+                    MockReactionRepo::default(),
+                    mock_post_repo(24, post),
+                )
+                .react(3, 24, ReactionKind::Love)
+                .await;
+
+                assert_matches!(result, Err(e) if e == expected);
+                assert!(!probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn treats_posts_hidden_from_the_user_as_nonexistent() -> Result<()> {
+        tokio_test(async {
+            let mock_post_repo = MockPostRepo {
+                is_visible_to: Some(Box::new(|_, _| Ok(false))),
+                ..mock_post_repo(24, Some(dummy_data::post::number1()?))
+            };
+
+            // The reaction repo should not be called
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let svc = ReactionDomainSvc::new(fake_uow, MockReactionRepo::default(), mock_post_repo);
+
+            assert_matches!(
+                svc.react(3, 24, ReactionKind::Love).await,
+                Err(ReactionError::PostNotFound)
+            );
+            assert_matches!(
+                svc.unreact(3, 24, ReactionKind::Love).await,
+                Err(ReactionError::PostNotFound)
+            );
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn sets_the_reaction_and_commits() -> Result<()> {
+        tokio_test(async {
+            let post = dummy_data::post::number1()?;
+            let mock_repo = MockReactionRepo {
+                upsert: Some(Box::new(|passed_user_id, passed_post_id, passed_kind| {
+                    assert_eq!(3, passed_user_id);
+                    assert_eq!(24, passed_post_id);
+                    assert_eq!(ReactionKind::Laugh, passed_kind);
+                    Ok(())
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result =
+                ReactionDomainSvc::new(fake_uow, mock_repo, mock_post_repo(24, Some(post)))
+                    .react(3, 24, ReactionKind::Laugh)
+                    .await;
+
+            assert_matches!(result, Ok(()));
+            assert!(probe.commit_called());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn removes_only_an_existing_reaction() -> Result<()> {
+        tokio_test(async {
+            for (existed, expected) in [(true, Ok(())), (false, Err(ReactionError::NoReaction))] {
+                let post = dummy_data::post::number1()?;
+                let mock_repo = MockReactionRepo {
+                    delete: Some(Box::new(move |passed_user_id, passed_post_id, passed_kind| {
+                        assert_eq!(3, passed_user_id);
+                        assert_eq!(24, passed_post_id);
+                        assert_eq!(ReactionKind::Sad, passed_kind);
+                        Ok(existed)
+                    })),
+                    ..Default::default()
+                };
+
+                let (fake_uow, probe) = FakeUow::with_probe()?;
+                let result =
+                    ReactionDomainSvc::new(fake_uow, mock_repo, mock_post_repo(24, Some(post)))
+                        .unreact(3, 24, ReactionKind::Sad)
+                        .await;
+
+                assert_eq!(expected, result);
+                assert_eq!(existed, probe.commit_called());
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn translates_repo_errors() -> Result<()> {
+        tokio_test(async {
+            let post = dummy_data::post::number1()?;
+            let mock_repo = MockReactionRepo {
+                upsert: Some(Box::new(|_, _, _| {
+                    Err(RepoError::Technical(anyhow!("something went wrong!")))
+                })),
+                ..Default::default()
+            };
+
+            let (fake_uow, probe) = FakeUow::with_probe()?;
+            let result =
+                ReactionDomainSvc::new(fake_uow, mock_repo, mock_post_repo(24, Some(post)))
+                    .react(3, 24, ReactionKind::Wow)
+                    .await;
+
+            assert_matches!(
+                result,
+                Err(e) if e == ReactionError::Internal(anyhow!("something went wrong!"))
+            );
+            assert!(!probe.commit_called());
+
+            Ok(())
+        })
+    }
+}
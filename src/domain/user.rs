@@ -33,6 +33,12 @@ pub trait UserRepo: Send + Sync {
         email: &str,
     ) -> Result<Option<User>, RepoError>;
 
+    async fn get_by_username(
+        &self,
+        exec: impl PgExecutor<'_>,
+        username: &str,
+    ) -> Result<Option<User>, RepoError>;
+
     /// Fetches a user by username, blocking concurrent writes to the same user until the
     /// surrounding transaction completes.
     async fn get_by_username_exclusive(
@@ -0,0 +1,57 @@
+use {
+    crate::domain::{RepoError, outbound::DisallowedUrl},
+    anyhow::anyhow,
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("No webhook found")]
+    NotFound,
+
+    #[error("Moderator access required to receive events involving all users")]
+    NotModerator,
+
+    #[error("Webhooks cannot be sent to this URL: {0}")]
+    DisallowedUrl(#[from] DisallowedUrl),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<RepoError> for WebhookError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::UniqueViolation(v) => {
+                Self::Internal(anyhow!("Unexpected unique violation: {v}"))
+            }
+            RepoError::CheckViolation(v) if v == "text_non_empty" => {
+                Self::Internal(anyhow!("Empty field made it past request validation: {v}"))
+            }
+            RepoError::CheckViolation(v) if v == "webhook_events_non_empty" => {
+                Self::Internal(anyhow!("Empty event list made it past request validation: {v}"))
+            }
+            RepoError::CheckViolation(v) => {
+                Self::Internal(anyhow!("Unexpected check violation: {v}"))
+            }
+            RepoError::Technical(err) => Self::Internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for WebhookError {
+    /// Compares the string representation of `e` for `Internal(e)`. Otherwise, just checks that the
+    /// variant is the same.
+    fn eq(&self, other: &Self) -> bool {
+        use std::mem::discriminant;
+
+        match self {
+            Self::Internal(self_e) => {
+                matches!(other,
+                    Self::Internal(other_e) if self_e.to_string() == other_e.to_string())
+            }
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
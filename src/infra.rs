@@ -1,11 +1,34 @@
 use crate::{domain::RepoError, read_models::ReadError};
 
+pub mod attachment_repo;
 pub mod auth_provider;
+pub mod bookmark_read;
+pub mod bookmark_repo;
+pub mod draft_read;
+pub mod draft_repo;
+pub mod federation_client;
+pub mod federation_repo;
 pub mod friendship_repo;
+pub mod fs_blob_store;
+pub mod job_repo;
+pub mod mention_read;
+pub mod moderation_read;
+pub mod moderation_repo;
+pub mod notification_read;
+pub mod notification_repo;
+pub mod outbound_resolver;
+pub mod pg_feed;
 pub mod post_repo;
 pub mod post_with_author_read;
+pub mod reaction_repo;
+pub mod s3_blob_store;
+pub mod search_read;
 pub mod social_read;
+pub mod tag_read;
 pub mod user_repo;
+pub mod webhook_client;
+pub mod webhook_read;
+pub mod webhook_repo;
 
 impl From<sqlx::Error> for ReadError {
     fn from(e: sqlx::Error) -> Self {
@@ -0,0 +1,278 @@
+use {
+    crate::{
+        domain::{RepoError, attachment::AttachmentRepo},
+        models::attachment::{Attachment, NewAttachment},
+    },
+    chrono::{DateTime, Utc},
+    sqlx::PgExecutor,
+};
+
+pub struct PgAttachmentRepo;
+
+#[async_trait::async_trait]
+impl AttachmentRepo for PgAttachmentRepo {
+    async fn insert_pending(
+        &self,
+        exec: impl PgExecutor<'_>,
+        attachment: &NewAttachment,
+    ) -> Result<Attachment, RepoError> {
+        sqlx::query_as!(
+            Attachment,
+            "
+            INSERT INTO attachment (
+                post_id, content_type, width, height, byte_size, storage_key, thumbnail_key
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING
+                id, post_id, content_type, width, height, byte_size, storage_key,
+                thumbnail_key, created_at
+            ",
+            attachment.post_id,
+            attachment.content_type,
+            attachment.width,
+            attachment.height,
+            attachment.byte_size,
+            attachment.storage_key,
+            attachment.thumbnail_key,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn mark_stored(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        sqlx::query!("UPDATE attachment SET stored_at = CURRENT_TIMESTAMP WHERE id = $1", id,)
+            .execute(exec)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        sqlx::query!("DELETE FROM attachment WHERE id = $1", id)
+            .execute(exec)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_for_post(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM attachment
+            WHERE post_id = $1 AND (stored_at IS NOT NULL OR created_at >= $2)
+            "#,
+            post_id,
+            abandoned_before,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn get_visible(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        viewer_id: i32,
+    ) -> Result<Option<Attachment>, RepoError> {
+        sqlx::query_as!(
+            Attachment,
+            "
+            SELECT
+                a.id, a.post_id, a.content_type, a.width, a.height, a.byte_size, a.storage_key,
+                a.thumbnail_key, a.created_at
+            FROM attachment a
+            JOIN post p ON p.id = a.post_id
+            WHERE a.id = $1
+                AND a.stored_at IS NOT NULL
+                AND p.deleted_at IS NULL
+                AND p.publish_at IS NULL
+                AND post_visible_to(p.id, $2)
+            ",
+            id,
+            viewer_id,
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::post::PostRepo as _,
+            infra::post_repo::PgPostRepo,
+            models::post::{BodyFormat, PostVisibility},
+            test_utils::seed_data::{seed_friends, seed_users_and_root_post},
+        },
+        anyhow::{Context as _, Result},
+        chrono::TimeDelta,
+        sqlx::PgPool,
+        std::assert_matches,
+    };
+
+    fn new_attachment(post_id: i32, key: &str) -> NewAttachment {
+        NewAttachment {
+            post_id,
+            content_type: String::from("image/png"),
+            width: 640,
+            height: 480,
+            byte_size: 12_345,
+            storage_key: format!("attachments/{key}.png"),
+            thumbnail_key: format!("attachments/{key}_thumb.png"),
+        }
+    }
+
+    #[sqlx::test]
+    async fn inserts_counts_and_fetches_attachments(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgAttachmentRepo;
+
+        let an_hour_ago = Utc::now()
+            .checked_sub_signed(TimeDelta::hours(1))
+            .context("out of range")?;
+        assert_eq!(0, repo.count_for_post(&pool, 1, an_hour_ago).await?);
+
+        let first = repo.insert_pending(&pool, &new_attachment(1, "a")).await?;
+        let second = repo.insert_pending(&pool, &new_attachment(1, "b")).await?;
+        // Pending attachments hold their place but stay hidden
+        assert_eq!(2, repo.count_for_post(&pool, 1, an_hour_ago).await?);
+        assert_eq!(None, repo.get_visible(&pool, second.id, 1).await?);
+        repo.mark_stored(&pool, second.id).await?;
+
+        assert_eq!(
+            (1, "image/png", 640, 480, 12_345, "attachments/a.png", "attachments/a_thumb.png"),
+            (
+                first.post_id,
+                first.content_type.as_str(),
+                first.width,
+                first.height,
+                first.byte_size,
+                first.storage_key.as_str(),
+                first.thumbnail_key.as_str(),
+            )
+        );
+        assert_eq!(Some(second.clone()), repo.get_visible(&pool, second.id, 1).await?);
+        assert_eq!(
+            None,
+            repo.get_visible(&pool, second.id.saturating_add(1), 1)
+                .await?
+        );
+
+        repo.delete(&pool, first.id).await?;
+        assert_eq!(1, repo.count_for_post(&pool, 1, an_hour_ago).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn stops_counting_abandoned_pending_attachments(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgAttachmentRepo;
+
+        let stored = repo.insert_pending(&pool, &new_attachment(1, "a")).await?;
+        repo.mark_stored(&pool, stored.id).await?;
+        repo.insert_pending(&pool, &new_attachment(1, "b")).await?;
+
+        // Treating everything created so far as abandoned leaves only the stored attachment
+        let later = Utc::now()
+            .checked_add_signed(TimeDelta::minutes(1))
+            .context("out of range")?;
+        assert_eq!(1, repo.count_for_post(&pool, 1, later).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn hides_attachments_of_deleted_posts(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgAttachmentRepo;
+
+        let reply_id = PgPostRepo
+            .insert_new(&pool, 2, 1, "Look at this", BodyFormat::Plain, None)
+            .await?;
+        let attachment = repo
+            .insert_pending(&pool, &new_attachment(reply_id, "a"))
+            .await?;
+        repo.mark_stored(&pool, attachment.id).await?;
+
+        PgPostRepo.soft_delete(&pool, reply_id).await?;
+        assert_eq!(None, repo.get_visible(&pool, attachment.id, 2).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn hides_attachments_of_posts_the_viewer_may_not_see(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        seed_friends(&pool).await?;
+        let repo = PgAttachmentRepo;
+
+        let reply_id = PgPostRepo
+            .insert_new(&pool, 2, 1, "Friends only", BodyFormat::Plain, None)
+            .await?;
+        PgPostRepo
+            .set_visibility(&pool, reply_id, PostVisibility::Friends)
+            .await?;
+        let attachment = repo
+            .insert_pending(&pool, &new_attachment(reply_id, "a"))
+            .await?;
+        repo.mark_stored(&pool, attachment.id).await?;
+
+        // 3 is a friend of 2, but 1 is not
+        assert_eq!(Some(attachment.clone()), repo.get_visible(&pool, attachment.id, 2).await?);
+        assert_eq!(Some(attachment.clone()), repo.get_visible(&pool, attachment.id, 3).await?);
+        assert_eq!(None, repo.get_visible(&pool, attachment.id, 1).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn hides_attachments_of_scheduled_posts(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgAttachmentRepo;
+
+        let later = Utc::now()
+            .checked_add_signed(TimeDelta::hours(1))
+            .context("out of range")?;
+        let scheduled_id = PgPostRepo
+            .insert_scheduled(&pool, 2, 1, "Not yet", BodyFormat::Plain, later)
+            .await?;
+        let attachment = repo
+            .insert_pending(&pool, &new_attachment(scheduled_id, "a"))
+            .await?;
+        repo.mark_stored(&pool, attachment.id).await?;
+
+        // Not even to the author until the post is published
+        assert_eq!(None, repo.get_visible(&pool, attachment.id, 2).await?);
+        assert_eq!(None, repo.get_visible(&pool, attachment.id, 3).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn rejects_reused_storage_keys(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgAttachmentRepo;
+
+        repo.insert_pending(&pool, &new_attachment(1, "a")).await?;
+        let result = repo.insert_pending(&pool, &new_attachment(1, "a")).await;
+
+        assert_matches!(
+            result,
+            Err(RepoError::UniqueViolation(v)) if v == "attachment_storage_key_unique"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use {
+    crate::{
+        models::{
+            attachment::AttachmentSummary,
+            bookmark::BookmarkPage,
+            mention::MentionSpan,
+            post::{
+                BodyFormat, PostCursor, PostVisibility, PostWithAuthor, QuotedPost, ReplyPolicy,
+            },
+            reaction::ReactionCount,
+        },
+        read_models::{BookmarkRead, ReadError},
+    },
+    sqlx::{PgPool, types::Json},
+};
+
+pub struct PgBookmarkRead {
+    pool: PgPool,
+}
+
+impl PgBookmarkRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl BookmarkRead for PgBookmarkRead {
+    async fn saved_by(
+        &self,
+        user_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<BookmarkPage, ReadError> {
+        // One extra post is fetched to find out whether there is another page
+        let mut rows = sqlx::query!(
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility",
+                NOT post_visible_to(p.id, $1) AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $1) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $1) AS "quoted: Json<QuotedPost>",
+                b.created_at AS saved_at
+            FROM bookmark b
+            JOIN post p ON p.id = b.post_id
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE b.user_id = $1
+                AND ($2::TIMESTAMPTZ IS NULL OR (b.created_at, b.post_id) < ($2, $3))
+            ORDER BY b.created_at DESC, b.post_id DESC
+            LIMIT $4
+            "#,
+            user_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit.saturating_add(1),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let has_more = i64::try_from(rows.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            rows.pop();
+        }
+
+        let next_cursor = has_more
+            .then(|| {
+                rows.last()
+                    .map(|last| PostCursor { created_at: last.saved_at, id: last.id })
+            })
+            .flatten();
+
+        let posts = rows
+            .into_iter()
+            .map(|row| PostWithAuthor {
+                id: row.id,
+                author_id: row.author_id,
+                parent_id: row.parent_id,
+                body: row.body,
+                body_format: row.body_format,
+                created_at: row.created_at,
+                edited_at: row.edited_at,
+                archived_at: row.archived_at,
+                deleted_at: row.deleted_at,
+                descendant_count: row.descendant_count,
+                reply_policy: row.reply_policy,
+                reply_policy_cascades: row.reply_policy_cascades,
+                visibility: row.visibility,
+                hidden: row.hidden,
+                author_username: row.author_username,
+                reactions: row.reactions,
+                mentions: row.mentions,
+                attachments: row.attachments,
+                quoted: row.quoted,
+            })
+            .map(PostWithAuthor::placeholder_if_hidden)
+            .collect();
+
+        Ok(BookmarkPage { posts, next_cursor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::{bookmark::BookmarkRepo as _, post::PostRepo as _},
+            infra::{
+                bookmark_repo::PgBookmarkRepo, post_repo::PgPostRepo,
+                post_with_author_read::PgPostWithAuthorRead,
+            },
+            read_models::PostWithAuthorRead as _,
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::{Context as _, Result},
+    };
+
+    #[sqlx::test]
+    async fn pages_through_bookmarks_in_save_order_including_tombstones(
+        pool: PgPool,
+    ) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let read = PgBookmarkRead::new(pool.clone());
+        let post_with_author_read = PgPostWithAuthorRead::new(pool.clone());
+        let post_repo = PgPostRepo;
+        let bookmark_repo = PgBookmarkRepo;
+
+        post_repo
+            .insert_new(&pool, 2, 1, "Saved second", BodyFormat::Plain, None)
+            .await?; // ID 2
+        post_repo
+            .insert_new(&pool, 3, 1, "Saved first", BodyFormat::Plain, None)
+            .await?; // ID 3
+        post_repo
+            .insert_new(&pool, 4, 1, "Saved last", BodyFormat::Plain, None)
+            .await?; // ID 4
+        post_repo
+            .insert_new(&pool, 2, 4, "Not saved", BodyFormat::Plain, None)
+            .await?; // ID 5
+
+        for post_id in [3, 2, 4] {
+            bookmark_repo.insert(&pool, 1, post_id).await?;
+        }
+        // Someone else's bookmark should not come up
+        bookmark_repo.insert(&pool, 2, 5).await?;
+        post_repo.soft_delete(&pool, 4).await?;
+
+        let first_page = read.saved_by(1, None, 2).await?;
+        let second_page = read.saved_by(1, first_page.next_cursor, 2).await?;
+
+        let ids = |page: &BookmarkPage| page.posts.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(vec![4, 2], ids(&first_page));
+        assert_eq!(vec![3], ids(&second_page));
+        assert_eq!(None, second_page.next_cursor);
+
+        // The deleted post is still there, without its content
+        let tombstone = first_page.posts.first().context("missing post 4")?;
+        assert_eq!(&post_with_author_read.by_post_id(4, Some(1)).await?, tombstone);
+        assert_eq!(None, tombstone.body);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use {
+    crate::domain::{RepoError, bookmark::BookmarkRepo},
+    sqlx::PgExecutor,
+};
+
+pub struct PgBookmarkRepo;
+
+#[async_trait::async_trait]
+impl BookmarkRepo for PgBookmarkRepo {
+    async fn insert(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO bookmark (user_id, post_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id,
+            post_id,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+    ) -> Result<bool, RepoError> {
+        sqlx::query!("DELETE FROM bookmark WHERE user_id = $1 AND post_id = $2", user_id, post_id,)
+            .execute(exec)
+            .await
+            .map_err(Into::into)
+            .map(|result| result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::seed_data::seed_users_and_root_post,
+        anyhow::Result,
+        chrono::{DateTime, Utc},
+        sqlx::PgPool,
+    };
+
+    /// Retrieves the time the user saved the post, if they did.
+    async fn saved_at(pool: &PgPool, user_id: i32, post_id: i32) -> Result<Option<DateTime<Utc>>> {
+        Ok(sqlx::query_scalar!(
+            "SELECT created_at FROM bookmark WHERE user_id = $1 AND post_id = $2",
+            user_id,
+            post_id,
+        )
+        .fetch_optional(pool)
+        .await?)
+    }
+
+    #[sqlx::test]
+    async fn saves_once_and_deletes_only_existing_bookmarks(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgBookmarkRepo;
+
+        repo.insert(&pool, 2, 1).await?;
+        let first_saved_at = saved_at(&pool, 2, 1).await?;
+        assert!(first_saved_at.is_some());
+
+        // Saving again keeps the original position
+        repo.insert(&pool, 2, 1).await?;
+        assert_eq!(first_saved_at, saved_at(&pool, 2, 1).await?);
+
+        assert!(!repo.delete(&pool, 3, 1).await?);
+        assert!(repo.delete(&pool, 2, 1).await?);
+        assert_eq!(None, saved_at(&pool, 2, 1).await?);
+        assert!(!repo.delete(&pool, 2, 1).await?);
+
+        Ok(())
+    }
+}
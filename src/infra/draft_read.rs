@@ -0,0 +1,180 @@
+use {
+    crate::{
+        infra::social_read::PARENT_EXCERPT_CHARS,
+        models::{
+            draft::{Draft, DraftWithParent},
+            post::{BodyFormat, ParentSummary},
+        },
+        read_models::{DraftRead, ReadError},
+    },
+    chrono::{DateTime, Utc},
+    sqlx::PgPool,
+};
+
+pub struct PgDraftRead {
+    pool: PgPool,
+}
+
+impl PgDraftRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+/// A draft joined with the post it replies to.
+struct DraftRow {
+    author_id: i32,
+    parent_id: i32,
+    body: String,
+    body_format: BodyFormat,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    publish_error: Option<String>,
+    parent_author_username: Option<String>,
+    parent_excerpt: Option<String>,
+}
+
+impl From<DraftRow> for DraftWithParent {
+    fn from(row: DraftRow) -> Self {
+        Self {
+            parent: ParentSummary {
+                id: row.parent_id,
+                author_username: row.parent_author_username,
+                excerpt: row.parent_excerpt,
+            },
+            draft: Draft {
+                author_id: row.author_id,
+                parent_id: row.parent_id,
+                body: row.body,
+                body_format: row.body_format,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                publish_error: row.publish_error,
+            },
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DraftRead for PgDraftRead {
+    async fn drafts_by(&self, author_id: i32) -> Result<Vec<DraftWithParent>, ReadError> {
+        let rows = sqlx::query_as!(
+            DraftRow,
+            r#"
+            SELECT
+                d.author_id, d.parent_id, d.body, d.created_at, d.updated_at, d.publish_error,
+                d.body_format AS "body_format: BodyFormat",
+                parent_author.username AS "parent_author_username?",
+                LEFT(parent.body, $2) AS parent_excerpt
+            FROM draft d
+            JOIN post parent ON parent.id = d.parent_id
+            LEFT JOIN users parent_author ON parent_author.id = parent.author_id
+            WHERE d.author_id = $1
+            ORDER BY d.updated_at DESC, d.parent_id DESC
+            "#,
+            author_id,
+            PARENT_EXCERPT_CHARS,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn by_parent(
+        &self,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<DraftWithParent, ReadError> {
+        sqlx::query_as!(
+            DraftRow,
+            r#"
+            SELECT
+                d.author_id, d.parent_id, d.body, d.created_at, d.updated_at, d.publish_error,
+                d.body_format AS "body_format: BodyFormat",
+                parent_author.username AS "parent_author_username?",
+                LEFT(parent.body, $3) AS parent_excerpt
+            FROM draft d
+            JOIN post parent ON parent.id = d.parent_id
+            LEFT JOIN users parent_author ON parent_author.id = parent.author_id
+            WHERE d.author_id = $1 AND d.parent_id = $2
+            "#,
+            author_id,
+            parent_id,
+            PARENT_EXCERPT_CHARS,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map(Into::into)
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::{draft::DraftRepo as _, post::PostRepo as _},
+            infra::{draft_repo::PgDraftRepo, post_repo::PgPostRepo},
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::Result,
+        std::assert_matches,
+    };
+
+    #[sqlx::test]
+    async fn lists_drafts_with_their_parents_most_recently_saved_first(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let read = PgDraftRead::new(pool.clone());
+        let draft_repo = PgDraftRepo;
+
+        let reply_id = PgPostRepo
+            .insert_new(&pool, 3, 1, &"b".repeat(200), BodyFormat::Plain, None)
+            .await?;
+
+        let older = draft_repo
+            .upsert(&pool, 2, 1, "First thoughts", BodyFormat::Plain, None)
+            .await?;
+        let newer = draft_repo
+            .upsert(&pool, 2, reply_id, "Second *thoughts*", BodyFormat::Markdown, None)
+            .await?;
+        // Someone else's draft
+        draft_repo
+            .upsert(&pool, 4, 1, "Not yours", BodyFormat::Plain, None)
+            .await?;
+
+        let drafts = read.drafts_by(2).await?;
+        assert_eq!(
+            vec![
+                (newer, Some(String::from("fe_to_the_lip_to_the_e")), "b".repeat(140)),
+                (older, Some(String::from("drake_conan")), String::from("root post")),
+            ],
+            drafts
+                .into_iter()
+                .map(|d| {
+                    (d.draft, d.parent.author_username, d.parent.excerpt.unwrap_or_default())
+                })
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(Vec::<DraftWithParent>::new(), read.drafts_by(1).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn retrieves_a_single_draft_by_its_parent(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let read = PgDraftRead::new(pool.clone());
+
+        let draft = PgDraftRepo
+            .upsert(&pool, 2, 1, "Almost done", BodyFormat::Plain, None)
+            .await?;
+
+        let found = read.by_parent(2, 1).await?;
+        assert_eq!((draft, 1), (found.draft, found.parent.id));
+
+        assert_matches!(read.by_parent(3, 1).await, Err(ReadError::NotFound));
+
+        Ok(())
+    }
+}
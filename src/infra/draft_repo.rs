@@ -0,0 +1,150 @@
+use {
+    crate::{
+        domain::{RepoError, draft::DraftRepo},
+        models::{draft::Draft, post::BodyFormat},
+    },
+    sqlx::PgExecutor,
+};
+
+pub struct PgDraftRepo;
+
+#[async_trait::async_trait]
+impl DraftRepo for PgDraftRepo {
+    async fn upsert(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        publish_error: Option<&str>,
+    ) -> Result<Draft, RepoError> {
+        sqlx::query_as!(
+            Draft,
+            r#"
+            INSERT INTO draft (author_id, parent_id, body, body_format, publish_error)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (author_id, parent_id) DO UPDATE
+            SET body = EXCLUDED.body,
+                body_format = EXCLUDED.body_format,
+                publish_error = EXCLUDED.publish_error,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING
+                author_id,
+                parent_id,
+                body,
+                body_format AS "body_format: BodyFormat",
+                created_at,
+                updated_at,
+                publish_error
+            "#,
+            author_id,
+            parent_id,
+            body,
+            body_format as BodyFormat,
+            publish_error,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn get_exclusive(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<Option<Draft>, RepoError> {
+        sqlx::query_as!(
+            Draft,
+            r#"
+            SELECT
+                author_id,
+                parent_id,
+                body,
+                body_format AS "body_format: BodyFormat",
+                created_at,
+                updated_at,
+                publish_error
+            FROM draft
+            WHERE author_id = $1 AND parent_id = $2
+            FOR UPDATE
+            "#,
+            author_id,
+            parent_id,
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<bool, RepoError> {
+        sqlx::query!(
+            "DELETE FROM draft WHERE author_id = $1 AND parent_id = $2",
+            author_id,
+            parent_id,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::test_utils::seed_data::seed_users_and_root_post, anyhow::Result,
+        sqlx::PgPool,
+    };
+
+    #[sqlx::test]
+    async fn saves_replaces_and_deletes_drafts(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgDraftRepo;
+
+        assert_eq!(None, repo.get_exclusive(&pool, 2, 1).await?);
+
+        let first = repo
+            .upsert(&pool, 2, 1, "Dear diary", BodyFormat::Plain, Some("Too late"))
+            .await?;
+        assert_eq!(
+            (2, 1, "Dear diary", BodyFormat::Plain),
+            (first.author_id, first.parent_id, first.body.as_str(), first.body_format)
+        );
+        assert_eq!(first.created_at, first.updated_at);
+        assert_eq!(Some("Too late"), first.publish_error.as_deref());
+
+        // Saving again replaces the body and error but keeps the creation time
+        let second = repo
+            .upsert(&pool, 2, 1, "Dear *diary*", BodyFormat::Markdown, None)
+            .await?;
+        assert_eq!(
+            ("Dear *diary*", BodyFormat::Markdown, None, first.created_at),
+            (
+                second.body.as_str(),
+                second.body_format,
+                second.publish_error.as_deref(),
+                second.created_at
+            )
+        );
+        assert!(second.updated_at >= first.updated_at);
+        assert_eq!(Some(second), repo.get_exclusive(&pool, 2, 1).await?);
+
+        // Other authors have their own drafts
+        repo.upsert(&pool, 3, 1, "", BodyFormat::Plain, None)
+            .await?;
+
+        assert!(repo.delete(&pool, 2, 1).await?);
+        assert!(!repo.delete(&pool, 2, 1).await?);
+        assert_eq!(None, repo.get_exclusive(&pool, 2, 1).await?);
+        assert!(repo.get_exclusive(&pool, 3, 1).await?.is_some());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use {
+    crate::{
+        domain::{federation::FederationClient, outbound::OutboundPolicy},
+        infra::outbound_resolver::PolicyResolver,
+        models::federation::RemoteActor,
+    },
+    anyhow::Result,
+    reqwest::header::{ACCEPT, CONTENT_TYPE},
+    std::time::Duration,
+};
+
+/// The media type of ActivityPub documents.
+const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// How long to wait for a remote server to respond.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+pub struct ReqwestFederationClient {
+    client: reqwest::Client,
+    policy: OutboundPolicy,
+}
+
+impl ReqwestFederationClient {
+    /// Builds an HTTP client for talking to the ActivityPub servers on hosts that the policy
+    /// allows. Redirects are not followed, so actors must be referred to by their canonical IDs,
+    /// and proxies are not used, so that the resolved addresses are the ones connected to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the TLS backend cannot be initialized.
+    pub fn new(policy: OutboundPolicy) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(PolicyResolver::new(policy))
+            .user_agent(concat!("Spur/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self { client, policy })
+    }
+}
+
+#[async_trait::async_trait]
+impl FederationClient for ReqwestFederationClient {
+    async fn fetch_actor(&self, url: &str) -> Result<RemoteActor> {
+        // Addresses in the URL itself are not resolved, so they are checked here instead
+        self.policy.check_url(url)?;
+
+        let body = self
+            .client
+            .get(url)
+            .header(ACCEPT, ACTIVITY_JSON)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: String,
+    ) -> Result<u16> {
+        self.policy.check_url(url)?;
+
+        let req = headers.into_iter().fold(
+            self.client
+                .post(url)
+                .header(CONTENT_TYPE, ACTIVITY_JSON)
+                .body(body),
+            |req_builder, (name, value)| req_builder.header(name, value),
+        );
+
+        Ok(req.send().await?.status().as_u16())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::federation::RemotePublicKey,
+            test_utils::{tokio_test, ws_client::serve},
+        },
+        anyhow::Context as _,
+        axum::{
+            Json, Router,
+            http::{HeaderMap, StatusCode},
+            routing::{any, get, post},
+        },
+        serde_json::json,
+        tokio::sync::mpsc,
+    };
+
+    #[test]
+    fn fetches_actor_documents() -> Result<()> {
+        tokio_test(async {
+            let app = Router::new().route(
+                "/users/alice",
+                get(async |headers: HeaderMap| {
+                    if headers.get("accept").and_then(|v| v.to_str().ok()) != Some(ACTIVITY_JSON) {
+                        return Err(StatusCode::NOT_ACCEPTABLE);
+                    }
+                    Ok(Json(json!({
+                        "@context": "https://www.w3.org/ns/activitystreams",
+                        "id": "https://remote.example/users/alice",
+                        "type": "Person",
+                        "inbox": "https://remote.example/users/alice/inbox",
+                        "publicKey": {
+                            "id": "https://remote.example/users/alice#main-key",
+                            "owner": "https://remote.example/users/alice",
+                            "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+                        }
+                    })))
+                }),
+            );
+            let addr = serve(app).await?;
+            let client = ReqwestFederationClient::new(OutboundPolicy::AllowLoopback)?;
+
+            assert_eq!(
+                RemoteActor {
+                    id: String::from("https://remote.example/users/alice"),
+                    inbox: String::from("https://remote.example/users/alice/inbox"),
+                    public_key: RemotePublicKey {
+                        id: String::from("https://remote.example/users/alice#main-key"),
+                        owner: String::from("https://remote.example/users/alice"),
+                        public_key_pem: String::from("-----BEGIN PUBLIC KEY-----"),
+                    },
+                },
+                client
+                    .fetch_actor(&format!("http://{addr}/users/alice"))
+                    .await?
+            );
+            assert!(
+                client
+                    .fetch_actor(&format!("http://{addr}/users/bob"))
+                    .await
+                    .is_err()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn posts_activities_with_the_headers_and_reports_the_status() -> Result<()> {
+        tokio_test(async {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+
+            let app = Router::new().route(
+                "/inbox",
+                post(async move |headers: HeaderMap, body: String| {
+                    if sender.send((headers, body)).is_err() {
+                        log::error!("Test receiver dropped");
+                    }
+                    StatusCode::ACCEPTED
+                }),
+            );
+            let addr = serve(app).await?;
+
+            let status = ReqwestFederationClient::new(OutboundPolicy::AllowLoopback)?
+                .post(
+                    &format!("http://{addr}/inbox"),
+                    vec![("signature", String::from(r#"keyId="k""#))],
+                    String::from(r#"{"type":"Accept"}"#),
+                )
+                .await?;
+            assert_eq!(202, status);
+
+            let (headers, body) = receiver.recv().await.context("no request received")?;
+            assert_eq!(
+                headers.get("content-type").and_then(|v| v.to_str().ok()),
+                Some(ACTIVITY_JSON)
+            );
+            assert_eq!(
+                headers.get("signature").and_then(|v| v.to_str().ok()),
+                Some(r#"keyId="k""#)
+            );
+            assert_eq!(body, r#"{"type":"Accept"}"#);
+
+            Ok(())
+        })
+    }
+    #[test]
+    fn refuses_to_contact_internal_hosts() -> Result<()> {
+        tokio_test(async {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+
+            let app = Router::new().route(
+                "/{*path}",
+                any(async move || {
+                    if sender.send(()).is_err() {
+                        log::error!("Test receiver dropped");
+                    }
+                    StatusCode::OK
+                }),
+            );
+            let addr = serve(app).await?;
+            let client = ReqwestFederationClient::new(OutboundPolicy::PublicOnly)?;
+
+            // By address, and by a name that resolves to it
+            for url in [
+                format!("http://{addr}/users/alice"),
+                format!("https://{addr}/users/alice"),
+                format!("https://localhost:{}/users/alice", addr.port()),
+            ] {
+                assert!(client.fetch_actor(&url).await.is_err(), "{url}");
+                assert!(client.post(&url, Vec::new(), String::new()).await.is_err(), "{url}");
+            }
+            assert!(receiver.try_recv().is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use {
+    crate::{
+        domain::{RepoError, federation::FederationRepo},
+        models::federation::{ActorKey, RemoteActor},
+    },
+    sqlx::PgExecutor,
+};
+
+pub struct PgFederationRepo;
+
+#[async_trait::async_trait]
+impl FederationRepo for PgFederationRepo {
+    async fn key_for(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+    ) -> Result<Option<ActorKey>, RepoError> {
+        sqlx::query_as!(
+            ActorKey,
+            "SELECT public_key_pem, private_key_pem FROM actor_key WHERE user_id = $1",
+            user_id,
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn insert_key(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        key: &ActorKey,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO actor_key (user_id, public_key_pem, private_key_pem)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            key.public_key_pem,
+            key.private_key_pem,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn add_follower(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        follower: &RemoteActor,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO remote_follower (user_id, actor_id, inbox_url)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, actor_id) DO UPDATE SET inbox_url = EXCLUDED.inbox_url
+            "#,
+            user_id,
+            follower.id,
+            follower.inbox,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            models::federation::RemotePublicKey,
+            test_utils::{dummy_data::actor_key, seed_data::seed_users},
+        },
+        anyhow::Result,
+        sqlx::PgPool,
+    };
+
+    fn remote_actor(inbox: &str) -> RemoteActor {
+        RemoteActor {
+            id: String::from("https://remote.example/users/alice"),
+            inbox: String::from(inbox),
+            public_key: RemotePublicKey {
+                id: String::from("https://remote.example/users/alice#main-key"),
+                owner: String::from("https://remote.example/users/alice"),
+                public_key_pem: actor_key::number2().public_key_pem,
+            },
+        }
+    }
+
+    /// Retrieves the actor IDs and inboxes of the user's remote followers in order of actor ID.
+    async fn followers(pool: &PgPool, user_id: i32) -> Result<Vec<(String, String)>> {
+        Ok(sqlx::query!(
+            "SELECT actor_id, inbox_url FROM remote_follower WHERE user_id = $1 ORDER BY actor_id",
+            user_id,
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.actor_id, row.inbox_url))
+        .collect())
+    }
+
+    #[sqlx::test]
+    async fn keeps_the_first_key_inserted_for_each_user(pool: PgPool) -> Result<()> {
+        seed_users(&pool).await?;
+        let repo = PgFederationRepo;
+
+        assert_eq!(None, repo.key_for(&pool, 1).await?);
+
+        repo.insert_key(&pool, 1, &actor_key::number1()).await?;
+        repo.insert_key(&pool, 1, &actor_key::number2()).await?;
+        repo.insert_key(&pool, 2, &actor_key::number2()).await?;
+
+        assert_eq!(Some(actor_key::number1()), repo.key_for(&pool, 1).await?);
+        assert_eq!(Some(actor_key::number2()), repo.key_for(&pool, 2).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn records_followers_once_with_their_latest_inbox(pool: PgPool) -> Result<()> {
+        seed_users(&pool).await?;
+        let repo = PgFederationRepo;
+
+        repo.add_follower(&pool, 1, &remote_actor("https://remote.example/inbox"))
+            .await?;
+        repo.add_follower(&pool, 1, &remote_actor("https://remote.example/users/alice/inbox"))
+            .await?;
+
+        assert_eq!(
+            vec![(
+                String::from("https://remote.example/users/alice"),
+                String::from("https://remote.example/users/alice/inbox")
+            )],
+            followers(&pool, 1).await?
+        );
+        assert!(followers(&pool, 2).await?.is_empty());
+
+        Ok(())
+    }
+}
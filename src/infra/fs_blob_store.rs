@@ -0,0 +1,114 @@
+use {
+    crate::domain::attachment::BlobStore,
+    anyhow::{Result, bail},
+    std::{
+        io::ErrorKind,
+        path::{Component, Path, PathBuf},
+    },
+    tokio::fs,
+};
+
+/// Stores blobs as files in a local directory, with each key used as a relative path.
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self { Self { root: root.into() } }
+
+    /// Resolves a key to a path inside the root directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the key is empty or could refer to something outside the root directory.
+    fn path_of(&self, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            bail!("invalid blob key {key:?}");
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(&self, key: &str, _content_type: &str, bytes: Vec<u8>) -> Result<()> {
+        let path = self.path_of(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Write to a temporary file first so that readers never see a partially written blob
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, bytes).await?;
+        fs::rename(&tmp_path, &path).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.path_of(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path_of(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::test_utils::tokio_test, std::env};
+
+    #[test]
+    fn puts_gets_and_deletes_blobs() -> Result<()> {
+        tokio_test(async {
+            let root = env::temp_dir()
+                .join(format!("spur-blobs-{}", hex::encode(rand::random::<[u8; 8]>())));
+            let store = FsBlobStore::new(&root);
+
+            assert_eq!(None, store.get("attachments/a.png").await?);
+
+            store
+                .put("attachments/a.png", "image/png", vec![1, 2, 3])
+                .await?;
+            assert_eq!(Some(vec![1, 2, 3]), store.get("attachments/a.png").await?);
+            assert!(root.join("attachments").join("a.png").is_file());
+
+            // Replaces the existing blob
+            store.put("attachments/a.png", "image/png", vec![4]).await?;
+            assert_eq!(Some(vec![4]), store.get("attachments/a.png").await?);
+
+            store.delete("attachments/a.png").await?;
+            assert_eq!(None, store.get("attachments/a.png").await?);
+            // Deleting again has no effect
+            store.delete("attachments/a.png").await?;
+
+            fs::remove_dir_all(&root).await?;
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_keys_outside_the_root() {
+        let store = FsBlobStore::new("/srv/blobs");
+
+        for key in ["", "../secret", "attachments/../../secret", "/etc/passwd", "./a.png"] {
+            assert!(store.path_of(key).is_err(), "{key:?} should be rejected");
+        }
+        assert!(store.path_of("attachments/a.png").is_ok());
+    }
+}
@@ -0,0 +1,360 @@
+use {
+    crate::{
+        domain::{
+            RepoError,
+            job::{JobRepo, NewJob},
+        },
+        models::job::{ClaimedJob, JobStatus},
+    },
+    chrono::{DateTime, TimeDelta, Utc},
+    sqlx::PgExecutor,
+};
+
+pub struct PgJobRepo;
+
+#[async_trait::async_trait]
+impl JobRepo for PgJobRepo {
+    async fn enqueue(
+        &self,
+        exec: impl PgExecutor<'_>,
+        job: &NewJob,
+    ) -> Result<Option<i32>, RepoError> {
+        sqlx::query_scalar!(
+            "
+            INSERT INTO job (kind, payload, run_at, dedupe_key)
+            VALUES ($1, $2, COALESCE($3, CURRENT_TIMESTAMP), $4)
+            ON CONFLICT (dedupe_key) WHERE status = 'pending' DO NOTHING
+            RETURNING id
+            ",
+            job.kind,
+            job.payload,
+            job.run_at,
+            job.dedupe_key,
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn claim_due(
+        &self,
+        exec: impl PgExecutor<'_>,
+        limit: i64,
+        lease: TimeDelta,
+    ) -> Result<Vec<ClaimedJob>, RepoError> {
+        sqlx::query_as!(
+            ClaimedJob,
+            "
+            UPDATE job
+            SET attempts = attempts + 1,
+                run_at = CURRENT_TIMESTAMP + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id FROM job
+                WHERE status = 'pending' AND run_at <= CURRENT_TIMESTAMP
+                ORDER BY run_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, kind, payload, attempts
+            ",
+            limit,
+            lease.as_seconds_f64(),
+        )
+        .fetch_all(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn complete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: i32,
+    ) -> Result<bool, RepoError> {
+        sqlx::query!(
+            "
+            UPDATE job
+            SET status = 'succeeded', last_error = NULL, finished_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND attempts = $2 AND status = 'pending'
+            ",
+            id,
+            attempt,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    async fn fail(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: i32,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, RepoError> {
+        let status = if retry_at.is_some() { JobStatus::Pending } else { JobStatus::Dead };
+
+        sqlx::query!(
+            "
+            UPDATE job
+            SET status = $3,
+                last_error = $4,
+                run_at = COALESCE($5, run_at),
+                finished_at = CASE WHEN $5::TIMESTAMPTZ IS NULL THEN CURRENT_TIMESTAMP END
+            WHERE id = $1 AND attempts = $2 AND status = 'pending'
+            ",
+            id,
+            attempt,
+            status as JobStatus,
+            error,
+            retry_at,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    async fn prune_finished(
+        &self,
+        exec: impl PgExecutor<'_>,
+        succeeded_before: DateTime<Utc>,
+        dead_before: DateTime<Utc>,
+    ) -> Result<u64, RepoError> {
+        sqlx::query!(
+            "
+            DELETE FROM job
+            WHERE (status = 'succeeded' AND finished_at < $1)
+                OR (status = 'dead' AND finished_at < $2)
+            ",
+            succeeded_before,
+            dead_before,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        anyhow::{Context as _, Result},
+        serde_json::json,
+        sqlx::PgPool,
+    };
+
+    /// Retrieves the status and last error of the job with the provided ID.
+    async fn job_state(pool: &PgPool, id: i32) -> Result<(JobStatus, Option<String>)> {
+        sqlx::query!(
+            r#"SELECT status AS "status: JobStatus", last_error FROM job WHERE id = $1"#,
+            id,
+        )
+        .fetch_one(pool)
+        .await
+        .map(|row| (row.status, row.last_error))
+        .context("failed to fetch job state")
+    }
+
+    fn new_job(kind: &'static str, run_at: Option<DateTime<Utc>>) -> NewJob {
+        NewJob { kind, payload: json!({ "kind": kind }), run_at, dedupe_key: None }
+    }
+
+    #[sqlx::test]
+    async fn only_enqueues_jobs_in_committed_transactions(pool: PgPool) -> Result<()> {
+        let repo = PgJobRepo;
+
+        let mut rolled_back_tx = pool.begin().await?;
+        repo.enqueue(&mut *rolled_back_tx, &new_job("rolled_back", None))
+            .await?;
+        rolled_back_tx.rollback().await?;
+
+        let mut committed_tx = pool.begin().await?;
+        let committed_id = repo
+            .enqueue(&mut *committed_tx, &new_job("committed", None))
+            .await?
+            .context("job not enqueued")?;
+        committed_tx.commit().await?;
+
+        let claimed = repo.claim_due(&pool, 10, TimeDelta::minutes(1)).await?;
+        let expected = vec![ClaimedJob {
+            id: committed_id,
+            kind: String::from("committed"),
+            payload: json!({ "kind": "committed" }),
+            attempts: 1,
+        }];
+        assert_eq!(expected, claimed);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn claims_due_jobs_only_once_per_lease(pool: PgPool) -> Result<()> {
+        let repo = PgJobRepo;
+
+        let now_id = repo
+            .enqueue(&pool, &new_job("now", None))
+            .await?
+            .context("job not enqueued")?;
+        let later = Utc::now()
+            .checked_add_signed(TimeDelta::hours(1))
+            .context("overflowed adding an hour to now")?;
+        repo.enqueue(&pool, &new_job("later", Some(later))).await?;
+
+        // Jobs scheduled for the future are not due yet
+        let claimed = repo.claim_due(&pool, 10, TimeDelta::minutes(1)).await?;
+        assert_eq!(claimed.iter().map(|job| job.id).collect::<Vec<_>>(), vec![now_id]);
+
+        // Still leased
+        assert!(
+            repo.claim_due(&pool, 10, TimeDelta::minutes(1))
+                .await?
+                .is_empty()
+        );
+
+        // Retrying now makes it due again, with the attempt counted
+        assert!(
+            repo.fail(&pool, now_id, 1, "oops", Some(Utc::now()))
+                .await?
+        );
+        assert_eq!(
+            (JobStatus::Pending, Some(String::from("oops"))),
+            job_state(&pool, now_id).await?
+        );
+
+        let reclaimed = repo.claim_due(&pool, 10, TimeDelta::minutes(1)).await?;
+        assert_eq!(reclaimed.first().map(|job| job.attempts), Some(2));
+
+        // Finished jobs are never claimed again
+        assert!(repo.complete(&pool, now_id, 2).await?);
+        assert_eq!((JobStatus::Succeeded, None), job_state(&pool, now_id).await?);
+        assert!(
+            repo.claim_due(&pool, 10, TimeDelta::zero())
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn dead_letters_jobs_without_a_retry_time(pool: PgPool) -> Result<()> {
+        let repo = PgJobRepo;
+
+        let id = repo
+            .enqueue(&pool, &new_job("doomed", None))
+            .await?
+            .context("job not enqueued")?;
+        repo.claim_due(&pool, 10, TimeDelta::zero()).await?;
+        assert!(repo.fail(&pool, id, 1, "no handler", None).await?);
+
+        assert_eq!(
+            (JobStatus::Dead, Some(String::from("no handler"))),
+            job_state(&pool, id).await?
+        );
+        assert!(
+            repo.claim_due(&pool, 10, TimeDelta::zero())
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn ignores_results_from_workers_that_lost_their_lease(pool: PgPool) -> Result<()> {
+        let repo = PgJobRepo;
+
+        let id = repo
+            .enqueue(&pool, &new_job("slow", None))
+            .await?
+            .context("job not enqueued")?;
+
+        // The first worker's lease expires, and another worker claims the job
+        repo.claim_due(&pool, 10, TimeDelta::zero()).await?;
+        let reclaimed = repo.claim_due(&pool, 10, TimeDelta::minutes(1)).await?;
+        assert_eq!(reclaimed.first().map(|job| job.attempts), Some(2));
+
+        // The first worker can no longer record its results
+        assert!(!repo.complete(&pool, id, 1).await?);
+        assert!(!repo.fail(&pool, id, 1, "too slow", None).await?);
+        assert_eq!((JobStatus::Pending, None), job_state(&pool, id).await?);
+
+        // Nor can any worker once the job has finished
+        assert!(repo.complete(&pool, id, 2).await?);
+        assert!(!repo.complete(&pool, id, 2).await?);
+        assert!(!repo.fail(&pool, id, 2, "too late", None).await?);
+        assert_eq!((JobStatus::Succeeded, None), job_state(&pool, id).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn skips_jobs_with_duplicate_pending_dedupe_keys(pool: PgPool) -> Result<()> {
+        let repo = PgJobRepo;
+        let job = new_job("once", None).dedupe_key(String::from("once:today"));
+
+        let id = repo
+            .enqueue(&pool, &job)
+            .await?
+            .context("job not enqueued")?;
+        assert_eq!(None, repo.enqueue(&pool, &job).await?);
+
+        // Including while it is claimed or waiting to be retried
+        repo.claim_due(&pool, 10, TimeDelta::zero()).await?;
+        assert_eq!(None, repo.enqueue(&pool, &job).await?);
+        repo.fail(&pool, id, 1, "oops", Some(Utc::now())).await?;
+        assert_eq!(None, repo.enqueue(&pool, &job).await?);
+
+        // The key can be reused once the job has finished
+        repo.claim_due(&pool, 10, TimeDelta::zero()).await?;
+        repo.complete(&pool, id, 2).await?;
+        let reused_id = repo.enqueue(&pool, &job).await?;
+        assert!(reused_id.is_some_and(|new_id| new_id != id));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn prunes_only_old_finished_jobs(pool: PgPool) -> Result<()> {
+        let repo = PgJobRepo;
+
+        let mut ids = Vec::new();
+        for kind in ["succeeded", "dead", "pending"] {
+            ids.push(
+                repo.enqueue(&pool, &new_job(kind, None))
+                    .await?
+                    .context("not enqueued")?,
+            );
+        }
+        let [succeeded_id, dead_id, pending_id] = ids.as_slice() else {
+            anyhow::bail!("unexpected number of jobs");
+        };
+
+        repo.claim_due(&pool, 10, TimeDelta::zero()).await?;
+        repo.complete(&pool, *succeeded_id, 1).await?;
+        repo.fail(&pool, *dead_id, 1, "oops", None).await?;
+
+        let now = Utc::now();
+        let past = now
+            .checked_sub_signed(TimeDelta::days(1))
+            .context("out of range")?;
+        let future = now
+            .checked_add_signed(TimeDelta::days(1))
+            .context("out of range")?;
+
+        // Nothing finished before the cutoffs
+        assert_eq!(0, repo.prune_finished(&pool, past, past).await?);
+        // Only succeeded jobs before the first cutoff
+        assert_eq!(1, repo.prune_finished(&pool, future, past).await?);
+        // Then the dead job, but never the pending one
+        assert_eq!(1, repo.prune_finished(&pool, future, future).await?);
+        assert_eq!(JobStatus::Pending, job_state(&pool, *pending_id).await?.0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use {
+    crate::{
+        models::{
+            attachment::AttachmentSummary,
+            mention::{MentionPage, MentionSpan},
+            post::{
+                BodyFormat, PostCursor, PostVisibility, PostWithAuthor, QuotedPost, ReplyPolicy,
+            },
+            reaction::ReactionCount,
+        },
+        read_models::{MentionRead, ReadError},
+    },
+    sqlx::{PgPool, types::Json},
+};
+
+pub struct PgMentionRead {
+    pool: PgPool,
+}
+
+impl PgMentionRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl MentionRead for PgMentionRead {
+    async fn mentioning(
+        &self,
+        user_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<MentionPage, ReadError> {
+        // One extra post is fetched to find out whether there is another page
+        let mut posts = sqlx::query_as!(
+            PostWithAuthor,
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $1) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $1) AS "quoted: Json<QuotedPost>"
+            FROM post p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.deleted_at IS NULL
+                AND EXISTS (SELECT 1 FROM post_mention m WHERE m.post_id = p.id AND m.user_id = $1)
+                AND post_visible_to(p.id, $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR (p.created_at, p.id) < ($2, $3))
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $4
+            "#,
+            user_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit.saturating_add(1),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let has_more = i64::try_from(posts.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            posts.pop();
+        }
+
+        let next_cursor = has_more.then(|| posts.last().map(PostCursor::of)).flatten();
+
+        Ok(MentionPage { posts, next_cursor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::post::{PostRepo as _, mention::parse_mentions},
+            infra::post_repo::PgPostRepo,
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::{Context as _, Result},
+    };
+
+    #[sqlx::test]
+    async fn pages_through_posts_mentioning_a_user_with_their_spans(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let read = PgMentionRead::new(pool.clone());
+        let repo = PgPostRepo;
+
+        for (author_id, parent_id, body) in [
+            (2, 1, "Hi @drake_conan"),                 // ID 2
+            (3, 1, "Not a mention: drake_conan"),      // ID 3
+            (4, 1, "🗺️ @drake_conan and @you_n_15"),   // ID 4
+            (3, 2, "@drake_conan, this gets deleted"), // ID 5
+            (2, 4, "Only @jill_e_ian_12345"),          // ID 6
+        ] {
+            let post_id = repo
+                .insert_new(&pool, author_id, parent_id, body, BodyFormat::Plain, None)
+                .await?;
+            repo.insert_mentions(&pool, post_id, &parse_mentions(body))
+                .await?;
+        }
+        repo.soft_delete(&pool, 5).await?;
+
+        let first_page = read.mentioning(1, None, 1).await?;
+        let second_page = read.mentioning(1, first_page.next_cursor, 1).await?;
+
+        let ids = |page: &MentionPage| page.posts.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(vec![4], ids(&first_page));
+        assert_eq!(vec![2], ids(&second_page));
+        assert_eq!(None, second_page.next_cursor);
+
+        let spans = first_page
+            .posts
+            .first()
+            .context("missing post 4")?
+            .mentions
+            .0
+            .iter()
+            .map(|span| (span.start, span.end, span.username.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(8, 20, "drake_conan"), (25, 34, "you_n_15")], spans);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use {
+    crate::{
+        models::moderation::{
+            ModerationActionKind, ModerationActionRecord, ReportReason, ReportedPost,
+        },
+        read_models::{ModerationRead, ReadError},
+    },
+    sqlx::PgPool,
+};
+
+pub struct PgModerationRead {
+    pool: PgPool,
+}
+
+impl PgModerationRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl ModerationRead for PgModerationRead {
+    async fn open_reports(&self) -> Result<Vec<ReportedPost>, ReadError> {
+        sqlx::query_as!(
+            ReportedPost,
+            r#"
+            SELECT
+                p.id AS post_id,
+                u.username AS "author_username?",
+                p.body,
+                COUNT(*) AS "report_count!",
+                array_agg(DISTINCT r.reason) AS "reasons!: Vec<ReportReason>",
+                MIN(r.created_at) AS "first_reported_at!",
+                MAX(r.created_at) AS "last_reported_at!"
+            FROM post_report r
+            JOIN post p ON p.id = r.post_id
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE r.action_id IS NULL
+            GROUP BY p.id, u.username
+            ORDER BY COUNT(*) DESC, MIN(r.created_at)
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn action_log(&self) -> Result<Vec<ModerationActionRecord>, ReadError> {
+        sqlx::query_as!(
+            ModerationActionRecord,
+            r#"
+            SELECT
+                a.id,
+                a.post_id,
+                u.username AS "moderator_username?",
+                a.action AS "action: ModerationActionKind",
+                a.note,
+                COUNT(r.id) AS "reports_resolved!",
+                a.created_at
+            FROM moderation_action a
+            LEFT JOIN users u ON u.id = a.moderator_id
+            LEFT JOIN post_report r ON r.action_id = a.id
+            GROUP BY a.id, u.username
+            ORDER BY a.created_at DESC, a.id DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::{moderation::ModerationRepo as _, post::PostRepo as _},
+            infra::{moderation_repo::PgModerationRepo, post_repo::PgPostRepo},
+            models::post::BodyFormat,
+            test_utils::seed_data::{seed_moderator, seed_users_and_root_post},
+        },
+        anyhow::Result,
+    };
+
+    #[sqlx::test]
+    async fn groups_open_reports_by_post_with_most_reported_first(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        let post_repo = PgPostRepo;
+        let mod_repo = PgModerationRepo;
+        let read = PgModerationRead::new(pool.clone());
+
+        assert!(read.open_reports().await?.is_empty());
+
+        post_repo
+            .insert_new(&pool, 2, 1, "Reported once", BodyFormat::Plain, None)
+            .await?; // ID 2
+        post_repo
+            .insert_new(&pool, 3, 1, "Reported twice", BodyFormat::Plain, None)
+            .await?; // ID 3
+
+        mod_repo
+            .insert_report(&pool, 2, 1, ReportReason::Other)
+            .await?;
+        mod_repo
+            .insert_report(&pool, 3, 1, ReportReason::Spam)
+            .await?;
+        mod_repo
+            .insert_report(&pool, 3, 4, ReportReason::Harassment)
+            .await?;
+
+        let reports = read.open_reports().await?;
+
+        assert_eq!(
+            reports
+                .iter()
+                .map(|r| (r.post_id, r.report_count))
+                .collect::<Vec<_>>(),
+            vec![(3, 2), (2, 1)]
+        );
+
+        let most_reported = reports.first();
+        assert_eq!(
+            most_reported.map(|r| &r.reasons),
+            Some(&vec![ReportReason::Spam, ReportReason::Harassment])
+        );
+        assert_eq!(
+            most_reported.and_then(|r| r.author_username.as_ref()),
+            Some(&users[2].username)
+        );
+        assert_eq!(most_reported.and_then(|r| r.body.as_deref()), Some("Reported twice"));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn excludes_resolved_reports_and_logs_actions(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        seed_moderator(&pool, 4).await?;
+        let mod_repo = PgModerationRepo;
+        let read = PgModerationRead::new(pool.clone());
+
+        mod_repo
+            .insert_report(&pool, 1, 2, ReportReason::Violence)
+            .await?;
+        mod_repo
+            .insert_report(&pool, 1, 3, ReportReason::Violence)
+            .await?;
+
+        let action_id = mod_repo
+            .record_action(&pool, 1, 4, ModerationActionKind::Archive, None)
+            .await?;
+        mod_repo.resolve_open_reports(&pool, 1, action_id).await?;
+
+        assert!(read.open_reports().await?.is_empty());
+
+        let log = read.action_log().await?;
+        assert_eq!(log.len(), 1);
+        let entry = log.first();
+        assert_eq!(entry.map(|a| a.id), Some(action_id));
+        assert_eq!(entry.map(|a| a.post_id), Some(1));
+        assert_eq!(entry.and_then(|a| a.moderator_username.as_ref()), Some(&users[3].username));
+        assert_eq!(entry.map(|a| a.action), Some(ModerationActionKind::Archive));
+        assert_eq!(entry.map(|a| a.reports_resolved), Some(2));
+        assert!(entry.is_some_and(|a| a.note.is_none()));
+
+        Ok(())
+    }
+}
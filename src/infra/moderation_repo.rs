@@ -0,0 +1,192 @@
+use {
+    crate::{
+        domain::{RepoError, moderation::ModerationRepo},
+        models::moderation::{ModerationActionKind, ReportReason},
+    },
+    sqlx::PgExecutor,
+};
+
+pub struct PgModerationRepo;
+
+#[async_trait::async_trait]
+impl ModerationRepo for PgModerationRepo {
+    async fn insert_report(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        reporter_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO post_report (post_id, reporter_id, reason) VALUES ($1, $2, $3)",
+            post_id,
+            reporter_id,
+            reason as ReportReason,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn insert_flag(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "INSERT INTO post_report (post_id, reason) VALUES ($1, $2)",
+            post_id,
+            reason as ReportReason,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn is_moderator(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+    ) -> Result<bool, RepoError> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM moderator WHERE user_id = $1) AS "exists!""#,
+            user_id
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn record_action(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        moderator_id: i32,
+        action: ModerationActionKind,
+        note: Option<&str>,
+    ) -> Result<i32, RepoError> {
+        sqlx::query_scalar!(
+            "
+            INSERT INTO moderation_action (post_id, moderator_id, action, note)
+            VALUES ($1, $2, $3, $4::text)
+            RETURNING id
+            ",
+            post_id,
+            moderator_id,
+            action as ModerationActionKind,
+            note,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn resolve_open_reports(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        action_id: i32,
+    ) -> Result<u64, RepoError> {
+        sqlx::query!(
+            "
+            UPDATE post_report
+            SET action_id = $2
+            WHERE post_id = $1 AND action_id IS NULL
+            ",
+            post_id,
+            action_id,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::seed_data::{seed_moderator, seed_users_and_root_post},
+        anyhow::{Context as _, Result},
+        sqlx::PgPool,
+        std::assert_matches,
+    };
+
+    /// Counts the reports on a post that have not yet been resolved.
+    async fn count_open_reports(pool: &PgPool, post_id: i32) -> Result<i64> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM post_report
+            WHERE post_id = $1 AND action_id IS NULL
+            "#,
+            post_id,
+        )
+        .fetch_one(pool)
+        .await
+        .context("failed to count open reports")
+    }
+
+    #[sqlx::test]
+    async fn rejects_duplicate_open_reports_by_the_same_user(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgModerationRepo;
+
+        repo.insert_report(&pool, 1, 2, ReportReason::Spam).await?;
+
+        // Same user, different reason, still a duplicate
+        assert_matches!(
+            repo.insert_report(&pool, 1, 2, ReportReason::Hate).await,
+            Err(RepoError::UniqueViolation(v)) if v == "post_report_open_unique"
+        );
+
+        // A different user can still report the same post
+        repo.insert_report(&pool, 1, 3, ReportReason::Hate).await?;
+
+        assert_eq!(count_open_reports(&pool, 1).await?, 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn allows_reporting_again_after_resolution(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        seed_moderator(&pool, 4).await?;
+        let repo = PgModerationRepo;
+
+        repo.insert_report(&pool, 1, 2, ReportReason::Spam).await?;
+        repo.insert_report(&pool, 1, 3, ReportReason::Other).await?;
+
+        let action_id = repo
+            .record_action(&pool, 1, 4, ModerationActionKind::Dismiss, Some("Looks fine"))
+            .await?;
+        assert_eq!(repo.resolve_open_reports(&pool, 1, action_id).await?, 2);
+        assert_eq!(count_open_reports(&pool, 1).await?, 0);
+
+        // Resolving again has nothing left to close
+        assert_eq!(repo.resolve_open_reports(&pool, 1, action_id).await?, 0);
+
+        // The earlier report is closed, so the same user can report again
+        repo.insert_report(&pool, 1, 2, ReportReason::Spam).await?;
+        assert_eq!(count_open_reports(&pool, 1).await?, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn identifies_moderators(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        seed_moderator(&pool, 3).await?;
+        let repo = PgModerationRepo;
+
+        assert!(repo.is_moderator(&pool, 3).await?);
+        assert!(!repo.is_moderator(&pool, 2).await?);
+        assert!(!repo.is_moderator(&pool, 999).await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+use {
+    crate::{
+        models::{
+            notification::{
+                NotificationKind, NotificationPage, NotificationWithActor, UnreadCounts,
+            },
+            post::PostCursor,
+        },
+        read_models::{NotificationRead, ReadError},
+    },
+    chrono::{DateTime, Utc},
+    sqlx::PgPool,
+};
+
+pub struct PgNotificationRead {
+    pool: PgPool,
+}
+
+impl PgNotificationRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl NotificationRead for PgNotificationRead {
+    async fn for_recipient(
+        &self,
+        recipient_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<NotificationPage, ReadError> {
+        // One extra notification is fetched to find out whether there is another page
+        let mut notifications = sqlx::query_as!(
+            NotificationWithActor,
+            r#"
+            SELECT
+                n.id,
+                n.kind AS "kind: NotificationKind",
+                n.post_id,
+                n.created_at,
+                n.read_at,
+                u.username AS "actor_username?"
+            FROM notification n
+            LEFT JOIN users u ON u.id = n.actor_id
+            WHERE n.recipient_id = $1
+                AND ($2::TIMESTAMPTZ IS NULL OR (n.created_at, n.id) < ($2, $3))
+            ORDER BY n.created_at DESC, n.id DESC
+            LIMIT $4
+            "#,
+            recipient_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit.saturating_add(1),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let has_more = i64::try_from(notifications.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            notifications.pop();
+        }
+
+        let next_cursor = has_more
+            .then(|| {
+                notifications
+                    .last()
+                    .map(|last| PostCursor { created_at: last.created_at, id: last.id })
+            })
+            .flatten();
+
+        Ok(NotificationPage { notifications, next_cursor })
+    }
+
+    async fn for_recipient_since(
+        &self,
+        recipient_id: i32,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<NotificationWithActor>, ReadError> {
+        sqlx::query_as!(
+            NotificationWithActor,
+            r#"
+            SELECT
+                n.id,
+                n.kind AS "kind: NotificationKind",
+                n.post_id,
+                n.created_at,
+                n.read_at,
+                u.username AS "actor_username?"
+            FROM notification n
+            LEFT JOIN users u ON u.id = n.actor_id
+            WHERE n.recipient_id = $1 AND n.created_at >= $2
+            ORDER BY n.created_at, n.id
+            "#,
+            recipient_id,
+            since,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn created_at(
+        &self,
+        recipient_id: i32,
+        notification_id: i32,
+    ) -> Result<Option<DateTime<Utc>>, ReadError> {
+        sqlx::query_scalar!(
+            "SELECT created_at FROM notification WHERE id = $1 AND recipient_id = $2",
+            notification_id,
+            recipient_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn unread_counts(&self, recipient_id: i32) -> Result<UnreadCounts, ReadError> {
+        sqlx::query_as!(
+            UnreadCounts,
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE kind = 'reply') AS "reply!",
+                COUNT(*) FILTER (WHERE kind = 'friend_request') AS "friend_request!",
+                COUNT(*) FILTER (WHERE kind = 'friend_accepted') AS "friend_accepted!",
+                COUNT(*) FILTER (WHERE kind = 'mention') AS "mention!"
+            FROM notification
+            WHERE recipient_id = $1 AND read_at IS NULL
+            "#,
+            recipient_id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::notification::NotificationRepo as _,
+            infra::notification_repo::PgNotificationRepo,
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::{Context as _, Result},
+        chrono::TimeDelta,
+    };
+
+    #[sqlx::test]
+    async fn retrieves_notifications_with_most_recent_first(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        let repo = PgNotificationRepo;
+        let read = PgNotificationRead::new(pool.clone());
+
+        assert_eq!(
+            NotificationPage { notifications: Vec::new(), next_cursor: None },
+            read.for_recipient(1, None, 10).await?
+        );
+
+        repo.insert_new(&pool, 1, 2, NotificationKind::Reply, Some(1))
+            .await?;
+        repo.insert_new(&pool, 4, 2, NotificationKind::FriendRequest, None)
+            .await?;
+        repo.insert_new(&pool, 1, 3, NotificationKind::FriendAccepted, None)
+            .await?;
+
+        let NotificationPage { notifications, next_cursor } =
+            read.for_recipient(1, None, 10).await?;
+        assert_eq!(None, next_cursor);
+
+        assert_eq!(
+            notifications
+                .iter()
+                .map(|n| (n.id, n.kind, n.post_id, n.actor_username.as_ref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, NotificationKind::FriendAccepted, None, Some(&users[2].username)),
+                (1, NotificationKind::Reply, Some(1), Some(&users[1].username)),
+            ]
+        );
+        assert!(notifications.iter().all(|n| n.read_at.is_none()));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn retrieves_notifications_since_a_time_with_oldest_first(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgNotificationRepo;
+        let read = PgNotificationRead::new(pool.clone());
+
+        repo.insert_new(&pool, 2, 1, NotificationKind::FriendRequest, None)
+            .await?; // ID 1
+        repo.insert_new(&pool, 2, 3, NotificationKind::Reply, Some(1))
+            .await?; // ID 2
+        repo.insert_new(&pool, 3, 1, NotificationKind::FriendRequest, None)
+            .await?; // ID 3
+        repo.insert_new(&pool, 2, 4, NotificationKind::Reply, Some(1))
+            .await?; // ID 4
+
+        // Backdate the first two so that the creation order differs from the ID order
+        sqlx::query!(
+            "UPDATE notification SET created_at = now() - make_interval(mins => 3 - id) WHERE id \
+             <= 2"
+        )
+        .execute(&pool)
+        .await?;
+
+        let ids = |notifications: Vec<NotificationWithActor>| {
+            notifications.into_iter().map(|n| n.id).collect::<Vec<_>>()
+        };
+        let created_at = |id| read.created_at(2, id);
+
+        let first = created_at(1).await?.context("no creation time")?;
+        let second = created_at(2).await?.context("no creation time")?;
+
+        assert_eq!(ids(read.for_recipient_since(2, first).await?), vec![1, 2, 4]);
+        assert_eq!(ids(read.for_recipient_since(2, second).await?), vec![2, 4]);
+        assert_eq!(None, created_at(3).await?);
+        let later = Utc::now()
+            .checked_add_signed(TimeDelta::minutes(1))
+            .context("out of range")?;
+        assert!(read.for_recipient_since(2, later).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn pages_through_notifications_by_creation_time(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgNotificationRepo;
+        let read = PgNotificationRead::new(pool.clone());
+
+        for actor_id in [1, 3, 4] {
+            repo.insert_new(&pool, 2, actor_id, NotificationKind::FriendRequest, None)
+                .await?; // IDs 1 to 3
+        }
+        // The first was created last
+        sqlx::query!(
+            "UPDATE notification SET created_at = now() + interval '1 minute' WHERE id = 1"
+        )
+        .execute(&pool)
+        .await?;
+
+        let ids =
+            |page: &NotificationPage| page.notifications.iter().map(|n| n.id).collect::<Vec<_>>();
+        let first_page = read.for_recipient(2, None, 2).await?;
+        let second_page = read.for_recipient(2, first_page.next_cursor, 2).await?;
+
+        assert_eq!(vec![1, 3], ids(&first_page));
+        assert_eq!(vec![2], ids(&second_page));
+        assert_eq!(None, second_page.next_cursor);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn counts_only_unread_notifications_by_kind(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgNotificationRepo;
+        let read = PgNotificationRead::new(pool.clone());
+
+        let empty = UnreadCounts { reply: 0, friend_request: 0, friend_accepted: 0, mention: 0 };
+        assert_eq!(read.unread_counts(1).await?, empty);
+
+        repo.insert_new(&pool, 1, 2, NotificationKind::Reply, Some(1))
+            .await?;
+        repo.insert_new(&pool, 1, 3, NotificationKind::Reply, Some(1))
+            .await?;
+        repo.insert_new(&pool, 1, 4, NotificationKind::FriendRequest, None)
+            .await?;
+        repo.insert_new(&pool, 2, 4, NotificationKind::FriendAccepted, None)
+            .await?;
+        repo.insert_new(&pool, 1, 3, NotificationKind::Mention, Some(1))
+            .await?;
+
+        assert_eq!(
+            read.unread_counts(1).await?,
+            UnreadCounts { reply: 2, friend_request: 1, friend_accepted: 0, mention: 1 }
+        );
+
+        repo.mark_read(&pool, 1, None).await?;
+        assert_eq!(read.unread_counts(1).await?, empty);
+
+        Ok(())
+    }
+}
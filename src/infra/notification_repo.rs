@@ -0,0 +1,138 @@
+use {
+    crate::{
+        domain::{RepoError, notification::NotificationRepo},
+        models::notification::NotificationKind,
+    },
+    sqlx::PgExecutor,
+};
+
+pub struct PgNotificationRepo;
+
+#[async_trait::async_trait]
+impl NotificationRepo for PgNotificationRepo {
+    async fn insert_new(
+        &self,
+        exec: impl PgExecutor<'_>,
+        recipient_id: i32,
+        actor_id: i32,
+        kind: NotificationKind,
+        post_id: Option<i32>,
+    ) -> Result<(), RepoError> {
+        // The notification is broadcast to listeners on every server instance once the surrounding
+        // transaction commits
+        sqlx::query!(
+            "
+            WITH new_notification AS (
+                INSERT INTO notification (recipient_id, actor_id, kind, post_id)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, recipient_id
+            )
+            SELECT pg_notify(
+                'notification',
+                json_build_object('id', id, 'recipient_id', recipient_id)::text
+            )
+            FROM new_notification
+            ",
+            recipient_id,
+            actor_id,
+            kind as NotificationKind,
+            post_id,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn mark_read(
+        &self,
+        exec: impl PgExecutor<'_>,
+        recipient_id: i32,
+        ids: Option<&[i32]>,
+    ) -> Result<u64, RepoError> {
+        sqlx::query!(
+            "
+            UPDATE notification
+            SET read_at = CURRENT_TIMESTAMP
+            WHERE recipient_id = $1
+                AND read_at IS NULL
+                AND ($2::int[] IS NULL OR id = ANY($2))
+            ",
+            recipient_id,
+            ids,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::seed_data::seed_users_and_root_post,
+        anyhow::{Context as _, Result},
+        sqlx::PgPool,
+    };
+
+    /// Retrieves the IDs of the recipient's unread notifications in ascending order.
+    async fn unread_ids(pool: &PgPool, recipient_id: i32) -> Result<Vec<i32>> {
+        sqlx::query_scalar!(
+            "
+            SELECT id FROM notification
+            WHERE recipient_id = $1 AND read_at IS NULL
+            ORDER BY id
+            ",
+            recipient_id,
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to fetch unread notification IDs")
+    }
+
+    #[sqlx::test]
+    async fn marks_only_the_specified_notifications_of_the_recipient(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgNotificationRepo;
+
+        repo.insert_new(&pool, 1, 2, NotificationKind::Reply, Some(1))
+            .await?; // ID 1
+        repo.insert_new(&pool, 1, 3, NotificationKind::FriendRequest, None)
+            .await?; // ID 2
+        repo.insert_new(&pool, 2, 1, NotificationKind::FriendAccepted, None)
+            .await?; // ID 3
+        repo.insert_new(&pool, 1, 4, NotificationKind::FriendRequest, None)
+            .await?; // ID 4
+
+        // ID 3 belongs to another user and should be ignored
+        assert_eq!(repo.mark_read(&pool, 1, Some(&[2, 3])).await?, 1);
+        assert_eq!(unread_ids(&pool, 1).await?, vec![1, 4]);
+        assert_eq!(unread_ids(&pool, 2).await?, vec![3]);
+
+        // Already read notifications are not counted again
+        assert_eq!(repo.mark_read(&pool, 1, Some(&[2])).await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn marks_all_notifications_of_the_recipient(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgNotificationRepo;
+
+        repo.insert_new(&pool, 3, 2, NotificationKind::FriendRequest, None)
+            .await?;
+        repo.insert_new(&pool, 3, 4, NotificationKind::FriendRequest, None)
+            .await?;
+        repo.insert_new(&pool, 4, 3, NotificationKind::FriendAccepted, None)
+            .await?;
+
+        assert_eq!(repo.mark_read(&pool, 3, None).await?, 2);
+        assert!(unread_ids(&pool, 3).await?.is_empty());
+        assert_eq!(unread_ids(&pool, 4).await?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use {
+    crate::domain::outbound::OutboundPolicy,
+    reqwest::dns::{Addrs, Name, Resolve, Resolving},
+    std::net::SocketAddr,
+};
+
+/// Resolves host names for HTTP clients that send requests to URLs chosen by users or remote
+/// servers, leaving out any addresses that the policy does not allow. Resolving while connecting
+/// means that a name cannot be pointed at an internal address after its URL was checked.
+pub struct PolicyResolver {
+    policy: OutboundPolicy,
+}
+
+impl PolicyResolver {
+    pub const fn new(policy: OutboundPolicy) -> Self { Self { policy } }
+}
+
+impl Resolve for PolicyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy;
+        let host = name.as_str().to_owned();
+
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| policy.allows_ip(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{host} does not resolve to an allowed address").into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::tokio_test,
+        anyhow::{Result, anyhow},
+        std::str::FromStr as _,
+    };
+
+    #[test]
+    fn leaves_out_addresses_that_are_not_allowed() -> Result<()> {
+        tokio_test(async {
+            let localhost = || Name::from_str("localhost").map_err(|e| anyhow!("{e:?}"));
+
+            assert!(
+                PolicyResolver::new(OutboundPolicy::PublicOnly)
+                    .resolve(localhost()?)
+                    .await
+                    .is_err()
+            );
+
+            let addrs = PolicyResolver::new(OutboundPolicy::AllowLoopback)
+                .resolve(localhost()?)
+                .await
+                .map_err(|e| anyhow!(e))?;
+            assert!(addrs.into_iter().all(|addr| addr.ip().is_loopback()));
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,167 @@
+use {
+    crate::{
+        models::{notification::NewNotification, post::PostChange},
+        read_models::{NotificationFeed, PostFeed},
+    },
+    anyhow::Result,
+    serde::de::DeserializeOwned,
+    sqlx::{PgPool, postgres::PgListener},
+    std::time::Duration,
+    tokio::sync::broadcast,
+};
+
+/// The Postgres channel that notification inserts are announced on.
+pub const NOTIFICATION_CHANNEL: &str = "notification";
+
+/// The Postgres channel that post creations, edits, and deletions are announced on.
+pub const POST_CHANGE_CHANNEL: &str = "post_change";
+
+/// The number of signals that can be buffered for a slow subscriber before it starts missing them.
+const CAPACITY: usize = 256;
+
+/// Relays JSON signals from a Postgres `LISTEN/NOTIFY` channel to in-process subscribers, so that
+/// each server instance needs only one listening connection per channel no matter how many clients
+/// it serves.
+pub struct PgFeed<T> {
+    sender: broadcast::Sender<T>,
+}
+
+impl<T> PgFeed<T>
+where
+    T: DeserializeOwned + Clone + Send + 'static,
+{
+    /// Starts listening for signals on `channel` in a background task.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if connecting to the database or subscribing to the channel fails.
+    pub async fn start(pool: &PgPool, channel: &'static str) -> Result<Self> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(channel).await?;
+
+        let (sender, _) = broadcast::channel(CAPACITY);
+        let relay_sender = sender.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(pg_notification) => {
+                        match serde_json::from_str::<T>(pg_notification.payload()) {
+                            Ok(signal) => {
+                                if relay_sender.send(signal).is_err() {
+                                    log::debug!("No subscribers for {channel} signal");
+                                }
+                            }
+                            Err(e) => log::error!("Malformed {channel} signal: {e}"),
+                        }
+                    }
+                    Err(e) => {
+                        // The listener reconnects on the next call, so just avoid a hot loop
+                        log::error!("Failed to receive {channel} signal: {e}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { sender })
+    }
+}
+
+impl NotificationFeed for PgFeed<NewNotification> {
+    fn subscribe(&self) -> broadcast::Receiver<NewNotification> { self.sender.subscribe() }
+}
+
+impl PostFeed for PgFeed<PostChange> {
+    fn subscribe(&self) -> broadcast::Receiver<PostChange> { self.sender.subscribe() }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            app_services::uow::{Tx as _, UnitOfWork as _},
+            domain::{notification::NotificationRepo as _, post::PostRepo as _},
+            infra::{notification_repo::PgNotificationRepo, post_repo::PgPostRepo},
+            models::{
+                notification::NotificationKind,
+                post::{BodyFormat, PostChangeKind},
+            },
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::Context as _,
+        tokio::time::timeout,
+    };
+
+    /// Receives the next signal, failing if none arrives within a few seconds.
+    async fn next_signal<T: Clone>(subscriber: &mut broadcast::Receiver<T>) -> Result<T> {
+        timeout(Duration::from_secs(5), subscriber.recv())
+            .await
+            .context("timed out waiting for signal")?
+            .map_err(Into::into)
+    }
+
+    #[sqlx::test]
+    async fn relays_notification_signals_only_after_the_insert_commits(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let feed = PgFeed::<NewNotification>::start(&pool, NOTIFICATION_CHANNEL).await?;
+        let mut subscriber = NotificationFeed::subscribe(&feed);
+
+        let mut tx = pool.begin_uow().await?;
+        PgNotificationRepo
+            .insert_new(tx.exec(), 3, 2, NotificationKind::FriendRequest, None)
+            .await?;
+
+        // Nothing is announced until the transaction commits
+        assert!(
+            timeout(Duration::from_millis(200), subscriber.recv())
+                .await
+                .is_err()
+        );
+
+        tx.commit_uow().await?;
+
+        let signal = next_signal(&mut subscriber).await?;
+        assert_eq!(signal, NewNotification { id: 1, recipient_id: 3 });
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn relays_post_creations_edits_and_deletions(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let feed = PgFeed::<PostChange>::start(&pool, POST_CHANGE_CHANNEL).await?;
+        let mut subscriber = PostFeed::subscribe(&feed);
+
+        let post_id = PgPostRepo
+            .insert_new(&pool, 2, 1, "First draft", BodyFormat::Plain, None)
+            .await?;
+        assert_eq!(
+            next_signal(&mut subscriber).await?,
+            PostChange { kind: PostChangeKind::Created, post_id, parent_id: Some(1) },
+        );
+
+        // Archiving does not change anything a thread viewer sees
+        PgPostRepo.archive(&pool, post_id).await?;
+
+        sqlx::query!(
+            "UPDATE post SET body = 'Second draft', edited_at = NOW() WHERE id = $1",
+            post_id,
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(
+            next_signal(&mut subscriber).await?,
+            PostChange { kind: PostChangeKind::Edited, post_id, parent_id: Some(1) },
+        );
+
+        PgPostRepo.soft_delete(&pool, post_id).await?;
+        assert_eq!(
+            next_signal(&mut subscriber).await?,
+            PostChange { kind: PostChangeKind::Deleted, post_id, parent_id: Some(1) },
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
 use {
     crate::{
-        domain::{RepoError, post::PostRepo},
-        models::post::Post,
+        domain::{
+            RepoError,
+            post::{PostRepo, ReplyRestriction, mention::Mention},
+        },
+        models::post::{BodyFormat, Post, PostVisibility, ReplyPolicy},
     },
+    anyhow::Context as _,
+    chrono::{DateTime, Utc},
     sqlx::PgExecutor,
 };
 
@@ -16,12 +21,111 @@ impl PostRepo for PgPostRepo {
         author_id: i32,
         parent_id: i32,
         body: &str,
-    ) -> Result<(), RepoError> {
-        sqlx::query!(
-            "INSERT INTO post (author_id, parent_id, body) VALUES ($1, $2, $3::text)",
+        body_format: BodyFormat,
+        quoted_post_id: Option<i32>,
+    ) -> Result<i32, RepoError> {
+        sqlx::query_scalar!(
+            "
+            INSERT INTO post (author_id, parent_id, body, body_format, quoted_post_id)
+            VALUES ($1, $2, $3::text, $4, $5)
+            RETURNING id
+            ",
+            author_id,
+            parent_id,
+            body,
+            body_format as BodyFormat,
+            quoted_post_id,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn insert_scheduled(
+        &self,
+        exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        publish_at: DateTime<Utc>,
+    ) -> Result<i32, RepoError> {
+        sqlx::query_scalar!(
+            "
+            INSERT INTO post (author_id, parent_id, body, body_format, publish_at)
+            VALUES ($1, $2, $3::text, $4, $5)
+            RETURNING id
+            ",
             author_id,
             parent_id,
-            body
+            body,
+            body_format as BodyFormat,
+            publish_at,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn insert_mentions(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        mentions: &[Mention<'_>],
+    ) -> Result<Vec<i32>, RepoError> {
+        let usernames = mentions.iter().map(|m| m.username).collect::<Vec<_>>();
+        let offsets = |offset_of: fn(&Mention<'_>) -> usize| {
+            mentions
+                .iter()
+                .map(|m| i32::try_from(offset_of(m)))
+                .collect::<Result<Vec<_>, _>>()
+                .context("mention offset out of range")
+        };
+        let starts = offsets(|m| m.start)?;
+        let ends = offsets(|m| m.end)?;
+
+        sqlx::query_scalar!(
+            r#"
+            WITH inserted AS (
+                INSERT INTO post_mention (post_id, user_id, start_offset, end_offset)
+                SELECT $1, u.id, m.start_offset, m.end_offset
+                FROM UNNEST($2::TEXT[], $3::INT[], $4::INT[])
+                    AS m(username, start_offset, end_offset)
+                JOIN users u ON u.username = m.username
+                RETURNING user_id
+            )
+            SELECT DISTINCT user_id AS "user_id!" FROM inserted ORDER BY user_id
+            "#,
+            post_id,
+            &usernames as &[&str],
+            &starts,
+            &ends,
+        )
+        .fetch_all(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn insert_tags(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        tags: &[String],
+    ) -> Result<(), RepoError> {
+        // The no-op update makes existing tags show up in the returned IDs too
+        sqlx::query!(
+            "
+            WITH tag_ids AS (
+                INSERT INTO tag (name)
+                SELECT UNNEST($2::TEXT[])
+                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING id
+            )
+            INSERT INTO post_tag (post_id, tag_id)
+            SELECT $1, id FROM tag_ids
+            ",
+            post_id,
+            tags,
         )
         .execute(exec)
         .await
@@ -29,15 +133,255 @@ impl PostRepo for PgPostRepo {
         .map(|_| ())
     }
 
+    async fn get_by_id(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+    ) -> Result<Option<Post>, RepoError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT
+                id,
+                author_id,
+                parent_id,
+                body,
+                body_format AS "body_format: BodyFormat",
+                created_at,
+                edited_at,
+                archived_at,
+                deleted_at,
+                descendant_count,
+                quoted_post_id,
+                reply_policy AS "reply_policy: ReplyPolicy",
+                reply_policy_cascades,
+                depth,
+                child_count
+            FROM post
+            WHERE id = $1 AND publish_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+
     async fn get_by_id_exclusive(
         &self,
         exec: impl PgExecutor<'_>,
         id: i32,
     ) -> Result<Option<Post>, RepoError> {
-        sqlx::query_as!(Post, "SELECT * FROM post WHERE id = $1 FOR UPDATE", id)
-            .fetch_optional(exec)
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT
+                id,
+                author_id,
+                parent_id,
+                body,
+                body_format AS "body_format: BodyFormat",
+                created_at,
+                edited_at,
+                archived_at,
+                deleted_at,
+                descendant_count,
+                quoted_post_id,
+                reply_policy AS "reply_policy: ReplyPolicy",
+                reply_policy_cascades,
+                depth,
+                child_count
+            FROM post
+            WHERE id = $1 AND publish_at IS NULL
+            FOR UPDATE
+            "#,
+            id
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn reply_restrictions(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        replier_id: i32,
+    ) -> Result<Vec<ReplyRestriction>, RepoError> {
+        sqlx::query_as!(
+            ReplyRestriction,
+            r#"
+            WITH RECURSIVE ancestry AS (
+                SELECT id, parent_id, author_id, reply_policy, reply_policy_cascades, 0 AS depth
+                FROM post
+                WHERE id = $1
+                UNION ALL
+                SELECT p.id, p.parent_id, p.author_id, p.reply_policy, p.reply_policy_cascades,
+                    a.depth + 1
+                FROM post p
+                JOIN ancestry a ON p.id = a.parent_id
+            )
+            SELECT
+                a.reply_policy AS "policy!: ReplyPolicy",
+                COALESCE(
+                    a.author_id = $2 OR EXISTS (
+                        SELECT 1 FROM friendship f
+                        WHERE f.confirmed_at IS NOT NULL
+                            AND f.lesser_id = LEAST($2, a.author_id)
+                            AND f.greater_id = GREATEST($2, a.author_id)
+                    ),
+                    false
+                ) AS "replier_is_author_or_friend!"
+            FROM ancestry a
+            WHERE a.reply_policy <> 'anyone' AND (a.depth = 0 OR a.reply_policy_cascades)
+            ORDER BY a.depth
+            "#,
+            post_id,
+            replier_id,
+        )
+        .fetch_all(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn is_visible_to(
+        &self,
+        exec: impl PgExecutor<'_>,
+        post_id: i32,
+        viewer_id: i32,
+    ) -> Result<bool, RepoError> {
+        sqlx::query_scalar!(
+            r#"SELECT COALESCE(post_visible_to($1, $2), false) AS "visible!""#,
+            post_id,
+            viewer_id,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn set_reply_policy(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        policy: ReplyPolicy,
+        cascades: bool,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "UPDATE post SET reply_policy = $2, reply_policy_cascades = $3 WHERE id = $1",
+            id,
+            policy as ReplyPolicy,
+            cascades,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn set_visibility(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        visibility: PostVisibility,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "UPDATE post SET visibility = $2 WHERE id = $1",
+            id,
+            visibility as PostVisibility,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn claim_due_scheduled(
+        &self,
+        exec: impl PgExecutor<'_>,
+    ) -> Result<Option<Post>, RepoError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT
+                id,
+                author_id,
+                parent_id,
+                body,
+                body_format AS "body_format: BodyFormat",
+                created_at,
+                edited_at,
+                archived_at,
+                deleted_at,
+                descendant_count,
+                quoted_post_id,
+                reply_policy AS "reply_policy: ReplyPolicy",
+                reply_policy_cascades,
+                depth,
+                child_count
+            FROM post
+            WHERE publish_at <= CURRENT_TIMESTAMP
+            ORDER BY publish_at, id
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .fetch_optional(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn publish(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        sqlx::query!(
+            "
+            UPDATE post
+            SET publish_at = NULL, created_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND publish_at IS NOT NULL
+            ",
+            id
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn delete_scheduled(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        sqlx::query!("DELETE FROM post WHERE id = $1 AND publish_at IS NOT NULL", id)
+            .execute(exec)
             .await
             .map_err(Into::into)
+            .map(|_| ())
+    }
+
+    async fn archive(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        sqlx::query!(
+            "
+            UPDATE post
+            SET archived_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND archived_at IS NULL
+            ",
+            id
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn soft_delete(&self, exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        sqlx::query!(
+            "
+            UPDATE post
+            SET deleted_at = CURRENT_TIMESTAMP, body = NULL
+            WHERE id = $1 AND deleted_at IS NULL
+            ",
+            id
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
     }
 }
 
@@ -45,13 +389,89 @@ impl PostRepo for PgPostRepo {
 mod tests {
     use {
         super::*,
-        crate::test_utils::{seed_data::seed_users_and_root_post, time::within_five_seconds},
-        anyhow::{Context as _, Result},
-        chrono::Utc,
+        crate::test_utils::{
+            seed_data::{seed_friends, seed_users_and_root_post},
+            time::within_five_seconds,
+        },
+        anyhow::Result,
+        chrono::TimeDelta,
         sqlx::PgPool,
-        std::assert_matches,
+        std::{assert_matches, time::Duration},
     };
 
+    #[sqlx::test]
+    async fn records_mentions_of_existing_users_only(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+
+        let mentions = [
+            Mention { username: "you_n_15", start: 0, end: 9 },
+            Mention { username: "nobody_here", start: 10, end: 22 },
+            Mention { username: "drake_conan", start: 23, end: 35 },
+            Mention { username: "you_n_15", start: 36, end: 45 },
+        ];
+        assert_eq!(vec![1, 2], repo.insert_mentions(&pool, 1, &mentions).await?);
+
+        let recorded = sqlx::query!(
+            "
+            SELECT user_id, start_offset, end_offset
+            FROM post_mention
+            WHERE post_id = 1
+            ORDER BY start_offset
+            "
+        )
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.user_id, row.start_offset, row.end_offset))
+        .collect::<Vec<_>>();
+        assert_eq!(vec![(2, 0, 9), (1, 23, 35), (2, 36, 45)], recorded);
+
+        // Nothing to record
+        assert_eq!(Vec::<i32>::new(), repo.insert_mentions(&pool, 1, &[]).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn tags_posts_reusing_existing_tags(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+
+        repo.insert_new(&pool, 2, 1, "#rust #async", BodyFormat::Plain, None)
+            .await?;
+        repo.insert_tags(&pool, 1, &[String::from("rust")]).await?;
+        repo.insert_tags(&pool, 2, &[String::from("rust"), String::from("async")])
+            .await?;
+
+        let tagged = sqlx::query!(
+            "
+            SELECT pt.post_id, t.name
+            FROM post_tag pt
+            JOIN tag t ON t.id = pt.tag_id
+            ORDER BY pt.post_id, t.name
+            "
+        )
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.post_id, row.name))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![(1, String::from("rust")), (2, String::from("async")), (2, String::from("rust")),],
+            tagged,
+        );
+
+        // Only one row per tag
+        let tag_count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM tag"#)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(2, tag_count);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn rejects_multiple_replies_to_the_same_post_by_the_same_user(
         pool: PgPool,
@@ -59,12 +479,16 @@ mod tests {
         seed_users_and_root_post(&pool).await?;
         let repo = PgPostRepo;
 
-        // First reply is valid
-        repo.insert_new(&pool, 2, 1, "My first reply").await?;
+        // First reply is valid and follows the root post
+        assert_eq!(
+            repo.insert_new(&pool, 2, 1, "My first reply", BodyFormat::Plain, None)
+                .await?,
+            2
+        );
 
         // Second reply to the same post by the same user is invalid
         assert_matches!(
-            repo.insert_new(&pool, 2, 1, "Oh no, replying again").await,
+            repo.insert_new(&pool, 2, 1, "Oh no, replying again", BodyFormat::Plain, None).await,
             Err(RepoError::UniqueViolation(v)) if v == "post_author_parent_unique"
         );
 
@@ -81,15 +505,15 @@ mod tests {
         seed_users_and_root_post(&pool).await?;
         let repo = PgPostRepo;
 
-        repo.insert_new(&pool, 2, 1, "My first reply")
+        repo.insert_new(&pool, 2, 1, "My first reply", BodyFormat::Plain, None)
             .await
             .context("first reply")?;
 
-        repo.insert_new(&pool, 3, 1, "I'm also replying to this post")
+        repo.insert_new(&pool, 3, 1, "I'm also replying to this post", BodyFormat::Plain, None)
             .await
             .context("same parent post as the first reply, but from a different user")?;
 
-        repo.insert_new(&pool, 2, 3, "I'm replying to your reply")
+        repo.insert_new(&pool, 2, 3, "I'm replying to your reply", BodyFormat::Plain, None)
             .await
             .context("same user as the first reply, but a different parent post")?;
 
@@ -107,7 +531,7 @@ mod tests {
         // from the ASCII space character).
         for empty_body in ["", " ", "   ", "　", "　　　", "\t", "\n\n", " \r\t \n"] {
             assert_matches!(
-                PgPostRepo.insert_new(&pool, 4, 1, empty_body).await,
+                PgPostRepo.insert_new(&pool, 4, 1, empty_body, BodyFormat::Plain, None).await,
                 Err(RepoError::CheckViolation(v)) if v == "text_non_empty"
             );
         }
@@ -127,15 +551,36 @@ mod tests {
             // Use a different parent ID for each insertion to avoid duplicate reply errors
             assert_matches!(
                 PgPostRepo
-                    .insert_new(&pool, 4, parent_id, non_empty_body)
+                    .insert_new(&pool, 4, parent_id, non_empty_body, BodyFormat::Plain, None)
                     .await,
-                Ok(())
+                Ok(_)
             );
         }
 
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn gets_posts_that_are_locked_for_writing(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+        let post_id = repo
+            .insert_new(&pool, 2, 1, "Locked", BodyFormat::Plain, None)
+            .await?;
+
+        let mut tx = pool.begin().await?;
+        repo.get_by_id_exclusive(&mut *tx, post_id).await?;
+
+        let post = tokio::time::timeout(Duration::from_secs(5), repo.get_by_id(&pool, post_id))
+            .await
+            .context("reading a locked post blocked")??
+            .context("post was unexpectedly None")?;
+        assert_eq!(Some(String::from("Locked")), post.body);
+        tx.rollback().await?;
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn returns_none_for_missing_post(pool: PgPool) -> Result<()> {
         seed_users_and_root_post(&pool).await?;
@@ -160,9 +605,12 @@ mod tests {
         let post_body_4 = "日本語の文字も使えるはずなので確認しておきましょう！";
 
         // All three posts should be successfully inserted
-        repo.insert_new(&pool, 4, 1, post_body_2).await?;
-        repo.insert_new(&pool, 3, 2, post_body_3).await?;
-        repo.insert_new(&pool, 2, 2, post_body_4).await?;
+        repo.insert_new(&pool, 4, 1, post_body_2, BodyFormat::Plain, None)
+            .await?;
+        repo.insert_new(&pool, 3, 2, post_body_3, BodyFormat::Plain, None)
+            .await?;
+        repo.insert_new(&pool, 2, 2, post_body_4, BodyFormat::Markdown, Some(1))
+            .await?;
 
         let post2 = repo
             .get_by_id_exclusive(&pool, 2)
@@ -181,28 +629,252 @@ mod tests {
         assert_eq!(post2.author_id, Some(4));
         assert_eq!(post2.parent_id, Some(1));
         assert_eq!(post2.body, Some(post_body_2.to_string()));
+        assert_eq!(post2.body_format, BodyFormat::Plain);
         assert!(within_five_seconds(post2.created_at, Utc::now()));
         assert!(post2.edited_at.is_none());
         assert!(post2.archived_at.is_none());
         assert!(post2.deleted_at.is_none());
+        assert_eq!(post2.descendant_count, 2);
+        assert_eq!(post2.quoted_post_id, None);
+        assert_eq!(post2.reply_policy, ReplyPolicy::Anyone);
+        assert!(!post2.reply_policy_cascades);
+        assert_eq!(post2.depth, 1);
+        assert_eq!(post2.child_count, 2);
 
         assert_eq!(post3.id, 3);
         assert_eq!(post3.author_id, Some(3));
         assert_eq!(post3.parent_id, Some(2));
         assert_eq!(post3.body, Some(post_body_3.to_string()));
+        assert_eq!(post3.body_format, BodyFormat::Plain);
         assert!(within_five_seconds(post3.created_at, Utc::now()));
         assert!(post3.edited_at.is_none());
         assert!(post3.archived_at.is_none());
         assert!(post3.deleted_at.is_none());
+        assert_eq!(post3.descendant_count, 0);
+        assert_eq!(post3.quoted_post_id, None);
+        assert_eq!(post3.reply_policy, ReplyPolicy::Anyone);
+        assert!(!post3.reply_policy_cascades);
+        assert_eq!(post3.depth, 2);
+        assert_eq!(post3.child_count, 0);
 
         assert_eq!(post4.id, 4);
         assert_eq!(post4.author_id, Some(2));
         assert_eq!(post4.parent_id, Some(2));
         assert_eq!(post4.body, Some(post_body_4.to_string()));
+        assert_eq!(post4.body_format, BodyFormat::Markdown);
         assert!(within_five_seconds(post4.created_at, Utc::now()));
         assert!(post4.edited_at.is_none());
         assert!(post4.archived_at.is_none());
         assert!(post4.deleted_at.is_none());
+        assert_eq!(post4.descendant_count, 0);
+        assert_eq!(post4.quoted_post_id, Some(1));
+        assert_eq!(post4.reply_policy, ReplyPolicy::Anyone);
+        assert!(!post4.reply_policy_cascades);
+        assert_eq!(post4.depth, 2);
+        assert_eq!(post4.child_count, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn keeps_scheduled_posts_hidden_and_uncounted_until_published(
+        pool: PgPool,
+    ) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+
+        let later = Utc::now()
+            .checked_add_signed(TimeDelta::hours(1))
+            .context("out of range")?;
+        let earlier = Utc::now()
+            .checked_sub_signed(TimeDelta::hours(1))
+            .context("out of range")?;
+        let not_due = repo
+            .insert_scheduled(&pool, 2, 1, "Not yet", BodyFormat::Plain, later)
+            .await?;
+        let due = repo
+            .insert_scheduled(&pool, 3, 1, "Right about now", BodyFormat::Markdown, earlier)
+            .await?;
+
+        assert_matches!(repo.get_by_id_exclusive(&pool, due).await, Ok(None));
+        let root = repo
+            .get_by_id_exclusive(&pool, 1)
+            .await?
+            .context("root post was unexpectedly None")?;
+        assert_eq!((0, 0), (root.descendant_count, root.child_count));
+
+        // Only the due post is claimed
+        let claimed = repo
+            .claim_due_scheduled(&pool)
+            .await?
+            .context("no due post was claimed")?;
+        assert_eq!(
+            (due, Some(3), Some("Right about now")),
+            (claimed.id, claimed.author_id, claimed.body.as_deref())
+        );
+
+        repo.publish(&pool, due).await?;
+        assert_matches!(repo.claim_due_scheduled(&pool).await, Ok(None));
+
+        let published = repo
+            .get_by_id_exclusive(&pool, due)
+            .await?
+            .context("published post was unexpectedly None")?;
+        assert!(within_five_seconds(published.created_at, Utc::now()));
+        let root_after = repo
+            .get_by_id_exclusive(&pool, 1)
+            .await?
+            .context("root post was unexpectedly None")?;
+        assert_eq!((0, 1), (root_after.descendant_count, root_after.child_count));
+        assert_eq!(1, published.depth);
+
+        // Published posts can't be deleted this way, but scheduled ones can
+        repo.delete_scheduled(&pool, due).await?;
+        repo.delete_scheduled(&pool, not_due).await?;
+        assert!(repo.get_by_id_exclusive(&pool, due).await?.is_some());
+        let remaining = sqlx::query_scalar!("SELECT id FROM post WHERE id = $1", not_due)
+            .fetch_optional(&pool)
+            .await?;
+        assert_eq!(None, remaining);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn finds_the_reply_policies_of_a_post_and_its_cascading_ancestors(
+        pool: PgPool,
+    ) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        seed_friends(&pool).await?;
+        let repo = PgPostRepo;
+
+        repo.insert_new(&pool, 2, 1, "Post 2", BodyFormat::Plain, None)
+            .await?;
+        repo.insert_new(&pool, 3, 2, "Post 3", BodyFormat::Plain, None)
+            .await?;
+
+        let restrictions = async |post_id, replier_id| -> Result<Vec<(ReplyPolicy, bool)>> {
+            Ok(repo
+                .reply_restrictions(&pool, post_id, replier_id)
+                .await?
+                .into_iter()
+                .map(|r| (r.policy, r.replier_is_author_or_friend))
+                .collect())
+        };
+
+        assert_eq!(Vec::<(ReplyPolicy, bool)>::new(), restrictions(3, 4).await?);
+
+        // User 3 is a friend of user 2, but user 1 isn't
+        repo.set_reply_policy(&pool, 2, ReplyPolicy::Friends, false)
+            .await?;
+        assert_eq!(vec![(ReplyPolicy::Friends, true)], restrictions(2, 3).await?);
+        assert_eq!(vec![(ReplyPolicy::Friends, false)], restrictions(2, 1).await?);
+        assert_eq!(Vec::<(ReplyPolicy, bool)>::new(), restrictions(3, 1).await?);
+
+        repo.set_reply_policy(&pool, 2, ReplyPolicy::Friends, true)
+            .await?;
+        assert_eq!(vec![(ReplyPolicy::Friends, false)], restrictions(3, 1).await?);
+        assert_eq!(vec![(ReplyPolicy::Friends, true)], restrictions(3, 2).await?);
+
+        // Nearest first
+        repo.set_reply_policy(&pool, 1, ReplyPolicy::Nobody, true)
+            .await?;
+        assert_eq!(
+            vec![(ReplyPolicy::Friends, true), (ReplyPolicy::Nobody, false)],
+            restrictions(3, 4).await?,
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn lets_only_the_chosen_audience_see_a_post(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        seed_friends(&pool).await?;
+        let repo = PgPostRepo;
+
+        // Post 2 by user 2 replies to user 1, and user 2 is friends with users 3 and 4
+        repo.insert_new(&pool, 2, 1, "Who can see this?", BodyFormat::Plain, None)
+            .await?;
+        let audience = async || -> Result<Vec<bool>> {
+            let mut visible = Vec::new();
+            for viewer_id in 1..=4 {
+                visible.push(repo.is_visible_to(&pool, 2, viewer_id).await?);
+            }
+            Ok(visible)
+        };
+
+        assert_eq!(vec![true, true, true, true], audience().await?);
+
+        repo.set_visibility(&pool, 2, PostVisibility::Friends)
+            .await?;
+        assert_eq!(vec![false, true, true, true], audience().await?);
+
+        repo.set_visibility(&pool, 2, PostVisibility::Participants)
+            .await?;
+        assert_eq!(vec![true, true, false, false], audience().await?);
+
+        let mention = Mention { username: &users[3].username, start: 0, end: 5 };
+        repo.insert_mentions(&pool, 2, &[mention]).await?;
+        assert_eq!(vec![true, true, false, true], audience().await?);
+
+        // Nonexistent posts are never visible
+        assert!(!repo.is_visible_to(&pool, 3, 2).await?);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn archives_a_post_only_once(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+
+        repo.insert_new(&pool, 2, 1, "Archive me", BodyFormat::Plain, None)
+            .await?;
+        repo.archive(&pool, 2).await?;
+
+        let first_archived_at = repo
+            .get_by_id_exclusive(&pool, 2)
+            .await?
+            .context("post 2 was unexpectedly None")?
+            .archived_at
+            .context("post 2 was not archived")?;
+        assert!(within_five_seconds(first_archived_at, Utc::now()));
+
+        // Archiving again should not overwrite the original time
+        repo.archive(&pool, 2).await?;
+        let post = repo
+            .get_by_id_exclusive(&pool, 2)
+            .await?
+            .context("post 2 was unexpectedly None")?;
+        assert_eq!(post.archived_at, Some(first_archived_at));
+        assert_eq!(post.body, Some(String::from("Archive me")));
+        assert!(post.deleted_at.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn soft_deletes_a_post_by_removing_its_body(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+
+        repo.insert_new(&pool, 3, 1, "Delete me", BodyFormat::Plain, None)
+            .await?;
+        repo.soft_delete(&pool, 2).await?;
+
+        let post = repo
+            .get_by_id_exclusive(&pool, 2)
+            .await?
+            .context("post 2 should still exist after a soft delete")?;
+
+        assert!(post.body.is_none());
+        assert!(within_five_seconds(
+            post.deleted_at.context("post 2 was not deleted")?,
+            Utc::now()
+        ));
+        assert_eq!(post.author_id, Some(3));
+        assert_eq!(post.parent_id, Some(1));
 
         Ok(())
     }
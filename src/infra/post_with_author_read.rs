@@ -1,9 +1,17 @@
 use {
     crate::{
-        models::post::PostWithAuthor,
+        models::{
+            attachment::AttachmentSummary,
+            mention::MentionSpan,
+            post::{
+                BodyFormat, PostCursor, PostSort, PostVisibility, PostWithAuthor, QuotePage,
+                QuotedPost, ReplyPolicy,
+            },
+            reaction::ReactionCount,
+        },
         read_models::{PostWithAuthorRead, ReadError},
     },
-    sqlx::PgPool,
+    sqlx::{PgPool, types::Json},
 };
 
 pub struct PgPostWithAuthorRead {
@@ -16,70 +24,363 @@ impl PgPostWithAuthorRead {
 
 #[async_trait::async_trait]
 impl PostWithAuthorRead for PgPostWithAuthorRead {
-    async fn by_post_id(&self, id: i32) -> Result<PostWithAuthor, ReadError> {
+    async fn by_post_id(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<PostWithAuthor, ReadError> {
         sqlx::query_as!(
             PostWithAuthor,
-            "
-            SELECT p.*, u.username AS author_username
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility",
+                NOT post_visible_to(p.id, $2) AS "hidden!",
+                u.username AS author_username,
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
             FROM post p
             LEFT JOIN users u ON p.author_id = u.id
-            WHERE p.id = $1
-            ",
-            id
+            WHERE p.id = $1 AND p.publish_at IS NULL
+            "#,
+            id,
+            viewer_id,
         )
         .fetch_one(&self.pool)
         .await
+        .map(PostWithAuthor::placeholder_if_hidden)
         .map_err(Into::into)
     }
 
-    async fn children_of(&self, parent_id: i32) -> Result<Vec<PostWithAuthor>, ReadError> {
+    #[expect(clippy::too_many_lines, reason = "One static query per sort order")]
+    async fn children_of(
+        &self,
+        parent_id: i32,
+        sort: PostSort,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<PostWithAuthor>, ReadError> {
+        // Each order is a separate static query so that each can use its matching index
+        let children = match sort {
+            PostSort::New => {
+                sqlx::query_as!(
+                    PostWithAuthor,
+                    r#"
+                SELECT
+                    p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                    p.archived_at, p.deleted_at, p.descendant_count,
+                    p.body_format AS "body_format: BodyFormat",
+                    p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                    p.visibility AS "visibility: PostVisibility",
+                    NOT post_visible_to(p.id, $2) AS "hidden!",
+                    u.username AS author_username,
+                    post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                    post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                    post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                    post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+                FROM post p
+                LEFT JOIN users u ON p.author_id = u.id
+                WHERE p.parent_id = $1 AND p.publish_at IS NULL
+                ORDER BY p.created_at DESC, p.id DESC
+                "#,
+                    parent_id,
+                    viewer_id,
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+            PostSort::Old => {
+                sqlx::query_as!(
+                    PostWithAuthor,
+                    r#"
+                SELECT
+                    p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                    p.archived_at, p.deleted_at, p.descendant_count,
+                    p.body_format AS "body_format: BodyFormat",
+                    p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                    p.visibility AS "visibility: PostVisibility",
+                    NOT post_visible_to(p.id, $2) AS "hidden!",
+                    u.username AS author_username,
+                    post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                    post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                    post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                    post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+                FROM post p
+                LEFT JOIN users u ON p.author_id = u.id
+                WHERE p.parent_id = $1 AND p.publish_at IS NULL
+                ORDER BY p.created_at, p.id
+                "#,
+                    parent_id,
+                    viewer_id,
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+            PostSort::Top => {
+                sqlx::query_as!(
+                    PostWithAuthor,
+                    r#"
+                SELECT
+                    p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                    p.archived_at, p.deleted_at, p.descendant_count,
+                    p.body_format AS "body_format: BodyFormat",
+                    p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                    p.visibility AS "visibility: PostVisibility",
+                    NOT post_visible_to(p.id, $2) AS "hidden!",
+                    u.username AS author_username,
+                    post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                    post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                    post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                    post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+                FROM post p
+                LEFT JOIN users u ON p.author_id = u.id
+                WHERE p.parent_id = $1 AND p.publish_at IS NULL
+                ORDER BY p.descendant_count DESC, p.id DESC
+                "#,
+                    parent_id,
+                    viewer_id,
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+            PostSort::Hot => {
+                sqlx::query_as!(
+                    PostWithAuthor,
+                    r#"
+                SELECT
+                    p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                    p.archived_at, p.deleted_at, p.descendant_count,
+                    p.body_format AS "body_format: BodyFormat",
+                    p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                    p.visibility AS "visibility: PostVisibility",
+                    NOT post_visible_to(p.id, $2) AS "hidden!",
+                    u.username AS author_username,
+                    post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                    post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                    post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                    post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+                FROM post p
+                LEFT JOIN users u ON p.author_id = u.id
+                WHERE p.parent_id = $1 AND p.publish_at IS NULL
+                ORDER BY p.hot_score DESC, p.id DESC
+                "#,
+                    parent_id,
+                    viewer_id,
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+        };
+
+        children
+            .map(|posts| {
+                posts
+                    .into_iter()
+                    .map(PostWithAuthor::placeholder_if_hidden)
+                    .collect()
+            })
+            .map_err(Into::into)
+    }
+
+    async fn written_by_id(&self, author_id: i32) -> Result<Vec<PostWithAuthor>, ReadError> {
         sqlx::query_as!(
             PostWithAuthor,
-            "
-            SELECT p.*, u.username AS author_username
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS author_username,
+                post_reactions(p.id, $1) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $1) AS "quoted: Json<QuotedPost>"
             FROM post p
             LEFT JOIN users u ON p.author_id = u.id
-            WHERE p.parent_id = $1
+            WHERE p.author_id = $1 AND p.publish_at IS NULL
             ORDER BY p.created_at DESC
-            ",
-            parent_id
+            "#,
+            author_id,
         )
         .fetch_all(&self.pool)
         .await
         .map_err(Into::into)
     }
 
-    async fn written_by_id(&self, author_id: i32) -> Result<Vec<PostWithAuthor>, ReadError> {
+    async fn written_by_username(
+        &self,
+        author_username: &str,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<PostWithAuthor>, ReadError> {
         sqlx::query_as!(
             PostWithAuthor,
-            "
-            SELECT p.*, u.username AS author_username
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                $1 AS author_username,
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
             FROM post p
-            LEFT JOIN users u ON p.author_id = u.id
-            WHERE p.author_id = $1
+            JOIN users u ON u.id = p.author_id
+            WHERE u.username = $1 AND p.publish_at IS NULL AND post_visible_to(p.id, $2)
             ORDER BY p.created_at DESC
-            ",
-            author_id,
+            "#,
+            author_username,
+            viewer_id,
         )
         .fetch_all(&self.pool)
         .await
         .map_err(Into::into)
     }
 
-    async fn written_by_username(
+    async fn quotes_of(
+        &self,
+        quoted_post_id: i32,
+        viewer_id: Option<i32>,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<QuotePage, ReadError> {
+        // One extra post is fetched to find out whether there is another page
+        let mut posts = sqlx::query_as!(
+            PostWithAuthor,
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+            FROM post p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.quoted_post_id = $1
+                AND p.deleted_at IS NULL
+                AND p.publish_at IS NULL
+                AND post_visible_to(p.id, $2)
+                AND ($3::TIMESTAMPTZ IS NULL OR (p.created_at, p.id) < ($3, $4))
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $5
+            "#,
+            quoted_post_id,
+            viewer_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit.saturating_add(1),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let has_more = i64::try_from(posts.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            posts.pop();
+        }
+
+        let next_cursor = has_more.then(|| posts.last().map(PostCursor::of)).flatten();
+
+        Ok(QuotePage { posts, next_cursor })
+    }
+
+    async fn recent_by_username(
         &self,
         author_username: &str,
+        viewer_id: Option<i32>,
+        limit: i64,
     ) -> Result<Vec<PostWithAuthor>, ReadError> {
-        sqlx::query_as!(
+        let posts = sqlx::query_as!(
             PostWithAuthor,
-            "
-            SELECT p.*, $1 AS author_username
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                $1 AS author_username,
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
             FROM post p
             JOIN users u ON u.id = p.author_id
             WHERE u.username = $1
-            ORDER BY p.created_at DESC
-            ",
+                AND p.deleted_at IS NULL
+                AND p.publish_at IS NULL
+                AND post_visible_to(p.id, $2)
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $3
+            "#,
             author_username,
+            viewer_id,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Only an empty result could be from a user who does not exist
+        if posts.is_empty() {
+            let exists = sqlx::query_scalar!(
+                r#"SELECT EXISTS (SELECT 1 FROM users WHERE username = $1) AS "exists!""#,
+                author_username,
+            )
+            .fetch_one(&self.pool)
+            .await?;
+
+            if !exists {
+                return Err(ReadError::NotFound);
+            }
+        }
+
+        Ok(posts)
+    }
+
+    async fn recent_replies_to(
+        &self,
+        parent_id: i32,
+        viewer_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<PostWithAuthor>, ReadError> {
+        sqlx::query_as!(
+            PostWithAuthor,
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+            FROM post p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.parent_id = $1
+                AND p.deleted_at IS NULL
+                AND p.publish_at IS NULL
+                AND post_visible_to(p.id, $2)
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $3
+            "#,
+            parent_id,
+            viewer_id,
+            limit,
         )
         .fetch_all(&self.pool)
         .await
@@ -92,11 +393,12 @@ mod tests {
     use {
         super::*,
         crate::{
-            domain::post::PostRepo as _, infra::post_repo::PgPostRepo,
-            test_utils::seed_data::seed_users_and_root_post,
+            domain::post::PostRepo as _,
+            infra::post_repo::PgPostRepo,
+            test_utils::seed_data::{seed_friends, seed_users_and_root_post},
         },
-        anyhow::Result,
-        chrono::Utc,
+        anyhow::{Context as _, Result},
+        chrono::{TimeDelta, Utc},
         std::assert_matches,
     };
 
@@ -107,19 +409,31 @@ mod tests {
         let read = PgPostWithAuthorRead::new(pool.clone());
 
         let body = "This post exists!";
-        repo.insert_new(&pool, 2, 1, body).await?;
+        repo.insert_new(&pool, 2, 1, body, BodyFormat::Plain, None)
+            .await?;
 
-        let actual = read.by_post_id(2).await;
+        let actual = read.by_post_id(2, Some(1)).await;
         let expected = PostWithAuthor {
             id: 2,
             author_id: Some(2),
             parent_id: Some(1),
             body: Some(String::from(body)),
+            body_format: BodyFormat::Plain,
             created_at: Utc::now(),
             edited_at: None,
             archived_at: None,
             deleted_at: None,
+            descendant_count: 0,
+            reply_policy: ReplyPolicy::Anyone,
+            reply_policy_cascades: false,
+            visibility: PostVisibility::Public,
+            hidden: false,
+
             author_username: Some(users[1].username.clone()),
+            reactions: Json(Vec::new()),
+            mentions: Json(Vec::new()),
+            attachments: Json(Vec::new()),
+            quoted: None,
         };
 
         assert_matches!(actual, Ok(p) if p == expected);
@@ -134,9 +448,10 @@ mod tests {
         let repo = PgPostRepo;
         let read = PgPostWithAuthorRead::new(pool.clone());
 
-        repo.insert_new(&pool, 2, 1, "This post exists!").await?;
+        repo.insert_new(&pool, 2, 1, "This post exists!", BodyFormat::Plain, None)
+            .await?;
 
-        let actual = read.by_post_id(3).await; // Only posts 1 and 2 exist
+        let actual = read.by_post_id(3, Some(1)).await; // Only posts 1 and 2 exist
         assert_matches!(actual, Err(ReadError::NotFound));
 
         Ok(())
@@ -152,34 +467,98 @@ mod tests {
 
         let parent_id = 2;
         // Insert post to be the parent
-        repo.insert_new(&pool, 4, 1, "I'm going to be a parent soon") // ID 2
+        repo.insert_new(&pool, 4, 1, "I'm going to be a parent soon", BodyFormat::Plain, None) // ID 2
             .await?;
         // Should not retrieve sibling
-        repo.insert_new(&pool, 3, 1, "I'm your sibling, not your child") // ID 3
+        repo.insert_new(&pool, 3, 1, "I'm your sibling, not your child", BodyFormat::Plain, None) // ID 3
             .await?;
         // No children at first
-        assert_matches!(read.children_of(parent_id).await, Ok(v) if v.is_empty());
+        assert_matches!(read.children_of(parent_id, PostSort::New, Some(1)).await, Ok(v) if v.is_empty());
         // First child
-        repo.insert_new(&pool, 1, parent_id, "I'm your first child") // ID 4
+        repo.insert_new(&pool, 1, parent_id, "I'm your first child", BodyFormat::Plain, None) // ID 4
             .await?;
         // Should not retrieve grandchildren
-        repo.insert_new(&pool, 2, 4, "I'm your grandchild, not your child") // ID 5
-            .await?;
-        let first_child = read.by_post_id(4).await?;
+        repo.insert_new(
+            &pool,
+            2,
+            4,
+            "I'm your grandchild, not your child",
+            BodyFormat::Plain,
+            None,
+        ) // ID 5
+        .await?;
+        let first_child = read.by_post_id(4, Some(1)).await?;
         assert_matches!(
-            read.children_of(parent_id).await,
+            read.children_of(parent_id, PostSort::New, Some(1)).await,
             Ok(v) if v.len() == 1 && v.first() == Some(&first_child)
         );
         // More children
-        repo.insert_new(&pool, 2, parent_id, "Second child here") // ID 6
+        repo.insert_new(&pool, 2, parent_id, "Second child here", BodyFormat::Plain, None) // ID 6
             .await?;
-        repo.insert_new(&pool, 3, parent_id, "Third child here") // ID 7
+        repo.insert_new(&pool, 3, parent_id, "Third child here", BodyFormat::Plain, None) // ID 7
             .await?;
-        let second_child = read.by_post_id(6).await?;
-        let third_child = read.by_post_id(7).await?;
+        let second_child = read.by_post_id(6, Some(1)).await?;
+        let third_child = read.by_post_id(7, Some(1)).await?;
         // Should be sorted in descending order of creation time
         let expected_children = vec![third_child, second_child, first_child];
-        assert_matches!(read.children_of(parent_id).await, Ok(v) if v == expected_children);
+        assert_matches!(read.children_of(parent_id, PostSort::New, Some(1)).await, Ok(v) if v == expected_children);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn counts_descendants_and_sorts_children_by_them(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+        let read = PgPostWithAuthorRead::new(pool.clone());
+
+        repo.insert_new(&pool, 2, 1, "A", BodyFormat::Plain, None)
+            .await?; // ID 2
+        repo.insert_new(&pool, 3, 1, "B", BodyFormat::Plain, None)
+            .await?; // ID 3
+        repo.insert_new(&pool, 4, 1, "C", BodyFormat::Plain, None)
+            .await?; // ID 4
+        repo.insert_new(&pool, 3, 2, "Under A", BodyFormat::Plain, None)
+            .await?; // ID 5
+        repo.insert_new(&pool, 2, 3, "Under B", BodyFormat::Plain, None)
+            .await?; // ID 6
+        repo.insert_new(&pool, 4, 3, "Also under B", BodyFormat::Plain, None)
+            .await?; // ID 7
+        repo.insert_new(&pool, 3, 6, "Deep under B", BodyFormat::Plain, None)
+            .await?; // ID 8
+
+        // A is the oldest, then B, then C
+        sqlx::query!(
+            "
+            UPDATE post
+            SET created_at = created_at - make_interval(hours => CASE id WHEN 2 THEN 8 ELSE 4 END)
+            WHERE id IN (2, 3)
+            "
+        )
+        .execute(&pool)
+        .await?;
+
+        // The root post's descendants aren't counted
+        assert_eq!(0, read.by_post_id(1, Some(1)).await?.descendant_count);
+        assert_eq!(3, read.by_post_id(3, Some(1)).await?.descendant_count);
+        assert_eq!(1, read.by_post_id(6, Some(1)).await?.descendant_count);
+
+        let mut orders = Vec::new();
+        for sort in [PostSort::New, PostSort::Old, PostSort::Top, PostSort::Hot] {
+            let children = read.children_of(1, sort, Some(1)).await?;
+            orders.push(children.iter().map(|child| child.id).collect::<Vec<_>>());
+        }
+
+        assert_eq!(
+            vec![
+                vec![4, 3, 2],
+                vec![2, 3, 4],
+                vec![3, 2, 4],
+                // B's activity makes up for its age, but A's does not
+                vec![3, 4, 2],
+            ],
+            orders,
+        );
 
         Ok(())
     }
@@ -191,19 +570,34 @@ mod tests {
         let read = PgPostWithAuthorRead::new(pool.clone());
 
         // Post ID 2
-        repo.insert_new(&pool, 3, 1, "First post by user 3").await?;
-        // Post ID 3
-        repo.insert_new(&pool, 2, 2, "This post by user 2 should not come up")
+        repo.insert_new(&pool, 3, 1, "First post by user 3", BodyFormat::Plain, None)
             .await?;
+        // Post ID 3
+        repo.insert_new(
+            &pool,
+            2,
+            2,
+            "This post by user 2 should not come up",
+            BodyFormat::Plain,
+            None,
+        )
+        .await?;
         // Post ID 4
-        repo.insert_new(&pool, 4, 1, "This post by user 4 should not come up ")
-            .await?;
+        repo.insert_new(
+            &pool,
+            4,
+            1,
+            "This post by user 4 should not come up ",
+            BodyFormat::Plain,
+            None,
+        )
+        .await?;
         // Post ID 5
-        repo.insert_new(&pool, 3, 4, "Second post by user 3")
+        repo.insert_new(&pool, 3, 4, "Second post by user 3", BodyFormat::Plain, None)
             .await?;
 
-        let expected1 = read.by_post_id(2).await?;
-        let expected2 = read.by_post_id(5).await?;
+        let expected1 = read.by_post_id(2, Some(1)).await?;
+        let expected2 = read.by_post_id(5, Some(1)).await?;
 
         // Should be sorted by created_at in descending order
         let expected_posts = vec![expected2, expected1];
@@ -211,10 +605,184 @@ mod tests {
 
         // Searching by username should be the same result
         assert_matches!(
-            read.written_by_username(&users[2].username).await,
+            read.written_by_username(&users[2].username, Some(3)).await,
             Ok(v) if v == expected_posts
         );
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn pages_through_posts_quoting_a_post_with_their_previews(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+        let read = PgPostWithAuthorRead::new(pool.clone());
+
+        for (author_id, parent_id, body, quoted_post_id) in [
+            (2, 1, "Original thought", None),           // ID 2
+            (3, 1, "Quoting 2", Some(2)),               // ID 3
+            (4, 1, "Also quoting 2", Some(2)),          // ID 4
+            (4, 3, "Quoting 2, then deleted", Some(2)), // ID 5
+            (2, 3, "Quoting the root", Some(1)),        // ID 6
+        ] {
+            repo.insert_new(&pool, author_id, parent_id, body, BodyFormat::Plain, quoted_post_id)
+                .await?;
+        }
+        repo.soft_delete(&pool, 5).await?;
+
+        let first_page = read.quotes_of(2, Some(1), None, 1).await?;
+        let second_page = read
+            .quotes_of(2, Some(1), first_page.next_cursor, 1)
+            .await?;
+
+        let ids = |page: &QuotePage| page.posts.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(vec![4], ids(&first_page));
+        assert_eq!(vec![3], ids(&second_page));
+        assert_eq!(None, second_page.next_cursor);
+
+        let preview = |post: Option<&PostWithAuthor>| {
+            post.and_then(|p| p.quoted.as_ref())
+                .map(|quoted| quoted.0.clone())
+        };
+        assert_eq!(
+            Some(QuotedPost {
+                id: 2,
+                author_username: Some(users[1].username.clone()),
+                excerpt: Some(String::from("Original thought")),
+                hidden: false,
+            }),
+            preview(first_page.posts.first()),
+        );
+
+        // Quoting posts outlive the quoted post, which is shown as deleted
+        repo.soft_delete(&pool, 2).await?;
+        let after_deletion = read.by_post_id(3, Some(1)).await?;
+        assert_eq!(Some(None), preview(Some(&after_deletion)).map(|quoted| quoted.excerpt),);
+        assert_eq!(None, read.by_post_id(2, Some(1)).await?.quoted);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn gets_a_limited_number_of_recent_posts_for_feeds(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        let repo = PgPostRepo;
+        let read = PgPostWithAuthorRead::new(pool.clone());
+
+        for (author_id, parent_id) in [
+            (2, 1), // ID 2
+            (3, 2), // ID 3
+            (4, 2), // ID 4, deleted
+            (1, 2), // ID 5
+            (2, 3), // ID 6, friends only
+            (2, 5), // ID 7, deleted
+            (2, 6), // ID 8
+        ] {
+            repo.insert_new(&pool, author_id, parent_id, "Post", BodyFormat::Plain, None)
+                .await?;
+        }
+        repo.set_visibility(&pool, 6, PostVisibility::Friends)
+            .await?;
+        repo.soft_delete(&pool, 4).await?;
+        repo.soft_delete(&pool, 7).await?;
+
+        let ids = |posts: Vec<PostWithAuthor>| posts.iter().map(|p| p.id).collect::<Vec<_>>();
+        let author = &users[1].username;
+        assert_eq!(vec![8, 2], ids(read.recent_by_username(author, Some(1), 10).await?));
+        assert_eq!(vec![8], ids(read.recent_by_username(author, Some(1), 1).await?));
+        assert_eq!(vec![5, 3], ids(read.recent_replies_to(2, Some(1), 10).await?));
+        assert_eq!(vec![5], ids(read.recent_replies_to(2, Some(1), 1).await?));
+
+        // Users without posts are told apart from users who don't exist
+        assert_matches!(read.recent_by_username(&users[3].username, None, 10).await, Ok(v) if v.is_empty());
+        assert_matches!(
+            read.recent_by_username("nobody_here", None, 10).await,
+            Err(ReadError::NotFound)
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn shows_hidden_posts_as_placeholders_in_threads_and_leaves_them_out_elsewhere(
+        pool: PgPool,
+    ) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        seed_friends(&pool).await?;
+        let repo = PgPostRepo;
+        let read = PgPostWithAuthorRead::new(pool.clone());
+
+        // User 3 is a friend of user 2, but user 1 isn't
+        repo.insert_new(&pool, 2, 1, "Friends only", BodyFormat::Plain, None)
+            .await?; // ID 2
+        repo.set_visibility(&pool, 2, PostVisibility::Friends)
+            .await?;
+        repo.insert_new(&pool, 3, 2, "Quoting a secret", BodyFormat::Plain, Some(2))
+            .await?; // ID 3
+
+        let seen_by_friend = read.by_post_id(2, Some(3)).await?;
+        assert!(!seen_by_friend.hidden);
+        assert_eq!(Some("Friends only"), seen_by_friend.body.as_deref());
+
+        let placeholder = read.by_post_id(2, Some(1)).await?;
+        assert!(placeholder.hidden);
+        assert_eq!(
+            (2, Some(1), None, None),
+            (
+                placeholder.id,
+                placeholder.parent_id,
+                placeholder.author_id,
+                placeholder.body.as_deref()
+            ),
+        );
+        assert_eq!(None, placeholder.author_username);
+
+        // The thread keeps its shape
+        assert_eq!(vec![placeholder], read.children_of(1, PostSort::New, Some(1)).await?);
+        let quote = read
+            .by_post_id(3, Some(1))
+            .await?
+            .quoted
+            .context("quote was unexpectedly None")?;
+        assert_eq!((true, None), (quote.hidden, quote.0.excerpt));
+
+        assert!(
+            read.written_by_username(&users[1].username, Some(1))
+                .await?
+                .is_empty()
+        );
+        assert_eq!(
+            1,
+            read.written_by_username(&users[1].username, Some(3))
+                .await?
+                .len()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn hides_scheduled_posts(pool: PgPool) -> Result<()> {
+        let users = seed_users_and_root_post(&pool).await?;
+        let read = PgPostWithAuthorRead::new(pool.clone());
+
+        let publish_at = Utc::now()
+            .checked_add_signed(TimeDelta::hours(1))
+            .context("out of range")?;
+        let id = PgPostRepo
+            .insert_scheduled(&pool, 3, 1, "Coming soon", BodyFormat::Plain, publish_at)
+            .await?;
+
+        assert_matches!(read.by_post_id(id, Some(1)).await, Err(ReadError::NotFound));
+        for sort in [PostSort::New, PostSort::Old, PostSort::Top, PostSort::Hot] {
+            assert_matches!(read.children_of(1, sort, Some(3)).await, Ok(v) if v.is_empty());
+        }
+        assert_matches!(read.written_by_id(3).await, Ok(v) if v.is_empty());
+        assert_matches!(
+            read.written_by_username(&users[2].username, Some(3)).await,
+            Ok(v) if v.is_empty()
+        );
+
+        Ok(())
+    }
 }
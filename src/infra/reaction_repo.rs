@@ -0,0 +1,106 @@
+use {
+    crate::{
+        domain::{RepoError, reaction::ReactionRepo},
+        models::reaction::ReactionKind,
+    },
+    sqlx::PgExecutor,
+};
+
+pub struct PgReactionRepo;
+
+#[async_trait::async_trait]
+impl ReactionRepo for PgReactionRepo {
+    async fn upsert(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "
+            INSERT INTO post_reaction (post_id, user_id, kind)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (post_id, user_id)
+            DO UPDATE SET kind = EXCLUDED.kind, created_at = CURRENT_TIMESTAMP
+            WHERE post_reaction.kind <> EXCLUDED.kind
+            ",
+            post_id,
+            user_id,
+            kind as ReactionKind,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<bool, RepoError> {
+        sqlx::query!(
+            "DELETE FROM post_reaction WHERE post_id = $1 AND user_id = $2 AND kind = $3",
+            post_id,
+            user_id,
+            kind as ReactionKind,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::post::PostRepo as _,
+            infra::{post_repo::PgPostRepo, post_with_author_read::PgPostWithAuthorRead},
+            models::reaction::ReactionCount,
+            read_models::PostWithAuthorRead as _,
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::Result,
+        sqlx::PgPool,
+    };
+
+    #[sqlx::test]
+    async fn counts_one_reaction_per_user_and_marks_the_viewers_own(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgReactionRepo;
+        let read = PgPostWithAuthorRead::new(pool.clone());
+
+        repo.upsert(&pool, 2, 1, ReactionKind::Like).await?;
+        repo.upsert(&pool, 3, 1, ReactionKind::Angry).await?;
+        repo.upsert(&pool, 4, 1, ReactionKind::Angry).await?;
+        // Reacting again replaces the previous reaction
+        repo.upsert(&pool, 2, 1, ReactionKind::Angry).await?;
+        repo.upsert(&pool, 4, 1, ReactionKind::Love).await?;
+
+        let count = |kind, count, reacted_by_me| ReactionCount { kind, count, reacted_by_me };
+        assert_eq!(
+            vec![count(ReactionKind::Love, 1, true), count(ReactionKind::Angry, 2, false)],
+            read.by_post_id(1, Some(4)).await?.reactions.0,
+        );
+
+        // Only a reaction of the matching kind is removed
+        assert!(!repo.delete(&pool, 2, 1, ReactionKind::Like).await?);
+        assert!(repo.delete(&pool, 2, 1, ReactionKind::Angry).await?);
+        assert_eq!(
+            vec![count(ReactionKind::Love, 1, false), count(ReactionKind::Angry, 1, true)],
+            read.by_post_id(1, Some(3)).await?.reactions.0,
+        );
+
+        // Deleted posts show no reactions
+        PgPostRepo.soft_delete(&pool, 1).await?;
+        assert!(read.by_post_id(1, Some(3)).await?.reactions.is_empty());
+
+        Ok(())
+    }
+}
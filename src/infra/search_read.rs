@@ -0,0 +1,176 @@
+use {
+    crate::{
+        models::{
+            attachment::AttachmentSummary,
+            mention::MentionSpan,
+            post::{BodyFormat, PostVisibility, PostWithAuthor, QuotedPost, ReplyPolicy},
+            reaction::ReactionCount,
+            search::{SearchPage, UserSearchResult},
+        },
+        read_models::{ReadError, SearchRead},
+    },
+    sqlx::{PgPool, types::Json},
+};
+
+pub struct PgSearchRead {
+    pool: PgPool,
+}
+
+impl PgSearchRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl SearchRead for PgSearchRead {
+    async fn posts(
+        &self,
+        query: &str,
+        viewer_id: i32,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SearchPage<PostWithAuthor>, ReadError> {
+        // One extra post is fetched to find out whether there is another page
+        let posts = sqlx::query_as!(
+            PostWithAuthor,
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+            FROM post p
+            CROSS JOIN websearch_to_tsquery('english', $1) AS query
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.deleted_at IS NULL
+                AND p.publish_at IS NULL
+                AND p.search_vector @@ query
+                AND post_visible_to(p.id, $2)
+            ORDER BY ts_rank_cd(p.search_vector, query) DESC, p.id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            query,
+            viewer_id,
+            limit.saturating_add(1),
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(SearchPage::from_overfetched(posts, offset, limit))
+    }
+
+    async fn users(
+        &self,
+        query: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SearchPage<UserSearchResult>, ReadError> {
+        // Word similarity lets partial usernames and single names match
+        let users = sqlx::query_as!(
+            UserSearchResult,
+            "
+            SELECT username, name
+            FROM users
+            WHERE $1 <% username OR $1 <% name
+            ORDER BY GREATEST(word_similarity($1, username), word_similarity($1, name)) DESC, id
+            LIMIT $2 OFFSET $3
+            ",
+            query,
+            limit.saturating_add(1),
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(SearchPage::from_overfetched(users, offset, limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::post::PostRepo as _,
+            infra::post_repo::PgPostRepo,
+            models::user::NewUser,
+            test_utils::seed_data::{seed_users, seed_users_and_root_post},
+        },
+        anyhow::Result,
+    };
+
+    #[sqlx::test]
+    async fn ranks_and_pages_matching_posts_excluding_deleted_ones(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let read = PgSearchRead::new(pool.clone());
+        let repo = PgPostRepo;
+
+        repo.insert_new(&pool, 2, 1, "I like gardening", BodyFormat::Plain, None)
+            .await?; // ID 2
+        repo.insert_new(
+            &pool,
+            3,
+            1,
+            "Gardens and gardeners and gardening",
+            BodyFormat::Plain,
+            None,
+        )
+        .await?; // ID 3
+        repo.insert_new(&pool, 4, 1, "Cooking is more my thing", BodyFormat::Plain, None)
+            .await?; // ID 4
+        let deleted_id = repo
+            .insert_new(&pool, 1, 2, "Gardening forever", BodyFormat::Plain, None)
+            .await?; // ID 5
+        repo.soft_delete(&pool, deleted_id).await?;
+
+        let ids = |page: &SearchPage<PostWithAuthor>| {
+            page.results.iter().map(|p| p.id).collect::<Vec<_>>()
+        };
+
+        // Stemming matches every form of the word, and more mentions rank higher
+        let first_page = read.posts("garden", 1, 0, 1).await?;
+        assert_eq!(vec![3], ids(&first_page));
+        assert_eq!(Some(1), first_page.next_offset);
+
+        let second_page = read.posts("garden", 1, 1, 1).await?;
+        assert_eq!(vec![2], ids(&second_page));
+        assert_eq!(None, second_page.next_offset);
+
+        assert!(read.posts("baking", 1, 0, 10).await?.results.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn finds_users_by_partial_username_or_name(pool: PgPool) -> Result<()> {
+        let [drake, eunice, _, gillian] = seed_users(&pool).await?;
+        let read = PgSearchRead::new(pool.clone());
+
+        let result = |user: &NewUser| UserSearchResult {
+            username: user.username.clone(),
+            name: user.name.clone(),
+        };
+
+        assert_eq!(
+            SearchPage { results: vec![result(&drake)], next_offset: None },
+            read.users("drake", 0, 10).await?,
+        );
+        assert_eq!(
+            SearchPage { results: vec![result(&eunice)], next_offset: Some(1) },
+            read.users("lee", 0, 1).await?,
+        );
+        assert_eq!(
+            SearchPage { results: vec![result(&gillian)], next_offset: None },
+            read.users("lee", 1, 1).await?,
+        );
+        assert!(read.users("zzz", 0, 10).await?.results.is_empty());
+
+        Ok(())
+    }
+}
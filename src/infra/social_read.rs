@@ -1,11 +1,22 @@
 use {
     crate::{
-        models::post::PostWithAuthor,
+        models::{
+            attachment::AttachmentSummary,
+            mention::MentionSpan,
+            post::{
+                BodyFormat, ParentSummary, PostCursor, PostVisibility, PostWithAuthor, QuotedPost,
+                ReplyPolicy, TimelinePage, TimelinePost,
+            },
+            reaction::ReactionCount,
+        },
         read_models::{ReadError, SocialRead},
     },
-    sqlx::PgPool,
+    sqlx::{PgPool, types::Json},
 };
 
+/// The maximum number of characters of a parent post's body included in its summary.
+pub const PARENT_EXCERPT_CHARS: i32 = 140;
+
 pub struct PgSocialRead {
     pool: PgPool,
 }
@@ -73,8 +84,18 @@ impl SocialRead for PgSocialRead {
     async fn friend_posts(&self, user_id: i32) -> Result<Vec<PostWithAuthor>, ReadError> {
         sqlx::query_as!(
             PostWithAuthor,
-            "
-            SELECT p.*, u.username AS author_username
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS author_username,
+                post_reactions(p.id, $1) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $1) AS "quoted: Json<QuotedPost>"
             FROM post p
             LEFT JOIN users u ON p.author_id = u.id
             JOIN (
@@ -87,14 +108,142 @@ impl SocialRead for PgSocialRead {
                 WHERE f.confirmed_at IS NOT NULL AND (f.lesser_id = $1 OR f.greater_id = $1)
             ) AS friends
             ON p.author_id = friends.friend_id
+            WHERE p.publish_at IS NULL AND post_visible_to(p.id, $1)
             ORDER BY p.created_at DESC
-            ",
+            "#,
             user_id,
         )
         .fetch_all(&self.pool)
         .await
         .map_err(Into::into)
     }
+
+    #[expect(clippy::too_many_lines, reason = "One static query with a candidate set per reason")]
+    async fn home_timeline(
+        &self,
+        user_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<TimelinePage, ReadError> {
+        // One extra post is fetched to find out whether there is another page
+        let mut rows = sqlx::query!(
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $1) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $1) AS "quoted: Json<QuotedPost>",
+                parent.id AS "parent_summary_id?",
+                -- Parents that the user may not see are summarized by their ID alone
+                CASE
+                    WHEN post_visible_to(parent.id, $1) THEN parent_author.username
+                END AS "parent_author_username?",
+                CASE WHEN post_visible_to(parent.id, $1) THEN LEFT(parent.body, $5) END
+                    AS parent_excerpt
+            FROM post p
+            LEFT JOIN users u ON u.id = p.author_id
+            LEFT JOIN post parent ON parent.id = p.parent_id
+            LEFT JOIN users parent_author ON parent_author.id = parent.author_id
+            WHERE p.id IN (
+                    -- Written by a friend
+                    SELECT friend_post.id
+                    FROM post friend_post
+                    WHERE friend_post.author_id IN (
+                        SELECT greater_id FROM friendship
+                        WHERE lesser_id = $1 AND confirmed_at IS NOT NULL
+                        UNION ALL
+                        SELECT lesser_id FROM friendship
+                        WHERE greater_id = $1 AND confirmed_at IS NOT NULL
+                    )
+                        AND friend_post.deleted_at IS NULL
+                        AND friend_post.publish_at IS NULL
+
+                    UNION
+
+                    -- Replying to the user
+                    SELECT reply.id
+                    FROM post own
+                    JOIN post reply ON reply.parent_id = own.id
+                    WHERE own.author_id = $1
+                        AND reply.deleted_at IS NULL
+                        AND reply.publish_at IS NULL
+
+                    UNION
+
+                    -- Anywhere in a thread that the user has posted in
+                    SELECT thread_post.id
+                    FROM post thread_post
+                    WHERE thread_post.thread_id IN (
+                        SELECT own.thread_id FROM post own
+                        WHERE own.author_id = $1 AND own.publish_at IS NULL
+                    )
+                        AND thread_post.deleted_at IS NULL
+                        AND thread_post.publish_at IS NULL
+                )
+                AND p.author_id IS DISTINCT FROM $1
+                AND post_visible_to(p.id, $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR (p.created_at, p.id) < ($2, $3))
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $4
+            "#,
+            user_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit.saturating_add(1),
+            PARENT_EXCERPT_CHARS,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let has_more = i64::try_from(rows.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            rows.pop();
+        }
+
+        let posts = rows
+            .into_iter()
+            .map(|row| TimelinePost {
+                post: PostWithAuthor {
+                    id: row.id,
+                    author_id: row.author_id,
+                    parent_id: row.parent_id,
+                    body: row.body,
+                    body_format: row.body_format,
+                    created_at: row.created_at,
+                    edited_at: row.edited_at,
+                    archived_at: row.archived_at,
+                    deleted_at: row.deleted_at,
+                    descendant_count: row.descendant_count,
+                    reply_policy: row.reply_policy,
+                    reply_policy_cascades: row.reply_policy_cascades,
+                    visibility: row.visibility,
+                    hidden: row.hidden,
+                    author_username: row.author_username,
+                    reactions: row.reactions,
+                    mentions: row.mentions,
+                    attachments: row.attachments,
+                    quoted: row.quoted,
+                },
+                parent: row.parent_summary_id.map(|id| ParentSummary {
+                    id,
+                    author_username: row.parent_author_username,
+                    excerpt: row.parent_excerpt,
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        let next_cursor = has_more
+            .then(|| posts.last().map(|last| PostCursor::of(&last.post)))
+            .flatten();
+
+        Ok(TimelinePage { posts, next_cursor })
+    }
 }
 
 #[cfg(test)]
@@ -114,6 +263,7 @@ mod tests {
             test_utils::seed_data::{seed_friends, seed_root_post, seed_users},
         },
         anyhow::{Context as _, Result},
+        chrono::{TimeDelta, Utc},
     };
 
     #[sqlx::test]
@@ -209,21 +359,29 @@ mod tests {
         let u4p1_body = "User four post one";
         let u4p2_body = "User four post two";
 
-        repo.insert_new(&pool, 4, 1, u4p1_body).await?; // ID 2
-        repo.insert_new(&pool, 3, 1, u3p1_body).await?; // ID 3
-        repo.insert_new(&pool, 2, 1, u2p1_body).await?; // ID 4
-        repo.insert_new(&pool, 1, 2, u1p2_body).await?; // ID 5
-        repo.insert_new(&pool, 4, 3, u4p2_body).await?; // ID 6
-        repo.insert_new(&pool, 3, 2, u3p2_body).await?; // ID 7
-        repo.insert_new(&pool, 2, 2, u2p2_body).await?; // ID 8
-        repo.insert_new(&pool, 1, 3, u1p3_body).await?; // ID 9
-
-        let u2p1 = post_with_author_read.by_post_id(4).await?;
-        let u2p2 = post_with_author_read.by_post_id(8).await?;
-        let u3p1 = post_with_author_read.by_post_id(3).await?;
-        let u3p2 = post_with_author_read.by_post_id(7).await?;
-        let u4p1 = post_with_author_read.by_post_id(2).await?;
-        let u4p2 = post_with_author_read.by_post_id(6).await?;
+        repo.insert_new(&pool, 4, 1, u4p1_body, BodyFormat::Plain, None)
+            .await?; // ID 2
+        repo.insert_new(&pool, 3, 1, u3p1_body, BodyFormat::Plain, None)
+            .await?; // ID 3
+        repo.insert_new(&pool, 2, 1, u2p1_body, BodyFormat::Plain, None)
+            .await?; // ID 4
+        repo.insert_new(&pool, 1, 2, u1p2_body, BodyFormat::Plain, None)
+            .await?; // ID 5
+        repo.insert_new(&pool, 4, 3, u4p2_body, BodyFormat::Plain, None)
+            .await?; // ID 6
+        repo.insert_new(&pool, 3, 2, u3p2_body, BodyFormat::Plain, None)
+            .await?; // ID 7
+        repo.insert_new(&pool, 2, 2, u2p2_body, BodyFormat::Plain, None)
+            .await?; // ID 8
+        repo.insert_new(&pool, 1, 3, u1p3_body, BodyFormat::Plain, None)
+            .await?; // ID 9
+
+        let u2p1 = post_with_author_read.by_post_id(4, Some(2)).await?;
+        let u2p2 = post_with_author_read.by_post_id(8, Some(2)).await?;
+        let u3p1 = post_with_author_read.by_post_id(3, Some(2)).await?;
+        let u3p2 = post_with_author_read.by_post_id(7, Some(3)).await?;
+        let u4p1 = post_with_author_read.by_post_id(2, Some(2)).await?;
+        let u4p2 = post_with_author_read.by_post_id(6, Some(2)).await?;
 
         let u1_friend_posts = read.friend_posts(1).await?;
         let u2_friend_posts = read.friend_posts(2).await?;
@@ -241,4 +399,106 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn builds_a_deduplicated_paginated_home_timeline(pool: PgPool) -> Result<()> {
+        let read = PgSocialRead::new(pool.clone());
+        let post_with_author_read = PgPostWithAuthorRead::new(pool.clone());
+        let repo = PgPostRepo;
+        let [u1, _, u3, _] = seed_users(&pool).await?;
+        seed_root_post(&pool).await?;
+        seed_friends(&pool).await?;
+
+        let long_body = "a".repeat(200);
+
+        repo.insert_new(&pool, 3, 1, "Mine", BodyFormat::Plain, None)
+            .await?; // ID 2
+        repo.insert_new(&pool, 1, 1, &long_body, BodyFormat::Plain, None)
+            .await?; // ID 3, starts another thread
+        repo.insert_new(&pool, 2, 2, "From a friend to me", BodyFormat::Plain, None)
+            .await?; // ID 4, both reasons
+        repo.insert_new(&pool, 4, 2, "From a stranger to me", BodyFormat::Plain, None)
+            .await?; // ID 5
+        repo.insert_new(&pool, 4, 3, "Unrelated", BodyFormat::Plain, None)
+            .await?; // ID 6
+        repo.insert_new(&pool, 2, 3, "From a friend elsewhere", BodyFormat::Plain, None)
+            .await?; // ID 7
+        repo.insert_new(&pool, 3, 5, "Mine again", BodyFormat::Plain, None)
+            .await?; // ID 8
+        let deleted_id = repo
+            .insert_new(&pool, 1, 2, "Deleted", BodyFormat::Plain, None)
+            .await?; // ID 9, to me
+        repo.soft_delete(&pool, deleted_id).await?;
+        repo.insert_new(&pool, 1, 4, "Deep in my thread", BodyFormat::Plain, None)
+            .await?; // ID 10
+
+        let first_page = read.home_timeline(3, None, 2).await?;
+        let second_page = read.home_timeline(3, first_page.next_cursor, 2).await?;
+
+        let ids = |page: &TimelinePage| page.posts.iter().map(|p| p.post.id).collect::<Vec<_>>();
+        assert_eq!(vec![10, 7], ids(&first_page));
+        assert_eq!(vec![5, 4], ids(&second_page));
+        assert_eq!(None, second_page.next_cursor);
+
+        let seven = first_page.posts.get(1).context("missing post 7")?;
+        assert_eq!(post_with_author_read.by_post_id(7, Some(3)).await?, seven.post);
+        assert_eq!(
+            Some(ParentSummary {
+                id: 3,
+                author_username: Some(u1.username),
+                excerpt: Some("a".repeat(140)),
+            }),
+            seven.parent,
+        );
+
+        let five = second_page.posts.first().context("missing post 5")?;
+        assert_eq!(
+            Some(ParentSummary {
+                id: 2,
+                author_username: Some(u3.username),
+                excerpt: Some(String::from("Mine")),
+            }),
+            five.parent,
+        );
+
+        // The next page begins right after the last post returned
+        assert_eq!(Some(PostCursor::of(&seven.post)), first_page.next_cursor);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn follows_every_branch_of_threads_the_user_has_posted_in(pool: PgPool) -> Result<()> {
+        let read = PgSocialRead::new(pool.clone());
+        let repo = PgPostRepo;
+        seed_users(&pool).await?;
+        seed_root_post(&pool).await?;
+        seed_friends(&pool).await?;
+
+        repo.insert_new(&pool, 4, 1, "A stranger's thread", BodyFormat::Plain, None)
+            .await?; // ID 2
+        repo.insert_new(&pool, 4, 2, "Another branch", BodyFormat::Plain, None)
+            .await?; // ID 3
+        repo.insert_new(&pool, 3, 2, "Joining in", BodyFormat::Plain, None)
+            .await?; // ID 4
+        repo.insert_new(&pool, 1, 3, "Deep in the other branch", BodyFormat::Plain, None)
+            .await?; // ID 5
+        repo.insert_new(&pool, 1, 1, "Elsewhere", BodyFormat::Plain, None)
+            .await?; // ID 6
+        repo.insert_new(&pool, 4, 6, "Still elsewhere", BodyFormat::Plain, None)
+            .await?; // ID 7
+        let later = Utc::now()
+            .checked_add_signed(TimeDelta::hours(1))
+            .context("out of range")?;
+        repo.insert_scheduled(&pool, 4, 3, "Not yet", BodyFormat::Plain, later)
+            .await?; // ID 8
+
+        let page = read.home_timeline(3, None, 20).await?;
+
+        let ids = page.posts.iter().map(|p| p.post.id).collect::<Vec<_>>();
+        assert_eq!(vec![5, 3, 2], ids);
+        assert_eq!(None, page.next_cursor);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,180 @@
+use {
+    crate::{
+        models::{
+            attachment::AttachmentSummary,
+            mention::MentionSpan,
+            post::{
+                BodyFormat, PostCursor, PostVisibility, PostWithAuthor, QuotedPost, ReplyPolicy,
+            },
+            reaction::ReactionCount,
+            tag::{TagPage, TrendingTag},
+        },
+        read_models::{ReadError, TagRead},
+    },
+    chrono::TimeDelta,
+    sqlx::{PgPool, types::Json},
+};
+
+pub struct PgTagRead {
+    pool: PgPool,
+}
+
+impl PgTagRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl TagRead for PgTagRead {
+    async fn tagged(
+        &self,
+        tag: &str,
+        viewer_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<TagPage, ReadError> {
+        // One extra post is fetched to find out whether there is another page
+        let mut posts = sqlx::query_as!(
+            PostWithAuthor,
+            r#"
+            SELECT
+                p.id, p.author_id, p.parent_id, p.body, p.created_at, p.edited_at,
+                p.archived_at, p.deleted_at, p.descendant_count,
+                p.body_format AS "body_format: BodyFormat",
+                p.reply_policy AS "reply_policy: ReplyPolicy", p.reply_policy_cascades,
+                p.visibility AS "visibility: PostVisibility", false AS "hidden!",
+                u.username AS "author_username?",
+                post_reactions(p.id, $2) AS "reactions!: Json<Vec<ReactionCount>>",
+                post_mentions(p.id) AS "mentions!: Json<Vec<MentionSpan>>",
+                post_attachments(p.id) AS "attachments!: Json<Vec<AttachmentSummary>>",
+                post_quote(p.quoted_post_id, $2) AS "quoted: Json<QuotedPost>"
+            FROM tag t
+            JOIN post_tag pt ON pt.tag_id = t.id
+            JOIN post p ON p.id = pt.post_id
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE t.name = $1
+                AND p.deleted_at IS NULL
+                AND post_visible_to(p.id, $2)
+                AND ($3::TIMESTAMPTZ IS NULL OR (p.created_at, p.id) < ($3, $4))
+            ORDER BY p.created_at DESC, p.id DESC
+            LIMIT $5
+            "#,
+            tag,
+            viewer_id,
+            after.map(|cursor| cursor.created_at),
+            after.map(|cursor| cursor.id),
+            limit.saturating_add(1),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let has_more = i64::try_from(posts.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            posts.pop();
+        }
+
+        let next_cursor = has_more.then(|| posts.last().map(PostCursor::of)).flatten();
+
+        Ok(TagPage { posts, next_cursor })
+    }
+
+    async fn trending(&self, window: TimeDelta, limit: i64) -> Result<Vec<TrendingTag>, ReadError> {
+        sqlx::query_as!(
+            TrendingTag,
+            r#"
+            SELECT t.name, COUNT(*) AS "post_count!"
+            FROM tag t
+            JOIN post_tag pt ON pt.tag_id = t.id
+            JOIN post p ON p.id = pt.post_id
+            WHERE p.deleted_at IS NULL
+                AND p.created_at >= CURRENT_TIMESTAMP - make_interval(secs => $1)
+            GROUP BY t.id
+            ORDER BY COUNT(*) DESC, t.name
+            LIMIT $2
+            "#,
+            window.as_seconds_f64(),
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::post::{PostRepo as _, hashtag::parse_hashtags},
+            infra::post_repo::PgPostRepo,
+            test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::Result,
+    };
+
+    /// Creates posts 2 through 6, tagged according to their bodies, and deletes post 5.
+    async fn seed_tagged_posts(pool: &PgPool) -> Result<()> {
+        let repo = PgPostRepo;
+
+        for (author_id, parent_id, body) in [
+            (2, 1, "Learning #Rust"),         // ID 2
+            (3, 1, "#rust and #async"),       // ID 3
+            (4, 1, "No tags here"),           // ID 4
+            (2, 3, "#async #rust (deleted)"), // ID 5
+            (3, 2, "Just #async"),            // ID 6
+        ] {
+            let post_id = repo
+                .insert_new(pool, author_id, parent_id, body, BodyFormat::Plain, None)
+                .await?;
+            repo.insert_tags(pool, post_id, &parse_hashtags(body))
+                .await?;
+        }
+        repo.soft_delete(pool, 5).await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn pages_through_posts_with_a_tag(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        seed_tagged_posts(&pool).await?;
+        let read = PgTagRead::new(pool.clone());
+
+        let first_page = read.tagged("rust", 1, None, 1).await?;
+        let second_page = read.tagged("rust", 1, first_page.next_cursor, 1).await?;
+
+        let ids = |page: &TagPage| page.posts.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(vec![3], ids(&first_page));
+        assert_eq!(vec![2], ids(&second_page));
+        assert_eq!(None, second_page.next_cursor);
+
+        assert!(read.tagged("nothing", 1, None, 10).await?.posts.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn ranks_tags_used_within_the_window(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        seed_tagged_posts(&pool).await?;
+        let read = PgTagRead::new(pool.clone());
+
+        // Post 2 is too old to count
+        sqlx::query!("UPDATE post SET created_at = created_at - INTERVAL '2 days' WHERE id = 2")
+            .execute(&pool)
+            .await?;
+
+        let trending = read
+            .trending(TimeDelta::days(1), 10)
+            .await?
+            .into_iter()
+            .map(|tag| (tag.name, tag.post_count))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(String::from("async"), 2), (String::from("rust"), 1)], trending);
+
+        let everything = read.trending(TimeDelta::days(7), 1).await?;
+        assert_eq!(vec![TrendingTag { name: String::from("async"), post_count: 2 }], everything,);
+
+        Ok(())
+    }
+}
@@ -54,6 +54,17 @@ impl UserRepo for PgUserRepo {
             .map_err(Into::into)
     }
 
+    async fn get_by_username(
+        &self,
+        exec: impl PgExecutor<'_>,
+        username: &str,
+    ) -> Result<Option<User>, RepoError> {
+        sqlx::query_as!(User, "SELECT * FROM users WHERE username = $1", username)
+            .fetch_optional(exec)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn get_by_username_exclusive(
         &self,
         exec: impl PgExecutor<'_>,
@@ -137,12 +148,18 @@ mod tests {
         // Get by username
         for user in &test_users {
             let got_by_username = repo
-                .get_by_username_exclusive(&pool, &user.username)
+                .get_by_username(&pool, &user.username)
                 .await
                 .context("failed to get user by username")?
                 .context("unexpected None user")?;
+            let got_exclusively = repo
+                .get_by_username_exclusive(&pool, &user.username)
+                .await
+                .context("failed to get user by username exclusively")?
+                .context("unexpected None user")?;
 
             assert_eq!(got_by_username, user);
+            assert_eq!(got_exclusively, user);
         }
 
         Ok(())
@@ -153,13 +170,15 @@ mod tests {
         let repo = PgUserRepo;
 
         let from_nonsense_email = repo.get_by_email(&pool, "nonsense@nothing.abc").await;
-        let from_nonsense_username = repo
+        let from_nonsense_username = repo.get_by_username(&pool, "nonsensical_naan").await;
+        let from_nonsense_username_exclusive = repo
             .get_by_username_exclusive(&pool, "nonsensical_naan")
             .await;
         let from_nonsense_id = repo.get_by_id(&pool, 642).await;
 
         assert_matches!(from_nonsense_email, Ok(None));
         assert_matches!(from_nonsense_username, Ok(None));
+        assert_matches!(from_nonsense_username_exclusive, Ok(None));
         assert_matches!(from_nonsense_id, Ok(None));
 
         Ok(())
@@ -0,0 +1,146 @@
+use {
+    crate::{
+        domain::{outbound::OutboundPolicy, webhook::WebhookClient},
+        infra::outbound_resolver::PolicyResolver,
+    },
+    anyhow::Result,
+    std::time::Duration,
+};
+
+/// How long to wait for an endpoint to respond before counting the attempt as failed.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ReqwestWebhookClient {
+    client: reqwest::Client,
+    policy: OutboundPolicy,
+}
+
+impl ReqwestWebhookClient {
+    /// Builds an HTTP client for sending webhooks to the hosts that the policy allows. Redirects
+    /// are not followed, so endpoints must be registered with their final URLs, and proxies are
+    /// not used, so that the resolved addresses are the ones connected to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the TLS backend cannot be initialized.
+    pub fn new(policy: OutboundPolicy) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(PolicyResolver::new(policy))
+            .user_agent(concat!("Spur-Webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self { client, policy })
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookClient for ReqwestWebhookClient {
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(&'static str, String)>,
+        body: String,
+    ) -> Result<u16> {
+        // Addresses in the URL itself are not resolved, so they are checked here instead
+        self.policy.check_url(url)?;
+
+        let req = headers.into_iter().fold(
+            self.client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body),
+            |req_builder, (name, value)| req_builder.header(name, value),
+        );
+
+        Ok(req.send().await?.status().as_u16())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::test_utils::{tokio_test, ws_client::serve},
+        anyhow::Context as _,
+        axum::{
+            Router,
+            http::{HeaderMap, StatusCode},
+            routing::post,
+        },
+        tokio::sync::mpsc,
+    };
+
+    #[test]
+    fn posts_the_body_with_the_headers_and_reports_the_status() -> Result<()> {
+        tokio_test(async {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+
+            let app = Router::new().route(
+                "/hook",
+                post(async move |headers: HeaderMap, body: String| {
+                    if sender.send((headers, body)).is_err() {
+                        log::error!("Test receiver dropped");
+                    }
+                    StatusCode::ACCEPTED
+                }),
+            );
+            let addr = serve(app).await?;
+
+            let status = ReqwestWebhookClient::new(OutboundPolicy::AllowLoopback)?
+                .post(
+                    &format!("http://{addr}/hook"),
+                    vec![("x-spur-event", String::from("post.created"))],
+                    String::from(r#"{"ok":true}"#),
+                )
+                .await?;
+            assert_eq!(202, status);
+
+            let (headers, body) = receiver.recv().await.context("no request received")?;
+            assert_eq!(
+                headers.get("content-type").and_then(|v| v.to_str().ok()),
+                Some("application/json")
+            );
+            assert_eq!(
+                headers.get("x-spur-event").and_then(|v| v.to_str().ok()),
+                Some("post.created")
+            );
+            assert_eq!(body, r#"{"ok":true}"#);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn refuses_to_send_to_internal_hosts() -> Result<()> {
+        tokio_test(async {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+
+            let app = Router::new().route(
+                "/hook",
+                post(async move || {
+                    if sender.send(()).is_err() {
+                        log::error!("Test receiver dropped");
+                    }
+                    StatusCode::OK
+                }),
+            );
+            let addr = serve(app).await?;
+            let client = ReqwestWebhookClient::new(OutboundPolicy::PublicOnly)?;
+
+            // By address, and by a name that resolves to it
+            for url in [
+                format!("http://{addr}/hook"),
+                format!("https://{addr}/hook"),
+                format!("https://localhost:{}/hook", addr.port()),
+            ] {
+                assert!(client.post(&url, Vec::new(), String::new()).await.is_err(), "{url}");
+            }
+            assert!(receiver.try_recv().is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use {
+    crate::{
+        models::webhook::{
+            Webhook, WebhookDeliveryRecord, WebhookDeliveryStatus, WebhookEventKind,
+        },
+        read_models::{ReadError, WebhookRead},
+    },
+    sqlx::PgPool,
+};
+
+/// The maximum number of entries returned from a delivery log.
+const DELIVERY_LOG_LIMIT: i64 = 100;
+
+pub struct PgWebhookRead {
+    pool: PgPool,
+}
+
+impl PgWebhookRead {
+    pub const fn new(pool: PgPool) -> Self { Self { pool } }
+}
+
+#[async_trait::async_trait]
+impl WebhookRead for PgWebhookRead {
+    async fn for_owner(&self, owner_id: i32) -> Result<Vec<Webhook>, ReadError> {
+        sqlx::query_as!(
+            Webhook,
+            r#"
+            SELECT id, url, events AS "events: Vec<WebhookEventKind>", all_users, created_at
+            FROM webhook
+            WHERE owner_id = $1
+            ORDER BY id DESC
+            "#,
+            owner_id,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn delivery_log(
+        &self,
+        owner_id: i32,
+        webhook_id: i32,
+    ) -> Result<Vec<WebhookDeliveryRecord>, ReadError> {
+        let owned = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM webhook WHERE id = $1 AND owner_id = $2) AS "owned!"
+            "#,
+            webhook_id,
+            owner_id,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !owned {
+            return Err(ReadError::NotFound);
+        }
+
+        sqlx::query_as!(
+            WebhookDeliveryRecord,
+            r#"
+            SELECT
+                d.id,
+                d.event_id,
+                e.kind AS "kind: WebhookEventKind",
+                d.status AS "status: WebhookDeliveryStatus",
+                d.attempts,
+                d.last_attempt_at,
+                d.next_attempt_at,
+                d.response_status,
+                d.last_error,
+                e.created_at AS event_created_at
+            FROM webhook_delivery d
+            JOIN webhook_event e ON e.id = d.event_id
+            WHERE d.webhook_id = $1
+            ORDER BY d.id DESC
+            LIMIT $2
+            "#,
+            webhook_id,
+            DELIVERY_LOG_LIMIT,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::webhook::WebhookRepo as _, infra::webhook_repo::PgWebhookRepo,
+            models::webhook::WebhookEvent, test_utils::seed_data::seed_users_and_root_post,
+        },
+        anyhow::Result,
+        std::assert_matches,
+    };
+
+    #[sqlx::test]
+    async fn retrieves_only_the_owners_webhooks_and_deliveries(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgWebhookRepo;
+        let read = PgWebhookRead::new(pool.clone());
+
+        let events = [WebhookEventKind::FriendshipRequested, WebhookEventKind::FriendshipAccepted];
+        let mine = repo
+            .insert_new(&pool, 2, "https://example.com/a", "s1", &events, false)
+            .await?;
+        let theirs = repo
+            .insert_new(&pool, 3, "https://example.com/b", "s2", &events, false)
+            .await?;
+
+        repo.enqueue(
+            &pool,
+            &WebhookEvent::FriendshipRequested { requester_id: 2, recipient_id: 4 },
+        )
+        .await?;
+        repo.enqueue(&pool, &WebhookEvent::FriendshipAccepted { requester_id: 2, accepter_id: 4 })
+            .await?;
+
+        let webhooks = read.for_owner(2).await?;
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(
+            webhooks.first().map(|w| (w.id, w.events.clone())),
+            Some((mine, events.to_vec()))
+        );
+
+        let log = read.delivery_log(2, mine).await?;
+        assert_eq!(
+            log.iter().map(|d| (d.kind, d.status)).collect::<Vec<_>>(),
+            vec![
+                (WebhookEventKind::FriendshipAccepted, WebhookDeliveryStatus::Pending),
+                (WebhookEventKind::FriendshipRequested, WebhookDeliveryStatus::Pending),
+            ],
+        );
+
+        assert_matches!(read.delivery_log(2, theirs).await, Err(ReadError::NotFound));
+
+        Ok(())
+    }
+}
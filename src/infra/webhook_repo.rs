@@ -0,0 +1,329 @@
+use {
+    crate::{
+        domain::{
+            RepoError,
+            webhook::{WebhookDeliveryRepo, WebhookRepo},
+        },
+        models::webhook::{
+            DeliveryAttempt, PendingDelivery, WebhookDeliveryStatus, WebhookEvent, WebhookEventKind,
+        },
+    },
+    anyhow::Context as _,
+    chrono::{DateTime, TimeDelta, Utc},
+    sqlx::PgExecutor,
+};
+
+pub struct PgWebhookRepo;
+
+#[async_trait::async_trait]
+impl WebhookRepo for PgWebhookRepo {
+    async fn insert_new(
+        &self,
+        exec: impl PgExecutor<'_>,
+        owner_id: i32,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEventKind],
+        all_users: bool,
+    ) -> Result<i32, RepoError> {
+        sqlx::query_scalar!(
+            "
+            INSERT INTO webhook (owner_id, url, secret, events, all_users)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            ",
+            owner_id,
+            url,
+            secret,
+            events as &[WebhookEventKind],
+            all_users,
+        )
+        .fetch_one(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn delete(
+        &self,
+        exec: impl PgExecutor<'_>,
+        owner_id: i32,
+        id: i32,
+    ) -> Result<bool, RepoError> {
+        sqlx::query!("DELETE FROM webhook WHERE id = $1 AND owner_id = $2", id, owner_id)
+            .execute(exec)
+            .await
+            .map_err(Into::into)
+            .map(|result| result.rows_affected() > 0)
+    }
+
+    async fn enqueue(
+        &self,
+        exec: impl PgExecutor<'_>,
+        event: &WebhookEvent,
+    ) -> Result<(), RepoError> {
+        let payload = serde_json::to_value(event).context("failed to serialize webhook event")?;
+
+        // The event is recorded even if no webhooks are subscribed to it
+        sqlx::query!(
+            "
+            WITH new_event AS (
+                INSERT INTO webhook_event (kind, payload)
+                VALUES ($1, $2)
+                RETURNING id
+            )
+            INSERT INTO webhook_delivery (webhook_id, event_id)
+            SELECT w.id, e.id
+            FROM webhook w
+            CROSS JOIN new_event e
+            WHERE $1 = ANY(w.events) AND (w.all_users OR w.owner_id = ANY($3))
+            ",
+            event.kind() as WebhookEventKind,
+            payload,
+            &event.involved_user_ids(),
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+
+    async fn prune_finished(
+        &self,
+        exec: impl PgExecutor<'_>,
+        before: DateTime<Utc>,
+    ) -> Result<u64, RepoError> {
+        sqlx::query!(
+            "
+            DELETE FROM webhook_event e
+            WHERE e.created_at < $1
+                AND NOT EXISTS (
+                    SELECT 1 FROM webhook_delivery d
+                    WHERE d.event_id = e.id AND d.status = 'pending'
+                )
+            ",
+            before,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|result| result.rows_affected())
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookDeliveryRepo for PgWebhookRepo {
+    async fn claim_due(
+        &self,
+        exec: impl PgExecutor<'_>,
+        limit: i64,
+        lease: TimeDelta,
+    ) -> Result<Vec<PendingDelivery>, RepoError> {
+        sqlx::query_as!(
+            PendingDelivery,
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_delivery
+                SET next_attempt_at = CURRENT_TIMESTAMP + make_interval(secs => $2)
+                WHERE id IN (
+                    SELECT id FROM webhook_delivery
+                    WHERE status = 'pending' AND next_attempt_at <= CURRENT_TIMESTAMP
+                    ORDER BY next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, webhook_id, event_id, attempts
+            )
+            SELECT
+                c.id AS "id!",
+                w.url,
+                w.secret,
+                c.event_id AS "event_id!",
+                e.kind AS "kind: WebhookEventKind",
+                e.payload,
+                e.created_at AS event_created_at,
+                c.attempts AS "attempts!"
+            FROM claimed c
+            JOIN webhook w ON w.id = c.webhook_id
+            JOIN webhook_event e ON e.id = c.event_id
+            ORDER BY c.id
+            "#,
+            limit,
+            lease.as_seconds_f64(),
+        )
+        .fetch_all(exec)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn record_attempt(
+        &self,
+        exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: &DeliveryAttempt,
+    ) -> Result<(), RepoError> {
+        sqlx::query!(
+            "
+            UPDATE webhook_delivery
+            SET status = $2,
+                attempts = attempts + 1,
+                last_attempt_at = CURRENT_TIMESTAMP,
+                next_attempt_at = $3,
+                response_status = $4,
+                last_error = $5
+            WHERE id = $1
+            ",
+            id,
+            attempt.status as WebhookDeliveryStatus,
+            attempt.next_attempt_at,
+            attempt.response_status,
+            attempt.error,
+        )
+        .execute(exec)
+        .await
+        .map_err(Into::into)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::test_utils::seed_data::seed_users_and_root_post, anyhow::Result,
+        serde_json::json, sqlx::PgPool,
+    };
+
+    /// Retrieves the IDs of the webhooks that have deliveries queued for the event with the
+    /// provided ID in ascending order.
+    async fn delivery_webhook_ids(pool: &PgPool, event_id: i32) -> Result<Vec<i32>> {
+        sqlx::query_scalar!(
+            "SELECT webhook_id FROM webhook_delivery WHERE event_id = $1 ORDER BY webhook_id",
+            event_id,
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to fetch delivery webhook IDs")
+    }
+
+    #[sqlx::test]
+    async fn queues_deliveries_for_subscribed_webhooks_of_involved_users(
+        pool: PgPool,
+    ) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgWebhookRepo;
+        let url = "https://example.com/hook";
+
+        let posts_of_2 = repo
+            .insert_new(&pool, 2, url, "s1", &[WebhookEventKind::PostCreated], false)
+            .await?;
+        let friends_of_2 = repo
+            .insert_new(&pool, 2, url, "s2", &[WebhookEventKind::FriendshipRequested], false)
+            .await?;
+        // Not involved in the post event, and not subscribed to the friendship event it is involved
+        // in
+        repo.insert_new(&pool, 3, url, "s3", &[WebhookEventKind::PostCreated], false)
+            .await?;
+        let everything = repo
+            .insert_new(
+                &pool,
+                1,
+                url,
+                "s4",
+                &[WebhookEventKind::PostCreated, WebhookEventKind::FriendshipRequested],
+                true,
+            )
+            .await?;
+
+        repo.enqueue(
+            &pool,
+            &WebhookEvent::PostCreated {
+                post_id: 2,
+                author_id: 2,
+                parent_id: 1,
+                body: String::from("Hi"),
+            },
+        )
+        .await?; // Event ID 1
+        repo.enqueue(
+            &pool,
+            &WebhookEvent::FriendshipRequested { requester_id: 4, recipient_id: 2 },
+        )
+        .await?; // Event ID 2
+        repo.enqueue(&pool, &WebhookEvent::FriendshipAccepted { requester_id: 4, accepter_id: 3 })
+            .await?; // Event ID 3
+
+        assert_eq!(delivery_webhook_ids(&pool, 1).await?, vec![posts_of_2, everything]);
+        assert_eq!(delivery_webhook_ids(&pool, 2).await?, vec![friends_of_2, everything]);
+        assert!(delivery_webhook_ids(&pool, 3).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn claims_due_deliveries_only_once_per_lease(pool: PgPool) -> Result<()> {
+        seed_users_and_root_post(&pool).await?;
+        let repo = PgWebhookRepo;
+
+        let webhook_id = repo
+            .insert_new(
+                &pool,
+                3,
+                "https://example.com/hook",
+                "shh",
+                &[WebhookEventKind::FriendshipAccepted],
+                false,
+            )
+            .await?;
+        repo.enqueue(&pool, &WebhookEvent::FriendshipAccepted { requester_id: 3, accepter_id: 4 })
+            .await?;
+
+        let claimed = repo.claim_due(&pool, 10, TimeDelta::minutes(5)).await?;
+        assert_eq!(claimed.len(), 1);
+
+        let delivery = claimed.first().context("no delivery claimed")?;
+        assert_eq!(delivery.url, "https://example.com/hook");
+        assert_eq!(delivery.secret, "shh");
+        assert_eq!(delivery.kind, WebhookEventKind::FriendshipAccepted);
+        assert_eq!(delivery.payload, json!({ "requesterId": 3, "accepterId": 4 }));
+        assert_eq!(delivery.attempts, 0);
+
+        // Still leased
+        assert!(
+            repo.claim_due(&pool, 10, TimeDelta::minutes(5))
+                .await?
+                .is_empty()
+        );
+
+        // Scheduling a retry for now makes it due again
+        let retry = DeliveryAttempt {
+            status: WebhookDeliveryStatus::Pending,
+            response_status: Some(503),
+            error: None,
+            next_attempt_at: Utc::now(),
+        };
+        repo.record_attempt(&pool, delivery.id, &retry).await?;
+
+        let reclaimed = repo.claim_due(&pool, 10, TimeDelta::minutes(5)).await?;
+        assert_eq!(reclaimed.first().map(|d| d.attempts), Some(1));
+
+        // Finished deliveries are never claimed again
+        let success = DeliveryAttempt {
+            status: WebhookDeliveryStatus::Succeeded,
+            response_status: Some(200),
+            error: None,
+            next_attempt_at: Utc::now(),
+        };
+        repo.record_attempt(&pool, delivery.id, &success).await?;
+        assert!(
+            repo.claim_due(&pool, 10, TimeDelta::zero())
+                .await?
+                .is_empty()
+        );
+
+        // Only the owner can delete the webhook
+        assert!(!repo.delete(&pool, 4, webhook_id).await?);
+        assert!(repo.delete(&pool, 3, webhook_id).await?);
+        assert!(!repo.delete(&pool, 3, webhook_id).await?);
+
+        Ok(())
+    }
+}
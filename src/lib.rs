@@ -1,7 +1,7 @@
 pub mod api;
 pub mod logger;
 
-pub use {config::AppConfig, state::AppState};
+pub use {app_services::background::Workers, config::AppConfig, state::AppState};
 
 mod app_services;
 mod config;
@@ -1,14 +1,15 @@
-use {anyhow::Result, tokio::net::TcpListener};
+use {anyhow::Result, std::net::SocketAddr, tokio::net::TcpListener};
 
-/// Sets up the async runtime, logger, config, state, and server, and then listens for requests
-/// until receiving a shutdown signal.
+/// Sets up the async runtime, logger, config, state, background workers, and server, and then
+/// listens for requests until receiving a shutdown signal, after which the workers are given a
+/// chance to finish what they are doing.
 fn main() -> Result<()> {
     spur::tokio_main(async {
         spur::logger::init_with_default(log::LevelFilter::Info);
         log::info!("Initializing app...");
 
         let config = spur::AppConfig::load()?;
-        let state = spur::AppState::init(&config).await?;
+        let (state, workers) = spur::AppState::init(&config).await?;
         let app = spur::api::build(state, &config.frontend_url)?;
         let listener = TcpListener::bind(&config.bind_addr).await?;
 
@@ -18,10 +19,17 @@ fn main() -> Result<()> {
         #[cfg(not(debug_assertions))]
         log::info!("Listening on {}", &config.bind_addr);
 
-        axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown_signal_handler()?)
+        // Event streams and WebSockets are closed as soon as the signal arrives so that the server
+        // is not left waiting on them
+        let shutdown = workers.request_shutdown_after(shutdown_signal_handler()?);
+
+        // Connection info lets anonymous requests be rate limited by IP address
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(shutdown)
             .await?;
 
+        workers.shut_down().await;
+
         Ok(())
     })
 }
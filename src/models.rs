@@ -1,2 +1,14 @@
+pub mod attachment;
+pub mod bookmark;
+pub mod draft;
+pub mod federation;
+pub mod job;
+pub mod mention;
+pub mod moderation;
+pub mod notification;
 pub mod post;
+pub mod reaction;
+pub mod search;
+pub mod tag;
 pub mod user;
+pub mod webhook;
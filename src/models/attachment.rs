@@ -0,0 +1,57 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::Deserialize,
+};
+
+/// The attachment entity as it exists in the database.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct Attachment {
+    pub id: i32,
+    pub post_id: i32,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub byte_size: i32,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A new attachment, recorded before its image and thumbnail are stored.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct NewAttachment {
+    pub post_id: i32,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub byte_size: i32,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+}
+
+/// Enough information about an attachment to show it alongside its post.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentSummary {
+    pub id: i32,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// The stored versions of an attachment's image.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy)]
+pub enum AttachmentVariant {
+    /// The full-size image.
+    Original,
+    /// A reduced copy for previews.
+    Thumbnail,
+}
+
+/// The contents of an attachment ready to be served.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct AttachmentFile {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
@@ -0,0 +1,11 @@
+use crate::models::post::{PostCursor, PostWithAuthor};
+
+/// One page of a user's bookmarked posts.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct BookmarkPage {
+    /// The bookmarked posts, most recently saved first.
+    pub posts: Vec<PostWithAuthor>,
+    /// Where the next page begins, `None` if this is the last page. Positions are based on when
+    /// each post was saved rather than when it was created.
+    pub next_cursor: Option<PostCursor>,
+}
@@ -0,0 +1,24 @@
+use {
+    crate::models::post::{BodyFormat, ParentSummary},
+    chrono::{DateTime, Utc},
+};
+
+/// The draft entity as it exists in the database.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct Draft {
+    pub author_id: i32,
+    pub parent_id: i32,
+    pub body: String,
+    pub body_format: BodyFormat,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Why the draft could not be published as scheduled, if it was returned for that reason.
+    pub publish_error: Option<String>,
+}
+
+/// A draft along with a summary of the post it replies to.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct DraftWithParent {
+    pub draft: Draft,
+    pub parent: ParentSummary,
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+
+/// The RSA key pair that a user's actor signs outgoing requests with, PEM-encoded (SPKI for the
+/// public key and PKCS#8 for the private key).
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub struct ActorKey {
+    pub public_key_pem: String,
+    pub private_key_pem: String,
+}
+
+/// A local user as seen by other ActivityPub servers.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct LocalActor {
+    pub user_id: i32,
+    pub username: String,
+    pub name: String,
+    pub public_key_pem: String,
+}
+
+/// An actor on another ActivityPub server, as described by its actor document.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteActor {
+    pub id: String,
+    pub inbox: String,
+    pub public_key: RemotePublicKey,
+}
+
+/// The key that a remote actor signs its requests with.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePublicKey {
+    pub id: String,
+    pub owner: String,
+    pub public_key_pem: String,
+}
+
+/// A request to a local user's inbox, with everything needed to verify its signature.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct InboxRequest {
+    /// The path and query that the request was sent to.
+    pub path: String,
+    /// Header names and values, excluding any that are not valid UTF-8.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
@@ -0,0 +1,22 @@
+/// The states a job can be in.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "job_status", rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The job has not finished yet, but another attempt will be made.
+    Pending,
+    /// The job finished without error.
+    Succeeded,
+    /// Every attempt failed (or no handler exists for the job), so no more attempts will be made.
+    Dead,
+}
+
+/// A job that has been claimed by a worker.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct ClaimedJob {
+    pub id: i32,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// The number of attempts, including the current one
+    pub attempts: i32,
+}
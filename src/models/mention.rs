@@ -0,0 +1,24 @@
+use {
+    crate::models::post::{PostCursor, PostWithAuthor},
+    serde::Deserialize,
+};
+
+/// The part of a post body that mentions a user.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MentionSpan {
+    /// The UTF-8 byte offset of the @ in the body.
+    pub start: i32,
+    /// The UTF-8 byte offset just after the end of the username in the body.
+    pub end: i32,
+    pub username: String,
+}
+
+/// One page of the posts that mention a user.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct MentionPage {
+    /// The posts, most recent first.
+    pub posts: Vec<PostWithAuthor>,
+    /// Where the next page begins, `None` if this is the last page.
+    pub next_cursor: Option<PostCursor>,
+}
@@ -0,0 +1,57 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    utoipa::ToSchema,
+};
+
+/// The reason a user gives for reporting a post.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "report_reason", rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    Hate,
+    Violence,
+    Sexual,
+    Other,
+}
+
+/// The ways a moderator can resolve the open reports on a post.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "moderation_action_kind", rename_all = "snake_case")]
+pub enum ModerationActionKind {
+    /// Close the reports without changing the post.
+    Dismiss,
+    /// Close the reports and archive the post so that it can no longer be replied to.
+    Archive,
+    /// Close the reports and soft delete the post, removing its content.
+    Delete,
+}
+
+/// A post with at least one open report, along with a summary of the open reports.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct ReportedPost {
+    pub post_id: i32,
+    pub author_username: Option<String>,
+    pub body: Option<String>,
+    pub report_count: i64,
+    pub reasons: Vec<ReportReason>,
+    pub first_reported_at: DateTime<Utc>,
+    pub last_reported_at: DateTime<Utc>,
+}
+
+/// An entry in the moderation audit trail with the moderator's username.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct ModerationActionRecord {
+    pub id: i32,
+    pub post_id: i32,
+    pub moderator_username: Option<String>,
+    pub action: ModerationActionKind,
+    pub note: Option<String>,
+    pub reports_resolved: i64,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,61 @@
+use {
+    crate::models::post::PostCursor,
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    utoipa::ToSchema,
+};
+
+/// The events that users are notified about.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "notification_kind", rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Someone replied to one of the recipient's posts.
+    Reply,
+    /// Someone sent the recipient a friend request.
+    FriendRequest,
+    /// Someone accepted the recipient's friend request.
+    FriendAccepted,
+    /// Someone mentioned the recipient in a post.
+    Mention,
+}
+
+/// A notification with the username of the user who caused it.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct NotificationWithActor {
+    pub id: i32,
+    pub kind: NotificationKind,
+    pub post_id: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+    /// From the users table
+    pub actor_username: Option<String>,
+}
+
+/// One page of a user's notifications.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct NotificationPage {
+    /// The notifications, most recent first.
+    pub notifications: Vec<NotificationWithActor>,
+    /// Where the next page begins, `None` if this is the last page. Positions are based on the
+    /// notifications' creation times and IDs.
+    pub next_cursor: Option<PostCursor>,
+}
+
+/// The number of unread notifications of each kind for a single recipient.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct UnreadCounts {
+    pub reply: i64,
+    pub friend_request: i64,
+    pub friend_accepted: i64,
+    pub mention: i64,
+}
+
+/// A signal that a notification was just created, as broadcast by the database.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Deserialize)]
+pub struct NewNotification {
+    pub id: i32,
+    pub recipient_id: i32,
+}
@@ -1,4 +1,12 @@
-use chrono::{DateTime, Utc};
+use {
+    crate::models::{attachment::AttachmentSummary, mention::MentionSpan, reaction::ReactionCount},
+    anyhow::Context as _,
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    sqlx::types::Json,
+    std::{fmt, str::FromStr},
+    utoipa::ToSchema,
+};
 
 /// The post entity as it exists in the database.
 #[cfg_attr(test, derive(Debug, Clone))]
@@ -14,10 +22,17 @@ pub struct Post {
     pub author_id: Option<i32>,
     pub parent_id: Option<i32>,
     pub body: Option<String>,
+    pub body_format: BodyFormat,
     pub created_at: DateTime<Utc>,
     pub edited_at: Option<DateTime<Utc>>,
     pub archived_at: Option<DateTime<Utc>>,
     pub deleted_at: Option<DateTime<Utc>>,
+    pub descendant_count: i32,
+    pub quoted_post_id: Option<i32>,
+    pub reply_policy: ReplyPolicy,
+    pub reply_policy_cascades: bool,
+    pub depth: i32,
+    pub child_count: i32,
 }
 
 /// The post entity as it exists in the database with the addition of the author's username.
@@ -27,12 +42,217 @@ pub struct PostWithAuthor {
     pub author_id: Option<i32>,
     pub parent_id: Option<i32>,
     pub body: Option<String>,
+    pub body_format: BodyFormat,
     pub created_at: DateTime<Utc>,
     pub edited_at: Option<DateTime<Utc>>,
     pub archived_at: Option<DateTime<Utc>>,
     pub deleted_at: Option<DateTime<Utc>>,
+    pub descendant_count: i32,
+    pub reply_policy: ReplyPolicy,
+    pub reply_policy_cascades: bool,
+    pub visibility: PostVisibility,
+    /// Whether the viewer may not see the post, in which case only its place in the tree is kept
+    pub hidden: bool,
     /// From the users table
     pub author_username: Option<String>,
+    /// From the `post_reaction` table, one element per kind of reaction the post has received
+    pub reactions: Json<Vec<ReactionCount>>,
+    /// From the `post_mention` table, the parts of the body that mention users
+    pub mentions: Json<Vec<MentionSpan>>,
+    /// From the `attachment` table, the images attached to the post
+    pub attachments: Json<Vec<AttachmentSummary>>,
+    /// A summary of the post that the post quotes, if any
+    pub quoted: Option<Json<QuotedPost>>,
+}
+
+impl PostWithAuthor {
+    /// Strips everything but the post's place in the tree if the viewer may not see the post.
+    #[must_use]
+    pub fn placeholder_if_hidden(self) -> Self {
+        if !self.hidden {
+            return self;
+        }
+
+        Self {
+            author_id: None,
+            body: None,
+            edited_at: None,
+            author_username: None,
+            reactions: Json(Vec::new()),
+            mentions: Json(Vec::new()),
+            attachments: Json(Vec::new()),
+            quoted: None,
+            ..self
+        }
+    }
+}
+
+/// Enough information about a quoted post to show a preview of it.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuotedPost {
+    pub id: i32,
+    pub author_username: Option<String>,
+    /// The beginning of the post's body, `None` if the post was deleted or is hidden.
+    pub excerpt: Option<String>,
+    /// Whether the viewer may not see the post, in which case there is no author or excerpt.
+    pub hidden: bool,
+}
+
+/// The ways a post body can be written.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "body_format", rename_all = "snake_case")]
+pub enum BodyFormat {
+    /// Text shown exactly as written.
+    #[default]
+    Plain,
+    /// A subset of Markdown: emphasis, strikethrough, code, links, and block quotes.
+    Markdown,
+}
+
+/// Who may reply to a post.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "reply_policy", rename_all = "snake_case")]
+pub enum ReplyPolicy {
+    /// Any user.
+    #[default]
+    Anyone,
+    /// Only friends of the post's author.
+    Friends,
+    /// No one, which locks the post.
+    Nobody,
+}
+
+/// Who may see a post, other than its author.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "post_visibility", rename_all = "snake_case")]
+pub enum PostVisibility {
+    /// Any user.
+    #[default]
+    Public,
+    /// Only friends of the post's author.
+    Friends,
+    /// Only the author of the post being replied to and the users mentioned in the post.
+    Participants,
+}
+
+/// The orders in which a post's children can be listed.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PostSort {
+    /// Most recent first.
+    #[default]
+    New,
+    /// Oldest first.
+    Old,
+    /// Most descendants first.
+    Top,
+    /// Most descendants first, adjusted so that newer posts rank higher than older ones with
+    /// similar activity.
+    Hot,
+}
+
+/// A position in a list of posts ordered by time (usually creation time), most recent first. Posts
+/// with the same time are ordered by descending ID so that every post has a unique position.
+///
+/// Represented externally as an opaque string so that clients only ever pass it back unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PostCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i32,
+}
+
+impl PostCursor {
+    /// The position of the provided post.
+    pub const fn of(post: &PostWithAuthor) -> Self {
+        Self { created_at: post.created_at, id: post.id }
+    }
+}
+
+impl fmt::Display for PostCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.created_at.timestamp_micros(), self.id)
+    }
+}
+
+impl FromStr for PostCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (micros, id) = s.split_once('_').context("invalid cursor")?;
+
+        Ok(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().context("invalid cursor")?)
+                .context("invalid cursor")?,
+            id: id.parse().context("invalid cursor")?,
+        })
+    }
+}
+
+impl TryFrom<String> for PostCursor {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { s.parse() }
+}
+
+/// Enough information about a post to show what a reply was in reply to.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct ParentSummary {
+    pub id: i32,
+    pub author_username: Option<String>,
+    /// The beginning of the post's body, `None` if the post was deleted.
+    pub excerpt: Option<String>,
+}
+
+/// A post in a user's home timeline along with a summary of the post it replied to.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct TimelinePost {
+    pub post: PostWithAuthor,
+    pub parent: Option<ParentSummary>,
+}
+
+/// One page of a user's home timeline.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct TimelinePage {
+    pub posts: Vec<TimelinePost>,
+    /// Where the next page begins, `None` if this is the last page.
+    pub next_cursor: Option<PostCursor>,
+}
+
+/// One page of the posts that quote a particular post.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct QuotePage {
+    /// The posts, most recent first.
+    pub posts: Vec<PostWithAuthor>,
+    /// Where the next page begins, `None` if this is the last page.
+    pub next_cursor: Option<PostCursor>,
+}
+
+/// The ways a post can change that are relevant to someone viewing its thread.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostChangeKind {
+    Created,
+    Edited,
+    Deleted,
+}
+
+/// A signal that a post was created, edited, or deleted.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Deserialize)]
+pub struct PostChange {
+    pub kind: PostChangeKind,
+    pub post_id: i32,
+    pub parent_id: Option<i32>,
 }
 
 #[cfg(test)]
@@ -49,10 +269,17 @@ mod post_info_test_impl {
                 author_id: pwa.author_id,
                 parent_id: pwa.parent_id,
                 body: pwa.body,
+                body_format: pwa.body_format,
                 created_at: pwa.created_at,
                 edited_at: pwa.edited_at,
                 archived_at: pwa.archived_at,
                 deleted_at: pwa.deleted_at,
+                descendant_count: pwa.descendant_count,
+                quoted_post_id: pwa.quoted.map(|quoted| quoted.0.id),
+                reply_policy: pwa.reply_policy,
+                reply_policy_cascades: pwa.reply_policy_cascades,
+                depth: 0,
+                child_count: 0,
             }
         }
     }
@@ -65,11 +292,21 @@ mod post_info_test_impl {
                 && self.author_id == other.author_id
                 && self.parent_id == other.parent_id
                 && self.body == other.body
+                && self.body_format == other.body_format
                 && within_five_seconds(self.created_at, other.created_at)
                 && both_none_or_within_five_seconds(self.edited_at, other.edited_at)
                 && both_none_or_within_five_seconds(self.archived_at, other.archived_at)
                 && both_none_or_within_five_seconds(self.deleted_at, other.deleted_at)
+                && self.descendant_count == other.descendant_count
+                && self.reply_policy == other.reply_policy
+                && self.reply_policy_cascades == other.reply_policy_cascades
+                && self.visibility == other.visibility
+                && self.hidden == other.hidden
                 && self.author_username == other.author_username
+                && self.reactions == other.reactions
+                && self.mentions == other.mentions
+                && self.attachments == other.attachments
+                && self.quoted == other.quoted
         }
     }
 }
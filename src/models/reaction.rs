@@ -0,0 +1,33 @@
+use {
+    serde::{Deserialize, Serialize},
+    utoipa::ToSchema,
+};
+
+/// The fixed set of reactions that users can leave on posts.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "reaction_kind", rename_all = "snake_case")]
+pub enum ReactionKind {
+    /// 👍
+    Like,
+    /// ❤️
+    Love,
+    /// 😂
+    Laugh,
+    /// 😮
+    Wow,
+    /// 😢
+    Sad,
+    /// 😠
+    Angry,
+}
+
+/// The number of reactions of one kind on a post and whether the viewer is one of the reactors.
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ReactionCount {
+    pub kind: ReactionKind,
+    pub count: i64,
+    pub reacted_by_me: bool,
+}
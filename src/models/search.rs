@@ -0,0 +1,41 @@
+use {serde::Deserialize, utoipa::ToSchema};
+
+/// The kinds of things that can be searched for.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    /// Posts whose content matches the query.
+    #[default]
+    Posts,
+    /// Users whose username or name resembles the query.
+    Users,
+}
+
+/// A user found by searching.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct UserSearchResult {
+    pub username: String,
+    pub name: String,
+}
+
+/// One page of search results, best match first.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct SearchPage<T> {
+    pub results: Vec<T>,
+    /// The offset of the next page, `None` if this is the last page.
+    pub next_offset: Option<i64>,
+}
+
+impl<T> SearchPage<T> {
+    /// Creates a page from up to `limit + 1` results beginning at `offset`, where the presence of
+    /// the extra result indicates that there is another page.
+    pub fn from_overfetched(mut results: Vec<T>, offset: i64, limit: i64) -> Self {
+        let has_more = i64::try_from(results.len()).is_ok_and(|len| len > limit);
+        if has_more {
+            results.pop();
+        }
+
+        Self { results, next_offset: has_more.then(|| offset.saturating_add(limit)) }
+    }
+}
@@ -0,0 +1,17 @@
+use crate::models::post::{PostCursor, PostWithAuthor};
+
+/// One page of the posts with a particular tag.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct TagPage {
+    /// The posts, most recent first.
+    pub posts: Vec<PostWithAuthor>,
+    /// Where the next page begins, `None` if this is the last page.
+    pub next_cursor: Option<PostCursor>,
+}
+
+/// A tag and how many recent posts used it.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct TrendingTag {
+    pub name: String,
+    pub post_count: i64,
+}
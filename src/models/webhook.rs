@@ -0,0 +1,139 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    utoipa::ToSchema,
+};
+
+/// The kinds of events that webhooks can subscribe to.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[sqlx(type_name = "webhook_event_kind")]
+pub enum WebhookEventKind {
+    /// A post was created.
+    #[serde(rename = "post.created")]
+    #[sqlx(rename = "post.created")]
+    PostCreated,
+    /// A user sent another user a friend request.
+    #[serde(rename = "friendship.requested")]
+    #[sqlx(rename = "friendship.requested")]
+    FriendshipRequested,
+    /// A user accepted another user's friend request.
+    #[serde(rename = "friendship.accepted")]
+    #[sqlx(rename = "friendship.accepted")]
+    FriendshipAccepted,
+}
+
+impl WebhookEventKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::PostCreated => "post.created",
+            Self::FriendshipRequested => "friendship.requested",
+            Self::FriendshipAccepted => "friendship.accepted",
+        }
+    }
+}
+
+/// Something that happened that webhooks may need to be told about. Serializes to the event's
+/// data, without the kind.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+#[derive(Serialize)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub enum WebhookEvent {
+    PostCreated { post_id: i32, author_id: i32, parent_id: i32, body: String },
+    FriendshipRequested { requester_id: i32, recipient_id: i32 },
+    FriendshipAccepted { requester_id: i32, accepter_id: i32 },
+}
+
+impl WebhookEvent {
+    pub const fn kind(&self) -> WebhookEventKind {
+        match self {
+            Self::PostCreated { .. } => WebhookEventKind::PostCreated,
+            Self::FriendshipRequested { .. } => WebhookEventKind::FriendshipRequested,
+            Self::FriendshipAccepted { .. } => WebhookEventKind::FriendshipAccepted,
+        }
+    }
+
+    /// The users whose own webhooks should be sent the event.
+    pub fn involved_user_ids(&self) -> Vec<i32> {
+        match *self {
+            Self::PostCreated { author_id, .. } => vec![author_id],
+            Self::FriendshipRequested { requester_id, recipient_id } => {
+                vec![requester_id, recipient_id]
+            }
+            Self::FriendshipAccepted { requester_id, accepter_id } => {
+                vec![requester_id, accepter_id]
+            }
+        }
+    }
+}
+
+/// A registered webhook, excluding its secret.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct Webhook {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<WebhookEventKind>,
+    pub all_users: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The states a webhook delivery can be in.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy, Serialize, Deserialize, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// The event has not been delivered yet, but another attempt will be made.
+    Pending,
+    /// The endpoint acknowledged the event with a 2xx response.
+    Succeeded,
+    /// Every attempt failed, so no more attempts will be made.
+    Failed,
+}
+
+/// A delivery that has been claimed for sending, along with everything needed to send it.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct PendingDelivery {
+    pub id: i32,
+    pub url: String,
+    pub secret: String,
+    pub event_id: i32,
+    pub kind: WebhookEventKind,
+    pub payload: serde_json::Value,
+    pub event_created_at: DateTime<Utc>,
+    /// The number of previous attempts
+    pub attempts: i32,
+}
+
+/// The result of attempting to send a delivery.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct DeliveryAttempt {
+    pub status: WebhookDeliveryStatus,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    /// Only relevant if the status is still pending
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+/// An entry in a webhook's delivery log.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct WebhookDeliveryRecord {
+    pub id: i32,
+    pub event_id: i32,
+    pub kind: WebhookEventKind,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    /// From the `webhook_event` table
+    pub event_created_at: DateTime<Utc>,
+}
+
+/// A newly registered webhook's ID and signing secret.
+#[cfg_attr(test, derive(Debug, Clone, PartialEq, Eq))]
+pub struct NewWebhook {
+    pub id: i32,
+    pub secret: String,
+}
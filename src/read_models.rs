@@ -1,4 +1,19 @@
-use {crate::models::post::PostWithAuthor, thiserror::Error};
+use {
+    crate::models::{
+        bookmark::BookmarkPage,
+        draft::DraftWithParent,
+        mention::MentionPage,
+        moderation::{ModerationActionRecord, ReportedPost},
+        notification::{NewNotification, NotificationPage, NotificationWithActor, UnreadCounts},
+        post::{PostChange, PostCursor, PostSort, PostWithAuthor, QuotePage, TimelinePage},
+        search::{SearchPage, UserSearchResult},
+        tag::{TagPage, TrendingTag},
+        webhook::{Webhook, WebhookDeliveryRecord},
+    },
+    chrono::{DateTime, TimeDelta, Utc},
+    thiserror::Error,
+    tokio::sync::broadcast,
+};
 
 #[derive(Debug, Error)]
 pub enum ReadError {
@@ -20,29 +35,255 @@ pub trait SocialRead: Send + Sync {
     /// provided ID in descending order of request time (most recent first).
     async fn pending_requests(&self, id: i32) -> Result<Vec<String>, ReadError>;
 
-    /// Retrieves all posts written by friends of a specific user in descending order of creation
-    /// time (most recent first).
+    /// Retrieves all posts written by friends of a specific user that the user may see in
+    /// descending order of creation time (most recent first).
     async fn friend_posts(&self, user_id: i32) -> Result<Vec<PostWithAuthor>, ReadError>;
+
+    /// Retrieves up to `limit` posts for a user's home timeline, beginning after `after` (or at the
+    /// most recent post if `None`), in descending order of creation time (most recent first).
+    ///
+    /// The timeline contains each post, other than the user's own, deleted, and hidden posts, that
+    /// was written by a friend of the user, replies to one of the user's posts, or is anywhere in a
+    /// thread that the user has posted in. A thread is a post directly below the root post and all
+    /// of the posts below it. Each post appears at most once. Parents that the user may not see are
+    /// summarized by their ID alone.
+    async fn home_timeline(
+        &self,
+        user_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<TimelinePage, ReadError>;
 }
 
+/// Reads posts as seen by a viewer. A `viewer_id` of `None` means an anonymous viewer, who can only
+/// see public posts and has no reactions of their own.
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait PostWithAuthorRead: Send + Sync {
-    /// Retrieves a post and its author's username by its post ID.
-    async fn by_post_id(&self, id: i32) -> Result<PostWithAuthor, ReadError>;
+    /// Retrieves a post and its author's username by its post ID. Reactions are marked as the
+    /// viewer's own where applicable. If the viewer may not see the post, only a placeholder is
+    /// returned.
+    async fn by_post_id(
+        &self,
+        id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<PostWithAuthor, ReadError>;
 
     /// Retrieves all children of the post with the provided ID and the usernames of the authors of
-    /// the posts in descending order of creation time (most recent first).
-    async fn children_of(&self, parent_id: i32) -> Result<Vec<PostWithAuthor>, ReadError>;
+    /// the posts in the specified order. Reactions are marked as the viewer's own where applicable.
+    /// Children that the viewer may not see are returned as placeholders.
+    async fn children_of(
+        &self,
+        parent_id: i32,
+        sort: PostSort,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<PostWithAuthor>, ReadError>;
 
     /// Retrieves all posts written by the user with the provided ID along with the user's username
     /// in descending order of creation time (most recent first).
     async fn written_by_id(&self, author_id: i32) -> Result<Vec<PostWithAuthor>, ReadError>;
 
-    /// Retrieves all posts written by the user with the provided username in descending order of
-    /// creation time (most recent first).
+    /// Retrieves all posts written by the user with the provided username that the viewer may see
+    /// in descending order of creation time (most recent first). Reactions are marked as the
+    /// viewer's own where applicable.
     async fn written_by_username(
         &self,
         author_username: &str,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<PostWithAuthor>, ReadError>;
+
+    /// Retrieves up to `limit` posts that quote the post with the provided ID, beginning after
+    /// `after` (or at the most recent post if `None`), in descending order of creation time (most
+    /// recent first). Deleted posts and posts that the viewer may not see are never included.
+    /// Reactions are marked as the viewer's own where applicable.
+    async fn quotes_of(
+        &self,
+        quoted_post_id: i32,
+        viewer_id: Option<i32>,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<QuotePage, ReadError>;
+
+    /// Retrieves up to `limit` of the most recent posts written by the user with the provided
+    /// username in descending order of creation time (most recent first). Deleted posts and posts
+    /// that the viewer may not see are never included. Reactions are marked as the viewer's own
+    /// where applicable. Returns `ReadError::NotFound` if no user has the username.
+    async fn recent_by_username(
+        &self,
+        author_username: &str,
+        viewer_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<PostWithAuthor>, ReadError>;
+
+    /// Retrieves up to `limit` of the most recent replies to the post with the provided ID in
+    /// descending order of creation time (most recent first). Deleted posts and posts that the
+    /// viewer may not see are never included. Reactions are marked as the viewer's own where
+    /// applicable.
+    async fn recent_replies_to(
+        &self,
+        parent_id: i32,
+        viewer_id: Option<i32>,
+        limit: i64,
     ) -> Result<Vec<PostWithAuthor>, ReadError>;
 }
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait BookmarkRead: Send + Sync {
+    /// Retrieves up to `limit` of a user's bookmarked posts, beginning after `after` (or at the
+    /// most recently saved post if `None`), in descending order of save time (most recent
+    /// first). Deleted posts are included so that they can be shown as tombstones, and posts that
+    /// the user may no longer see are returned as placeholders.
+    async fn saved_by(
+        &self,
+        user_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<BookmarkPage, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait DraftRead: Send + Sync {
+    /// Retrieves all of a user's drafts along with summaries of the posts they reply to, in
+    /// descending order of save time (most recent first).
+    async fn drafts_by(&self, author_id: i32) -> Result<Vec<DraftWithParent>, ReadError>;
+
+    /// Retrieves a user's draft reply to a post along with a summary of the post.
+    async fn by_parent(&self, author_id: i32, parent_id: i32)
+    -> Result<DraftWithParent, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait MentionRead: Send + Sync {
+    /// Retrieves up to `limit` posts that mention a user, beginning after `after` (or at the most
+    /// recent post if `None`), in descending order of creation time (most recent first). Deleted
+    /// posts and posts that the user may not see are never included. Reactions are marked as the
+    /// mentioned user's own where applicable.
+    async fn mentioning(
+        &self,
+        user_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<MentionPage, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait SearchRead: Send + Sync {
+    /// Retrieves up to `limit` posts whose content matches `query`, skipping the first `offset`
+    /// matches, in descending order of relevance. Deleted posts and posts that the viewer may not
+    /// see are never included. Reactions are marked as the viewer's own where applicable.
+    async fn posts(
+        &self,
+        query: &str,
+        viewer_id: i32,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SearchPage<PostWithAuthor>, ReadError>;
+
+    /// Retrieves up to `limit` users whose username or name resembles `query`, skipping the first
+    /// `offset` matches, in descending order of similarity.
+    async fn users(
+        &self,
+        query: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SearchPage<UserSearchResult>, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait TagRead: Send + Sync {
+    /// Retrieves up to `limit` posts tagged with the normalized tag `tag`, beginning after `after`
+    /// (or at the most recent post if `None`), in descending order of creation time (most recent
+    /// first). Deleted posts and posts that the viewer may not see are never included. Reactions
+    /// are marked as the viewer's own where applicable.
+    async fn tagged(
+        &self,
+        tag: &str,
+        viewer_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<TagPage, ReadError>;
+
+    /// Retrieves up to `limit` tags used by the most posts created within the last `window`, most
+    /// used first, with ties broken alphabetically. Deleted posts are not counted.
+    async fn trending(&self, window: TimeDelta, limit: i64) -> Result<Vec<TrendingTag>, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait ModerationRead: Send + Sync {
+    /// Retrieves every post that has at least one open report, grouping the reports by post. The
+    /// most reported posts come first, with ties going to the post that was reported first.
+    async fn open_reports(&self) -> Result<Vec<ReportedPost>, ReadError>;
+
+    /// Retrieves the moderation audit trail in descending order of action time (most recent
+    /// first).
+    async fn action_log(&self) -> Result<Vec<ModerationActionRecord>, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait NotificationRead: Send + Sync {
+    /// Retrieves up to `limit` notifications for the user with the provided ID along with the
+    /// usernames of the users who caused them, beginning after `after` (or at the most recent
+    /// notification if `None`), in descending order of creation time (most recent first).
+    async fn for_recipient(
+        &self,
+        recipient_id: i32,
+        after: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<NotificationPage, ReadError>;
+
+    /// Retrieves the notifications for the user with the provided ID that were created at or after
+    /// `since` in ascending order of creation time (oldest first).
+    async fn for_recipient_since(
+        &self,
+        recipient_id: i32,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<NotificationWithActor>, ReadError>;
+
+    /// Retrieves the creation time of the notification with ID `notification_id`, or `None` if it
+    /// does not exist or belongs to another user.
+    async fn created_at(
+        &self,
+        recipient_id: i32,
+        notification_id: i32,
+    ) -> Result<Option<DateTime<Utc>>, ReadError>;
+
+    /// Counts the unread notifications of each kind for the user with the provided ID.
+    async fn unread_counts(&self, recipient_id: i32) -> Result<UnreadCounts, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait WebhookRead: Send + Sync {
+    /// Retrieves all webhooks registered by the user with the provided ID in descending order of
+    /// registration time (most recent first).
+    async fn for_owner(&self, owner_id: i32) -> Result<Vec<Webhook>, ReadError>;
+
+    /// Retrieves the most recent deliveries for a webhook, most recent first. Returns
+    /// `ReadError::NotFound` if the webhook does not exist or belongs to someone else.
+    async fn delivery_log(
+        &self,
+        owner_id: i32,
+        webhook_id: i32,
+    ) -> Result<Vec<WebhookDeliveryRecord>, ReadError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+pub trait NotificationFeed: Send + Sync {
+    /// Subscribes to signals for all notifications created from now on, for any recipient and from
+    /// any server instance.
+    fn subscribe(&self) -> broadcast::Receiver<NewNotification>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+pub trait PostFeed: Send + Sync {
+    /// Subscribes to signals for all posts created, edited, or deleted from now on, from any server
+    /// instance.
+    fn subscribe(&self) -> broadcast::Receiver<PostChange>;
+}
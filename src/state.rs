@@ -1,42 +1,121 @@
 use {
     crate::{
         app_services::{
-            Authenticator, MutateFriendshipByUsername, authenticator_svc::AuthenticatorSvc,
+            Authenticator, Federation, MutateFriendshipByUsername, PostModeration, RateLimiter,
+            WebhookManagement,
+            authenticator_svc::AuthenticatorSvc,
+            background::{ShutdownSignal, Workers},
+            deliver_activity::DeliverActivityHandler,
+            federation_svc::FederationSvc,
+            fixed_window_limiter::FixedWindowLimiter,
+            job_worker::JobWorker,
             mutate_friendship_by_username_svc::MutateFriendshipByUsernameSvc,
+            post_moderation_svc::PostModerationSvc,
+            prune_history::{PruneHistory, PruneHistoryHandler},
+            scheduled_post_publisher::ScheduledPostPublisher,
+            webhook_dispatcher::WebhookDispatcher,
+            webhook_management_svc::WebhookManagementSvc,
+        },
+        config::{AppConfig, BlobStoreConfig, PublicUrl, TrustedProxies},
+        domain::{
+            attachment::{
+                AttachmentSvc, BlobStore, image::ImageLimits, service::AttachmentDomainSvc,
+            },
+            bookmark::{BookmarkSvc, service::BookmarkDomainSvc},
+            draft::{DraftSvc, service::DraftDomainSvc},
+            job::JobRepo as _,
+            notification::{NotificationSvc, service::NotificationDomainSvc},
+            post::{
+                PostSvc, body::BodyLimits, content_filter::WordListFilter, service::PostDomainSvc,
+                thread::ThreadLimits,
+            },
+            reaction::{ReactionSvc, service::ReactionDomainSvc},
         },
-        config::AppConfig,
-        domain::post::{PostSvc, service::PostDomainSvc},
         infra::{
-            auth_provider::BcryptJwtAuthProvider, friendship_repo::PgFriendshipRepo,
-            post_repo::PgPostRepo, post_with_author_read::PgPostWithAuthorRead,
-            social_read::PgSocialRead, user_repo::PgUserRepo,
+            attachment_repo::PgAttachmentRepo,
+            auth_provider::BcryptJwtAuthProvider,
+            bookmark_read::PgBookmarkRead,
+            bookmark_repo::PgBookmarkRepo,
+            draft_read::PgDraftRead,
+            draft_repo::PgDraftRepo,
+            federation_client::ReqwestFederationClient,
+            federation_repo::PgFederationRepo,
+            friendship_repo::PgFriendshipRepo,
+            fs_blob_store::FsBlobStore,
+            job_repo::PgJobRepo,
+            mention_read::PgMentionRead,
+            moderation_read::PgModerationRead,
+            moderation_repo::PgModerationRepo,
+            notification_read::PgNotificationRead,
+            notification_repo::PgNotificationRepo,
+            pg_feed::{NOTIFICATION_CHANNEL, POST_CHANGE_CHANNEL, PgFeed},
+            post_repo::PgPostRepo,
+            post_with_author_read::PgPostWithAuthorRead,
+            reaction_repo::PgReactionRepo,
+            s3_blob_store::S3BlobStore,
+            search_read::PgSearchRead,
+            social_read::PgSocialRead,
+            tag_read::PgTagRead,
+            user_repo::PgUserRepo,
+            webhook_client::ReqwestWebhookClient,
+            webhook_read::PgWebhookRead,
+            webhook_repo::PgWebhookRepo,
+        },
+        models::{notification::NewNotification, post::PostChange},
+        read_models::{
+            BookmarkRead, DraftRead, MentionRead, ModerationRead, NotificationFeed,
+            NotificationRead, PostFeed, PostWithAuthorRead, SearchRead, SocialRead, TagRead,
+            WebhookRead,
         },
-        read_models::{PostWithAuthorRead, SocialRead},
     },
     anyhow::Result,
     axum::extract::FromRef,
+    chrono::Utc,
     sqlx::{PgPool, postgres::PgPoolOptions},
     std::{sync::Arc, time::Duration},
 };
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
+    pub public_url: PublicUrl,
+    pub trusted_proxies: TrustedProxies,
     pub auth: Arc<dyn Authenticator>,
+    pub anonymous_rate_limiter: Arc<dyn RateLimiter>,
     pub mutate_friendship_by_username: Arc<dyn MutateFriendshipByUsername>,
     pub post_svc: Arc<dyn PostSvc>,
+    pub attachment_svc: Arc<dyn AttachmentSvc>,
+    pub reaction_svc: Arc<dyn ReactionSvc>,
+    pub bookmark_svc: Arc<dyn BookmarkSvc>,
+    pub draft_svc: Arc<dyn DraftSvc>,
+    pub post_moderation: Arc<dyn PostModeration>,
+    pub webhook_management: Arc<dyn WebhookManagement>,
+    pub federation: Arc<dyn Federation>,
+    pub notification_svc: Arc<dyn NotificationSvc>,
     pub social_read: Arc<dyn SocialRead>,
     pub post_with_author_read: Arc<dyn PostWithAuthorRead>,
+    pub bookmark_read: Arc<dyn BookmarkRead>,
+    pub draft_read: Arc<dyn DraftRead>,
+    pub mention_read: Arc<dyn MentionRead>,
+    pub search_read: Arc<dyn SearchRead>,
+    pub tag_read: Arc<dyn TagRead>,
+    pub moderation_read: Arc<dyn ModerationRead>,
+    pub notification_read: Arc<dyn NotificationRead>,
+    pub webhook_read: Arc<dyn WebhookRead>,
+    pub notification_feed: Arc<dyn NotificationFeed>,
+    pub post_feed: Arc<dyn PostFeed>,
+    pub shutdown: ShutdownSignal,
 }
 
 impl AppState {
     /// Wires together concrete infrastructure implementations (including the database connection),
     /// domain services, application services, and read models to be accessed as `State` in the API
-    /// layer.
+    /// layer, and starts the background workers.
     ///
     /// # Errors
     ///
-    /// Returns `Err` if connecting to the database fails or times out.
-    pub async fn init(config: &AppConfig) -> Result<Self> {
+    /// Returns `Err` if connecting to the database fails or times out, or if the webhook,
+    /// federation, or object storage HTTP client cannot be built.
+    pub async fn init(config: &AppConfig) -> Result<(Self, Workers)> {
         let pool = PgPoolOptions::new()
             .max_connections(config.max_pool_connections)
             .acquire_timeout(Duration::from_secs(config.db_conn_timeout_secs))
@@ -45,27 +124,194 @@ impl AppState {
 
         log::info!("Connected to database");
 
-        Ok(Self::build(pool, config.jwt_secret.clone()))
+        let notification_feed = Arc::new(PgFeed::start(&pool, NOTIFICATION_CHANNEL).await?);
+        let post_feed = Arc::new(PgFeed::start(&pool, POST_CHANGE_CHANNEL).await?);
+
+        log::info!("Listening for notification and post change signals");
+
+        let blob_store: Arc<dyn BlobStore> = match &config.blob_store {
+            BlobStoreConfig::Local { dir } => Arc::new(FsBlobStore::new(dir)),
+            BlobStoreConfig::S3(s3_config) => Arc::new(S3BlobStore::new(s3_config.clone())?),
+        };
+
+        let federation_client = ReqwestFederationClient::new(config.outbound_policy())?;
+
+        let mut workers = Workers::new();
+
+        let state = Self::build(
+            pool.clone(),
+            config,
+            blob_store,
+            federation_client.clone(),
+            notification_feed,
+            post_feed,
+            workers.shutdown_signal(),
+        );
+
+        let webhook_dispatcher = WebhookDispatcher::new(
+            pool.clone(),
+            PgWebhookRepo,
+            ReqwestWebhookClient::new(config.outbound_policy())?,
+        );
+        workers.spawn(webhook_dispatcher);
+
+        // Starts the daily chain of history pruning unless today's run is already pending. If it
+        // has already finished, it runs once more, which is harmless.
+        PgJobRepo
+            .enqueue(&pool, &PruneHistory::for_day(Utc::now())?)
+            .await?;
+
+        let job_worker = JobWorker::new(pool.clone(), PgJobRepo)
+            .with_handler(PruneHistoryHandler::new(pool.clone(), PgJobRepo, PgWebhookRepo))
+            .with_handler(DeliverActivityHandler::new(
+                pool.clone(),
+                PgFederationRepo,
+                federation_client,
+            ));
+        workers.spawn(job_worker);
+
+        workers.spawn(ScheduledPostPublisher::new(Arc::clone(&state.post_svc)));
+
+        log::info!("Started background workers");
+
+        Ok((state, workers))
     }
 
-    fn build(pool: PgPool, jwt_secret: String) -> Self {
+    #[expect(clippy::too_many_lines, reason = "Wires together every service in one place")]
+    fn build(
+        pool: PgPool,
+        config: &AppConfig,
+        blob_store: Arc<dyn BlobStore>,
+        federation_client: ReqwestFederationClient,
+        notification_feed: Arc<PgFeed<NewNotification>>,
+        post_feed: Arc<PgFeed<PostChange>>,
+        shutdown: ShutdownSignal,
+    ) -> Self {
         let auth = Arc::new(AuthenticatorSvc::new(
             pool.clone(),
             PgUserRepo,
-            BcryptJwtAuthProvider::new(jwt_secret),
+            BcryptJwtAuthProvider::new(config.jwt_secret.clone()),
         ));
 
         let mutate_friendship_by_username = Arc::new(MutateFriendshipByUsernameSvc::new(
             pool.clone(),
             PgUserRepo,
             PgFriendshipRepo,
+            PgNotificationRepo,
+            PgWebhookRepo,
         ));
 
-        let post_svc = Arc::new(PostDomainSvc::new(pool.clone(), PgPostRepo));
+        let post_svc = Arc::new(PostDomainSvc::new(
+            pool.clone(),
+            PgPostRepo,
+            PgNotificationRepo,
+            PgWebhookRepo,
+            PgModerationRepo,
+            PgDraftRepo,
+            WordListFilter::from_csv(&config.rejected_words, &config.flagged_words),
+            BodyLimits {
+                max_graphemes: config.max_post_graphemes,
+                max_bytes: config.max_post_bytes,
+            },
+            ThreadLimits {
+                max_depth: config.max_reply_depth,
+                max_children: config.max_children_per_post,
+            },
+        ));
+
+        let attachment_svc = Arc::new(AttachmentDomainSvc::new(
+            pool.clone(),
+            PgAttachmentRepo,
+            PgPostRepo,
+            blob_store,
+            ImageLimits {
+                max_bytes: config.max_attachment_bytes,
+                max_dimension: config.max_image_dimension,
+            },
+        ));
+
+        let reaction_svc =
+            Arc::new(ReactionDomainSvc::new(pool.clone(), PgReactionRepo, PgPostRepo));
+
+        let bookmark_svc =
+            Arc::new(BookmarkDomainSvc::new(pool.clone(), PgBookmarkRepo, PgPostRepo));
+
+        let draft_svc = Arc::new(DraftDomainSvc::new(
+            pool.clone(),
+            PgDraftRepo,
+            PgPostRepo,
+            config.max_post_bytes,
+        ));
+
+        let post_moderation =
+            Arc::new(PostModerationSvc::new(pool.clone(), PgPostRepo, PgModerationRepo));
+
+        let webhook_management = Arc::new(WebhookManagementSvc::new(
+            pool.clone(),
+            PgWebhookRepo,
+            PgModerationRepo,
+            config.outbound_policy(),
+        ));
+
+        let public_url = config.public_url.clone();
+
+        let federation = Arc::new(FederationSvc::new(
+            pool.clone(),
+            PgUserRepo,
+            PgFederationRepo,
+            PgJobRepo,
+            federation_client,
+            public_url.clone(),
+            config.outbound_policy(),
+        ));
+
+        let notification_svc =
+            Arc::new(NotificationDomainSvc::new(pool.clone(), PgNotificationRepo));
         let social_read = Arc::new(PgSocialRead::new(pool.clone()));
-        let post_with_author_read = Arc::new(PgPostWithAuthorRead::new(pool));
+        let post_with_author_read = Arc::new(PgPostWithAuthorRead::new(pool.clone()));
+        let bookmark_read = Arc::new(PgBookmarkRead::new(pool.clone()));
+        let draft_read = Arc::new(PgDraftRead::new(pool.clone()));
+        let mention_read = Arc::new(PgMentionRead::new(pool.clone()));
+        let search_read = Arc::new(PgSearchRead::new(pool.clone()));
+        let tag_read = Arc::new(PgTagRead::new(pool.clone()));
+        let moderation_read = Arc::new(PgModerationRead::new(pool.clone()));
+        let notification_read = Arc::new(PgNotificationRead::new(pool.clone()));
+        let webhook_read = Arc::new(PgWebhookRead::new(pool));
+
+        let anonymous_rate_limiter = Arc::new(FixedWindowLimiter::new(
+            config.anonymous_requests_per_minute,
+            Duration::from_mins(1),
+        ));
 
-        Self { auth, mutate_friendship_by_username, post_svc, social_read, post_with_author_read }
+        Self {
+            public_url,
+            trusted_proxies: config.trusted_proxies.clone(),
+            auth,
+            anonymous_rate_limiter,
+            mutate_friendship_by_username,
+            post_svc,
+            attachment_svc,
+            reaction_svc,
+            bookmark_svc,
+            draft_svc,
+            post_moderation,
+            webhook_management,
+            federation,
+            notification_svc,
+            social_read,
+            post_with_author_read,
+            bookmark_read,
+            draft_read,
+            mention_read,
+            search_read,
+            tag_read,
+            moderation_read,
+            notification_read,
+            webhook_read,
+            notification_feed,
+            post_feed,
+            shutdown,
+        }
     }
 }
 
@@ -73,17 +319,49 @@ impl AppState {
 impl Default for AppState {
     fn default() -> Self {
         use crate::{
-            app_services::{MockAuthenticator, MockMutateFriendshipByUsername},
-            domain::post::MockPostSvc,
-            read_models::{MockPostWithAuthorRead, MockSocialRead},
+            app_services::{
+                MockAuthenticator, MockFederation, MockMutateFriendshipByUsername,
+                MockPostModeration, MockRateLimiter, MockWebhookManagement,
+            },
+            domain::{
+                attachment::MockAttachmentSvc, bookmark::MockBookmarkSvc, draft::MockDraftSvc,
+                notification::MockNotificationSvc, post::MockPostSvc, reaction::MockReactionSvc,
+            },
+            read_models::{
+                MockBookmarkRead, MockDraftRead, MockMentionRead, MockModerationRead,
+                MockNotificationFeed, MockNotificationRead, MockPostFeed, MockPostWithAuthorRead,
+                MockSearchRead, MockSocialRead, MockTagRead, MockWebhookRead,
+            },
         };
 
         Self {
+            public_url: PublicUrl::default(),
+            trusted_proxies: TrustedProxies::default(),
             auth: Arc::new(MockAuthenticator::new()),
+            anonymous_rate_limiter: Arc::new(MockRateLimiter::new()),
             mutate_friendship_by_username: Arc::new(MockMutateFriendshipByUsername::new()),
             post_svc: Arc::new(MockPostSvc::new()),
+            attachment_svc: Arc::new(MockAttachmentSvc::new()),
+            reaction_svc: Arc::new(MockReactionSvc::new()),
+            bookmark_svc: Arc::new(MockBookmarkSvc::new()),
+            draft_svc: Arc::new(MockDraftSvc::new()),
+            post_moderation: Arc::new(MockPostModeration::new()),
+            webhook_management: Arc::new(MockWebhookManagement::new()),
+            federation: Arc::new(MockFederation::new()),
+            notification_svc: Arc::new(MockNotificationSvc::new()),
             social_read: Arc::new(MockSocialRead::new()),
             post_with_author_read: Arc::new(MockPostWithAuthorRead::new()),
+            bookmark_read: Arc::new(MockBookmarkRead::new()),
+            draft_read: Arc::new(MockDraftRead::new()),
+            mention_read: Arc::new(MockMentionRead::new()),
+            search_read: Arc::new(MockSearchRead::new()),
+            tag_read: Arc::new(MockTagRead::new()),
+            moderation_read: Arc::new(MockModerationRead::new()),
+            notification_read: Arc::new(MockNotificationRead::new()),
+            webhook_read: Arc::new(MockWebhookRead::new()),
+            notification_feed: Arc::new(MockNotificationFeed::new()),
+            post_feed: Arc::new(MockPostFeed::new()),
+            shutdown: ShutdownSignal::default(),
         }
     }
 }
@@ -4,6 +4,7 @@ pub mod http_bodies;
 pub mod mock_repos;
 pub mod seed_data;
 pub mod time;
+pub mod ws_client;
 
 use anyhow::{Context as _, Result};
 
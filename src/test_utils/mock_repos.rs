@@ -2,16 +2,34 @@ use {
     crate::{
         domain::{
             RepoError,
+            attachment::AttachmentRepo,
+            bookmark::BookmarkRepo,
+            draft::DraftRepo,
+            federation::FederationRepo,
             friendship::{FriendshipRepo, FriendshipStatus, user_id_pair::UserIdPair},
-            post::PostRepo,
+            job::{JobRepo, NewJob},
+            moderation::ModerationRepo,
+            notification::NotificationRepo,
+            post::{PostRepo, ReplyRestriction, mention::Mention},
+            reaction::ReactionRepo,
             user::UserRepo,
+            webhook::{WebhookDeliveryRepo, WebhookRepo},
         },
         models::{
-            post::Post,
+            attachment::{Attachment, NewAttachment},
+            draft::Draft,
+            federation::{ActorKey, RemoteActor},
+            job::ClaimedJob,
+            moderation::{ModerationActionKind, ReportReason},
+            notification::NotificationKind,
+            post::{BodyFormat, Post, PostVisibility, ReplyPolicy},
+            reaction::ReactionKind,
             user::{NewUser, User},
+            webhook::{DeliveryAttempt, PendingDelivery, WebhookEvent, WebhookEventKind},
         },
     },
     anyhow::Context as _,
+    chrono::{DateTime, TimeDelta, Utc},
     sqlx::PgExecutor,
 };
 
@@ -21,6 +39,7 @@ pub struct MockUserRepo {
     pub insert_new: Option<Box<dyn Fn(&NewUser) -> Result<User, RepoError> + Send + Sync>>,
     pub get_by_id: Option<Box<dyn Fn(i32) -> Result<Option<User>, RepoError> + Send + Sync>>,
     pub get_by_email: Option<Box<dyn Fn(&str) -> Result<Option<User>, RepoError> + Send + Sync>>,
+    pub get_by_username: Option<Box<dyn Fn(&str) -> Result<Option<User>, RepoError> + Send + Sync>>,
     pub get_by_username_exclusive:
         Option<Box<dyn Fn(&str) -> Result<Option<User>, RepoError> + Send + Sync>>,
 }
@@ -57,6 +76,16 @@ impl UserRepo for MockUserRepo {
             .as_ref()
             .context("mock user repo get by email")?)(email)
     }
+    async fn get_by_username(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        username: &str,
+    ) -> Result<Option<User>, RepoError> {
+        (self
+            .get_by_username
+            .as_ref()
+            .context("mock user repo get by username")?)(username)
+    }
     async fn get_by_username_exclusive(
         &self,
         _exec: impl PgExecutor<'_>,
@@ -118,9 +147,36 @@ impl FriendshipRepo for MockFriendshipRepo {
 #[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
 #[derive(Default)]
 pub struct MockPostRepo {
-    pub insert_new: Option<Box<dyn Fn(i32, i32, &str) -> Result<(), RepoError> + Send + Sync>>,
+    pub insert_new: Option<
+        Box<
+            dyn Fn(i32, i32, &str, BodyFormat, Option<i32>) -> Result<i32, RepoError> + Send + Sync,
+        >,
+    >,
+    pub insert_scheduled: Option<
+        Box<
+            dyn Fn(i32, i32, &str, BodyFormat, DateTime<Utc>) -> Result<i32, RepoError>
+                + Send
+                + Sync,
+        >,
+    >,
+    pub insert_mentions:
+        Option<Box<dyn Fn(i32, &[Mention<'_>]) -> Result<Vec<i32>, RepoError> + Send + Sync>>,
+    pub insert_tags: Option<Box<dyn Fn(i32, &[String]) -> Result<(), RepoError> + Send + Sync>>,
+    pub get_by_id: Option<Box<dyn Fn(i32) -> Result<Option<Post>, RepoError> + Send + Sync>>,
     pub get_by_id_exclusive:
         Option<Box<dyn Fn(i32) -> Result<Option<Post>, RepoError> + Send + Sync>>,
+    pub is_visible_to: Option<Box<dyn Fn(i32, i32) -> Result<bool, RepoError> + Send + Sync>>,
+    pub reply_restrictions:
+        Option<Box<dyn Fn(i32, i32) -> Result<Vec<ReplyRestriction>, RepoError> + Send + Sync>>,
+    pub set_reply_policy:
+        Option<Box<dyn Fn(i32, ReplyPolicy, bool) -> Result<(), RepoError> + Send + Sync>>,
+    pub set_visibility:
+        Option<Box<dyn Fn(i32, PostVisibility) -> Result<(), RepoError> + Send + Sync>>,
+    pub claim_due_scheduled: Option<Box<dyn Fn() -> Result<Option<Post>, RepoError> + Send + Sync>>,
+    pub publish: Option<Box<dyn Fn(i32) -> Result<(), RepoError> + Send + Sync>>,
+    pub delete_scheduled: Option<Box<dyn Fn(i32) -> Result<(), RepoError> + Send + Sync>>,
+    pub archive: Option<Box<dyn Fn(i32) -> Result<(), RepoError> + Send + Sync>>,
+    pub soft_delete: Option<Box<dyn Fn(i32) -> Result<(), RepoError> + Send + Sync>>,
 }
 
 #[async_trait::async_trait]
@@ -131,11 +187,75 @@ impl PostRepo for MockPostRepo {
         author_id: i32,
         parent_id: i32,
         body: &str,
-    ) -> Result<(), RepoError> {
+        body_format: BodyFormat,
+        quoted_post_id: Option<i32>,
+    ) -> Result<i32, RepoError> {
         (self
             .insert_new
             .as_ref()
-            .context("mock post repo insert new")?)(author_id, parent_id, body)
+            .context("mock post repo insert new")?)(
+            author_id,
+            parent_id,
+            body,
+            body_format,
+            quoted_post_id,
+        )
+    }
+
+    async fn insert_scheduled(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        publish_at: DateTime<Utc>,
+    ) -> Result<i32, RepoError> {
+        (self
+            .insert_scheduled
+            .as_ref()
+            .context("mock post repo insert scheduled")?)(
+            author_id,
+            parent_id,
+            body,
+            body_format,
+            publish_at,
+        )
+    }
+
+    async fn insert_mentions(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        mentions: &[Mention<'_>],
+    ) -> Result<Vec<i32>, RepoError> {
+        (self
+            .insert_mentions
+            .as_ref()
+            .context("mock post repo insert mentions")?)(post_id, mentions)
+    }
+
+    async fn insert_tags(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        tags: &[String],
+    ) -> Result<(), RepoError> {
+        (self
+            .insert_tags
+            .as_ref()
+            .context("mock post repo insert tags")?)(post_id, tags)
+    }
+
+    async fn get_by_id(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+    ) -> Result<Option<Post>, RepoError> {
+        (self
+            .get_by_id
+            .as_ref()
+            .context("mock post repo get by ID")?)(id)
     }
 
     async fn get_by_id_exclusive(
@@ -148,4 +268,606 @@ impl PostRepo for MockPostRepo {
             .as_ref()
             .context("mock post repo get by ID exclusive")?)(id)
     }
+
+    async fn is_visible_to(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        viewer_id: i32,
+    ) -> Result<bool, RepoError> {
+        (self
+            .is_visible_to
+            .as_ref()
+            .context("mock post repo is visible to")?)(post_id, viewer_id)
+    }
+
+    async fn reply_restrictions(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        replier_id: i32,
+    ) -> Result<Vec<ReplyRestriction>, RepoError> {
+        (self
+            .reply_restrictions
+            .as_ref()
+            .context("mock post repo reply restrictions")?)(post_id, replier_id)
+    }
+
+    async fn set_reply_policy(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+        policy: ReplyPolicy,
+        cascades: bool,
+    ) -> Result<(), RepoError> {
+        (self
+            .set_reply_policy
+            .as_ref()
+            .context("mock post repo set reply policy")?)(id, policy, cascades)
+    }
+
+    async fn set_visibility(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+        visibility: PostVisibility,
+    ) -> Result<(), RepoError> {
+        (self
+            .set_visibility
+            .as_ref()
+            .context("mock post repo set visibility")?)(id, visibility)
+    }
+
+    async fn claim_due_scheduled(
+        &self,
+        _exec: impl PgExecutor<'_>,
+    ) -> Result<Option<Post>, RepoError> {
+        (self
+            .claim_due_scheduled
+            .as_ref()
+            .context("mock post repo claim due scheduled")?)()
+    }
+
+    async fn publish(&self, _exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        (self.publish.as_ref().context("mock post repo publish")?)(id)
+    }
+
+    async fn delete_scheduled(&self, _exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        (self
+            .delete_scheduled
+            .as_ref()
+            .context("mock post repo delete scheduled")?)(id)
+    }
+
+    async fn archive(&self, _exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        (self.archive.as_ref().context("mock post repo archive")?)(id)
+    }
+
+    async fn soft_delete(&self, _exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        (self
+            .soft_delete
+            .as_ref()
+            .context("mock post repo soft delete")?)(id)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockModerationRepo {
+    pub insert_report:
+        Option<Box<dyn Fn(i32, i32, ReportReason) -> Result<(), RepoError> + Send + Sync>>,
+    pub insert_flag: Option<Box<dyn Fn(i32, ReportReason) -> Result<(), RepoError> + Send + Sync>>,
+    pub is_moderator: Option<Box<dyn Fn(i32) -> Result<bool, RepoError> + Send + Sync>>,
+    pub record_action: Option<
+        Box<
+            dyn Fn(i32, i32, ModerationActionKind, Option<&str>) -> Result<i32, RepoError>
+                + Send
+                + Sync,
+        >,
+    >,
+    pub resolve_open_reports: Option<Box<dyn Fn(i32, i32) -> Result<u64, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl ModerationRepo for MockModerationRepo {
+    async fn insert_report(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        reporter_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), RepoError> {
+        (self
+            .insert_report
+            .as_ref()
+            .context("mock moderation repo insert report")?)(post_id, reporter_id, reason)
+    }
+
+    async fn insert_flag(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        reason: ReportReason,
+    ) -> Result<(), RepoError> {
+        (self
+            .insert_flag
+            .as_ref()
+            .context("mock moderation repo insert flag")?)(post_id, reason)
+    }
+
+    async fn is_moderator(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+    ) -> Result<bool, RepoError> {
+        (self
+            .is_moderator
+            .as_ref()
+            .context("mock moderation repo is moderator")?)(user_id)
+    }
+
+    async fn record_action(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        moderator_id: i32,
+        action: ModerationActionKind,
+        note: Option<&str>,
+    ) -> Result<i32, RepoError> {
+        (self
+            .record_action
+            .as_ref()
+            .context("mock moderation repo record action")?)(
+            post_id, moderator_id, action, note
+        )
+    }
+
+    async fn resolve_open_reports(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        action_id: i32,
+    ) -> Result<u64, RepoError> {
+        (self
+            .resolve_open_reports
+            .as_ref()
+            .context("mock moderation repo resolve open reports")?)(post_id, action_id)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockNotificationRepo {
+    pub insert_new: Option<
+        Box<dyn Fn(i32, i32, NotificationKind, Option<i32>) -> Result<(), RepoError> + Send + Sync>,
+    >,
+    pub mark_read: Option<Box<dyn Fn(i32, Option<&[i32]>) -> Result<u64, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl NotificationRepo for MockNotificationRepo {
+    async fn insert_new(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        recipient_id: i32,
+        actor_id: i32,
+        kind: NotificationKind,
+        post_id: Option<i32>,
+    ) -> Result<(), RepoError> {
+        (self
+            .insert_new
+            .as_ref()
+            .context("mock notification repo insert new")?)(
+            recipient_id, actor_id, kind, post_id
+        )
+    }
+
+    async fn mark_read(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        recipient_id: i32,
+        ids: Option<&[i32]>,
+    ) -> Result<u64, RepoError> {
+        (self
+            .mark_read
+            .as_ref()
+            .context("mock notification repo mark read")?)(recipient_id, ids)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockReactionRepo {
+    pub upsert: Option<Box<dyn Fn(i32, i32, ReactionKind) -> Result<(), RepoError> + Send + Sync>>,
+    pub delete:
+        Option<Box<dyn Fn(i32, i32, ReactionKind) -> Result<bool, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl ReactionRepo for MockReactionRepo {
+    async fn upsert(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<(), RepoError> {
+        (self.upsert.as_ref().context("mock reaction repo upsert")?)(user_id, post_id, kind)
+    }
+
+    async fn delete(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+        kind: ReactionKind,
+    ) -> Result<bool, RepoError> {
+        (self.delete.as_ref().context("mock reaction repo delete")?)(user_id, post_id, kind)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockBookmarkRepo {
+    pub insert: Option<Box<dyn Fn(i32, i32) -> Result<(), RepoError> + Send + Sync>>,
+    pub delete: Option<Box<dyn Fn(i32, i32) -> Result<bool, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl BookmarkRepo for MockBookmarkRepo {
+    async fn insert(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+    ) -> Result<(), RepoError> {
+        (self.insert.as_ref().context("mock bookmark repo insert")?)(user_id, post_id)
+    }
+
+    async fn delete(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+        post_id: i32,
+    ) -> Result<bool, RepoError> {
+        (self.delete.as_ref().context("mock bookmark repo delete")?)(user_id, post_id)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockDraftRepo {
+    pub upsert: Option<
+        Box<
+            dyn Fn(i32, i32, &str, BodyFormat, Option<&str>) -> Result<Draft, RepoError>
+                + Send
+                + Sync,
+        >,
+    >,
+    pub get_exclusive:
+        Option<Box<dyn Fn(i32, i32) -> Result<Option<Draft>, RepoError> + Send + Sync>>,
+    pub delete: Option<Box<dyn Fn(i32, i32) -> Result<bool, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl DraftRepo for MockDraftRepo {
+    async fn upsert(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+        body: &str,
+        body_format: BodyFormat,
+        publish_error: Option<&str>,
+    ) -> Result<Draft, RepoError> {
+        (self.upsert.as_ref().context("mock draft repo upsert")?)(
+            author_id,
+            parent_id,
+            body,
+            body_format,
+            publish_error,
+        )
+    }
+
+    async fn get_exclusive(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<Option<Draft>, RepoError> {
+        (self
+            .get_exclusive
+            .as_ref()
+            .context("mock draft repo get exclusive")?)(author_id, parent_id)
+    }
+
+    async fn delete(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        author_id: i32,
+        parent_id: i32,
+    ) -> Result<bool, RepoError> {
+        (self.delete.as_ref().context("mock draft repo delete")?)(author_id, parent_id)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockAttachmentRepo {
+    pub insert_pending:
+        Option<Box<dyn Fn(&NewAttachment) -> Result<Attachment, RepoError> + Send + Sync>>,
+    pub mark_stored: Option<Box<dyn Fn(i32) -> Result<(), RepoError> + Send + Sync>>,
+    pub delete: Option<Box<dyn Fn(i32) -> Result<(), RepoError> + Send + Sync>>,
+    pub count_for_post:
+        Option<Box<dyn Fn(i32, DateTime<Utc>) -> Result<i64, RepoError> + Send + Sync>>,
+    pub get_visible:
+        Option<Box<dyn Fn(i32, i32) -> Result<Option<Attachment>, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl AttachmentRepo for MockAttachmentRepo {
+    async fn insert_pending(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        attachment: &NewAttachment,
+    ) -> Result<Attachment, RepoError> {
+        (self
+            .insert_pending
+            .as_ref()
+            .context("mock attachment repo insert pending")?)(attachment)
+    }
+
+    async fn mark_stored(&self, _exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        (self
+            .mark_stored
+            .as_ref()
+            .context("mock attachment repo mark stored")?)(id)
+    }
+
+    async fn delete(&self, _exec: impl PgExecutor<'_>, id: i32) -> Result<(), RepoError> {
+        (self
+            .delete
+            .as_ref()
+            .context("mock attachment repo delete")?)(id)
+    }
+
+    async fn count_for_post(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        post_id: i32,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<i64, RepoError> {
+        (self
+            .count_for_post
+            .as_ref()
+            .context("mock attachment repo count for post")?)(post_id, abandoned_before)
+    }
+
+    async fn get_visible(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+        viewer_id: i32,
+    ) -> Result<Option<Attachment>, RepoError> {
+        (self
+            .get_visible
+            .as_ref()
+            .context("mock attachment repo get visible")?)(id, viewer_id)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockWebhookRepo {
+    pub insert_new: Option<
+        Box<
+            dyn Fn(i32, &str, &str, Vec<WebhookEventKind>, bool) -> Result<i32, RepoError>
+                + Send
+                + Sync,
+        >,
+    >,
+    pub delete: Option<Box<dyn Fn(i32, i32) -> Result<bool, RepoError> + Send + Sync>>,
+    pub enqueue: Option<Box<dyn Fn(&WebhookEvent) -> Result<(), RepoError> + Send + Sync>>,
+    pub prune_finished: Option<Box<dyn Fn(DateTime<Utc>) -> Result<u64, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl WebhookRepo for MockWebhookRepo {
+    async fn insert_new(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        owner_id: i32,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEventKind],
+        all_users: bool,
+    ) -> Result<i32, RepoError> {
+        (self
+            .insert_new
+            .as_ref()
+            .context("mock webhook repo insert new")?)(
+            owner_id,
+            url,
+            secret,
+            events.to_vec(),
+            all_users,
+        )
+    }
+
+    async fn delete(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        owner_id: i32,
+        id: i32,
+    ) -> Result<bool, RepoError> {
+        (self.delete.as_ref().context("mock webhook repo delete")?)(owner_id, id)
+    }
+
+    async fn enqueue(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        event: &WebhookEvent,
+    ) -> Result<(), RepoError> {
+        (self.enqueue.as_ref().context("mock webhook repo enqueue")?)(event)
+    }
+
+    async fn prune_finished(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        before: DateTime<Utc>,
+    ) -> Result<u64, RepoError> {
+        (self
+            .prune_finished
+            .as_ref()
+            .context("mock webhook repo prune finished")?)(before)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockWebhookDeliveryRepo {
+    pub claim_due: Option<
+        Box<dyn Fn(i64, TimeDelta) -> Result<Vec<PendingDelivery>, RepoError> + Send + Sync>,
+    >,
+    pub record_attempt:
+        Option<Box<dyn Fn(i32, &DeliveryAttempt) -> Result<(), RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl WebhookDeliveryRepo for MockWebhookDeliveryRepo {
+    async fn claim_due(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        limit: i64,
+        lease: TimeDelta,
+    ) -> Result<Vec<PendingDelivery>, RepoError> {
+        (self
+            .claim_due
+            .as_ref()
+            .context("mock webhook delivery repo claim due")?)(limit, lease)
+    }
+
+    async fn record_attempt(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: &DeliveryAttempt,
+    ) -> Result<(), RepoError> {
+        (self
+            .record_attempt
+            .as_ref()
+            .context("mock webhook delivery repo record attempt")?)(id, attempt)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockJobRepo {
+    pub enqueue: Option<Box<dyn Fn(&NewJob) -> Result<Option<i32>, RepoError> + Send + Sync>>,
+    pub claim_due:
+        Option<Box<dyn Fn(i64, TimeDelta) -> Result<Vec<ClaimedJob>, RepoError> + Send + Sync>>,
+    pub complete: Option<Box<dyn Fn(i32, i32) -> Result<bool, RepoError> + Send + Sync>>,
+    pub fail: Option<
+        Box<dyn Fn(i32, i32, &str, Option<DateTime<Utc>>) -> Result<bool, RepoError> + Send + Sync>,
+    >,
+    pub prune_finished:
+        Option<Box<dyn Fn(DateTime<Utc>, DateTime<Utc>) -> Result<u64, RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl JobRepo for MockJobRepo {
+    async fn enqueue(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        job: &NewJob,
+    ) -> Result<Option<i32>, RepoError> {
+        (self.enqueue.as_ref().context("mock job repo enqueue")?)(job)
+    }
+
+    async fn claim_due(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        limit: i64,
+        lease: TimeDelta,
+    ) -> Result<Vec<ClaimedJob>, RepoError> {
+        (self.claim_due.as_ref().context("mock job repo claim due")?)(limit, lease)
+    }
+
+    async fn complete(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: i32,
+    ) -> Result<bool, RepoError> {
+        (self.complete.as_ref().context("mock job repo complete")?)(id, attempt)
+    }
+
+    async fn fail(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        id: i32,
+        attempt: i32,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, RepoError> {
+        (self.fail.as_ref().context("mock job repo fail")?)(id, attempt, error, retry_at)
+    }
+
+    async fn prune_finished(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        succeeded_before: DateTime<Utc>,
+        dead_before: DateTime<Utc>,
+    ) -> Result<u64, RepoError> {
+        (self
+            .prune_finished
+            .as_ref()
+            .context("mock job repo prune finished")?)(succeeded_before, dead_before)
+    }
+}
+
+#[expect(clippy::type_complexity, reason = "Hand-rolled mock")]
+#[derive(Default)]
+pub struct MockFederationRepo {
+    pub key_for: Option<Box<dyn Fn(i32) -> Result<Option<ActorKey>, RepoError> + Send + Sync>>,
+    pub insert_key: Option<Box<dyn Fn(i32, &ActorKey) -> Result<(), RepoError> + Send + Sync>>,
+    pub add_follower: Option<Box<dyn Fn(i32, &RemoteActor) -> Result<(), RepoError> + Send + Sync>>,
+}
+
+#[async_trait::async_trait]
+impl FederationRepo for MockFederationRepo {
+    async fn key_for(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+    ) -> Result<Option<ActorKey>, RepoError> {
+        (self
+            .key_for
+            .as_ref()
+            .context("mock federation repo key for")?)(user_id)
+    }
+
+    async fn insert_key(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+        key: &ActorKey,
+    ) -> Result<(), RepoError> {
+        (self
+            .insert_key
+            .as_ref()
+            .context("mock federation repo insert key")?)(user_id, key)
+    }
+
+    async fn add_follower(
+        &self,
+        _exec: impl PgExecutor<'_>,
+        user_id: i32,
+        follower: &RemoteActor,
+    ) -> Result<(), RepoError> {
+        (self
+            .add_follower
+            .as_ref()
+            .context("mock federation repo add follower")?)(user_id, follower)
+    }
 }
@@ -123,3 +123,12 @@ pub async fn seed_users_and_root_post(pool: &PgPool) -> Result<[NewUser; 4]> {
     seed_root_post(pool).await?;
     Ok(new_users)
 }
+
+/// Grants moderator access to the user with the provided ID, assuming the user already exists.
+pub async fn seed_moderator(pool: &PgPool, user_id: i32) -> Result<()> {
+    sqlx::query!("INSERT INTO moderator (user_id) VALUES ($1)", user_id)
+        .execute(pool)
+        .await
+        .context("failed to insert moderator")
+        .map(|_| ())
+}
@@ -0,0 +1,80 @@
+use {
+    anyhow::{Context as _, Result, anyhow},
+    axum::{
+        Router,
+        http::{HeaderValue, header::AUTHORIZATION},
+    },
+    futures_util::{SinkExt as _, StreamExt as _},
+    serde::{Serialize, de::DeserializeOwned},
+    std::{net::SocketAddr, time::Duration},
+    tokio::{
+        net::{TcpListener, TcpStream},
+        time::timeout,
+    },
+    tokio_tungstenite::{
+        MaybeTlsStream, WebSocketStream,
+        tungstenite::{self, Message, client::IntoClientRequest as _},
+    },
+};
+
+pub type TestSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serves `app` on an ephemeral local port in a background task, since WebSocket upgrades need a
+/// real connection rather than a `oneshot` request.
+pub async fn serve(app: Router) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            log::error!("Test server failed: {e}");
+        }
+    });
+
+    Ok(addr)
+}
+
+/// Opens a WebSocket connection to `path` on the server at `addr`, sending `token` as a bearer
+/// token if provided.
+pub async fn connect(
+    addr: SocketAddr,
+    path: &str,
+    token: Option<&str>,
+) -> Result<TestSocket, tungstenite::Error> {
+    let mut req = format!("ws://{addr}{path}").into_client_request()?;
+
+    if let Some(tk) = token {
+        let header_val = HeaderValue::from_str(&format!("Bearer {tk}"))
+            .map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+        req.headers_mut().insert(AUTHORIZATION, header_val);
+    }
+
+    tokio_tungstenite::connect_async(req)
+        .await
+        .map(|(socket, _)| socket)
+}
+
+/// Sends `message` as a JSON text frame.
+pub async fn send_json(socket: &mut TestSocket, message: &(impl Serialize + Sync)) -> Result<()> {
+    socket
+        .send(Message::text(serde_json::to_string(message)?))
+        .await
+        .map_err(Into::into)
+}
+
+/// Receives the next text frame and deserializes it as JSON, failing if none arrives within a few
+/// seconds.
+pub async fn recv_json<T: DeserializeOwned>(socket: &mut TestSocket) -> Result<T> {
+    loop {
+        let message = timeout(Duration::from_secs(5), socket.next())
+            .await
+            .context("timed out waiting for a message")?
+            .context("socket closed")??;
+
+        match message {
+            Message::Text(text) => return serde_json::from_str(&text).map_err(Into::into),
+            Message::Ping(_) | Message::Pong(_) => {}
+            other => return Err(anyhow!("unexpected message: {other:?}")),
+        }
+    }
+}